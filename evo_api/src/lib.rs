@@ -0,0 +1,295 @@
+//! A small, stable API for applications that embed evo.
+//!
+//! The types in `evo_domain` change whenever the simulation does. This crate wraps them
+//! in plain `f64` values and read-only views, so code written against it keeps compiling
+//! across internal refactors. Anything not exposed here is deliberately unstable.
+
+use evo_domain::biology::cell::Cell;
+use evo_domain::physics::newtonian::NewtonianBody;
+use evo_domain::physics::quantities::*;
+use evo_domain::physics::shapes::Circle;
+use evo_domain::world::World;
+
+/// An x-y pair, in world units.
+pub type Point = (f64, f64);
+
+/// Owns a running world and exposes the controls for running it.
+pub struct WorldHandle {
+    world: World,
+    tick_count: u64,
+}
+
+impl WorldHandle {
+    /// Creates an empty world with walls, collisions, and bond forces.
+    pub fn new(min_corner: Point, max_corner: Point) -> Self {
+        Self::from_world(
+            World::new(Position::from(min_corner), Position::from(max_corner))
+                .with_standard_influences(),
+        )
+    }
+
+    /// Wraps a world built with `evo_domain` directly, for setups this API doesn't cover.
+    pub fn from_world(world: World) -> Self {
+        WorldHandle {
+            world,
+            tick_count: 0,
+        }
+    }
+
+    /// Adds a simple inert ball cell.
+    pub fn add_ball(&mut self, radius: f64, mass: f64, position: Point, velocity: Point) {
+        self.world.add_cell(Cell::ball(
+            Length::new(radius),
+            Mass::new(mass),
+            Position::from(position),
+            Velocity::from(velocity),
+        ));
+    }
+
+    pub fn min_corner(&self) -> Point {
+        self.world.min_corner().into()
+    }
+
+    pub fn max_corner(&self) -> Point {
+        self.world.max_corner().into()
+    }
+
+    /// Number of ticks run through this handle.
+    pub fn tick_count(&self) -> u64 {
+        self.tick_count
+    }
+
+    pub fn tick(&mut self) {
+        self.world.tick();
+        self.tick_count += 1;
+    }
+
+    pub fn run_for(&mut self, ticks: u64) {
+        for _ in 0..ticks {
+            self.tick();
+        }
+    }
+
+    /// Ticks until `keep_running` returns false or `max_ticks` ticks have run.
+    /// Returns the number of ticks run.
+    pub fn run_while<F>(&mut self, max_ticks: u64, mut keep_running: F) -> u64
+    where
+        F: FnMut(&WorldHandle) -> bool,
+    {
+        let mut ticks = 0;
+        while ticks < max_ticks && keep_running(self) {
+            self.tick();
+            ticks += 1;
+        }
+        ticks
+    }
+
+    pub fn cell_count(&self) -> usize {
+        self.world.cells().len()
+    }
+
+    pub fn bond_count(&self) -> usize {
+        self.world.bonds().len()
+    }
+
+    pub fn cloud_count(&self) -> usize {
+        self.world.clouds().len()
+    }
+
+    /// Views of the cells currently in the world. Their order may change from tick to tick.
+    pub fn cells(&self) -> impl Iterator<Item = CellView<'_>> {
+        self.world.cells().iter().map(CellView::new)
+    }
+
+    /// The plain-data state of every cell, e.g. to hand across an FFI boundary.
+    pub fn cell_states(&self) -> Vec<CellState> {
+        self.cells().map(|cell| cell.state()).collect()
+    }
+
+    /// The cell covering `point`, if any.
+    pub fn cell_at(&self, point: Point) -> Option<CellView<'_>> {
+        let pos = Position::from(point);
+        self.world
+            .cells()
+            .iter()
+            .find(|cell| cell.overlaps(pos))
+            .map(CellView::new)
+    }
+
+    /// Called with the parent and child each time a cell buds.
+    pub fn on_birth<F>(&mut self, mut hook: F)
+    where
+        F: 'static + FnMut(CellView, CellView) + Send,
+    {
+        self.world
+            .on_birth(move |parent, child| hook(CellView::new(parent), CellView::new(child)));
+    }
+
+    /// Called with each cell that dies, just before it is removed.
+    pub fn on_death<F>(&mut self, mut hook: F)
+    where
+        F: 'static + FnMut(CellView) + Send,
+    {
+        self.world.on_death(move |cell| hook(CellView::new(cell)));
+    }
+}
+
+/// A read-only snapshot of one cell, valid until the world next ticks.
+#[derive(Clone, Copy)]
+pub struct CellView<'a> {
+    cell: &'a Cell,
+}
+
+impl<'a> CellView<'a> {
+    fn new(cell: &'a Cell) -> Self {
+        CellView { cell }
+    }
+
+    pub fn position(&self) -> Point {
+        self.cell.position().into()
+    }
+
+    pub fn velocity(&self) -> Point {
+        self.cell.velocity().into()
+    }
+
+    pub fn radius(&self) -> f64 {
+        self.cell.radius().value()
+    }
+
+    pub fn mass(&self) -> f64 {
+        self.cell.mass().value()
+    }
+
+    pub fn energy(&self) -> f64 {
+        self.cell.energy().value()
+    }
+
+    pub fn layer_count(&self) -> usize {
+        self.cell.layers().len()
+    }
+
+    pub fn is_selected(&self) -> bool {
+        self.cell.is_selected()
+    }
+
+    pub fn state(&self) -> CellState {
+        let (x, y) = self.position();
+        let (velocity_x, velocity_y) = self.velocity();
+        CellState {
+            x,
+            y,
+            velocity_x,
+            velocity_y,
+            radius: self.radius(),
+            mass: self.mass(),
+            energy: self.energy(),
+            layer_count: self.layer_count() as u32,
+            is_selected: self.is_selected(),
+        }
+    }
+}
+
+/// A cell's state as a flat, C-compatible struct of plain values, for bindings,
+/// telemetry, and WASM, which need a fixed layout rather than a view with a lifetime.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CellState {
+    pub x: f64,
+    pub y: f64,
+    pub velocity_x: f64,
+    pub velocity_y: f64,
+    pub radius: f64,
+    pub mass: f64,
+    pub energy: f64,
+    pub layer_count: u32,
+    pub is_selected: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn ball_moves_when_world_runs() {
+        let mut world = WorldHandle::new((-10.0, -10.0), (10.0, 10.0));
+        world.add_ball(1.0, 1.0, (0.0, 0.0), (1.0, 0.0));
+
+        world.run_for(2);
+
+        assert_eq!(world.tick_count(), 2);
+        let ball = world.cells().next().unwrap();
+        assert_eq!(ball.position(), (2.0, 0.0));
+        assert_eq!(ball.radius(), 1.0);
+    }
+
+    #[test]
+    fn run_while_stops_when_condition_fails() {
+        let mut world = WorldHandle::new((-10.0, -10.0), (10.0, 10.0));
+        world.add_ball(1.0, 1.0, (0.0, 0.0), (1.0, 0.0));
+
+        let ticks = world.run_while(100, |world| {
+            world.cells().next().unwrap().position().0 < 3.0
+        });
+
+        assert_eq!(ticks, 3);
+        assert_eq!(world.tick_count(), 3);
+    }
+
+    #[test]
+    fn run_while_stops_at_max_ticks() {
+        let mut world = WorldHandle::new((-10.0, -10.0), (10.0, 10.0));
+
+        let ticks = world.run_while(5, |_| true);
+
+        assert_eq!(ticks, 5);
+    }
+
+    #[test]
+    fn cell_at_finds_covering_cell() {
+        let mut world = WorldHandle::new((-10.0, -10.0), (10.0, 10.0));
+        world.add_ball(1.0, 1.0, (5.0, 5.0), (0.0, 0.0));
+
+        assert!(world.cell_at((5.5, 5.0)).is_some());
+        assert!(world.cell_at((0.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn cell_state_is_flat_copy_of_cell() {
+        let mut world = WorldHandle::new((-10.0, -10.0), (10.0, 10.0));
+        world.add_ball(1.0, 2.0, (5.0, 5.0), (0.5, -0.5));
+
+        assert_eq!(
+            world.cell_states(),
+            vec![CellState {
+                x: 5.0,
+                y: 5.0,
+                velocity_x: 0.5,
+                velocity_y: -0.5,
+                radius: 1.0,
+                mass: 2.0,
+                energy: 0.0,
+                layer_count: 1,
+                is_selected: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn death_hook_is_not_called_for_intact_cells() {
+        let mut world = WorldHandle::new((-10.0, -10.0), (10.0, 10.0));
+        world.add_ball(1.0, 1.0, (0.0, 0.0), (0.0, 0.0));
+        let deaths = Arc::new(AtomicUsize::new(0));
+        let deaths_clone = Arc::clone(&deaths);
+        world.on_death(move |_| {
+            deaths_clone.fetch_add(1, Ordering::SeqCst);
+        });
+
+        world.run_for(3);
+
+        assert_eq!(deaths.load(Ordering::SeqCst), 0);
+        assert_eq!(world.cell_count(), 1);
+    }
+}
@@ -0,0 +1,98 @@
+// Criterion benchmarks of whole ticks and of the physics that dominates them, over
+// worlds that are the same from run to run, so that a change's effect on speed can be
+// measured instead of guessed. Run them, e.g. before and after a change, with
+//
+//   cargo bench -p evo_domain --features test-support
+//
+// Criterion keeps the last run's results under target/criterion and reports the change
+// from them. The worlds keep ticking from one iteration to the next, as in a real run,
+// so their cells move but don't multiply or die off.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use evo_domain::benchmark::dense_world;
+use evo_domain::environment::influences::{BondForces, CrossCellInfluence};
+use evo_domain::physics::overlap::find_pair_overlaps;
+use evo_domain::test_support::{colony_chain, crowded_cluster};
+use evo_domain::world::World;
+use std::time::Duration;
+
+const DENSE_WORLD_SIZES: [usize; 3] = [100, 1_000, 10_000];
+const CHAIN_LENGTH: usize = 1_000;
+const CROWDED_CLUSTER_SIZE: usize = 300;
+
+// Labeled worlds: the standard dense world at each size, a long bonded chain, and
+// unit balls piled on top of each other.
+fn bench_worlds() -> Vec<(String, World)> {
+    let mut worlds = DENSE_WORLD_SIZES
+        .iter()
+        .map(|&size| (format!("dense {}", size), dense_world(size, 0)))
+        .collect::<Vec<_>>();
+    worlds.push((
+        format!("chain {}", CHAIN_LENGTH),
+        colony_chain(CHAIN_LENGTH),
+    ));
+    worlds.push((
+        format!("crowded {}", CROWDED_CLUSTER_SIZE),
+        crowded_cluster(CROWDED_CLUSTER_SIZE, 0),
+    ));
+    worlds
+}
+
+fn bench_tick(c: &mut Criterion) {
+    let mut group = c.benchmark_group("World::tick");
+    configure_for_big_worlds(&mut group);
+    for (label, mut world) in bench_worlds() {
+        group.bench_function(BenchmarkId::from_parameter(label), |b| {
+            b.iter(|| world.tick())
+        });
+    }
+    group.finish();
+}
+
+fn bench_find_pair_overlaps(c: &mut Criterion) {
+    let mut group = c.benchmark_group("find_pair_overlaps");
+    configure_for_big_worlds(&mut group);
+    for (label, mut world) in bench_worlds() {
+        world.tick();
+        let (cell_graph, circle_handles) = world.cell_graph_and_handles_mut();
+        group.bench_function(BenchmarkId::from_parameter(label), |b| {
+            b.iter(|| find_pair_overlaps(cell_graph, circle_handles))
+        });
+    }
+    group.finish();
+}
+
+// Only over the worlds with bonds. Repeated applications keep adding to the cells' net
+// forces, which costs the same as setting them.
+fn bench_bond_forces(c: &mut Criterion) {
+    let mut group = c.benchmark_group("BondForces::apply_to");
+    configure_for_big_worlds(&mut group);
+    let bond_forces = BondForces::new();
+    for (label, mut world) in bench_worlds() {
+        if world.bonds().is_empty() {
+            continue;
+        }
+        world.tick();
+        let (cell_graph, circle_handles) = world.cell_graph_and_handles_mut();
+        group.bench_function(BenchmarkId::from_parameter(label), |b| {
+            b.iter(|| bond_forces.apply_to(cell_graph, circle_handles))
+        });
+    }
+    group.finish();
+}
+
+// Fewer samples, so that a 10,000-cell tick doesn't take minutes to measure.
+fn configure_for_big_worlds(
+    group: &mut criterion::BenchmarkGroup<criterion::measurement::WallTime>,
+) {
+    group.sample_size(20);
+    group.measurement_time(Duration::from_secs(10));
+}
+
+criterion_group!(
+    benches,
+    bench_tick,
+    bench_find_pair_overlaps,
+    bench_bond_forces
+);
+criterion_main!(benches);
@@ -150,6 +150,22 @@ pub fn newtonian_body_derive(input: TokenStream) -> TokenStream {
                     self.#field_name.kick(impulse);
                 }
 
+                fn angle(&self) -> Angle {
+                    self.#field_name.angle()
+                }
+
+                fn angular_velocity(&self) -> AngularVelocity {
+                    self.#field_name.angular_velocity()
+                }
+
+                fn moment_of_inertia(&self) -> MomentOfInertia {
+                    self.#field_name.moment_of_inertia()
+                }
+
+                fn spin(&mut self, angular_impulse: AngularImpulse) {
+                    self.#field_name.spin(angular_impulse);
+                }
+
                 fn net_force(&self) -> &NetForce {
                     self.#field_name.net_force()
                 }
@@ -0,0 +1,132 @@
+use crate::biology::cell::Cell;
+use crate::biology::control::*;
+use crate::biology::genome::*;
+use crate::biology::layers::*;
+use crate::environment::influences::*;
+use crate::physics::quantities::*;
+use crate::world::World;
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg64Mcg;
+
+const PHOTO_LAYER_INDEX: usize = 0;
+const BONDING_LAYER_INDEX: usize = 1;
+// a little more than a cell's diameter, so that jittered neighbors often overlap but
+// the cells aren't packed tightly enough to blow the collision forces up
+const CELL_SPACING: Value1D = 5.5;
+
+// A world for timing the simulation that is the same from version to version and
+// machine to machine, so that ticks per second can be compared across changes and
+// hardware: photosynthetic cells packed on a jittered grid, tightly enough that many
+// neighbors overlap, with half of them bonded in pairs and each run by a small neural
+// net. Change it only knowing that it makes old timings incomparable.
+pub fn dense_world(cell_count: usize, seed: u64) -> World {
+    let mut rng = Pcg64Mcg::seed_from_u64(seed);
+    // even, so that each bonded pair is in one row
+    let columns = ((cell_count as Value1D).sqrt().ceil() as usize).div_ceil(2) * 2;
+    let rows = cell_count.div_ceil(columns.max(1));
+    let max_corner = Position::new(
+        columns as Value1D * CELL_SPACING,
+        rows.max(1) as Value1D * CELL_SPACING,
+    );
+    let cells = (0..cell_count)
+        .map(|index| {
+            let grid_position = Position::new(
+                ((index % columns) as Value1D + 0.5) * CELL_SPACING,
+                ((index / columns) as Value1D + 0.5) * CELL_SPACING,
+            );
+            let jitter = Displacement::new(rng.gen_range(-0.5, 0.5), rng.gen_range(-0.5, 0.5));
+            dense_world_cell(grid_position + jitter, seed.wrapping_add(index as u64))
+        })
+        .collect();
+    World::new(Position::ORIGIN, max_corner)
+        .with_standard_influences()
+        .with_sunlight(0.0, 1.0)
+        .with_per_cell_influence(Box::new(SimpleForceInfluence::new(Box::new(
+            DragForce::new(0.01),
+        ))))
+        .with_cells(cells)
+        .with_bonds(
+            (0..cell_count / 2)
+                .map(|pair| (2 * pair, 2 * pair + 1))
+                .collect(),
+        )
+}
+
+fn dense_world_cell(position: Position, seed: u64) -> Cell {
+    Cell::new(
+        position,
+        Velocity::ZERO,
+        vec![
+            CellLayer::new(
+                Area::new(10.0),
+                Density::new(1.0),
+                Tissue::Photosynthetic,
+                Box::new(PhotoCellLayerSpecialty::new(Fraction::new(0.1))),
+            ),
+            CellLayer::new(
+                Area::new(5.0),
+                Density::new(1.0),
+                Tissue::Bonding,
+                Box::new(BondingCellLayerSpecialty::new()),
+            ),
+            CellLayer::new(
+                Area::new(5.0),
+                Density::new(1.0),
+                Tissue::CellWall,
+                Box::new(NullCellLayerSpecialty::new()),
+            ),
+        ],
+    )
+    .with_control(Box::new(dense_world_control(seed)))
+    .with_initial_energy(BioEnergy::new(50.0))
+}
+
+// Keeps the photo layer near its starting size and healthy, and keeps the bonds.
+fn dense_world_control(seed: u64) -> NeuralNetControl {
+    let mut builder = NeuralNetControlBuilder::new(TransferFn::IDENTITY);
+    let area_index = builder.add_input_node("<photo area", |cell_state| {
+        cell_state.layers[PHOTO_LAYER_INDEX].area.value()
+    });
+    let health_index = builder.add_input_node("<photo health", |cell_state| {
+        cell_state.layers[PHOTO_LAYER_INDEX].health.value()
+    });
+    builder.add_output_node(">photo resize", &[(area_index, -0.1)], 1.0, |value| {
+        CellLayer::resize_request(PHOTO_LAYER_INDEX, AreaDelta::new(value))
+    });
+    builder.add_output_node(">photo healing", &[(health_index, -1.0)], 1.0, |value| {
+        CellLayer::healing_request(PHOTO_LAYER_INDEX, HealthDelta::new(value.max(0.0)))
+    });
+    for bond_index in 0..2 {
+        builder.add_output_node(">retain", &[], 1.0, move |value| {
+            BondingCellLayerSpecialty::retain_bond_request(
+                BONDING_LAYER_INDEX,
+                bond_index,
+                value > 0.0,
+            )
+        });
+    }
+    builder.build(SeededMutationRandomness::new(
+        seed,
+        &MutationParameters::NO_MUTATION,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dense_world_ticks_with_phase_times() {
+        let mut world = dense_world(10, 0).with_phase_timing();
+
+        world.tick();
+        world.tick();
+
+        assert_eq!(world.cells().len(), 10);
+        assert_eq!(world.bonds().len(), 5);
+        let phase_times = world.phase_times().unwrap();
+        assert_eq!(phase_times.ticks(), 2);
+        assert_eq!(phase_times.phases()[0].0, "cross-cell influences");
+        assert_eq!(phase_times.phases().len(), 6);
+    }
+}
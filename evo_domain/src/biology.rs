@@ -6,3 +6,5 @@ pub mod control;
 pub mod control_requests;
 pub mod genome;
 pub mod layers;
+pub mod phylogeny;
+pub mod template;
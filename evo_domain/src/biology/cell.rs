@@ -1,7 +1,10 @@
 use crate::biology::changes::*;
 use crate::biology::control::*;
 use crate::biology::control_requests::*;
+use crate::biology::genome::SparseNeuralNetGenome;
 use crate::biology::layers::*;
+use crate::biology::template::CellTemplate;
+use crate::energy_audit::EnergyAudit;
 use crate::environment::local_environment::*;
 use crate::physics::handles::*;
 use crate::physics::newtonian::*;
@@ -9,11 +12,15 @@ use crate::physics::node_graph::*;
 use crate::physics::overlap::Overlap;
 use crate::physics::quantities::*;
 use crate::physics::shapes::*;
+use crate::state_hash::StateHasher;
+use crate::world_state::CellState;
+use crate::{Parameters, UnitScales};
 use evo_domain_derive::*;
 use std::f64::consts::PI;
 use std::io;
 use std::io::{Result, StdoutLock, Write};
 use std::ptr;
+use std::time;
 use std::usize;
 
 #[allow(clippy::vec_box)]
@@ -28,7 +35,36 @@ pub struct Cell {
     energy: BioEnergy,
     received_donated_energy: BioEnergy,
     thrust: Force,
+    thrust_offset: Value1D,
+    grip: Value1D,
+    anchor: Option<Position>,
+    // whether the cell has stopped asking for an anchor since its last one broke, as it
+    // must before it can anchor again
+    anchor_released: bool,
+    // bit per bond slot: empty slots the cell asked in its last tick to bond in
+    open_bond_slots: u8,
+    // bit per bond slot: bonds strained past breaking this tick
+    snapped_bonds: u8,
+    last_net_force: NetForce,
     selected: bool,
+    // whether to record the individual forces even when not selected
+    records_force_additions: bool,
+    ticks_lived: u64,
+    offspring_count: u32,
+    peak_energy: BioEnergy,
+    energy_gathered: BioEnergy,
+    last_control_time: time::Duration,
+    // control requests in the last tick that the layers allowed less of than requested
+    capped_request_count: u32,
+    // control requests in the last tick with values that weren't finite, and so were
+    // ignored
+    dropped_request_count: u32,
+    last_nutrient_uptake: Value1D,
+    bites: Vec<Bite>,
+    // None unless the world is auditing energy
+    last_energy_changes: Option<Vec<EnergyChange>>,
+    cell_id: u64,
+    lineage_id: u64,
 }
 
 impl Cell {
@@ -41,14 +77,35 @@ impl Cell {
         Cell {
             graph_node_data: GraphNodeData::new(),
             radius,
-            newtonian_state: NewtonianState::new(Self::calc_mass(&layers), position, velocity),
+            newtonian_state: NewtonianState::new(Self::calc_mass(&layers), position, velocity)
+                .with_moment_of_inertia(Self::calc_moment_of_inertia(&layers)),
             environment: LocalEnvironment::new(),
             layers,
             control: Box::new(NullControl::new()),
             energy: BioEnergy::ZERO,
             received_donated_energy: BioEnergy::ZERO,
             thrust: Force::ZERO,
+            thrust_offset: 0.0,
+            grip: 0.0,
+            anchor: None,
+            anchor_released: true,
+            open_bond_slots: 0,
+            snapped_bonds: 0,
+            last_net_force: NetForce::ZERO,
             selected: false,
+            records_force_additions: false,
+            ticks_lived: 0,
+            offspring_count: 0,
+            peak_energy: BioEnergy::ZERO,
+            energy_gathered: BioEnergy::ZERO,
+            last_control_time: time::Duration::ZERO,
+            capped_request_count: 0,
+            dropped_request_count: 0,
+            last_nutrient_uptake: 0.0,
+            bites: vec![],
+            last_energy_changes: None,
+            cell_id: 0,
+            lineage_id: 0,
         }
     }
 
@@ -76,7 +133,7 @@ impl Cell {
     }
 
     pub fn with_initial_position(mut self, position: Position) -> Self {
-        self.newtonian_state.position = position;
+        self.newtonian_state.set_position(position);
         self
     }
 
@@ -99,14 +156,35 @@ impl Cell {
                 Self::calc_mass(&layers),
                 Position::ORIGIN,
                 Velocity::ZERO,
-            ),
+            )
+            .with_moment_of_inertia(Self::calc_moment_of_inertia(&layers)),
             environment: LocalEnvironment::new(),
             layers,
             control: self.control.spawn(),
             energy: BioEnergy::ZERO,
             received_donated_energy: BioEnergy::ZERO,
             thrust: Force::ZERO,
+            thrust_offset: 0.0,
+            grip: 0.0,
+            anchor: None,
+            anchor_released: true,
+            open_bond_slots: 0,
+            snapped_bonds: 0,
+            last_net_force: NetForce::ZERO,
             selected: false,
+            records_force_additions: false,
+            ticks_lived: 0,
+            offspring_count: 0,
+            peak_energy: BioEnergy::ZERO,
+            energy_gathered: BioEnergy::ZERO,
+            last_control_time: time::Duration::ZERO,
+            capped_request_count: 0,
+            dropped_request_count: 0,
+            last_nutrient_uptake: 0.0,
+            bites: vec![],
+            last_energy_changes: None,
+            cell_id: 0,
+            lineage_id: 0,
         }
     }
 
@@ -118,6 +196,48 @@ impl Cell {
         self.energy
     }
 
+    pub fn ticks_lived(&self) -> u64 {
+        self.ticks_lived
+    }
+
+    // Unique within a world, from 1 up, or 0 if the cell isn't in a world yet.
+    pub fn cell_id(&self) -> u64 {
+        self.cell_id
+    }
+
+    // The cell ID of the founder this cell descends from, which may be itself.
+    pub fn lineage_id(&self) -> u64 {
+        self.lineage_id
+    }
+
+    // Called by World when the cell is added. A cell with no lineage yet is a founder.
+    pub fn set_cell_id(&mut self, cell_id: u64) {
+        self.cell_id = cell_id;
+        if self.lineage_id == 0 {
+            self.lineage_id = cell_id;
+        }
+    }
+
+    pub fn offspring_count(&self) -> u32 {
+        self.offspring_count
+    }
+
+    // the most energy the cell has held at the end of any tick
+    pub fn peak_energy(&self) -> BioEnergy {
+        self.peak_energy
+    }
+
+    // all the energy the cell has taken in over its life, from light, nutrients, and
+    // bites, but not from donations or from its parent
+    pub fn energy_gathered(&self) -> BioEnergy {
+        self.energy_gathered
+    }
+
+    pub fn add_gathered_energy(&mut self, energy: BioEnergy) {
+        self.add_energy(energy);
+        self.energy_gathered += energy;
+    }
+
     pub fn add_received_donated_energy(&mut self, energy: BioEnergy) {
         self.add_energy(energy);
         self.received_donated_energy += energy;
@@ -127,10 +247,139 @@ impl Cell {
         self.energy += energy;
     }
 
+    // Loses this fraction of the cell's energy, e.g. as a penalty.
+    pub fn lose_energy_fraction(&mut self, fraction: Fraction) {
+        self.energy = BioEnergy::new(self.energy.value() - self.energy.value() * fraction);
+    }
+
+    // How long the cell's control took to run in its last tick, always zero without the
+    // "timing" feature.
+    pub fn last_control_time(&self) -> time::Duration {
+        self.last_control_time
+    }
+
+    pub fn capped_request_count(&self) -> u32 {
+        self.capped_request_count
+    }
+
+    pub fn dropped_request_count(&self) -> u32 {
+        self.dropped_request_count
+    }
+
+    // How much nutrient the cell absorbed from the chemical field in its last tick.
+    pub fn last_nutrient_uptake(&self) -> Value1D {
+        self.last_nutrient_uptake
+    }
+
+    // What the cell's eater layers bit in its last tick.
+    pub fn bites(&self) -> &[Bite] {
+        &self.bites
+    }
+
+    // Loses up to the energy, and returns how much was lost, and damages the outer layer.
+    pub fn take_bite(&mut self, energy: BioEnergy, damage: HealthDelta) -> BioEnergy {
+        let taken = BioEnergy::new(energy.value().min(self.energy.value()));
+        self.energy = BioEnergy::new(self.energy.value() - taken.value());
+        if let Some(outer_layer) = self.layers.last_mut() {
+            outer_layer.damage(damage);
+        }
+        taken
+    }
+
+    pub fn grip(&self) -> Value1D {
+        self.grip
+    }
+
+    // The point on the bottom wall the cell is tied to, if any.
+    pub fn anchor(&self) -> Option<Position> {
+        self.anchor
+    }
+
+    pub fn break_anchor(&mut self) {
+        self.anchor = None;
+        self.anchor_released = false;
+    }
+
+    // Whether asking for an anchor this tick would hold one: the cell keeps the anchor it
+    // has, or makes one where it touches the bottom, unless its last one broke and it
+    // hasn't let go since.
+    pub fn can_hold_anchor(&self) -> bool {
+        self.anchor.is_some()
+            || (self.anchor_released && self.environment.is_touching_bottom_wall())
+    }
+
+    // The first empty bond slot the cell asked in its last tick to retain a bond in,
+    // without budding into it, if any. Another cell with an open slot can bond to it.
+    pub fn open_bond_slot(&self) -> Option<usize> {
+        (0..BondRequest::MAX_BONDS)
+            .find(|&index| self.open_bond_slots & (1 << index) != 0 && !self.has_edge(index))
+    }
+
+    fn update_open_bond_slots(&mut self, bond_requests: &BondRequests) {
+        self.open_bond_slots = 0;
+        for (index, request) in bond_requests.iter().enumerate() {
+            if request.retain_bond
+                && request.donation_energy == BioEnergy::ZERO
+                && !self.has_edge(index)
+            {
+                self.open_bond_slots |= 1 << index;
+            }
+        }
+    }
+
+    // Marks the bond for breaking at the end of the tick, even if the cell retains it.
+    pub fn snap_bond(&mut self, index: usize) {
+        self.snapped_bonds |= 1 << index;
+    }
+
+    pub fn is_bond_snapped(&self, index: usize) -> bool {
+        self.snapped_bonds & (1 << index) != 0
+    }
+
+    pub fn clear_snapped_bonds(&mut self) {
+        self.snapped_bonds = 0;
+    }
+
+    fn update_anchor(&mut self, hold_anchor: bool) {
+        if !hold_anchor {
+            self.anchor = None;
+            self.anchor_released = true;
+        } else if self.anchor.is_none() && self.can_hold_anchor() {
+            self.anchor = Some(self.bottom_point());
+        }
+    }
+
+    pub fn bottom_point(&self) -> Position {
+        Position::new(self.center().x(), self.center().y() - self.radius.value())
+    }
+
+    // The forces that moved the cell during its last tick. The individual non-dominant
+    // forces are only recorded while the cell is selected, or if record_force_additions
+    // was called.
+    pub fn last_net_force(&self) -> &NetForce {
+        &self.last_net_force
+    }
+
     pub fn is_intact(&self) -> bool {
         self.layers.last().unwrap().is_intact()
     }
 
+    // The energy changes of the cell's last tick, by kind, if they are being recorded.
+    pub fn last_energy_changes(&self) -> Option<&[EnergyChange]> {
+        self.last_energy_changes.as_deref()
+    }
+
+    pub fn record_energy_changes(&mut self) {
+        if self.last_energy_changes.is_none() {
+            self.last_energy_changes = Some(vec![]);
+        }
+    }
+
+    pub fn record_force_additions(&mut self) {
+        self.records_force_additions = true;
+        self.net_force_mut().start_recording_force_additions();
+    }
+
     pub fn is_selected(&self) -> bool {
         self.selected
     }
@@ -140,21 +389,200 @@ impl Cell {
         if is_selected {
             self.control.print(self.node_handle());
             self.net_force_mut().start_recording_force_additions();
-        } else {
+        } else if !self.records_force_additions {
             self.net_force_mut().stop_recording_force_additions();
         }
     }
 
+    pub fn genome(&self) -> Option<&SparseNeuralNetGenome> {
+        self.control.genome()
+    }
+
+    pub fn neural_net_snapshot(&self) -> Option<NeuralNetSnapshot> {
+        self.control.neural_net_snapshot()
+    }
+
+    pub fn set_genome(&mut self, genome: SparseNeuralNetGenome) -> std::result::Result<(), String> {
+        self.control.set_genome(genome)
+    }
+
+    // the (mean, variance) each normalized control input has adapted to
+    pub fn input_statistics(&self) -> Vec<(Value1D, Value1D)> {
+        self.control.input_statistics()
+    }
+
+    // Restores the energy, layer areas and health, and genome of a cell saved from a run
+    // of the same program, and what its control inputs had adapted to.
+    pub fn apply_template(&mut self, template: &CellTemplate) -> std::result::Result<(), String> {
+        template.check_matches(self)?;
+        if let (Some(genome), Some(coefficients)) =
+            (self.control.genome(), &template.genome_coefficients)
+        {
+            let genome = genome
+                .with_coefficients(coefficients)?
+                .with_transfer_fns(&template.genome_transfer_fns)?;
+            self.control.set_genome(genome)?;
+        }
+        self.control
+            .set_input_statistics(&template.input_statistics)?;
+        let mut layers = self
+            .layers
+            .iter()
+            .zip(&template.layers)
+            .map(|(layer, layer_template)| {
+                layer
+                    .spawn(layer_template.area)
+                    .with_health(layer_template.health)
+            })
+            .collect();
+        self.radius = Self::update_layer_outer_radii(&mut layers);
+        self.newtonian_state =
+            NewtonianState::new(Self::calc_mass(&layers), self.position(), self.velocity())
+                .with_moment_of_inertia(Self::calc_moment_of_inertia(&layers));
+        self.layers = layers;
+        self.set_initial_energy(template.energy);
+        Ok(())
+    }
+
+    pub fn save_state(&self) -> CellState {
+        CellState {
+            cell_id: self.cell_id,
+            lineage_id: self.lineage_id,
+            position: [self.center().x(), self.center().y()],
+            velocity: [self.velocity().x(), self.velocity().y()],
+            angle: self.newtonian_state.unnormalized_angle(),
+            angular_velocity: self.angular_velocity().radians(),
+            energy: self.energy.value(),
+            peak_energy: self.peak_energy.value(),
+            energy_gathered: self.energy_gathered.value(),
+            received_donated_energy: self.received_donated_energy.value(),
+            thrust: [self.thrust.x(), self.thrust.y()],
+            thrust_offset: self.thrust_offset,
+            grip: self.grip,
+            anchor: self.anchor.map(|anchor| [anchor.x(), anchor.y()]),
+            anchor_released: self.anchor_released,
+            open_bond_slots: self.open_bond_slots,
+            ticks_lived: self.ticks_lived,
+            offspring_count: self.offspring_count,
+            layers: self.layers.iter().map(CellLayer::save_state).collect(),
+            genome: self.control.genome().cloned(),
+            input_statistics: self.control.input_statistics(),
+        }
+    }
+
+    // Restores a state saved from this cell, or from the same cell in a world built by
+    // the same program. See WorldState.
+    pub fn load_state(&mut self, state: &CellState) -> std::result::Result<(), String> {
+        if state.layers.len() != self.layers.len() {
+            return Err(format!(
+                "Cell {} has {} layers, not {}",
+                self.cell_id,
+                self.layers.len(),
+                state.layers.len()
+            ));
+        }
+        if state.genome.is_some() != self.control.genome().is_some() {
+            return Err(format!(
+                "Cell {} genome doesn't match its control",
+                self.cell_id
+            ));
+        }
+        if ![
+            state.energy,
+            state.peak_energy,
+            state.energy_gathered,
+            state.received_donated_energy,
+        ]
+        .iter()
+        .all(|&energy| energy >= 0.0)
+        {
+            return Err(format!(
+                "Cell {} energies must be nonnegative",
+                self.cell_id
+            ));
+        }
+        if let Some(genome) = &state.genome {
+            self.control.set_genome(genome.clone())?;
+        }
+        self.control.set_input_statistics(&state.input_statistics)?;
+        for (layer, layer_state) in self.layers.iter_mut().zip(&state.layers) {
+            layer.load_state(layer_state)?;
+        }
+        self.radius = self.layers.last().unwrap().outer_radius();
+        self.newtonian_state.mass = Self::calc_mass(&self.layers);
+        self.newtonian_state.moment_of_inertia = Self::calc_moment_of_inertia(&self.layers);
+        self.newtonian_state
+            .set_position(Position::new(state.position[0], state.position[1]));
+        self.newtonian_state
+            .set_velocity(Velocity::new(state.velocity[0], state.velocity[1]));
+        self.newtonian_state.set_unnormalized_angle(state.angle);
+        self.newtonian_state
+            .set_angular_velocity(AngularVelocity::new(state.angular_velocity));
+        self.lineage_id = state.lineage_id;
+        self.energy = BioEnergy::new(state.energy);
+        self.peak_energy = BioEnergy::new(state.peak_energy);
+        self.energy_gathered = BioEnergy::new(state.energy_gathered);
+        self.received_donated_energy = BioEnergy::new(state.received_donated_energy);
+        self.thrust = Force::new(state.thrust[0], state.thrust[1]);
+        self.thrust_offset = state.thrust_offset;
+        self.grip = state.grip;
+        self.anchor = state
+            .anchor
+            .map(|anchor| Position::new(anchor[0], anchor[1]));
+        self.anchor_released = state.anchor_released;
+        self.open_bond_slots = state.open_bond_slots;
+        self.ticks_lived = state.ticks_lived;
+        self.offspring_count = state.offspring_count;
+        Ok(())
+    }
+
+    pub fn hash_state(&self, hasher: &mut StateHasher) {
+        hasher.write_u64(self.cell_id);
+        hasher.write_f64(self.center().x());
+        hasher.write_f64(self.center().y());
+        hasher.write_f64(self.velocity().x());
+        hasher.write_f64(self.velocity().y());
+        hasher.write_f64(self.angle().radians());
+        hasher.write_f64(self.angular_velocity().radians());
+        hasher.write_f64(self.energy.value());
+        for layer in &self.layers {
+            hasher.write_f64(layer.area().value());
+            hasher.write_f64(layer.health().value());
+        }
+        hasher.write_u64(self.control.random_draws());
+    }
+
+    pub fn print_genome_table(&self) {
+        self.control.print_table(self.node_handle());
+    }
+
     pub fn set_initial_position(&mut self, position: Position) {
-        self.newtonian_state.position = position;
+        self.newtonian_state.set_position(position);
     }
 
     pub fn set_initial_velocity(&mut self, velocity: Velocity) {
-        self.newtonian_state.velocity = velocity;
+        self.newtonian_state.set_velocity(velocity);
+    }
+
+    // Kicks the cell during its next tick, along with the other forces on it, so that the
+    // kick is among the forces recorded for a selected cell.
+    pub fn apply_impulse(&mut self, impulse: Impulse) {
+        // the force that delivers the impulse over one tick
+        self.net_force_mut()
+            .add_non_dominant_force(Force::new(impulse.x(), impulse.y()), "impulse");
+    }
+
+    // Applies the impulse that brings the cell to the velocity, before the other forces
+    // on it during its next tick.
+    pub fn set_velocity(&mut self, velocity: Velocity) {
+        let delta_v = velocity - self.velocity();
+        let mass = self.mass().value();
+        self.apply_impulse(Impulse::new(mass * delta_v.x(), mass * delta_v.y()));
     }
 
     pub fn set_initial_energy(&mut self, energy: BioEnergy) {
         self.energy = energy;
+        self.peak_energy = energy;
     }
 
     pub fn overlaps(&self, pos: Position) -> bool {
@@ -162,35 +590,110 @@ impl Cell {
     }
 
     pub fn tick(&mut self) -> BondRequests {
+        self.tick_with_parameters(&Parameters::DEFAULT)
+    }
+
+    // Of the parameters, only the genome weight upkeep affects the cell. The units only
+    // scale the numbers printed for a selected cell.
+    pub fn tick_with_parameters(&mut self, parameters: &Parameters) -> BondRequests {
         let start_snapshot = self.get_state_snapshot();
-        let mut changes = CellChanges::new(self.layers.len(), self.is_selected());
+        let mut changes = CellChanges::new(
+            self.layers.len(),
+            self.is_selected() || self.last_energy_changes.is_some(),
+        );
         self.calculate_automatic_changes(&mut changes);
+        self.pay_genome_upkeep(parameters.genome_weight_upkeep, &mut changes);
         self.calculate_requested_changes(&mut changes);
+        self.record_biomass_changes(&mut changes);
         self.apply_changes(&changes);
-        self.print_tick_info(&start_snapshot, &changes).unwrap();
+        self.ticks_lived += 1;
+        if self.energy > self.peak_energy {
+            self.peak_energy = self.energy;
+        }
+        self.print_tick_info(&start_snapshot, &changes, &parameters.units)
+            .unwrap();
+        if self.last_energy_changes.is_some() {
+            self.last_energy_changes = changes.energy_changes.take();
+        }
         self.clear_environment();
+        self.update_open_bond_slots(&changes.bond_requests);
         changes.bond_requests
     }
 
+    // Records, for the energy audit, the energy equivalent of the mass that mass-energy
+    // coupled layers are about to gain or shed, which is not a change to the cell's energy.
+    fn record_biomass_changes(&self, changes: &mut CellChanges) {
+        if let Some(energy_changes) = &mut changes.energy_changes {
+            for (index, layer) in self.layers.iter().enumerate() {
+                if let Some(energy_delta) = layer.biomass_energy_delta(&changes.layers[index]) {
+                    energy_changes.push(EnergyChange {
+                        energy_delta,
+                        label: EnergyAudit::BIOMASS,
+                        index,
+                    });
+                }
+            }
+        }
+    }
+
     pub fn calculate_automatic_changes(&mut self, changes: &mut CellChanges) {
         for (index, layer) in self.layers.iter_mut().enumerate() {
             layer.calculate_automatic_changes(&self.environment, changes, index);
+            layer.calculate_aging_damage(self.ticks_lived, changes, index);
+        }
+        let net_force = self.newtonian_state.net_force_mut();
+        net_force.add_non_dominant_force(self.thrust, "thrust");
+        // the thrust's offset is at right angles to it
+        net_force.add_torque(Torque::new(
+            self.thrust_offset * self.thrust.value().length(),
+        ));
+    }
+
+    // The upkeep for each nonzero weight of the cell's neural net, or as much of it as
+    // the cell has energy for, comes out of the energy its requests are budgeted from.
+    fn pay_genome_upkeep(&self, upkeep_per_weight: BioEnergy, changes: &mut CellChanges) {
+        if upkeep_per_weight == BioEnergy::ZERO {
+            return;
+        }
+        let weight_count = self
+            .genome()
+            .map_or(0, |genome| genome.num_nonzero_weights());
+        let upkeep = BioEnergy::new(
+            (upkeep_per_weight.value() * weight_count as Value1D).min(self.energy.value()),
+        );
+        if upkeep != BioEnergy::ZERO {
+            changes.genome_upkeep = upkeep;
+            changes.add_energy_change(-BioEnergyDelta::from(upkeep), "genome upkeep", usize::MAX);
         }
-        self.newtonian_state
-            .net_force_mut()
-            .add_non_dominant_force(self.thrust, "thrust");
     }
 
     pub fn calculate_requested_changes(&mut self, changes: &mut CellChanges) {
-        let budgeted_control_requests = self.get_budgeted_control_requests();
+        let budgeted_control_requests = self.get_budgeted_control_requests(changes.genome_upkeep);
+        changes.can_anchor = self.can_hold_anchor();
         self.execute_control_requests(&budgeted_control_requests, changes);
     }
 
-    fn get_budgeted_control_requests(&mut self) -> Vec<BudgetedControlRequest> {
+    fn get_budgeted_control_requests(
+        &mut self,
+        genome_upkeep: BioEnergy,
+    ) -> Vec<BudgetedControlRequest> {
         let cell_state = self.get_state_snapshot();
-        let control_requests = self.control.run(&cell_state);
+        #[cfg(feature = "timing")]
+        let start = time::Instant::now();
+        let mut control_requests = self.control.run(&cell_state);
+        #[cfg(feature = "timing")]
+        {
+            self.last_control_time = start.elapsed();
+        }
+        let request_count = control_requests.len();
+        control_requests.retain(|request| request.requested_value().is_finite());
+        self.dropped_request_count = (request_count - control_requests.len()) as u32;
         let costed_requests = self.cost_control_requests(&control_requests);
-        Self::budget_control_requests(self.energy, &costed_requests)
+        self.capped_request_count = costed_requests
+            .iter()
+            .filter(|request| request.allowed_value() != request.requested_value())
+            .count() as u32;
+        Self::budget_control_requests(self.energy - genome_upkeep, &costed_requests)
     }
 
     fn get_state_snapshot(&self) -> CellStateSnapshot {
@@ -200,10 +703,17 @@ impl Cell {
             mass: self.mass(),
             center: self.center(),
             velocity: self.velocity(),
+            angle: self.angle(),
+            angular_velocity: self.angular_velocity(),
             net_force: self.newtonian_state.net_force.net_force(),
             energy: self.energy(),
+            age: self.ticks_lived,
             bond_0_exists: self.has_edge(0),
+            bond_strains: *self.environment.bond_strains(),
             touches: sense_touches(self.environment.overlaps()),
+            cloud_concentration: self.environment.cloud_concentration(),
+            light_intensity: self.environment.light_intensity(),
+            light_gradient: self.environment.light_gradient(),
             layers: self.get_layer_state_snapshots(),
         }
     }
@@ -214,6 +724,7 @@ impl Cell {
             result.push(CellLayerStateSnapshot {
                 area: layer.area(),
                 mass: layer.mass(),
+                density: layer.density(),
                 health: layer.health(),
             });
         }
@@ -285,6 +796,8 @@ impl Cell {
 
     fn clear_environment(&mut self) {
         self.environment_mut().clear();
+        self.last_net_force
+            .clone_from(self.newtonian_state.net_force());
         self.net_force_mut().clear();
         self.received_donated_energy = BioEnergy::ZERO;
     }
@@ -293,15 +806,16 @@ impl Cell {
         &self,
         start_snapshot: &CellStateSnapshot,
         changes: &CellChanges,
+        units: &UnitScales,
     ) -> Result<()> {
-        if self.is_selected() {
+        if cfg!(feature = "console") && self.is_selected() {
             let stdout = io::stdout();
             let mut out = stdout.lock();
 
             self.print_id_info(&mut out)?;
             self.print_force_info(&mut out)?;
-            self.print_other_quantities_info(&mut out, start_snapshot)?;
-            self.print_energy_info(&mut out, start_snapshot, changes)?;
+            self.print_other_quantities_info(&mut out, start_snapshot, units)?;
+            self.print_energy_info(&mut out, start_snapshot, changes, units)?;
             self.print_layers_info(&mut out, start_snapshot, changes)?;
             Cell::print_bond_request_info(&mut out, changes)?;
         }
@@ -353,6 +867,7 @@ impl Cell {
         &self,
         out: &mut StdoutLock,
         start_snapshot: &CellStateSnapshot,
+        units: &UnitScales,
     ) -> Result<()> {
         writeln_value2d_change_info(
             out,
@@ -369,8 +884,8 @@ impl Cell {
         writeln_value1d_change_info(
             out,
             "  mass",
-            start_snapshot.mass.value(),
-            self.mass().value(),
+            start_snapshot.mass.value() * units.mass_display_factor,
+            self.mass().value() * units.mass_display_factor,
         )?;
         writeln_value1d_change_info(
             out,
@@ -386,17 +901,19 @@ impl Cell {
         out: &mut StdoutLock,
         start_snapshot: &CellStateSnapshot,
         changes: &CellChanges,
+        units: &UnitScales,
     ) -> Result<()> {
+        let factor = units.energy_display_factor;
         writeln_value1d_change_info(
             out,
             "  energy",
-            start_snapshot.energy.value() - self.received_donated_energy.value(),
-            self.energy().value(),
+            (start_snapshot.energy.value() - self.received_donated_energy.value()) * factor,
+            self.energy().value() * factor,
         )?;
         writeln!(
             out,
             "    received {:+.4}",
-            self.received_donated_energy.value()
+            self.received_donated_energy.value() * factor
         )?;
         if let Some(energy_changes) = &changes.energy_changes {
             for energy_change in energy_changes {
@@ -409,7 +926,7 @@ impl Cell {
                     } else {
                         "".to_string()
                     },
-                    energy_change.energy_delta.value()
+                    energy_change.energy_delta.value() * factor
                 )?;
             }
         }
@@ -454,6 +971,8 @@ impl Cell {
         child.set_initial_velocity(self.velocity());
         child.set_initial_energy(initial_energy);
         child.set_selected(self.is_selected());
+        child.lineage_id = self.lineage_id;
+        self.offspring_count += 1;
         child
     }
 
@@ -473,15 +992,56 @@ impl Cell {
             .fold(Mass::new(0.0), |mass, layer| mass + layer.mass())
     }
 
+    // Each layer is a uniform ring around the ones inside it.
+    fn calc_moment_of_inertia(layers: &[CellLayer]) -> MomentOfInertia {
+        layers
+            .iter()
+            .fold(
+                (MomentOfInertia::ZERO, Length::ZERO),
+                |(moment, inner_radius), layer| {
+                    (
+                        moment
+                            + MomentOfInertia::of_annulus(
+                                layer.mass(),
+                                inner_radius,
+                                layer.outer_radius(),
+                            ),
+                        layer.outer_radius(),
+                    )
+                },
+            )
+            .0
+    }
+
     pub fn apply_changes(&mut self, changes: &CellChanges) {
         self.move_from_forces();
         self.energy += changes.energy;
+        self.energy_gathered += changes.energy_gathered;
+        self.last_nutrient_uptake = changes.nutrient_uptake;
+        self.bites.clone_from(&changes.bites);
         self.thrust = changes.thrust;
+        self.thrust_offset = changes.thrust_offset;
+        self.grip = changes.grip;
+        self.update_anchor(changes.anchor);
+        let mut layers_changed = false;
         for (index, layer) in self.layers.iter_mut().enumerate() {
             layer.apply_changes(&changes.layers[index]);
+            layers_changed |= changes.layers[index].area != AreaDelta::ZERO
+                || changes.layers[index].density != 0.0;
         }
+        // radius and mass depend only on the layer areas and densities, but wall stress
+        // also relaxes over time, so update the radii every tick
+        if layers_changed {
+            self.update_radius_and_mass();
+        } else {
+            Self::update_layer_outer_radii(&mut self.layers);
+        }
+    }
+
+    fn update_radius_and_mass(&mut self) {
         self.radius = Self::update_layer_outer_radii(&mut self.layers);
         self.newtonian_state.mass = Self::calc_mass(&self.layers);
+        self.newtonian_state.moment_of_inertia = Self::calc_moment_of_inertia(&self.layers);
     }
 }
 
@@ -497,7 +1057,7 @@ impl Circle for Cell {
     }
 
     fn center(&self) -> Position {
-        self.newtonian_state.position
+        self.newtonian_state.position()
     }
 }
 
@@ -547,7 +1107,8 @@ pub fn sense_touches(overlaps: &[Overlap]) -> TouchPoints {
 
 fn sense_touch(overlap: &Overlap, touches: &mut TouchPoints) {
     let touch_angle_in_octants = (-overlap.incursion()).to_polar_angle().radians() / (PI / 4.0);
-    let min_touch_point = touch_angle_in_octants.floor() as usize;
+    // an angle just under a full turn can round up to NUM_TOUCH_POINTS octants
+    let min_touch_point = (touch_angle_in_octants.floor() as usize) % NUM_TOUCH_POINTS;
     let max_touch_point = (touch_angle_in_octants.ceil() as usize) % NUM_TOUCH_POINTS;
     touches[min_touch_point] += (1.0 - touch_angle_in_octants.fract()) * overlap.area();
     touches[max_touch_point] += touch_angle_in_octants.fract() * overlap.area();
@@ -556,6 +1117,7 @@ fn sense_touch(overlap: &Overlap, touches: &mut TouchPoints) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::biology::genome::*;
     use crate::physics::overlap::Overlap;
 
     #[test]
@@ -665,6 +1227,28 @@ mod tests {
         assert_eq!(cell.radius(), Length::new(2.0));
     }
 
+    #[test]
+    fn cell_whose_wall_is_stretched_too_fast_bursts() {
+        const WALL_PARAMS: LayerParameters = LayerParameters {
+            max_wall_stress: Positive::unchecked(1.0),
+            ..LayerParameters::DEFAULT
+        };
+        let mut cell = simple_layered_cell(vec![
+            simple_cell_layer(Area::new(PI), Density::new(1.0)),
+            simple_cell_layer(Area::new(0.1), Density::new(1.0)).with_parameters(&WALL_PARAMS),
+        ])
+        .with_control(Box::new(ContinuousResizeControl::new(
+            0,
+            AreaDelta::new(3.0 * PI),
+        )));
+        assert!(cell.is_intact());
+
+        let mut changes = CellChanges::new(cell.layers.len(), false);
+        cell.calculate_requested_changes(&mut changes);
+        cell.apply_changes(&changes);
+        assert!(!cell.is_intact());
+    }
+
     #[test]
     fn applying_cell_changes_updates_cell_mass() {
         let mut cell =
@@ -766,6 +1350,34 @@ mod tests {
         assert_eq!(Force::new(1.0, -1.0), cell.net_force().net_force());
     }
 
+    #[test]
+    fn off_center_thrust_spins_cell() {
+        let mut cell = simple_layered_cell(vec![CellLayer::new(
+            Area::new(PI),
+            Density::new(1.0),
+            Tissue::Photosynthetic,
+            Box::new(ThrusterCellLayerSpecialty::new()),
+        )])
+        .with_control(Box::new(ContinuousRequestsControl::new(vec![
+            ThrusterCellLayerSpecialty::force_x_request(0, 3.0),
+            ThrusterCellLayerSpecialty::force_y_request(0, 4.0),
+            ThrusterCellLayerSpecialty::offset_request(0, 0.5),
+        ])));
+        let mut changes = CellChanges::new(cell.layers.len(), false);
+        cell.calculate_requested_changes(&mut changes);
+        cell.apply_changes(&changes);
+
+        // next tick
+        let mut changes2 = CellChanges::new(cell.layers.len(), false);
+        cell.calculate_automatic_changes(&mut changes2);
+        assert_eq!(Torque::new(2.5), cell.net_force().net_torque());
+        cell.apply_changes(&changes2);
+        // a unit disk of mass pi; allow for the rounding of stored angles under the
+        // "f32-physics" feature
+        assert!((cell.angular_velocity().radians() - 5.0 / PI).abs() < 1e-6);
+        assert!((cell.angle().radians() - 5.0 / PI).abs() < 1e-6);
+    }
+
     #[test]
     fn photo_layer_adds_energy_to_cell() {
         let mut cell = simple_layered_cell(vec![CellLayer::new(
@@ -797,15 +1409,29 @@ mod tests {
             Area::new(PI),
         );
 
-        assert_eq!(
-            child.center(),
-            Position::new(
-                cell.center().x() + cell.radius().value() + child.radius().value(),
-                cell.center().y()
-            )
+        // allow for the rounding of stored positions under the "f32-physics" feature
+        let expected_center = Position::new(
+            cell.center().x() + cell.radius().value() + child.radius().value(),
+            cell.center().y(),
         );
+        assert!((child.center() - expected_center).to_polar_radius().value() < 1e-5);
         assert_eq!(child.velocity(), cell.velocity());
         assert_eq!(child.energy(), BioEnergy::new(1.0));
+        assert_eq!(cell.offspring_count(), 1);
+        assert_eq!(child.offspring_count(), 0);
+    }
+
+    #[test]
+    fn tick_tracks_age_and_peak_energy() {
+        let mut cell =
+            simple_layered_cell(vec![simple_cell_layer(Area::new(1.0), Density::new(1.0))]);
+        cell.set_initial_energy(BioEnergy::new(5.0));
+        cell.tick();
+        cell.energy = BioEnergy::new(2.0);
+        cell.tick();
+
+        assert_eq!(cell.ticks_lived(), 2);
+        assert_eq!(cell.peak_energy(), BioEnergy::new(5.0));
     }
 
     #[test]
@@ -967,6 +1593,88 @@ mod tests {
         assert_eq!(BioEnergy::new(5.0), cell.energy());
     }
 
+    #[test]
+    fn genome_upkeep_comes_out_of_request_budget() {
+        const LAYER_PARAMS: LayerParameters = LayerParameters {
+            growth_energy_delta: BioEnergyDelta::new(-1.0),
+            ..LayerParameters::DEFAULT
+        };
+        const PARAMETERS: Parameters = Parameters {
+            genome_weight_upkeep: BioEnergy::unchecked(1.0),
+            ..Parameters::DEFAULT
+        };
+        // two nonzero weights, from inputs that are always zero, and one zero weight
+        let mut builder = NeuralNetControlBuilder::new(TransferFn::IDENTITY);
+        let zero_index1 = builder.add_input_node("zero 1", |_| 0.0);
+        let zero_index2 = builder.add_input_node("zero 2", |_| 0.0);
+        let energy_index = builder.add_input_node("energy", |cell_state| cell_state.energy.value());
+        builder.add_output_node(
+            "grow",
+            &[(zero_index1, 1.0), (zero_index2, -1.0), (energy_index, 0.0)],
+            10.0,
+            |value| CellLayer::resize_request(0, AreaDelta::new(value)),
+        );
+        let control = builder.build(SeededMutationRandomness::new(
+            0,
+            &MutationParameters::NO_MUTATION,
+        ));
+        let mut cell =
+            simple_layered_cell(vec![simple_cell_layer(Area::new(10.0), Density::new(1.0))
+                .with_parameters(&LAYER_PARAMS)])
+            .with_control(Box::new(control))
+            .with_initial_energy(BioEnergy::new(10.0));
+
+        cell.tick_with_parameters(&PARAMETERS);
+
+        assert_eq!(cell.layers()[0].area(), Area::new(18.0));
+        assert_eq!(cell.energy(), BioEnergy::ZERO);
+    }
+
+    #[test]
+    fn cached_radius_and_mass_match_recomputation_after_resizing() {
+        let mut cell = simple_layered_cell(vec![
+            simple_cell_layer(Area::new(10.0), Density::new(1.0)),
+            simple_cell_layer(Area::new(5.0), Density::new(2.0)),
+        ])
+        .with_control(Box::new(ContinuousRequestsControl::new(vec![
+            CellLayer::resize_request(0, AreaDelta::new(-1.0)),
+            CellLayer::resize_request(1, AreaDelta::new(2.0)),
+        ])))
+        .with_initial_energy(BioEnergy::new(100.0));
+
+        for _ in 0..3 {
+            cell.tick();
+            assert_cached_radius_and_mass_are_current(&cell);
+        }
+        assert_ne!(cell.layers()[1].area(), Area::new(5.0));
+    }
+
+    #[test]
+    fn cached_radius_and_mass_match_recomputation_without_resizing() {
+        let mut cell = simple_layered_cell(vec![
+            simple_cell_layer(Area::new(10.0), Density::new(1.0)),
+            simple_cell_layer(Area::new(5.0), Density::new(2.0)),
+        ]);
+
+        for _ in 0..3 {
+            cell.tick();
+            assert_cached_radius_and_mass_are_current(&cell);
+        }
+        assert_eq!(cell.mass(), Mass::new(20.0));
+    }
+
+    fn assert_cached_radius_and_mass_are_current(cell: &Cell) {
+        let mut radius = Length::new(0.0);
+        let mut mass = Mass::new(0.0);
+        for layer in cell.layers() {
+            radius = (radius.sqr() + layer.area() / PI).sqrt();
+            assert_eq!(layer.outer_radius(), radius);
+            mass = mass + layer.area() * layer.density();
+        }
+        assert_eq!(cell.radius(), radius);
+        assert_eq!(cell.mass(), mass);
+    }
+
     #[test]
     fn touch_value_is_overlap_area() {
         let overlaps = vec![Overlap::new(Displacement::new(-1.5, 0.0), 2.0)];
@@ -998,6 +1706,114 @@ mod tests {
         assert_eq!((touches[0] * 10.0).round(), 18.0);
     }
 
+    #[test]
+    fn touch_just_under_full_turn_registers_at_first_sensor() {
+        let overlaps = vec![Overlap::new(Displacement::new(-1.0, 1.0e-17), 1.0)];
+        let touches = sense_touches(&overlaps);
+        assert_eq!(touches[0], 1.0);
+    }
+
+    #[test]
+    fn broken_anchor_is_not_remade_until_released() {
+        let anchor_control = |hold| {
+            Box::new(ContinuousRequestsControl::new(vec![
+                AnchorCellLayerSpecialty::anchor_request(0, hold),
+            ]))
+        };
+        let mut cell = Cell::new(
+            Position::new(0.0, 1.0),
+            Velocity::ZERO,
+            vec![CellLayer::new(
+                Area::new(PI),
+                Density::new(1.0),
+                Tissue::CellWall,
+                Box::new(AnchorCellLayerSpecialty::new()),
+            )],
+        )
+        .with_control(anchor_control(true));
+        let tick_on_bottom = |cell: &mut Cell| {
+            cell.environment_mut()
+                .add_wall_contact_normal(Value2D::new(0.0, 1.0));
+            cell.tick();
+        };
+        tick_on_bottom(&mut cell);
+        assert!(cell.anchor().is_some());
+
+        cell.break_anchor();
+        tick_on_bottom(&mut cell);
+        assert_eq!(cell.anchor(), None);
+
+        cell = cell.with_control(anchor_control(false));
+        tick_on_bottom(&mut cell);
+        cell = cell.with_control(anchor_control(true));
+        tick_on_bottom(&mut cell);
+        assert!(cell.anchor().is_some());
+    }
+
+    #[test]
+    fn apply_template_restores_saved_cell() {
+        let saved_cell = simple_layered_cell(vec![
+            simple_cell_layer(Area::new(2.0), Density::new(1.0)).with_health(Health::new(0.5)),
+            simple_cell_layer(Area::new(3.0), Density::new(1.0)),
+        ])
+        .with_initial_energy(BioEnergy::new(7.0));
+        let template = CellTemplate::from_cell(&saved_cell);
+        let mut cell = simple_layered_cell(vec![
+            simple_cell_layer(Area::new(1.0), Density::new(1.0)),
+            simple_cell_layer(Area::new(1.0), Density::new(1.0)),
+        ]);
+
+        cell.apply_template(&template).unwrap();
+
+        assert_eq!(cell.radius(), saved_cell.radius());
+        assert_eq!(cell.mass(), saved_cell.mass());
+        assert_eq!(cell.energy(), BioEnergy::new(7.0));
+        assert_eq!(cell.layers()[0].health(), Health::new(0.5));
+    }
+
+    #[test]
+    fn apply_template_restores_transfer_fns() {
+        let saved_cell = neural_net_cell(TransferFn::TANH);
+        let template = CellTemplate::from_cell(&saved_cell);
+        let mut cell = neural_net_cell(TransferFn::IDENTITY);
+
+        cell.apply_template(&template).unwrap();
+
+        assert_eq!(
+            cell.genome().unwrap().transfer_fns(),
+            saved_cell.genome().unwrap().transfer_fns()
+        );
+    }
+
+    #[test]
+    fn apply_template_rejects_different_layers() {
+        let template = CellTemplate::from_cell(&simple_layered_cell(vec![simple_cell_layer(
+            Area::new(2.0),
+            Density::new(1.0),
+        )]));
+        let mut cell = simple_layered_cell(vec![
+            simple_cell_layer(Area::new(1.0), Density::new(1.0)),
+            simple_cell_layer(Area::new(1.0), Density::new(1.0)),
+        ]);
+
+        assert!(cell.apply_template(&template).is_err());
+    }
+
+    fn neural_net_cell(hidden_transfer_fn: TransferFn) -> Cell {
+        let mut builder = NeuralNetControlBuilder::new(TransferFn::IDENTITY);
+        let energy_index = builder.add_input_node("energy", |cell_state| cell_state.energy.value());
+        let hidden_index = builder.add_node("hidden", &[(energy_index, 1.0)], 0.0);
+        builder.set_transfer_fn(hidden_index, hidden_transfer_fn);
+        builder.add_output_node("resize", &[(hidden_index, 1.0)], 0.0, |value| {
+            CellLayer::resize_request(0, AreaDelta::new(value))
+        });
+        simple_layered_cell(vec![simple_cell_layer(Area::new(1.0), Density::new(1.0))])
+            .with_control(Box::new(builder.build(SeededMutationRandomness::new(
+                0,
+                &MutationParameters::NO_MUTATION,
+            ))))
+    }
+
     fn simple_layered_cell(layers: Vec<CellLayer>) -> Cell {
         Cell::new(Position::ORIGIN, Velocity::ZERO, layers)
     }
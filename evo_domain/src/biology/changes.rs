@@ -1,4 +1,6 @@
+use crate::biology::cell::Cell;
 use crate::biology::control_requests::*;
+use crate::physics::handles::Handle;
 use crate::physics::quantities::*;
 use std::fmt;
 
@@ -6,9 +8,23 @@ use std::fmt;
 pub struct CellChanges {
     pub energy: BioEnergyDelta,
     pub energy_changes: Option<Vec<EnergyChange>>,
+    // energy taken in from the environment, part of the energy delta
+    pub energy_gathered: BioEnergy,
+    // energy paid for the cell's neural net before its requests are budgeted, part of the
+    // energy delta
+    pub genome_upkeep: BioEnergy,
     pub thrust: Force,
+    // how far the thrust's line of action passes to the right of the cell's center
+    pub thrust_offset: Value1D,
+    pub grip: Value1D,
+    pub anchor: bool,
+    // whether an anchor request would hold an anchor, and so be paid for
+    pub can_anchor: bool,
     pub layers: Vec<CellLayerChanges>,
     pub bond_requests: BondRequests,
+    // nutrient absorbed from the world's chemical field
+    pub nutrient_uptake: Value1D,
+    pub bites: Vec<Bite>,
 }
 
 impl CellChanges {
@@ -16,9 +32,17 @@ impl CellChanges {
         CellChanges {
             energy: BioEnergyDelta::ZERO,
             energy_changes: if selected { Some(vec![]) } else { None },
+            energy_gathered: BioEnergy::ZERO,
+            genome_upkeep: BioEnergy::ZERO,
             thrust: Force::ZERO,
+            thrust_offset: 0.0,
+            grip: 0.0,
+            anchor: false,
+            can_anchor: false,
             layers: vec![CellLayerChanges::new(selected); num_layers],
             bond_requests: NONE_BOND_REQUESTS,
+            nutrient_uptake: 0.0,
+            bites: vec![],
         }
     }
 
@@ -38,6 +62,13 @@ impl CellChanges {
             });
         }
     }
+
+    // Adds energy taken in from light, nutrients, and so on, rather than shifted around
+    // inside the cell.
+    pub fn add_gathered_energy(&mut self, energy: BioEnergy, label: &'static str) {
+        self.energy_gathered += energy;
+        self.add_energy_change(energy.into(), label, usize::MAX);
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -56,6 +87,8 @@ pub struct CellLayerChanges {
     pub area: AreaDelta,
     pub requested_area: AreaDelta,
     pub allowed_area: AreaDelta,
+    // from pumping, e.g. by a float layer
+    pub density: Value1D,
 }
 
 impl CellLayerChanges {
@@ -68,6 +101,7 @@ impl CellLayerChanges {
             area: AreaDelta::ZERO,
             requested_area: AreaDelta::ZERO,
             allowed_area: AreaDelta::ZERO,
+            density: 0.0,
         }
     }
 
@@ -83,6 +117,10 @@ impl CellLayerChanges {
         self.allowed_area = AreaDelta::new(request.allowed_value());
     }
 
+    pub fn add_density_change(&mut self, density_delta: Value1D) {
+        self.density += density_delta;
+    }
+
     pub fn add_decay_resize(&mut self, area_delta: AreaDelta) {
         self.area += area_delta;
     }
@@ -105,11 +143,23 @@ pub struct HealthChange {
     pub label: &'static str,
 }
 
+// What an eater layer tries to take from a cell it overlaps. The world takes it after
+// all cells have ticked, so that a victim can't lose more energy than it has.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bite {
+    pub victim: Handle<Cell>,
+    pub energy: BioEnergy,
+    // to the victim's outer layer
+    pub damage: HealthDelta,
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct BondRequest {
     pub retain_bond: bool,
     pub budding_angle: Angle,
     pub donation_energy: BioEnergy,
+    // paid on top of the donation, and not received by anyone
+    pub donation_tax: BioEnergy,
 }
 
 impl BondRequest {
@@ -119,6 +169,7 @@ impl BondRequest {
         retain_bond: false,
         budding_angle: Angle::ZERO,
         donation_energy: BioEnergy::ZERO,
+        donation_tax: BioEnergy::ZERO,
     };
 
     pub fn reset(&mut self) {
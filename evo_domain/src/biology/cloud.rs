@@ -33,6 +33,11 @@ impl Cloud {
         }
     }
 
+    pub fn with_concentration(mut self, concentration: Fraction) -> Self {
+        self.concentration = concentration;
+        self
+    }
+
     pub fn tick(&mut self, parameters: &CloudParameters) {
         self.radius *= parameters.resize_factor.value();
         self.concentration /= parameters.resize_factor.sqr().value();
@@ -45,6 +50,16 @@ impl Cloud {
     pub fn concentration(&self) -> Fraction {
         self.concentration
     }
+
+    // The concentration a cell with this center and radius senses, scaled by roughly how
+    // much of the cell's width is inside the cloud, so that it rises smoothly as the
+    // cell moves in across the cloud's edge.
+    pub fn concentration_sensed_at(&self, center: Position, radius: Length) -> Value1D {
+        let distance = (center - self.position).length().value();
+        let depth = self.radius.value() + radius.value() - distance;
+        let covered = (depth / (2.0 * radius.value())).clamp(0.0, 1.0);
+        self.concentration.value() * covered
+    }
 }
 
 impl ObjectWithHandle<Cloud> for Cloud {
@@ -98,6 +113,35 @@ mod tests {
         assert_eq!(cloud.concentration(), Fraction::new(0.25));
     }
 
+    #[test]
+    fn sensed_concentration_rises_across_cloud_edge() {
+        let cloud = Cloud::new(Position::ORIGIN, Length::new(10.0));
+        let radius = Length::new(1.0);
+
+        let sensed = [12.0, 10.5, 10.0, 9.5, 8.0]
+            .iter()
+            .map(|x| cloud.concentration_sensed_at(Position::new(*x, 0.0), radius))
+            .collect::<Vec<_>>();
+
+        assert_eq!(sensed, vec![0.0, 0.25, 0.5, 0.75, 1.0]);
+    }
+
+    #[test]
+    fn sensed_concentration_scales_with_cloud_concentration() {
+        let parameters = CloudParameters {
+            resize_factor: Positive::new(2.0),
+            ..CloudParameters::DEFAULT
+        };
+        let mut cloud = Cloud::new(Position::ORIGIN, Length::new(1.0));
+
+        cloud.tick(&parameters);
+
+        assert_eq!(
+            cloud.concentration_sensed_at(Position::ORIGIN, Length::new(0.5)),
+            0.25
+        );
+    }
+
     #[test]
     fn cloud_disappears_below_minimum_concentration() {
         let parameters = CloudParameters {
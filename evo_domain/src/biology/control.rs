@@ -1,4 +1,5 @@
 use crate::biology::cell::Cell;
+use crate::biology::changes::BondRequest;
 use crate::biology::control_requests::*;
 use crate::biology::genome::*;
 use crate::biology::layers::*;
@@ -13,7 +14,65 @@ pub trait CellControl: fmt::Debug + Send + Sync {
 
     fn spawn(&mut self) -> Box<dyn CellControl>;
 
+    // None for controls that don't evolve
+    fn genome(&self) -> Option<&SparseNeuralNetGenome> {
+        None
+    }
+
+    // the net's nodes and connections as of the control's last run, for controls that
+    // have one, e.g. to draw it
+    fn neural_net_snapshot(&self) -> Option<NeuralNetSnapshot> {
+        None
+    }
+
+    // replaces the whole genome, e.g. with one evolved in another run
+    fn set_genome(&mut self, _genome: SparseNeuralNetGenome) -> Result<(), String> {
+        Err("Cell control has no genome".to_string())
+    }
+
+    // how many random values the control has used, for state hashes
+    fn random_draws(&self) -> u64 {
+        0
+    }
+
+    // the (mean, variance) each normalized input has adapted to, in input order
+    fn input_statistics(&self) -> Vec<(Value1D, Value1D)> {
+        vec![]
+    }
+
+    fn set_input_statistics(&mut self, statistics: &[(Value1D, Value1D)]) -> Result<(), String> {
+        if statistics.is_empty() {
+            Ok(())
+        } else {
+            Err("Cell control has no normalized inputs".to_string())
+        }
+    }
+
     fn print(&self, _cell_handle: Handle<Cell>) {}
+
+    fn print_table(&self, _cell_handle: Handle<Cell>) {}
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NeuralNetSnapshot {
+    // by node index
+    pub nodes: Vec<NeuralNetNode>,
+    // (from node, to node, weight)
+    pub connections: Vec<(VecIndex, VecIndex, Coefficient)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NeuralNetNode {
+    pub label: &'static str,
+    pub kind: NeuralNetNodeKind,
+    pub value: NodeValue,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NeuralNetNodeKind {
+    Input,
+    Hidden,
+    Output,
 }
 
 #[derive(Debug)]
@@ -23,11 +82,23 @@ pub struct CellStateSnapshot {
     pub mass: Mass,
     pub center: Position,
     pub velocity: Velocity,
+    // which way the cell faces, so that a bonded organism can steer by it
+    pub angle: Angle,
+    pub angular_velocity: AngularVelocity,
     pub net_force: Force,
     pub energy: BioEnergy,
+    // ticks lived
+    pub age: u64,
     pub layers: Vec<CellLayerStateSnapshot>,
     pub bond_0_exists: bool,
+    // the magnitude of each bond's strain at the start of the tick, zero for empty slots
+    pub bond_strains: BondStrains,
     pub touches: TouchPoints,
+    // the summed concentration of the clouds the cell is in, at the start of the tick
+    pub cloud_concentration: Value1D,
+    pub light_intensity: Value1D,
+    // pointing toward brighter light, in world coordinates; see LocalEnvironment
+    pub light_gradient: Value2D,
 }
 
 impl CellStateSnapshot {
@@ -37,11 +108,18 @@ impl CellStateSnapshot {
         mass: Mass::ZERO,
         center: Position::ORIGIN,
         velocity: Velocity::ZERO,
+        angle: Angle::ZERO,
+        angular_velocity: AngularVelocity::ZERO,
         net_force: Force::ZERO,
         energy: BioEnergy::ZERO,
+        age: 0,
         layers: Vec::new(),
         bond_0_exists: false,
+        bond_strains: NO_BOND_STRAINS,
         touches: NO_TOUCHES,
+        cloud_concentration: 0.0,
+        light_intensity: 0.0,
+        light_gradient: Value2D::ZERO,
     };
 }
 
@@ -49,6 +127,7 @@ impl CellStateSnapshot {
 pub struct CellLayerStateSnapshot {
     pub area: Area,
     pub mass: Mass,
+    pub density: Density,
     pub health: Health,
 }
 
@@ -58,6 +137,22 @@ pub type TouchPoints = [Value1D; NUM_TOUCH_POINTS];
 
 pub const NO_TOUCHES: TouchPoints = [0.0; NUM_TOUCH_POINTS];
 
+// by the direction of each touch point, counterclockwise from the positive x axis
+const TOUCH_INPUT_LABELS: [&str; NUM_TOUCH_POINTS] = [
+    "<touch E",
+    "<touch NE",
+    "<touch N",
+    "<touch NW",
+    "<touch W",
+    "<touch SW",
+    "<touch S",
+    "<touch SE",
+];
+
+pub type BondStrains = [Value1D; BondRequest::MAX_BONDS];
+
+pub const NO_BOND_STRAINS: BondStrains = [0.0; BondRequest::MAX_BONDS];
+
 #[derive(Debug)]
 pub struct NullControl {}
 
@@ -166,6 +261,8 @@ type ValueToRequestFns = Vec<(
 
 pub struct NeuralNetControl {
     get_value_fns: Arc<GetValueFns>,
+    // in the same order as get_value_fns, None for inputs used as they are
+    input_normalizers: Vec<Option<InputNormalizer>>,
     nnet: SparseNeuralNet,
     value_to_request_fns: Arc<ValueToRequestFns>,
     randomness: SeededMutationRandomness,
@@ -175,6 +272,7 @@ pub struct NeuralNetControl {
 impl NeuralNetControl {
     fn new(
         get_value_fns: GetValueFns,
+        input_normalizers: Vec<Option<InputNormalizer>>,
         genome: SparseNeuralNetGenome,
         value_to_request_fns: ValueToRequestFns,
         randomness: SeededMutationRandomness,
@@ -182,6 +280,7 @@ impl NeuralNetControl {
     ) -> Self {
         NeuralNetControl {
             get_value_fns: Arc::new(get_value_fns),
+            input_normalizers,
             nnet: SparseNeuralNet::new(genome),
             value_to_request_fns: Arc::new(value_to_request_fns),
             randomness,
@@ -190,12 +289,21 @@ impl NeuralNetControl {
     }
 
     fn set_input_values(&mut self, cell_state: &CellStateSnapshot) {
-        for (node_index, get_value_fn) in &*self.get_value_fns {
-            self.nnet
-                .set_node_value(*node_index, get_value_fn(cell_state) as NodeValue);
+        for ((node_index, get_value_fn), normalizer) in
+            self.get_value_fns.iter().zip(&mut self.input_normalizers)
+        {
+            let mut value = get_value_fn(cell_state);
+            if let Some(normalizer) = normalizer {
+                value = normalizer.normalize(value);
+            }
+            self.nnet.set_node_value(*node_index, value as NodeValue);
         }
     }
 
+    fn normalizers(&self) -> impl Iterator<Item = &InputNormalizer> {
+        self.input_normalizers.iter().flatten()
+    }
+
     fn get_output_requests(&self) -> Vec<ControlRequest> {
         let mut requests = Vec::with_capacity(self.value_to_request_fns.len());
         for (node_index, value_to_request_fn) in &*self.value_to_request_fns {
@@ -211,6 +319,7 @@ impl fmt::Debug for NeuralNetControl {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("NeuralNetControl")
             // TODO get_value_fns?
+            .field("input_normalizers", &self.input_normalizers)
             .field("nnet", &self.nnet)
             // TODO value_to_request_fns?
             .field("randomness", &self.randomness)
@@ -228,6 +337,7 @@ impl CellControl for NeuralNetControl {
     fn spawn(&mut self) -> Box<dyn CellControl> {
         Box::new(NeuralNetControl {
             get_value_fns: Arc::clone(&self.get_value_fns),
+            input_normalizers: self.input_normalizers.clone(),
             nnet: self.nnet.spawn(&mut self.randomness),
             value_to_request_fns: Arc::clone(&self.value_to_request_fns),
             randomness: self.randomness.clone(),
@@ -235,14 +345,166 @@ impl CellControl for NeuralNetControl {
         })
     }
 
+    fn genome(&self) -> Option<&SparseNeuralNetGenome> {
+        Some(self.nnet.genome())
+    }
+
+    fn neural_net_snapshot(&self) -> Option<NeuralNetSnapshot> {
+        let nodes = self
+            .nnet
+            .node_values()
+            .iter()
+            .enumerate()
+            .map(|(index, value)| {
+                let index = index as VecIndex;
+                let kind = if self.get_value_fns.iter().any(|(i, _)| *i == index) {
+                    NeuralNetNodeKind::Input
+                } else if self.value_to_request_fns.iter().any(|(i, _)| *i == index) {
+                    NeuralNetNodeKind::Output
+                } else {
+                    NeuralNetNodeKind::Hidden
+                };
+                NeuralNetNode {
+                    label: self.node_labels.get(index as usize).copied().unwrap_or(""),
+                    kind,
+                    value: *value,
+                }
+            })
+            .collect();
+        Some(NeuralNetSnapshot {
+            nodes,
+            connections: self.nnet.genome().connections(),
+        })
+    }
+
+    // The genome must at least have the nodes that the inputs and outputs are wired to.
+    fn set_genome(&mut self, genome: SparseNeuralNetGenome) -> Result<(), String> {
+        let wired_indexes = self
+            .get_value_fns
+            .iter()
+            .map(|(index, _)| *index)
+            .chain(self.value_to_request_fns.iter().map(|(index, _)| *index));
+        if let Some(index) = wired_indexes
+            .filter(|index| *index >= genome.num_nodes())
+            .max()
+        {
+            return Err(format!(
+                "Genome has {} nodes, but the control uses node {}",
+                genome.num_nodes(),
+                index
+            ));
+        }
+        self.nnet = SparseNeuralNet::new(genome);
+        Ok(())
+    }
+
+    fn random_draws(&self) -> u64 {
+        self.randomness.draws()
+    }
+
+    fn input_statistics(&self) -> Vec<(Value1D, Value1D)> {
+        self.normalizers()
+            .map(|normalizer| (normalizer.mean(), normalizer.variance()))
+            .collect()
+    }
+
+    fn set_input_statistics(&mut self, statistics: &[(Value1D, Value1D)]) -> Result<(), String> {
+        if statistics.len() != self.normalizers().count() {
+            return Err(format!(
+                "Expected statistics for {} normalized inputs, got {}",
+                self.normalizers().count(),
+                statistics.len()
+            ));
+        }
+        for (normalizer, (mean, variance)) in
+            self.input_normalizers.iter_mut().flatten().zip(statistics)
+        {
+            normalizer.set_statistics(*mean, *variance);
+        }
+        Ok(())
+    }
+
     fn print(&self, cell_handle: Handle<Cell>) {
         println!("Cell {} genome:", cell_handle);
         self.nnet.print(&self.node_labels);
     }
+
+    fn print_table(&self, cell_handle: Handle<Cell>) {
+        let input_indexes = self
+            .get_value_fns
+            .iter()
+            .map(|(index, _)| *index)
+            .collect::<Vec<_>>();
+        let output_indexes = self
+            .value_to_request_fns
+            .iter()
+            .map(|(index, _)| *index)
+            .collect::<Vec<_>>();
+        println!("Cell {} genome:", cell_handle);
+        self.nnet
+            .print_table(&self.node_labels, &input_indexes, &output_indexes);
+    }
+}
+
+// Rescales an input to about zero mean and unit variance, by running estimates of the
+// input's mean and variance that weight recent values more, so that inputs like energy
+// and area, which can grow without bound, don't saturate the net. The rate is the
+// weight of each new value, so the estimates adapt over roughly 1/rate ticks. Until
+// then they start from a mean of 0 and variance of 1, leaving the input nearly as is.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct InputNormalizer {
+    rate: Value1D,
+    mean: Value1D,
+    variance: Value1D,
+}
+
+impl InputNormalizer {
+    // Keep an input that has stopped changing from being scaled up without bound: a
+    // change of 1% of the mean, or of 0.001, scores at most 1.
+    const MIN_RELATIVE_STDEV: Value1D = 0.01;
+    const MIN_STDEV: Value1D = 0.001;
+
+    pub fn new(rate: Value1D) -> Self {
+        assert!(rate > 0.0 && rate <= 1.0);
+        InputNormalizer {
+            rate,
+            mean: 0.0,
+            variance: 1.0,
+        }
+    }
+
+    pub fn mean(&self) -> Value1D {
+        self.mean
+    }
+
+    pub fn variance(&self) -> Value1D {
+        self.variance
+    }
+
+    pub fn set_statistics(&mut self, mean: Value1D, variance: Value1D) {
+        self.mean = mean;
+        self.variance = variance;
+    }
+
+    // Scores the value against the estimates so far, then adds it to them.
+    pub fn normalize(&mut self, value: Value1D) -> Value1D {
+        let deviation = value - self.mean;
+        let stdev = self
+            .variance
+            .sqrt()
+            .max(Self::MIN_RELATIVE_STDEV * self.mean.abs())
+            .max(Self::MIN_STDEV);
+        let normalized = deviation / stdev;
+        let mean_change = self.rate * deviation;
+        self.mean += mean_change;
+        self.variance = (1.0 - self.rate) * (self.variance + deviation * mean_change);
+        normalized
+    }
 }
 
 pub struct NeuralNetControlBuilder {
     get_value_fns: GetValueFns,
+    input_normalizers: Vec<Option<InputNormalizer>>,
     genome: SparseNeuralNetGenome,
     value_to_request_fns: ValueToRequestFns,
     next_index: VecIndex,
@@ -253,6 +515,7 @@ impl NeuralNetControlBuilder {
     pub fn new(transfer_fn: TransferFn) -> Self {
         NeuralNetControlBuilder {
             get_value_fns: vec![],
+            input_normalizers: vec![],
             genome: SparseNeuralNetGenome::new(transfer_fn),
             value_to_request_fns: vec![],
             next_index: 0,
@@ -266,10 +529,68 @@ impl NeuralNetControlBuilder {
     {
         let node_index = self.next_node_index();
         self.get_value_fns.push((node_index, Box::new(get_value)));
+        self.input_normalizers.push(None);
         self.add_node_label(node_index, node_label);
         node_index
     }
 
+    // An input that adapts to the range of its values. See InputNormalizer.
+    pub fn add_normalized_input_node<F>(
+        &mut self,
+        node_label: &'static str,
+        adaptation_rate: Value1D,
+        get_value: F,
+    ) -> VecIndex
+    where
+        F: 'static + Fn(&CellStateSnapshot) -> Value1D + Send + Sync,
+    {
+        let node_index = self.add_input_node(node_label, get_value);
+        *self.input_normalizers.last_mut().unwrap() = Some(InputNormalizer::new(adaptation_rate));
+        node_index
+    }
+
+    // An input for the concentration of the clouds around the cell, so that a control
+    // can evolve to steer toward food.
+    pub fn add_cloud_concentration_input(&mut self) -> VecIndex {
+        self.add_input_node("<cloud concentration", |cell_state| {
+            cell_state.cloud_concentration
+        })
+    }
+
+    // Inputs for how bright the light around the cell is and which way it gets brighter,
+    // so that a control can evolve to steer toward the light or away from it.
+    pub fn add_light_intensity_input(&mut self) -> VecIndex {
+        self.add_input_node("<light", |cell_state| cell_state.light_intensity)
+    }
+
+    // The x and y light gradient inputs.
+    pub fn add_light_gradient_inputs(&mut self) -> (VecIndex, VecIndex) {
+        (
+            self.add_input_node("<light gradient x", |cell_state| {
+                cell_state.light_gradient.x()
+            }),
+            self.add_input_node("<light gradient y", |cell_state| {
+                cell_state.light_gradient.y()
+            }),
+        )
+    }
+
+    // Inputs for the overlap area the cell feels at each of its touch points (see
+    // sense_touches) and for their total, so that a control can evolve to avoid or seek
+    // contact. Returns the touch point inputs in touch point order, then the total.
+    pub fn add_touch_inputs(&mut self) -> ([VecIndex; NUM_TOUCH_POINTS], VecIndex) {
+        let mut touch_indexes = [0; NUM_TOUCH_POINTS];
+        for (touch_point, touch_index) in touch_indexes.iter_mut().enumerate() {
+            *touch_index = self
+                .add_input_node(TOUCH_INPUT_LABELS[touch_point], move |cell_state| {
+                    cell_state.touches[touch_point]
+                });
+        }
+        let total_index =
+            self.add_input_node("<touch total", |cell_state| cell_state.touches.iter().sum());
+        (touch_indexes, total_index)
+    }
+
     pub fn add_node(
         &mut self,
         node_label: &'static str,
@@ -298,6 +619,11 @@ impl NeuralNetControlBuilder {
         node_index
     }
 
+    // Overrides the builder's transfer function for one hidden or output node.
+    pub fn set_transfer_fn(&mut self, node_index: VecIndex, transfer_fn: TransferFn) {
+        self.genome.set_transfer_fn(node_index, transfer_fn);
+    }
+
     fn next_node_index(&mut self) -> VecIndex {
         let node_index = self.next_index;
         self.next_index += 1;
@@ -322,6 +648,7 @@ impl NeuralNetControlBuilder {
     pub fn build(self, randomness: SeededMutationRandomness) -> NeuralNetControl {
         NeuralNetControl::new(
             self.get_value_fns,
+            self.input_normalizers,
             self.genome,
             self.value_to_request_fns,
             randomness,
@@ -391,4 +718,193 @@ mod tests {
             vec![CellLayer::resize_request(0, AreaDelta::new(-48.0))]
         );
     }
+
+    #[test]
+    fn builder_can_override_transfer_fn_per_node() {
+        let mut builder = NeuralNetControlBuilder::new(TransferFn::IDENTITY);
+
+        let energy_input_index =
+            builder.add_input_node("energy", |cell_state| cell_state.energy.value());
+        let adjusted_energy_index =
+            builder.add_node("adj energy", &[(energy_input_index, -1.0)], -2.0);
+        builder.set_transfer_fn(adjusted_energy_index, TransferFn::RELU);
+        builder.add_output_node("resize", &[(adjusted_energy_index, 10.0)], 2.0, |value| {
+            CellLayer::resize_request(0, AreaDelta::new(value))
+        });
+
+        let mut control = builder.build(SeededMutationRandomness::new(
+            0,
+            &MutationParameters::NO_MUTATION,
+        ));
+        let requests = control.run(&CellStateSnapshot {
+            energy: BioEnergy::new(3.0),
+            ..CellStateSnapshot::ZEROS
+        });
+
+        assert_eq!(
+            requests,
+            vec![CellLayer::resize_request(0, AreaDelta::new(2.0))]
+        );
+    }
+
+    #[test]
+    fn neural_net_snapshot_has_values_of_last_run() {
+        let mut builder = NeuralNetControlBuilder::new(TransferFn::IDENTITY);
+        let energy_index = builder.add_input_node("energy", |cell_state| cell_state.energy.value());
+        let adjusted_index = builder.add_node("adj energy", &[(energy_index, -1.0)], -2.0);
+        builder.add_output_node("resize", &[(adjusted_index, 10.0)], 2.0, |value| {
+            CellLayer::resize_request(0, AreaDelta::new(value))
+        });
+        let mut control = builder.build(SeededMutationRandomness::new(
+            0,
+            &MutationParameters::NO_MUTATION,
+        ));
+
+        control.run(&CellStateSnapshot {
+            energy: BioEnergy::new(3.0),
+            ..CellStateSnapshot::ZEROS
+        });
+
+        let snapshot = control.neural_net_snapshot().unwrap();
+        assert_eq!(
+            snapshot.nodes,
+            vec![
+                NeuralNetNode {
+                    label: "energy",
+                    kind: NeuralNetNodeKind::Input,
+                    value: 3.0,
+                },
+                NeuralNetNode {
+                    label: "adj energy",
+                    kind: NeuralNetNodeKind::Hidden,
+                    value: -5.0,
+                },
+                NeuralNetNode {
+                    label: "resize",
+                    kind: NeuralNetNodeKind::Output,
+                    value: -48.0,
+                },
+            ]
+        );
+        assert_eq!(snapshot.connections, vec![(0, 1, -1.0), (1, 2, 10.0)]);
+    }
+
+    #[test]
+    fn touch_inputs_read_each_touch_point_and_total() {
+        let mut builder = NeuralNetControlBuilder::new(TransferFn::IDENTITY);
+        let (touch_indexes, total_index) = builder.add_touch_inputs();
+        builder.add_output_node(
+            "resize",
+            &[(touch_indexes[2], 10.0), (total_index, 1.0)],
+            0.0,
+            |value| CellLayer::resize_request(0, AreaDelta::new(value)),
+        );
+        let mut control = builder.build(SeededMutationRandomness::new(
+            0,
+            &MutationParameters::NO_MUTATION,
+        ));
+        let mut touches = NO_TOUCHES;
+        touches[2] = 0.5;
+        touches[7] = 0.25;
+
+        let requests = control.run(&CellStateSnapshot {
+            touches,
+            ..CellStateSnapshot::ZEROS
+        });
+
+        assert_eq!(
+            requests,
+            vec![CellLayer::resize_request(0, AreaDelta::new(5.75))]
+        );
+        let snapshot = control.neural_net_snapshot().unwrap();
+        assert_eq!(snapshot.nodes[2].label, "<touch N");
+        assert_eq!(snapshot.nodes[8].label, "<touch total");
+    }
+
+    #[test]
+    fn neural_net_control_takes_genome_that_fits_its_wiring() {
+        let mut builder = NeuralNetControlBuilder::new(TransferFn::IDENTITY);
+        let energy_index = builder.add_input_node("energy", |cell_state| cell_state.energy.value());
+        builder.add_output_node("resize", &[(energy_index, 1.0)], 0.0, |value| {
+            CellLayer::resize_request(0, AreaDelta::new(value))
+        });
+        let mut control = builder.build(SeededMutationRandomness::new(
+            0,
+            &MutationParameters::NO_MUTATION,
+        ));
+        let mut doubling_genome = SparseNeuralNetGenome::new(TransferFn::IDENTITY);
+        doubling_genome.connect_node(1, 0.0, &[(0, 2.0)]);
+        let mut too_small_genome = SparseNeuralNetGenome::new(TransferFn::IDENTITY);
+        too_small_genome.connect_node(0, 1.0, &[]);
+
+        assert!(control.set_genome(too_small_genome).is_err());
+        control.set_genome(doubling_genome.clone()).unwrap();
+
+        assert_eq!(control.genome(), Some(&doubling_genome));
+        let requests = control.run(&CellStateSnapshot {
+            energy: BioEnergy::new(3.0),
+            ..CellStateSnapshot::ZEROS
+        });
+        assert_eq!(
+            requests,
+            vec![CellLayer::resize_request(0, AreaDelta::new(6.0))]
+        );
+    }
+
+    #[test]
+    fn normalized_input_is_independent_of_magnitude() {
+        let normalized_values = |scale: Value1D| {
+            let mut normalizer = InputNormalizer::new(0.1);
+            let mut last_value = 0.0;
+            for tick in 0..200 {
+                let value = if tick % 2 == 0 { 11.0 } else { 9.0 };
+                last_value = normalizer.normalize(scale * value);
+            }
+            last_value
+        };
+
+        assert!((normalized_values(1.0) - normalized_values(1e9)).abs() < 1e-6);
+        assert!(normalized_values(1.0) < -0.5 && normalized_values(1.0) > -1.5);
+    }
+
+    #[test]
+    fn normalized_input_adapts_to_constant_value() {
+        let mut normalizer = InputNormalizer::new(0.5);
+        for _ in 0..100 {
+            normalizer.normalize(1e6);
+        }
+
+        assert!(normalizer.normalize(1e6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn control_with_normalized_input_keeps_responding_to_large_values() {
+        let mut builder = NeuralNetControlBuilder::new(TransferFn::IDENTITY);
+        let energy_index = builder
+            .add_normalized_input_node("energy", 0.1, |cell_state| cell_state.energy.value());
+        builder.add_output_node("resize", &[(energy_index, 1.0)], 0.0, |value| {
+            CellLayer::resize_request(0, AreaDelta::new(value))
+        });
+        let mut control = builder.build(SeededMutationRandomness::new(
+            0,
+            &MutationParameters::NO_MUTATION,
+        ));
+        let mut run_with_energy = |energy: Value1D| {
+            control.run(&CellStateSnapshot {
+                energy: BioEnergy::new(energy),
+                ..CellStateSnapshot::ZEROS
+            })[0]
+                .requested_value()
+        };
+        for _ in 0..200 {
+            run_with_energy(1e9);
+        }
+
+        let rising = run_with_energy(1.1e9);
+        let falling = run_with_energy(0.9e9);
+
+        assert!(rising > 1.0 && rising < 1e3);
+        assert!(falling < -1.0 && falling > -1e3);
+        assert_eq!(control.input_statistics().len(), 1);
+    }
 }
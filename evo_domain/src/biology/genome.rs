@@ -6,6 +6,9 @@ use rand::distributions::Distribution;
 use rand::{Rng, SeedableRng};
 use rand_distr::Normal;
 use rand_pcg::Pcg64Mcg;
+use serde::de::{self, Deserializer};
+use serde::ser::{self, Serializer};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::f32;
 use std::fmt;
@@ -15,6 +18,10 @@ pub type Coefficient = f32;
 pub type VecIndex = u16;
 pub type NodeValue = f32;
 
+// how much a node's switch to another transfer function adds to genome distances, as
+// much as a unit change in one of its coefficients
+const TRANSFER_FN_DISTANCE: f32 = 1.0;
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct SparseNeuralNet {
     genome: SparseNeuralNetGenome,
@@ -42,6 +49,15 @@ impl SparseNeuralNet {
         self.node_values[index as usize]
     }
 
+    // as of the last run, by node index
+    pub fn node_values(&self) -> &[NodeValue] {
+        &self.node_values
+    }
+
+    pub fn genome(&self) -> &SparseNeuralNetGenome {
+        &self.genome
+    }
+
     pub fn run(&mut self) {
         self.genome.run(&mut self.node_values);
     }
@@ -49,9 +65,19 @@ impl SparseNeuralNet {
     pub fn print(&self, node_labels: &[&str]) {
         self.genome.print(node_labels);
     }
+
+    pub fn print_table(
+        &self,
+        node_labels: &[&str],
+        input_indexes: &[VecIndex],
+        output_indexes: &[VecIndex],
+    ) {
+        self.genome
+            .print_table(node_labels, input_indexes, output_indexes);
+    }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct SparseNeuralNetGenome {
     ops: Vec<Op>,
     transfer_fn: TransferFn,
@@ -72,6 +98,23 @@ impl SparseNeuralNetGenome {
         to_value_index: VecIndex,
         bias: Coefficient,
         from_value_weights: &[(VecIndex, Coefficient)],
+    ) {
+        self.connect_node_with_transfer_fn(
+            to_value_index,
+            bias,
+            from_value_weights,
+            self.transfer_fn,
+        );
+    }
+
+    // Like connect_node, but with the node's own transfer function instead of the
+    // genome's default one.
+    pub fn connect_node_with_transfer_fn(
+        &mut self,
+        to_value_index: VecIndex,
+        bias: Coefficient,
+        from_value_weights: &[(VecIndex, Coefficient)],
+        transfer_fn: TransferFn,
     ) {
         self.grow_num_nodes_if_needed(to_value_index);
         self.ops.push(Op::Bias {
@@ -88,10 +131,42 @@ impl SparseNeuralNetGenome {
         }
         self.ops.push(Op::Transfer {
             value_index: to_value_index,
-            transfer_fn: self.transfer_fn,
+            transfer_fn,
         });
     }
 
+    // Replaces the transfer function of an already connected node.
+    pub fn set_transfer_fn(&mut self, value_index: VecIndex, transfer_fn: TransferFn) {
+        let mut found = false;
+        for op in &mut self.ops {
+            if let Op::Transfer {
+                value_index: op_value_index,
+                transfer_fn: op_transfer_fn,
+            } = op
+            {
+                if *op_value_index == value_index {
+                    *op_transfer_fn = transfer_fn;
+                    found = true;
+                }
+            }
+        }
+        assert!(found, "Node {} has no transfer function", value_index);
+    }
+
+    // (node, transfer function) of each connected node, in op order
+    pub fn transfer_fns(&self) -> Vec<(VecIndex, TransferFn)> {
+        self.ops
+            .iter()
+            .filter_map(|op| match op {
+                Op::Transfer {
+                    value_index,
+                    transfer_fn,
+                } => Some((*value_index, *transfer_fn)),
+                _ => None,
+            })
+            .collect()
+    }
+
     fn grow_num_nodes_if_needed(&mut self, new_index: VecIndex) {
         self.num_nodes = self.num_nodes.max(new_index + 1);
     }
@@ -110,9 +185,153 @@ impl SparseNeuralNetGenome {
         }
     }
 
+    // The Euclidean distance between the two genomes' biases and weights, with each
+    // node whose transfer function was switched counting as TRANSFER_FN_DISTANCE.
+    // Mutation doesn't change the net's shape, so descendants of one genome line up op
+    // by op; any op that doesn't line up counts its whole coefficient as difference.
+    pub fn distance(&self, other: &SparseNeuralNetGenome) -> f32 {
+        let max_len = self.ops.len().max(other.ops.len());
+        let sum_of_squares: f32 = (0..max_len)
+            .map(|i| {
+                match (self.ops.get(i), other.ops.get(i)) {
+                    (Some(op1), Some(op2)) if op1.same_position(op2) => op1.difference(op2),
+                    (op1, op2) => op1
+                        .map_or(0.0, Op::coefficient)
+                        .hypot(op2.map_or(0.0, Op::coefficient)),
+                }
+                .powi(2)
+            })
+            .sum();
+        sum_of_squares.sqrt()
+    }
+
+    // The biases and weights, in op order. With the net's shape, which only code builds,
+    // they are all there is to a genome.
+    pub fn coefficients(&self) -> Vec<Coefficient> {
+        self.ops
+            .iter()
+            .filter(|op| op.has_coefficient())
+            .map(Op::coefficient)
+            .collect()
+    }
+
+    // The whole genome, shape as well as biases and weights, as JSON, e.g. to inspect
+    // offline or to transplant into a cell of a later run.
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|err| err.to_string())
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let genome: SparseNeuralNetGenome =
+            serde_json::from_str(json).map_err(|err| format!("Invalid genome JSON: {}", err))?;
+        if let Some(index) = genome
+            .ops
+            .iter()
+            .flat_map(Op::value_indexes)
+            .find(|index| *index >= genome.num_nodes)
+        {
+            return Err(format!(
+                "Genome node index {} is not below its node count {}",
+                index, genome.num_nodes
+            ));
+        }
+        Ok(genome)
+    }
+
+    pub fn num_nodes(&self) -> VecIndex {
+        self.num_nodes
+    }
+
+    // The connections with nonzero weights. A zero weight carries nothing, so it adds
+    // nothing to the net's complexity.
+    pub fn num_nonzero_weights(&self) -> usize {
+        self.ops
+            .iter()
+            .filter(|op| matches!(op, Op::Connection { weight, .. } if *weight != 0.0))
+            .count()
+    }
+
+    // (from node, to node, weight) of each connection, in op order
+    pub fn connections(&self) -> Vec<(VecIndex, VecIndex, Coefficient)> {
+        self.ops
+            .iter()
+            .filter_map(|op| match op {
+                Op::Connection {
+                    from_value_index,
+                    to_value_index,
+                    weight,
+                } => Some((*from_value_index, *to_value_index, *weight)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // A copy of this genome with the given biases and weights, e.g. ones saved from a
+    // descendant of it.
+    pub fn with_coefficients(&self, coefficients: &[Coefficient]) -> Result<Self, String> {
+        let num_coefficients = self.ops.iter().filter(|op| op.has_coefficient()).count();
+        if coefficients.len() != num_coefficients {
+            return Err(format!(
+                "Expected {} genome coefficients, got {}",
+                num_coefficients,
+                coefficients.len()
+            ));
+        }
+        let mut coefficients = coefficients.iter();
+        Ok(SparseNeuralNetGenome {
+            ops: self
+                .ops
+                .iter()
+                .map(|op| op.copy_with_mutated_weight(|_| *coefficients.next().unwrap()))
+                .collect(),
+            transfer_fn: self.transfer_fn,
+            num_nodes: self.num_nodes,
+        })
+    }
+
+    // A copy with the nodes' transfer functions replaced, as (node, transfer function)
+    // pairs like those from transfer_fns.
+    pub fn with_transfer_fns(
+        &self,
+        transfer_fns: &[(VecIndex, TransferFn)],
+    ) -> Result<Self, String> {
+        let mut genome = self.clone();
+        for (value_index, transfer_fn) in transfer_fns {
+            let mut found = false;
+            for op in &mut genome.ops {
+                if let Op::Transfer {
+                    value_index: op_value_index,
+                    transfer_fn: op_transfer_fn,
+                } = op
+                {
+                    if op_value_index == value_index {
+                        *op_transfer_fn = *transfer_fn;
+                        found = true;
+                    }
+                }
+            }
+            if !found {
+                return Err(format!(
+                    "Genome node {} has no transfer function",
+                    value_index
+                ));
+            }
+        }
+        Ok(genome)
+    }
+
     fn copy_with_mutated_weights(ops: &[Op], randomness: &mut dyn MutationRandomness) -> Vec<Op> {
         ops.iter()
-            .map(|op| op.copy_with_mutated_weight(|weight| randomness.mutate_weight(weight)))
+            .map(|op| match op {
+                Op::Transfer {
+                    value_index,
+                    transfer_fn,
+                } => Op::Transfer {
+                    value_index: *value_index,
+                    transfer_fn: randomness.mutate_transfer_fn(*transfer_fn),
+                },
+                _ => op.copy_with_mutated_weight(|weight| randomness.mutate_weight(weight)),
+            })
             .collect()
     }
 
@@ -122,6 +341,101 @@ impl SparseNeuralNetGenome {
         }
     }
 
+    pub fn print_table(
+        &self,
+        node_labels: &[&str],
+        input_indexes: &[VecIndex],
+        output_indexes: &[VecIndex],
+    ) {
+        print!(
+            "{}",
+            self.format_table(node_labels, input_indexes, output_indexes)
+        );
+    }
+
+    // One row per node (inputs, then hidden, then outputs), followed by the connections
+    // with the largest weights.
+    pub fn format_table(
+        &self,
+        node_labels: &[&str],
+        input_indexes: &[VecIndex],
+        output_indexes: &[VecIndex],
+    ) -> String {
+        let printable_nodes = self.get_printable_nodes();
+        let (output_nodes, hidden_nodes): (Vec<&PrintableNode>, Vec<&PrintableNode>) =
+            printable_nodes
+                .iter()
+                .partition(|node| output_indexes.contains(&node.index));
+
+        let label_width = (0..self.num_nodes)
+            .map(|index| PrintableNode::format_node_index(index, node_labels).len())
+            .max()
+            .unwrap_or(0)
+            .max("node".len());
+
+        let mut result = format!(
+            "  {:<width$}  {:<6}  {:<9}  {:>9}  inputs\n",
+            "node",
+            "kind",
+            "transfer",
+            "bias",
+            width = label_width
+        );
+        for input_index in input_indexes {
+            result += &format!(
+                "  {:<width$}  input\n",
+                PrintableNode::format_node_index(*input_index, node_labels),
+                width = label_width
+            );
+        }
+        for (kind, nodes) in &[("hidden", hidden_nodes), ("output", output_nodes)] {
+            for node in nodes {
+                result += &format!(
+                    "  {:<width$}  {:<6}  {:<9}  {:>9.4}  {}\n",
+                    PrintableNode::format_node_index(node.index, node_labels),
+                    kind,
+                    node.transfer_fn.name().unwrap_or("custom"),
+                    node.bias,
+                    node.inputs
+                        .iter()
+                        .filter(|(weight, _)| *weight != 0.0)
+                        .count(),
+                    width = label_width
+                );
+            }
+        }
+
+        result += "  strongest weights:\n";
+        for (weight, from_index, to_index) in Self::strongest_weights(&printable_nodes) {
+            result += &format!(
+                "    {:>9.4}  {} -> {}\n",
+                weight,
+                PrintableNode::format_node_index(from_index, node_labels),
+                PrintableNode::format_node_index(to_index, node_labels)
+            );
+        }
+        result
+    }
+
+    fn strongest_weights(
+        printable_nodes: &[PrintableNode],
+    ) -> Vec<(Coefficient, VecIndex, VecIndex)> {
+        const MAX_STRONGEST_WEIGHTS: usize = 5;
+
+        let mut weights = printable_nodes
+            .iter()
+            .flat_map(|node| {
+                node.inputs
+                    .iter()
+                    .filter(|(weight, _)| *weight != 0.0)
+                    .map(move |(weight, from_index)| (*weight, *from_index, node.index))
+            })
+            .collect::<Vec<_>>();
+        weights.sort_by(|a, b| b.0.abs().partial_cmp(&a.0.abs()).unwrap());
+        weights.truncate(MAX_STRONGEST_WEIGHTS);
+        weights
+    }
+
     fn get_printable_nodes(&self) -> Vec<PrintableNode> {
         let mut printable_nodes = HashMap::new();
         for op in &self.ops {
@@ -274,7 +588,8 @@ impl PrintableNode {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
 enum Op {
     Bias {
         value_index: VecIndex,
@@ -319,6 +634,81 @@ impl Op {
         }
     }
 
+    fn value_indexes(&self) -> Vec<VecIndex> {
+        match self {
+            Self::Bias { value_index, .. } | Self::Transfer { value_index, .. } => {
+                vec![*value_index]
+            }
+            Self::Connection {
+                from_value_index,
+                to_value_index,
+                ..
+            } => vec![*from_value_index, *to_value_index],
+        }
+    }
+
+    // zero for ops without one
+    fn coefficient(&self) -> Coefficient {
+        match self {
+            Self::Bias { bias, .. } => *bias,
+            Self::Connection { weight, .. } => *weight,
+            Self::Transfer { .. } => 0.0,
+        }
+    }
+
+    fn has_coefficient(&self) -> bool {
+        !matches!(self, Self::Transfer { .. })
+    }
+
+    // how far the op is from another in the same position, for genome distances
+    fn difference(&self, other: &Op) -> f32 {
+        match (self, other) {
+            (
+                Self::Transfer { transfer_fn, .. },
+                Self::Transfer {
+                    transfer_fn: other_transfer_fn,
+                    ..
+                },
+            ) if transfer_fn != other_transfer_fn => TRANSFER_FN_DISTANCE,
+            _ => self.coefficient() - other.coefficient(),
+        }
+    }
+
+    // whether the two ops have the same role in the net, whatever their coefficients
+    fn same_position(&self, other: &Op) -> bool {
+        match (self, other) {
+            (
+                Self::Bias { value_index, .. },
+                Self::Bias {
+                    value_index: other_value_index,
+                    ..
+                },
+            ) => value_index == other_value_index,
+            (
+                Self::Connection {
+                    from_value_index,
+                    to_value_index,
+                    ..
+                },
+                Self::Connection {
+                    from_value_index: other_from_value_index,
+                    to_value_index: other_to_value_index,
+                    ..
+                },
+            ) => {
+                from_value_index == other_from_value_index && to_value_index == other_to_value_index
+            }
+            (
+                Self::Transfer { value_index, .. },
+                Self::Transfer {
+                    value_index: other_value_index,
+                    ..
+                },
+            ) => value_index == other_value_index,
+            _ => false,
+        }
+    }
+
     fn copy_with_mutated_weight<F>(&self, mut mutate_weight: F) -> Self
     where
         F: FnMut(Coefficient) -> Coefficient,
@@ -362,6 +752,8 @@ impl TransferFn {
     pub const SIGMOIDAL: TransferFn = TransferFn {
         the_fn: Self::sigmoidal,
     };
+    pub const TANH: TransferFn = TransferFn { the_fn: Self::tanh };
+    pub const RELU: TransferFn = TransferFn { the_fn: Self::relu };
 
     pub fn new(the_fn: fn(&mut NodeValue)) -> Self {
         TransferFn { the_fn }
@@ -371,6 +763,29 @@ impl TransferFn {
         (self.the_fn)(value)
     }
 
+    // The named functions, which are the only ones that can be saved with a genome or
+    // that mutation can switch a node to.
+    const NAMED: [(&'static str, TransferFn); 4] = [
+        ("identity", Self::IDENTITY),
+        ("sigmoidal", Self::SIGMOIDAL),
+        ("tanh", Self::TANH),
+        ("relu", Self::RELU),
+    ];
+
+    pub fn name(self) -> Option<&'static str> {
+        Self::NAMED
+            .iter()
+            .find(|(_, transfer_fn)| *transfer_fn == self)
+            .map(|(name, _)| *name)
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::NAMED
+            .iter()
+            .find(|(named, _)| *named == name)
+            .map(|(_, transfer_fn)| *transfer_fn)
+    }
+
     fn identity(_value: &mut NodeValue) {}
 
     fn sigmoidal(value: &mut NodeValue) {
@@ -380,6 +795,14 @@ impl TransferFn {
     fn sigmoidal_fn(val: NodeValue) -> NodeValue {
         1.0_f32 / (1.0_f32 + (-4.9_f32 * val).exp())
     }
+
+    fn tanh(value: &mut NodeValue) {
+        *value = value.tanh();
+    }
+
+    fn relu(value: &mut NodeValue) {
+        *value = value.max(0.0);
+    }
 }
 
 impl Clone for TransferFn {
@@ -390,8 +813,29 @@ impl Clone for TransferFn {
 
 impl fmt::Debug for TransferFn {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
-        // TODO match against constants and print name?
-        write!(f, "{}", self.the_fn as usize)
+        match self.name() {
+            Some(name) => write!(f, "{}", name),
+            None => write!(f, "{}", self.the_fn as usize),
+        }
+    }
+}
+
+impl Serialize for TransferFn {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.name() {
+            Some(name) => serializer.serialize_str(name),
+            None => Err(ser::Error::custom(
+                "only named transfer functions can be saved",
+            )),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for TransferFn {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        TransferFn::from_name(&name)
+            .ok_or_else(|| de::Error::custom(format!("unknown transfer function \"{}\"", name)))
     }
 }
 
@@ -406,6 +850,8 @@ pub struct MutationParameters {
     pub weight_mutation_probability: f32,
     pub weight_mutation_stdev: f32,
     pub add_node_probability: f32,
+    // the chance that each node's transfer function switches to another named one
+    pub transfer_fn_mutation_probability: f32,
 }
 
 impl MutationParameters {
@@ -413,10 +859,12 @@ impl MutationParameters {
         weight_mutation_probability: 0.0,
         weight_mutation_stdev: 0.0,
         add_node_probability: 0.0,
+        transfer_fn_mutation_probability: 0.0,
     };
 
     fn _validate(&self) {
         assert!(Self::_is_probability(self.weight_mutation_probability));
+        assert!(Self::_is_probability(self.transfer_fn_mutation_probability));
     }
 
     fn _is_probability(num: f32) -> bool {
@@ -426,12 +874,16 @@ impl MutationParameters {
 
 pub trait MutationRandomness {
     fn mutate_weight(&mut self, weight: Coefficient) -> Coefficient;
+
+    fn mutate_transfer_fn(&mut self, transfer_fn: TransferFn) -> TransferFn;
 }
 
 #[derive(Clone, Debug)]
 pub struct SeededMutationRandomness {
     rng: Pcg64Mcg,
     mutation_parameters: &'static MutationParameters,
+    // how many random values have been taken from rng, for state hashes
+    draws: u64,
 }
 
 impl SeededMutationRandomness {
@@ -439,18 +891,25 @@ impl SeededMutationRandomness {
         SeededMutationRandomness {
             rng: rand_pcg::Pcg64Mcg::seed_from_u64(seed),
             mutation_parameters,
+            draws: 0,
         }
     }
 
+    pub fn draws(&self) -> u64 {
+        self.draws
+    }
+
     pub fn spawn(&mut self) -> Self {
         Self::new(self.child_seed(), self.mutation_parameters)
     }
 
     pub fn child_seed(&mut self) -> u64 {
+        self.draws += 1;
         self.rng.gen()
     }
 
     fn should_mutate_this_weight(&mut self) -> bool {
+        self.draws += 1;
         self.rng
             .gen_bool(self.mutation_parameters.weight_mutation_probability as f64)
     }
@@ -469,8 +928,29 @@ impl MutationRandomness for SeededMutationRandomness {
             self.mutation_parameters.weight_mutation_stdev * weight.abs(),
         )
         .unwrap();
+        self.draws += 1;
         normal.sample(&mut self.rng)
     }
+
+    // Draws nothing unless transfer functions can mutate, so that turning their mutation
+    // on is the only thing that changes which weights mutate.
+    fn mutate_transfer_fn(&mut self, transfer_fn: TransferFn) -> TransferFn {
+        let probability = self.mutation_parameters.transfer_fn_mutation_probability;
+        if probability <= 0.0 {
+            return transfer_fn;
+        }
+        self.draws += 1;
+        if !self.rng.gen_bool(probability as f64) {
+            return transfer_fn;
+        }
+        let others = TransferFn::NAMED
+            .iter()
+            .map(|(_, named)| *named)
+            .filter(|named| *named != transfer_fn)
+            .collect::<Vec<_>>();
+        self.draws += 1;
+        others[self.rng.gen_range(0, others.len())]
+    }
 }
 
 #[cfg(test)]
@@ -492,6 +972,32 @@ mod tests {
         assert_eq!(nnet.node_value(3), 3.5);
     }
 
+    #[test]
+    fn nodes_apply_their_own_transfer_fns() {
+        let mut genome = SparseNeuralNetGenome::new(TransferFn::IDENTITY);
+        genome.connect_node_with_transfer_fn(1, 0.0, &[(0, 1.0)], TransferFn::RELU);
+        genome.connect_node_with_transfer_fn(2, 0.0, &[(0, 1.0)], TransferFn::TANH);
+        genome.connect_node(3, 0.0, &[(0, 1.0)]);
+        genome.set_transfer_fn(3, TransferFn::SIGMOIDAL);
+
+        let mut nnet = SparseNeuralNet::new(genome);
+        nnet.set_node_value(0, -2.0);
+        nnet.run();
+
+        assert_eq!(nnet.node_value(1), 0.0);
+        assert_eq!(nnet.node_value(2), (-2.0_f32).tanh());
+        assert_eq!(nnet.node_value(3), TransferFn::sigmoidal_fn(-2.0));
+    }
+
+    #[test]
+    fn counts_nonzero_weights() {
+        let mut genome = SparseNeuralNetGenome::new(TransferFn::IDENTITY);
+        genome.connect_node(2, 1.0, &[(0, 0.5), (1, 0.0)]);
+        genome.connect_node(3, 0.0, &[(2, -0.25)]);
+
+        assert_eq!(genome.num_nonzero_weights(), 2);
+    }
+
     #[test]
     fn run_clears_previous_values() {
         let mut genome = SparseNeuralNetGenome::new(TransferFn::IDENTITY);
@@ -591,6 +1097,87 @@ mod tests {
         );
     }
 
+    #[test]
+    fn spawn_can_switch_transfer_fns() {
+        const ALWAYS_SWITCH: MutationParameters = MutationParameters {
+            transfer_fn_mutation_probability: 1.0,
+            ..MutationParameters::NO_MUTATION
+        };
+        let mut genome = SparseNeuralNetGenome::new(TransferFn::IDENTITY);
+        genome.connect_node(2, 1.5, &[(0, 1.0)]);
+        genome.connect_node_with_transfer_fn(3, 0.0, &[(1, 2.0)], TransferFn::TANH);
+
+        let mut randomness = SeededMutationRandomness::new(0, &ALWAYS_SWITCH);
+        let copy = genome.spawn(&mut randomness);
+
+        assert_eq!(copy.coefficients(), genome.coefficients());
+        let transfer_fns = copy.transfer_fns();
+        assert_eq!(transfer_fns.len(), 2);
+        assert_ne!(transfer_fns[0], (2, TransferFn::IDENTITY));
+        assert_ne!(transfer_fns[1], (3, TransferFn::TANH));
+        assert!(transfer_fns
+            .iter()
+            .all(|(_, transfer_fn)| transfer_fn.name().is_some()));
+    }
+
+    #[test]
+    fn distance_between_mutated_genomes() {
+        let mut genome = SparseNeuralNetGenome::new(TransferFn::SIGMOIDAL);
+        genome.connect_node(2, 1.5, &[(0, 1.0), (1, 2.0)]);
+
+        let mut randomness = StubMutationRandomness {
+            mutated_weights: vec![(1.5, 1.5 + 3.0), (2.0, 2.0 - 4.0)],
+        };
+        let copy = genome.spawn(&mut randomness);
+
+        assert_eq!(genome.distance(&genome), 0.0);
+        assert_eq!(genome.distance(&copy), 5.0);
+        assert_eq!(copy.distance(&genome), 5.0);
+    }
+
+    #[test]
+    fn distance_counts_unmatched_ops_in_full() {
+        let mut genome1 = SparseNeuralNetGenome::new(TransferFn::IDENTITY);
+        genome1.connect_node(1, 0.0, &[(0, 3.0)]);
+        let mut genome2 = SparseNeuralNetGenome::new(TransferFn::IDENTITY);
+        genome2.connect_node(1, 0.0, &[(0, 3.0)]);
+        genome2.connect_node(2, 4.0, &[]);
+
+        assert_eq!(genome1.distance(&genome2), 4.0);
+    }
+
+    #[test]
+    fn distance_counts_transfer_fn_switches() {
+        let mut genome1 = SparseNeuralNetGenome::new(TransferFn::IDENTITY);
+        genome1.connect_node(1, 0.0, &[(0, 3.0)]);
+        genome1.connect_node(2, 0.0, &[(0, 3.0)]);
+        let genome2 = genome1
+            .with_transfer_fns(&[(1, TransferFn::RELU), (2, TransferFn::TANH)])
+            .unwrap();
+
+        assert_eq!(genome1.distance(&genome2), 2.0_f32.sqrt());
+    }
+
+    #[test]
+    fn with_transfer_fns_rejects_unconnected_node() {
+        let mut genome = SparseNeuralNetGenome::new(TransferFn::IDENTITY);
+        genome.connect_node(1, 0.0, &[(0, 3.0)]);
+
+        assert!(genome.with_transfer_fns(&[(0, TransferFn::RELU)]).is_err());
+    }
+
+    #[test]
+    fn genome_coefficients_round_trip() {
+        let mut genome = SparseNeuralNetGenome::new(TransferFn::SIGMOIDAL);
+        genome.connect_node(2, 1.5, &[(0, 1.0), (1, 2.0)]);
+
+        let copy = genome.with_coefficients(&[0.5, -1.0, 3.0]).unwrap();
+
+        assert_eq!(genome.coefficients(), vec![1.5, 1.0, 2.0]);
+        assert_eq!(copy.coefficients(), vec![0.5, -1.0, 3.0]);
+        assert!(genome.with_coefficients(&[0.5]).is_err());
+    }
+
     #[test]
     fn seeded_mutation_randomness_leaves_weight_unmutated() {
         let mut randomness = SeededMutationRandomness::new(0, &MutationParameters::NO_MUTATION);
@@ -661,6 +1248,71 @@ mod tests {
         assert_eq!(node.format_inputs(&vec![]), "1.2500*[1]");
     }
 
+    #[test]
+    fn format_table_groups_nodes_and_lists_strongest_weights() {
+        let mut genome = SparseNeuralNetGenome::new(TransferFn::IDENTITY);
+        genome.connect_node(2, -2.0, &[(0, -1.0), (1, 0.0)]);
+        genome.connect_node_with_transfer_fn(3, 2.0, &[(2, 10.0)], TransferFn::RELU);
+
+        assert_eq!(
+            genome.format_table(&["<energy", "<area", "adj", ">resize"], &[0, 1], &[3]),
+            concat!(
+                "  node       kind    transfer        bias  inputs\n",
+                "  [<energy]  input\n",
+                "  [<area]    input\n",
+                "  [adj]      hidden  identity     -2.0000  1\n",
+                "  [>resize]  output  relu          2.0000  1\n",
+                "  strongest weights:\n",
+                "      10.0000  [adj] -> [>resize]\n",
+                "      -1.0000  [<energy] -> [adj]\n",
+            )
+        );
+    }
+
+    #[test]
+    fn genome_survives_json_round_trip() {
+        let mut genome = SparseNeuralNetGenome::new(TransferFn::SIGMOIDAL);
+        genome.connect_node(2, 0.5, &[(0, -0.25), (1, 1.5)]);
+        genome.connect_node(3, -1.0, &[(2, 0.75)]);
+
+        let json = genome.to_json().unwrap();
+
+        assert!(json.contains("\"transfer_fn\": \"sigmoidal\""));
+        assert_eq!(SparseNeuralNetGenome::from_json(&json).unwrap(), genome);
+    }
+
+    #[test]
+    fn genome_with_unnamed_transfer_fn_cannot_be_saved() {
+        let mut genome = SparseNeuralNetGenome::new(TransferFn::new(plus_one));
+        genome.connect_node(1, 0.0, &[(0, 1.0)]);
+
+        assert!(genome.to_json().is_err());
+    }
+
+    #[test]
+    fn genome_json_with_node_index_out_of_range_is_rejected() {
+        let json = r#"{
+            "ops": [
+                {"op": "bias", "value_index": 1, "bias": 0.0},
+                {"op": "connection", "from_value_index": 5, "to_value_index": 1, "weight": 1.0},
+                {"op": "transfer", "value_index": 1, "transfer_fn": "identity"}
+            ],
+            "transfer_fn": "identity",
+            "num_nodes": 2
+        }"#;
+
+        let result = SparseNeuralNetGenome::from_json(json);
+
+        assert_eq!(
+            result.unwrap_err(),
+            "Genome node index 5 is not below its node count 2"
+        );
+        assert!(
+            SparseNeuralNetGenome::from_json(&json.replace("\"identity\"", "\"softplus\""))
+                .is_err()
+        );
+    }
+
     fn plus_one(value: &mut NodeValue) {
         *value += 1.0;
     }
@@ -678,5 +1330,9 @@ mod tests {
             }
             weight
         }
+
+        fn mutate_transfer_fn(&mut self, transfer_fn: TransferFn) -> TransferFn {
+            transfer_fn
+        }
     }
 }
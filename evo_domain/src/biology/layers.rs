@@ -1,58 +1,207 @@
 use crate::biology::changes::*;
 use crate::biology::control::*;
 use crate::biology::control_requests::*;
+use crate::energy_audit::EnergyAudit;
 use crate::environment::local_environment::LocalEnvironment;
 use crate::physics::overlap::Overlap;
 use crate::physics::quantities::*;
+use crate::world_state::LayerState;
+use serde::Deserialize;
 use std::f64::consts::PI;
 use std::fmt::Debug;
 use std::io::{Result, StdoutLock, Write};
 use std::usize;
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq)]
 pub enum Tissue {
     AirBubble,
     Bonding,
     CellWall,
     Photosynthetic,
+    Chemosynthetic,
+    Predatory,
+}
+
+impl Tissue {
+    pub const ALL: [Tissue; 6] = [
+        Tissue::AirBubble,
+        Tissue::Bonding,
+        Tissue::CellWall,
+        Tissue::Photosynthetic,
+        Tissue::Chemosynthetic,
+        Tissue::Predatory,
+    ];
 }
 
 #[derive(Debug, Clone, Copy)]
 pub struct LayerParameters {
     pub minimum_intact_thickness: Fraction,
     pub healing_energy_delta: BioEnergyDelta,
+    // the fraction by which the healing cost grows per degree of local temperature
+    pub healing_cost_temperature_factor: Value1D,
     pub entropic_damage_health_delta: HealthDelta,
+    // the fraction by which entropic damage grows per degree of local temperature, so
+    // that a layer wears out faster where it is warm and slower where it is cold
+    pub entropic_damage_temperature_factor: Value1D,
+    // added to the entropic damage for each tick of the cell's age
+    pub aging_damage_health_delta: HealthDelta,
     pub overlap_damage_health_delta: HealthDelta,
+    // per unit of the speed at which an overlap is closing, so that crashes do damage
+    // that gentle contact doesn't
+    pub impact_damage_health_delta: HealthDelta,
     pub growth_energy_delta: BioEnergyDelta,
     pub max_growth_rate: Positive,
     pub shrinkage_energy_delta: BioEnergyDelta,
     pub max_shrinkage_rate: Value1D,
+    // If above zero, growth turns the cell's energy into the layer's mass at this many
+    // units of mass per unit of energy, and shrinkage turns the mass back into energy at
+    // the same rate, so that the cell's energy plus its layers' mass is conserved. Then
+    // growth_energy_delta and shrinkage_energy_delta must be zero. Buds still start
+    // with their layers' initial area for free.
+    pub mass_per_energy: Value1D,
     pub decay_rate: Fraction,
+    pub max_wall_stress: Positive,
+    pub wall_relaxation_rate: Fraction,
+    pub pressure_damage_health_delta: HealthDelta,
 }
 
 impl LayerParameters {
     pub const DEFAULT: LayerParameters = LayerParameters {
         minimum_intact_thickness: Fraction::ZERO,
         healing_energy_delta: BioEnergyDelta::ZERO,
+        healing_cost_temperature_factor: 0.0,
         entropic_damage_health_delta: HealthDelta::ZERO,
+        entropic_damage_temperature_factor: 0.0,
+        aging_damage_health_delta: HealthDelta::ZERO,
         overlap_damage_health_delta: HealthDelta::ZERO,
+        impact_damage_health_delta: HealthDelta::ZERO,
         growth_energy_delta: BioEnergyDelta::ZERO,
         max_growth_rate: Positive::MAX,
         shrinkage_energy_delta: BioEnergyDelta::ZERO,
         max_shrinkage_rate: 1.0,
+        mass_per_energy: 0.0,
         decay_rate: Fraction::ZERO,
+        max_wall_stress: Positive::MAX,
+        wall_relaxation_rate: Fraction::ONE,
+        pressure_damage_health_delta: HealthDelta::ZERO,
     };
 
     fn validate(&self) {
         self.minimum_intact_thickness.validate();
         assert!(self.healing_energy_delta <= BioEnergyDelta::ZERO);
+        assert!(self.healing_cost_temperature_factor.is_finite());
         assert!(self.entropic_damage_health_delta <= HealthDelta::ZERO);
+        assert!(self.entropic_damage_temperature_factor.is_finite());
+        assert!(self.aging_damage_health_delta <= HealthDelta::ZERO);
         assert!(self.overlap_damage_health_delta <= HealthDelta::ZERO);
+        assert!(self.impact_damage_health_delta <= HealthDelta::ZERO);
         assert!(self.growth_energy_delta <= BioEnergyDelta::ZERO);
         self.max_growth_rate.validate();
         // self.shrinkage_energy_delta can be negative or positive
         assert!(self.max_shrinkage_rate >= 0.0);
+        if let Err(message) = self.check_mass_energy_coupling() {
+            panic!("{}", message);
+        }
         self.decay_rate.validate();
+        self.max_wall_stress.validate();
+        self.wall_relaxation_rate.validate();
+        assert!(self.pressure_damage_health_delta <= HealthDelta::ZERO);
+    }
+
+    // Names accepted by set.
+    pub const NAMES: [&'static str; 17] = [
+        "minimum_intact_thickness",
+        "healing_energy_delta",
+        "healing_cost_temperature_factor",
+        "entropic_damage_health_delta",
+        "entropic_damage_temperature_factor",
+        "aging_damage_health_delta",
+        "overlap_damage_health_delta",
+        "impact_damage_health_delta",
+        "growth_energy_delta",
+        "max_growth_rate",
+        "shrinkage_energy_delta",
+        "max_shrinkage_rate",
+        "mass_per_energy",
+        "decay_rate",
+        "max_wall_stress",
+        "wall_relaxation_rate",
+        "pressure_damage_health_delta",
+    ];
+
+    // Sets a field by name, e.g. for layers described in a scenario file, with an error
+    // instead of validate's panic for a value out of range.
+    pub fn set(&mut self, name: &str, value: f64) -> std::result::Result<(), String> {
+        if !Self::NAMES.contains(&name) {
+            return Err(format!("Unknown layer parameter: {}", name));
+        }
+        let valid = match name {
+            "minimum_intact_thickness" | "decay_rate" | "wall_relaxation_rate" => {
+                (0.0..=1.0).contains(&value)
+            }
+            "max_growth_rate" | "max_shrinkage_rate" | "max_wall_stress" | "mass_per_energy" => {
+                value >= 0.0
+            }
+            "healing_cost_temperature_factor"
+            | "entropic_damage_temperature_factor"
+            | "shrinkage_energy_delta" => true,
+            // the energy costs and damage
+            _ => value <= 0.0,
+        };
+        if !valid || value.is_nan() {
+            return Err(format!("Invalid value for {}: {}", name, value));
+        }
+        match name {
+            "minimum_intact_thickness" => self.minimum_intact_thickness = Fraction::new(value),
+            "healing_energy_delta" => self.healing_energy_delta = BioEnergyDelta::new(value),
+            "healing_cost_temperature_factor" => self.healing_cost_temperature_factor = value,
+            "entropic_damage_health_delta" => {
+                self.entropic_damage_health_delta = HealthDelta::new(value)
+            }
+            "entropic_damage_temperature_factor" => self.entropic_damage_temperature_factor = value,
+            "aging_damage_health_delta" => self.aging_damage_health_delta = HealthDelta::new(value),
+            "overlap_damage_health_delta" => {
+                self.overlap_damage_health_delta = HealthDelta::new(value)
+            }
+            "impact_damage_health_delta" => {
+                self.impact_damage_health_delta = HealthDelta::new(value)
+            }
+            "growth_energy_delta" => self.growth_energy_delta = BioEnergyDelta::new(value),
+            "max_growth_rate" => self.max_growth_rate = Positive::new(value),
+            "shrinkage_energy_delta" => self.shrinkage_energy_delta = BioEnergyDelta::new(value),
+            "max_shrinkage_rate" => self.max_shrinkage_rate = value,
+            "mass_per_energy" => self.mass_per_energy = value,
+            "decay_rate" => self.decay_rate = Fraction::new(value),
+            "max_wall_stress" => self.max_wall_stress = Positive::new(value),
+            "wall_relaxation_rate" => self.wall_relaxation_rate = Fraction::new(value),
+            "pressure_damage_health_delta" => {
+                self.pressure_damage_health_delta = HealthDelta::new(value)
+            }
+            _ => return Err(format!("Unknown layer parameter: {}", name)),
+        }
+        Ok(())
+    }
+
+    // Whether growth and shrinkage trade energy for mass, by mass_per_energy.
+    pub fn is_mass_energy_coupled(&self) -> bool {
+        self.mass_per_energy > 0.0
+    }
+
+    // Coupled layers, whose resizing costs follow from their density, can't also have
+    // their own growth and shrinkage costs. set can't check this, since it sets one
+    // field at a time.
+    pub fn check_mass_energy_coupling(&self) -> std::result::Result<(), String> {
+        if self.is_mass_energy_coupled()
+            && (self.growth_energy_delta != BioEnergyDelta::ZERO
+                || self.shrinkage_energy_delta != BioEnergyDelta::ZERO)
+        {
+            Err(format!(
+                "A layer with mass_per_energy {} must have zero growth_energy_delta and shrinkage_energy_delta",
+                self.mass_per_energy
+            ))
+        } else {
+            Ok(())
+        }
     }
 }
 
@@ -108,6 +257,11 @@ impl CellLayer {
         self
     }
 
+    // Damage from outside the cell's own tick, e.g. from being bitten.
+    pub fn damage(&mut self, delta_health: HealthDelta) {
+        self.update_health(delta_health);
+    }
+
     pub fn dead(mut self) -> Self {
         self.die();
         self
@@ -125,6 +279,44 @@ impl CellLayer {
         }
     }
 
+    pub fn save_state(&self) -> LayerState {
+        LayerState {
+            area: self.body.area.value(),
+            density: self.body.density.value(),
+            health: self.body.health.value(),
+            outer_radius: self.body.outer_radius.value(),
+            rest_inner_radius: self.body.rest_inner_radius.map(Length::value),
+            wall_stress: self.body.wall_stress,
+        }
+    }
+
+    // Restores a state saved from a layer of the same tissue and specialty. The cell
+    // must then update its own radius and mass.
+    pub fn load_state(&mut self, state: &LayerState) -> std::result::Result<(), String> {
+        if !(state.area >= 0.0
+            && state.density >= 0.0
+            && (0.0..=1.0).contains(&state.health)
+            && state.outer_radius >= 0.0
+            && state.rest_inner_radius.unwrap_or(0.0) >= 0.0
+            && state.wall_stress >= 0.0)
+        {
+            return Err(format!("Invalid layer state: {:?}", state));
+        }
+        self.body.area = Area::new(state.area);
+        self.body.density = Density::new(state.density);
+        self.body.mass = self.body.area * self.body.density;
+        self.body.health = Health::new(state.health);
+        self.brain = if self.body.health > Health::ZERO {
+            &CellLayer::LIVING_BRAIN
+        } else {
+            &CellLayer::DEAD_BRAIN
+        };
+        self.body.outer_radius = Length::new(state.outer_radius);
+        self.body.rest_inner_radius = state.rest_inner_radius.map(Length::new);
+        self.body.wall_stress = state.wall_stress;
+        Ok(())
+    }
+
     pub fn is_alive(&self) -> bool {
         self.brain.is_alive()
     }
@@ -157,6 +349,14 @@ impl CellLayer {
         self.body.mass
     }
 
+    pub fn density(&self) -> Density {
+        self.body.density
+    }
+
+    pub fn wall_stress(&self) -> Value1D {
+        self.body.wall_stress
+    }
+
     pub fn update_outer_radius(&mut self, inner_radius: Length) {
         self.body.update_outer_radius(inner_radius);
     }
@@ -167,6 +367,7 @@ impl CellLayer {
         changes: &mut CellChanges,
         layer_index: usize,
     ) {
+        self.body.temperature = env.temperature();
         self.brain.calculate_automatic_changes(
             &*self.specialty,
             &self.body,
@@ -176,6 +377,16 @@ impl CellLayer {
         );
     }
 
+    // Living layers of older cells wear out faster.
+    pub fn calculate_aging_damage(&self, age: u64, changes: &mut CellChanges, layer_index: usize) {
+        if self.brain.is_alive() {
+            changes.layers[layer_index].add_health_change(
+                self.body.parameters.aging_damage_health_delta * age as Value1D,
+                "aging",
+            );
+        }
+    }
+
     pub fn cost_control_request(&self, request: &ControlRequest) -> CostedControlRequest {
         self.brain
             .cost_control_request(&*self.specialty, &self.body, request)
@@ -193,6 +404,20 @@ impl CellLayer {
     pub fn apply_changes(&mut self, changes: &CellLayerChanges) {
         self.update_health(changes.health);
         self.body.resize(changes.area);
+        self.body.change_density(changes.density);
+    }
+
+    // For a living mass-energy coupled layer, the energy equivalent of the mass that
+    // applying the changes will add to it, or take away if negative.
+    pub fn biomass_energy_delta(&self, changes: &CellLayerChanges) -> Option<BioEnergyDelta> {
+        if !self.is_alive() || !self.body.parameters.is_mass_energy_coupled() {
+            return None;
+        }
+        let new_area = (self.body.area.value() + changes.area.value()).max(0.0);
+        let delta_mass = (new_area - self.body.area.value()) * self.body.density.value();
+        Some(BioEnergyDelta::new(
+            delta_mass / self.body.parameters.mass_per_energy,
+        ))
     }
 
     fn update_health(&mut self, delta_health: HealthDelta) {
@@ -326,6 +551,7 @@ struct LivingCellLayerBrain {}
 impl LivingCellLayerBrain {
     fn entropic_damage(&self, body: &CellLayerBody) -> HealthDelta {
         body.parameters.entropic_damage_health_delta
+            * body.temperature_scale(body.parameters.entropic_damage_temperature_factor)
     }
 
     fn overlap_damage(&self, body: &CellLayerBody, overlaps: &[Overlap]) -> HealthDelta {
@@ -333,6 +559,21 @@ impl LivingCellLayerBrain {
             damage + body.parameters.overlap_damage_health_delta * overlap.depth()
         })
     }
+
+    // Only overlaps that are getting deeper do impact damage.
+    fn impact_damage(&self, body: &CellLayerBody, overlaps: &[Overlap]) -> HealthDelta {
+        overlaps.iter().fold(HealthDelta::ZERO, |damage, overlap| {
+            damage + body.parameters.impact_damage_health_delta * overlap.closing_speed().max(0.0)
+        })
+    }
+
+    fn pressure_damage(&self, body: &CellLayerBody) -> HealthDelta {
+        // even a maximally stressed layer takes no damage when pressure damage is off
+        if body.parameters.pressure_damage_health_delta == HealthDelta::ZERO {
+            return HealthDelta::ZERO;
+        }
+        body.parameters.pressure_damage_health_delta * body.wall_stress
+    }
 }
 
 impl CellLayerBrain for LivingCellLayerBrain {
@@ -340,8 +581,8 @@ impl CellLayerBrain for LivingCellLayerBrain {
         true
     }
 
-    fn is_intact(&self, _body: &CellLayerBody) -> bool {
-        true
+    fn is_intact(&self, body: &CellLayerBody) -> bool {
+        body.withstands_pressure()
     }
 
     fn calculate_automatic_changes(
@@ -355,6 +596,9 @@ impl CellLayerBrain for LivingCellLayerBrain {
         changes.layers[layer_index].add_health_change(self.entropic_damage(body), "entropy");
         changes.layers[layer_index]
             .add_health_change(self.overlap_damage(body, env.overlaps()), "overlap");
+        changes.layers[layer_index]
+            .add_health_change(self.impact_damage(body, env.overlaps()), "impact");
+        changes.layers[layer_index].add_health_change(self.pressure_damage(body), "pressure");
         specialty.calculate_automatic_changes(body, env, changes)
     }
 
@@ -387,7 +631,12 @@ impl CellLayerBrain for LivingCellLayerBrain {
             CellLayer::RESIZE_CHANNEL_INDEX => {
                 let delta_area = body.actual_delta_area(request);
                 changes.layers[request.layer_index()].add_resize(delta_area, request);
-                CellLayer::record_request_energy_change(request, "resize", changes);
+                let label = if body.parameters.is_mass_energy_coupled() {
+                    EnergyAudit::BIOMASS_CONVERSION
+                } else {
+                    "resize"
+                };
+                CellLayer::record_request_energy_change(request, label, changes);
             }
             _ => specialty.execute_control_request(body, request, changes),
         }
@@ -447,6 +696,11 @@ pub struct CellLayerBody {
     outer_radius: Length,
     health: Health,
     tissue: Tissue,
+    // the inner radius the layer has grown used to; None until the first radius update
+    rest_inner_radius: Option<Length>,
+    wall_stress: Value1D,
+    // the local temperature, as of the layer's latest automatic changes
+    temperature: Value1D,
     // TODO move to CellLayerParameters struct?
     parameters: &'static LayerParameters,
 }
@@ -460,6 +714,9 @@ impl CellLayerBody {
             outer_radius: Length::ZERO,
             health: Health::FULL,
             tissue,
+            rest_inner_radius: None,
+            wall_stress: 0.0,
+            temperature: 0.0,
             parameters: &LayerParameters::DEFAULT,
         };
         body.init_from_area();
@@ -479,6 +736,9 @@ impl CellLayerBody {
             outer_radius,
             health: Health::FULL,
             tissue,
+            rest_inner_radius: Some(inner_radius),
+            wall_stress: 0.0,
+            temperature: 0.0,
             parameters: &LayerParameters::DEFAULT,
         };
         body.init_from_radii(inner_radius);
@@ -504,12 +764,19 @@ impl CellLayerBody {
     fn is_intact(&self) -> bool {
         let thickness = self.outer_radius.value() - self.inner_radius().value();
         thickness / self.outer_radius.value() >= self.parameters.minimum_intact_thickness.value()
+            && self.withstands_pressure()
+    }
+
+    fn withstands_pressure(&self) -> bool {
+        self.wall_stress <= self.parameters.max_wall_stress.value()
     }
 
     fn spawn(&self, area: Area) -> Self {
         let mut copy = Self {
             area,
             health: Health::FULL,
+            rest_inner_radius: None,
+            wall_stress: 0.0,
             ..*self
         };
         copy.init_from_area();
@@ -528,15 +795,53 @@ impl CellLayerBody {
 
     fn update_outer_radius(&mut self, inner_radius: Length) {
         self.outer_radius = (inner_radius.sqr() + self.area / PI).sqrt();
+        self.update_wall_stress(inner_radius);
+    }
+
+    // When the layers inside this one grow faster than this layer can adjust to, they
+    // press outward on it. By the thin-wall (Laplace) relation, the stress on the layer
+    // is the pressure, here the stretch beyond its rest inner radius, times its radius
+    // over its thickness, so a thin layer under the same pressure is stressed more.
+    fn update_wall_stress(&mut self, inner_radius: Length) {
+        let rest_inner_radius = self.rest_inner_radius.unwrap_or(inner_radius);
+        let pressure = if rest_inner_radius.value() > 0.0 {
+            ((inner_radius.value() - rest_inner_radius.value()) / rest_inner_radius.value())
+                .max(0.0)
+        } else {
+            0.0
+        };
+        let thickness = self.outer_radius.value() - inner_radius.value();
+        // a layer with no thickness left is as stressed as can be, but not infinitely, so
+        // that the stress stays usable in arithmetic
+        self.wall_stress = if pressure == 0.0 {
+            0.0
+        } else if thickness > 0.0 {
+            (pressure * inner_radius.value() / thickness).min(Value1D::MAX)
+        } else {
+            Value1D::MAX
+        };
+        self.rest_inner_radius = Some(Length::new(
+            rest_inner_radius.value()
+                + self.parameters.wall_relaxation_rate
+                    * (inner_radius.value() - rest_inner_radius.value()),
+        ));
     }
 
     fn cost_restore_health(&self, request: &ControlRequest) -> CostedControlRequest {
         CostedControlRequest::unlimited(
             request,
-            self.parameters.healing_energy_delta * self.area.value() * request.requested_value(),
+            self.parameters.healing_energy_delta
+                * self.temperature_scale(self.parameters.healing_cost_temperature_factor)
+                * self.area.value()
+                * request.requested_value(),
         )
     }
 
+    // A rate multiplier of 1 at temperature zero, never negative.
+    fn temperature_scale(&self, factor_per_degree: Value1D) -> Value1D {
+        (1.0 + factor_per_degree * self.temperature).max(0.0)
+    }
+
     fn actual_delta_health(&self, request: &BudgetedControlRequest) -> HealthDelta {
         assert!(request.budgeted_value() >= 0.0);
         HealthDelta::new(request.budgeted_value())
@@ -553,7 +858,9 @@ impl CellLayerBody {
 
     fn cost_resize(&self, request: &ControlRequest) -> CostedControlRequest {
         let allowed_delta_area = self.allowed_resize_delta_area(request.requested_value());
-        let energy_delta_per_area = if request.requested_value() >= 0.0 {
+        let energy_delta_per_area = if self.parameters.is_mass_energy_coupled() {
+            BioEnergyDelta::new(-self.density.value() / self.parameters.mass_per_energy)
+        } else if request.requested_value() >= 0.0 {
             self.parameters.growth_energy_delta
         } else {
             -self.parameters.shrinkage_energy_delta
@@ -589,7 +896,21 @@ impl CellLayerBody {
     }
 
     fn resize(&mut self, delta_area: AreaDelta) {
-        self.area += delta_area;
+        if delta_area == AreaDelta::ZERO {
+            return;
+        }
+
+        // several shrink requests in one tick can add up to more than the whole area
+        self.area = Area::new((self.area.value() + delta_area.value()).max(0.0));
+        self.mass = self.area * self.density;
+    }
+
+    fn change_density(&mut self, delta_density: Value1D) {
+        if delta_density == 0.0 {
+            return;
+        }
+
+        self.density = Density::new((self.density.value() + delta_density).max(0.0));
         self.mass = self.area * self.density;
     }
 }
@@ -682,7 +1003,105 @@ impl CellLayerSpecialty for PhotoCellLayerSpecialty {
                 * body.health.value()
                 * body.area.value(),
         );
-        changes.add_energy_change(energy.into(), "photo", usize::MAX);
+        changes.add_gathered_energy(energy, "photo");
+    }
+}
+
+// Absorbs nutrient from the world's chemical field, as a photosynthetic layer absorbs
+// light, turning each unit absorbed into a unit of energy.
+#[derive(Debug, Clone)]
+pub struct ChemoCellLayerSpecialty {
+    efficiency: Fraction,
+}
+
+impl ChemoCellLayerSpecialty {
+    pub fn new(efficiency: Fraction) -> Self {
+        ChemoCellLayerSpecialty { efficiency }
+    }
+}
+
+impl CellLayerSpecialty for ChemoCellLayerSpecialty {
+    fn box_spawn(&self) -> Box<dyn CellLayerSpecialty> {
+        Box::new(self.clone())
+    }
+
+    fn calculate_automatic_changes(
+        &self,
+        body: &CellLayerBody,
+        env: &LocalEnvironment,
+        changes: &mut CellChanges,
+    ) {
+        let uptake = env.nutrient_concentration()
+            * self.efficiency.value()
+            * body.health.value()
+            * body.area.value();
+        changes.nutrient_uptake += uptake;
+        changes.add_gathered_energy(BioEnergy::new(uptake), "chemo");
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct EaterLayerParameters {
+    // the most energy a healthy layer takes per unit of overlap area per tick
+    pub bite_energy_per_unit_area: BioEnergy,
+    // to the victim's outer layer, per unit of overlap depth
+    pub bite_damage_health_delta: HealthDelta,
+}
+
+impl EaterLayerParameters {
+    pub const DEFAULT: EaterLayerParameters = EaterLayerParameters {
+        bite_energy_per_unit_area: BioEnergy::unchecked(1.0),
+        bite_damage_health_delta: HealthDelta::ZERO,
+    };
+
+    fn validate(&self) {
+        assert!(self.bite_damage_health_delta <= HealthDelta::ZERO);
+    }
+}
+
+// Bites every cell that the layer's cell overlaps, taking energy from it and damaging
+// its outer layer, both in proportion to the overlap and to this layer's health.
+#[derive(Debug)]
+pub struct EaterCellLayerSpecialty {
+    parameters: &'static EaterLayerParameters,
+}
+
+impl EaterCellLayerSpecialty {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        EaterCellLayerSpecialty {
+            parameters: &EaterLayerParameters::DEFAULT,
+        }
+    }
+
+    pub fn with_parameters(mut self, parameters: &'static EaterLayerParameters) -> Self {
+        parameters.validate();
+        self.parameters = parameters;
+        self
+    }
+}
+
+impl CellLayerSpecialty for EaterCellLayerSpecialty {
+    fn box_spawn(&self) -> Box<dyn CellLayerSpecialty> {
+        Box::new(EaterCellLayerSpecialty {
+            parameters: self.parameters,
+        })
+    }
+
+    fn calculate_automatic_changes(
+        &self,
+        body: &CellLayerBody,
+        env: &LocalEnvironment,
+        changes: &mut CellChanges,
+    ) {
+        let health = body.health.value();
+        for (victim, overlap) in env.cell_overlaps() {
+            changes.bites.push(Bite {
+                victim: *victim,
+                energy: self.parameters.bite_energy_per_unit_area * (overlap.area() * health),
+                damage: self.parameters.bite_damage_health_delta * (overlap.depth() * health),
+            });
+        }
     }
 }
 
@@ -817,7 +1236,9 @@ impl CellLayerSpecialty for BondingCellLayerSpecialty {
             Self::DONATION_ENERGY_CHANNEL_INDEX => {
                 bond_request.donation_energy =
                     request.budgeted_fraction().value() * BioEnergy::new(request.allowed_value());
-                CellLayer::record_request_energy_change(request, "donated", changes);
+                bond_request.donation_tax =
+                    bond_request.donation_energy * self.parameters.donation_energy_tax_rate.value();
+                CellLayer::record_request_energy_change(request, EnergyAudit::DONATED, changes);
             }
             _ => panic!("Invalid control channel index: {}", request.channel_index()),
         }
@@ -830,6 +1251,7 @@ pub struct ThrusterCellLayerSpecialty {}
 impl ThrusterCellLayerSpecialty {
     const FORCE_X_CHANNEL_INDEX: usize = 2;
     const FORCE_Y_CHANNEL_INDEX: usize = 3;
+    const OFFSET_CHANNEL_INDEX: usize = 4;
 
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
@@ -843,6 +1265,13 @@ impl ThrusterCellLayerSpecialty {
     pub fn force_y_request(layer_index: usize, value: Value1D) -> ControlRequest {
         ControlRequest::new(layer_index, Self::FORCE_Y_CHANNEL_INDEX, 0, value)
     }
+
+    // Moves the thrust's line of action off the cell's center, by the fraction of the
+    // layer's outer radius, from -1 to 1, so that it spins the cell: counterclockwise
+    // for positive fractions.
+    pub fn offset_request(layer_index: usize, fraction: Value1D) -> ControlRequest {
+        ControlRequest::new(layer_index, Self::OFFSET_CHANNEL_INDEX, 0, fraction)
+    }
 }
 
 impl CellLayerSpecialty for ThrusterCellLayerSpecialty {
@@ -857,9 +1286,9 @@ impl CellLayerSpecialty for ThrusterCellLayerSpecialty {
     ) -> CostedControlRequest {
         match request.channel_index() {
             // TODO cost forces based on a parameter struct(?)
-            Self::FORCE_X_CHANNEL_INDEX | Self::FORCE_Y_CHANNEL_INDEX => {
-                CostedControlRequest::free(request)
-            }
+            Self::FORCE_X_CHANNEL_INDEX
+            | Self::FORCE_Y_CHANNEL_INDEX
+            | Self::OFFSET_CHANNEL_INDEX => CostedControlRequest::free(request),
             _ => panic!("Invalid control channel index: {}", request.channel_index()),
         }
     }
@@ -881,100 +1310,430 @@ impl CellLayerSpecialty for ThrusterCellLayerSpecialty {
                     body.health.value() * request.budgeted_fraction() * request.requested_value();
                 changes.thrust += Force::new(0.0, force_y);
             }
+            Self::OFFSET_CHANNEL_INDEX => {
+                let fraction = request.requested_value().clamp(-1.0, 1.0);
+                changes.thrust_offset += fraction * body.outer_radius.value();
+            }
             _ => panic!("Invalid control channel index: {}", request.channel_index()),
         }
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::biology::control_requests::BudgetedControlRequest;
-    use crate::environment::local_environment::LocalEnvironment;
-    use crate::physics::overlap::Overlap;
+#[derive(Debug, Clone, Copy)]
+pub struct GripLayerParameters {
+    pub max_grip: Value1D,
+    // per unit of grip per tick
+    pub grip_energy_delta: BioEnergyDelta,
+}
 
-    #[test]
-    fn layer_calculates_mass() {
-        let layer = simple_cell_layer(Area::new(2.0 * PI), Density::new(3.0));
-        assert_eq!(layer.mass(), Mass::new(6.0 * PI));
+impl GripLayerParameters {
+    pub const DEFAULT: GripLayerParameters = GripLayerParameters {
+        max_grip: f64::INFINITY,
+        grip_energy_delta: BioEnergyDelta::ZERO,
+    };
+
+    fn validate(&self) {
+        assert!(self.max_grip >= 0.0);
+        assert!(self.grip_energy_delta <= BioEnergyDelta::ZERO);
     }
+}
 
-    #[test]
-    fn single_layer_calculates_outer_radius() {
-        let layer = simple_cell_layer(Area::new(4.0 * PI), Density::new(1.0));
-        assert_eq!(layer.outer_radius(), Length::new(2.0));
+// Lets a cell resting on the bottom grip the substrate, adding to the friction that
+// resists sideways forces. See SubstrateFriction.
+#[derive(Debug)]
+pub struct GripCellLayerSpecialty {
+    parameters: &'static GripLayerParameters,
+}
+
+impl GripCellLayerSpecialty {
+    const GRIP_CHANNEL_INDEX: usize = 2;
+
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        GripCellLayerSpecialty {
+            parameters: &GripLayerParameters::DEFAULT,
+        }
     }
 
-    #[test]
-    fn single_layer_calculates_inner_radius_as_zero() {
-        let layer = simple_cell_layer(Area::new(1.0), Density::new(1.0));
-        assert_eq!(layer.inner_radius(), Length::ZERO);
+    pub fn with_parameters(mut self, parameters: &'static GripLayerParameters) -> Self {
+        parameters.validate();
+        self.parameters = parameters;
+        self
     }
 
-    #[test]
-    fn can_create_layer_from_radii() {
-        let layer =
-            simple_cell_layer_with_radii(Length::new(3.0), Length::new(2.0), Density::new(1.0));
-        assert_eq!(layer.outer_radius(), Length::new(3.0));
-        assert_eq!(layer.inner_radius(), Length::new(2.0));
+    pub fn grip_request(layer_index: usize, value: Value1D) -> ControlRequest {
+        ControlRequest::new(layer_index, Self::GRIP_CHANNEL_INDEX, 0, value)
     }
+}
 
-    #[test]
-    fn layer_updates_outer_radius_based_on_inner_radius() {
-        let mut layer = simple_cell_layer(Area::new(3.0 * PI), Density::new(1.0));
-        layer.update_outer_radius(Length::new(1.0));
-        assert_eq!(layer.outer_radius(), Length::new(2.0));
+impl CellLayerSpecialty for GripCellLayerSpecialty {
+    fn box_spawn(&self) -> Box<dyn CellLayerSpecialty> {
+        Box::new(GripCellLayerSpecialty::new().with_parameters(self.parameters))
     }
 
-    #[test]
-    #[should_panic]
-    fn healing_request_cannot_be_negative() {
-        CellLayer::healing_request(0, HealthDelta::new(-0.25));
+    fn cost_control_request(
+        &self,
+        request: &ControlRequest,
+        _body: &CellLayerBody,
+    ) -> CostedControlRequest {
+        match request.channel_index() {
+            Self::GRIP_CHANNEL_INDEX => {
+                let allowed_grip = request
+                    .requested_value()
+                    .clamp(0.0, self.parameters.max_grip);
+                CostedControlRequest::limited(
+                    request,
+                    allowed_grip,
+                    self.parameters.grip_energy_delta * allowed_grip,
+                )
+            }
+            _ => panic!("Invalid control channel index: {}", request.channel_index()),
+        }
     }
 
-    #[test]
-    fn layer_costs_health_restoration() {
-        const LAYER_PARAMS: LayerParameters = LayerParameters {
-            healing_energy_delta: BioEnergyDelta::new(-3.0),
-            ..LayerParameters::DEFAULT
-        };
-        let layer = simple_cell_layer(Area::new(2.0), Density::new(1.0))
-            .with_parameters(&LAYER_PARAMS)
-            .with_health(Health::new(0.5));
+    fn execute_control_request(
+        &self,
+        body: &CellLayerBody,
+        request: &BudgetedControlRequest,
+        changes: &mut CellChanges,
+    ) {
+        match request.channel_index() {
+            Self::GRIP_CHANNEL_INDEX => {
+                changes.grip += body.health.value() * request.budgeted_value().max(0.0);
+                CellLayer::record_request_energy_change(request, "gripping", changes);
+            }
+            _ => panic!("Invalid control channel index: {}", request.channel_index()),
+        }
+    }
+}
 
-        let control_request = CellLayer::healing_request(0, HealthDelta::new(0.25));
-        let costed_request = layer.cost_control_request(&control_request);
+#[derive(Debug, Clone, Copy)]
+pub struct AnchorLayerParameters {
+    pub anchoring_energy_delta: BioEnergyDelta,
+}
 
-        assert_eq!(
-            costed_request,
-            CostedControlRequest::unlimited(&control_request, BioEnergyDelta::new(-1.5))
-        );
+impl AnchorLayerParameters {
+    pub const DEFAULT: AnchorLayerParameters = AnchorLayerParameters {
+        anchoring_energy_delta: BioEnergyDelta::ZERO,
+    };
+
+    fn validate(&self) {
+        assert!(self.anchoring_energy_delta <= BioEnergyDelta::ZERO);
     }
+}
 
-    #[test]
-    fn layer_health_restoration_is_limited_by_budgeted_fraction() {
-        let layer =
-            simple_cell_layer(Area::new(1.0), Density::new(1.0)).with_health(Health::new(0.5));
-        let mut changes = CellChanges::new(1, false);
-        layer.execute_control_request(
-            &budgeted(
-                &CellLayer::healing_request(0, HealthDelta::new(0.5)),
-                BioEnergyDelta::ZERO,
-                Fraction::new(0.5),
-            ),
-            &mut changes,
-        );
-        assert_eq!(changes.layers[0].health, HealthDelta::new(0.25));
+// Lets a cell touching the bottom tie itself to the bottom wall. The anchor holds
+// for as long as the cell keeps requesting it and paying for it, unless it is
+// stretched far enough to break, and then the cell must stop requesting it before it
+// can anchor again. The cell pays only in ticks when it can hold an anchor. See
+// AnchorForces.
+#[derive(Debug)]
+pub struct AnchorCellLayerSpecialty {
+    parameters: &'static AnchorLayerParameters,
+}
+
+impl AnchorCellLayerSpecialty {
+    const ANCHOR_CHANNEL_INDEX: usize = 2;
+
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        AnchorCellLayerSpecialty {
+            parameters: &AnchorLayerParameters::DEFAULT,
+        }
     }
 
-    #[test]
-    fn layer_healing_records_energy_change() {
-        let layer =
-            simple_cell_layer(Area::new(1.0), Density::new(1.0)).with_health(Health::new(0.5));
-        let mut changes = CellChanges::new(1, false);
-        layer.execute_control_request(
-            &budgeted(
-                &CellLayer::healing_request(0, HealthDelta::new(0.5)),
+    pub fn with_parameters(mut self, parameters: &'static AnchorLayerParameters) -> Self {
+        parameters.validate();
+        self.parameters = parameters;
+        self
+    }
+
+    pub fn anchor_request(layer_index: usize, flag: bool) -> ControlRequest {
+        ControlRequest::new(
+            layer_index,
+            Self::ANCHOR_CHANNEL_INDEX,
+            0,
+            if flag { 1.0 } else { 0.0 },
+        )
+    }
+}
+
+impl CellLayerSpecialty for AnchorCellLayerSpecialty {
+    fn box_spawn(&self) -> Box<dyn CellLayerSpecialty> {
+        Box::new(AnchorCellLayerSpecialty::new().with_parameters(self.parameters))
+    }
+
+    fn cost_control_request(
+        &self,
+        request: &ControlRequest,
+        _body: &CellLayerBody,
+    ) -> CostedControlRequest {
+        match request.channel_index() {
+            Self::ANCHOR_CHANNEL_INDEX => {
+                if request.requested_value() > 0.0 {
+                    CostedControlRequest::unlimited(request, self.parameters.anchoring_energy_delta)
+                } else {
+                    CostedControlRequest::free(request)
+                }
+            }
+            _ => panic!("Invalid control channel index: {}", request.channel_index()),
+        }
+    }
+
+    fn execute_control_request(
+        &self,
+        _body: &CellLayerBody,
+        request: &BudgetedControlRequest,
+        changes: &mut CellChanges,
+    ) {
+        match request.channel_index() {
+            Self::ANCHOR_CHANNEL_INDEX => {
+                changes.anchor =
+                    request.requested_value() > 0.0 && request.budgeted_fraction() == Fraction::ONE;
+                if changes.anchor && changes.can_anchor {
+                    CellLayer::record_request_energy_change(request, "anchoring", changes);
+                }
+            }
+            _ => panic!("Invalid control channel index: {}", request.channel_index()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FloatLayerParameters {
+    pub min_density: Density,
+    pub max_density: Density,
+    // the most the density can change in a tick, up or down
+    pub max_pumping_rate: Value1D,
+    // per unit of density change per unit of area, pumping in or out
+    pub pumping_energy_delta: BioEnergyDelta,
+}
+
+impl FloatLayerParameters {
+    pub const DEFAULT: FloatLayerParameters = FloatLayerParameters {
+        min_density: Density::ZERO,
+        max_density: Density::MAX,
+        max_pumping_rate: f64::INFINITY,
+        pumping_energy_delta: BioEnergyDelta::ZERO,
+    };
+
+    fn validate(&self) {
+        assert!(self.min_density <= self.max_density);
+        assert!(self.max_pumping_rate >= 0.0);
+        assert!(self.pumping_energy_delta <= BioEnergyDelta::ZERO);
+    }
+}
+
+// Lets a layer pump water in and out to change its density, within bounds and at a
+// cost, so that a cell can control its buoyancy and choose its depth. The water isn't
+// biomass, so the layer shouldn't be mass-energy coupled, or shrinking it after
+// pumping it up would turn the water into energy.
+#[derive(Debug)]
+pub struct FloatCellLayerSpecialty {
+    parameters: &'static FloatLayerParameters,
+}
+
+impl FloatCellLayerSpecialty {
+    const DENSITY_CHANNEL_INDEX: usize = 2;
+
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        FloatCellLayerSpecialty {
+            parameters: &FloatLayerParameters::DEFAULT,
+        }
+    }
+
+    pub fn with_parameters(mut self, parameters: &'static FloatLayerParameters) -> Self {
+        parameters.validate();
+        self.parameters = parameters;
+        self
+    }
+
+    // Positive to pump water in and sink, negative to pump it out and rise.
+    pub fn density_request(layer_index: usize, delta_density: Value1D) -> ControlRequest {
+        ControlRequest::new(layer_index, Self::DENSITY_CHANNEL_INDEX, 0, delta_density)
+    }
+
+    fn cost_density_request(
+        &self,
+        request: &ControlRequest,
+        body: &CellLayerBody,
+    ) -> CostedControlRequest {
+        let allowed_delta_density = self.bounded_delta_density(
+            body.health.value() * request.requested_value(),
+            body.density.value(),
+        );
+        CostedControlRequest::limited(
+            request,
+            allowed_delta_density,
+            self.parameters.pumping_energy_delta
+                * (allowed_delta_density.abs() * body.area.value()),
+        )
+    }
+
+    fn bounded_delta_density(&self, delta_density: Value1D, density: Value1D) -> Value1D {
+        delta_density
+            .clamp(
+                -self.parameters.max_pumping_rate,
+                self.parameters.max_pumping_rate,
+            )
+            .clamp(
+                (self.parameters.min_density.value() - density).min(0.0),
+                (self.parameters.max_density.value() - density).max(0.0),
+            )
+    }
+}
+
+impl CellLayerSpecialty for FloatCellLayerSpecialty {
+    fn box_spawn(&self) -> Box<dyn CellLayerSpecialty> {
+        Box::new(FloatCellLayerSpecialty::new().with_parameters(self.parameters))
+    }
+
+    fn cost_control_request(
+        &self,
+        request: &ControlRequest,
+        body: &CellLayerBody,
+    ) -> CostedControlRequest {
+        match request.channel_index() {
+            Self::DENSITY_CHANNEL_INDEX => self.cost_density_request(request, body),
+            _ => panic!("Invalid control channel index: {}", request.channel_index()),
+        }
+    }
+
+    fn execute_control_request(
+        &self,
+        body: &CellLayerBody,
+        request: &BudgetedControlRequest,
+        changes: &mut CellChanges,
+    ) {
+        match request.channel_index() {
+            Self::DENSITY_CHANNEL_INDEX => {
+                let layer_changes = &mut changes.layers[request.layer_index()];
+                // so that several requests in a tick can't add up past the bounds
+                let pending_density = body.density.value() + layer_changes.density;
+                let delta_density =
+                    self.bounded_delta_density(request.budgeted_value(), pending_density);
+                layer_changes.add_density_change(delta_density);
+                CellLayer::record_request_energy_change(request, "pumping", changes);
+            }
+            _ => panic!("Invalid control channel index: {}", request.channel_index()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biology::control_requests::BudgetedControlRequest;
+    use crate::environment::local_environment::LocalEnvironment;
+    use crate::physics::handles::Handle;
+    use crate::physics::overlap::Overlap;
+
+    #[test]
+    fn layer_calculates_mass() {
+        let layer = simple_cell_layer(Area::new(2.0 * PI), Density::new(3.0));
+        assert_eq!(layer.mass(), Mass::new(6.0 * PI));
+    }
+
+    #[test]
+    fn single_layer_calculates_outer_radius() {
+        let layer = simple_cell_layer(Area::new(4.0 * PI), Density::new(1.0));
+        assert_eq!(layer.outer_radius(), Length::new(2.0));
+    }
+
+    #[test]
+    fn single_layer_calculates_inner_radius_as_zero() {
+        let layer = simple_cell_layer(Area::new(1.0), Density::new(1.0));
+        assert_eq!(layer.inner_radius(), Length::ZERO);
+    }
+
+    #[test]
+    fn can_create_layer_from_radii() {
+        let layer =
+            simple_cell_layer_with_radii(Length::new(3.0), Length::new(2.0), Density::new(1.0));
+        assert_eq!(layer.outer_radius(), Length::new(3.0));
+        assert_eq!(layer.inner_radius(), Length::new(2.0));
+    }
+
+    #[test]
+    fn layer_updates_outer_radius_based_on_inner_radius() {
+        let mut layer = simple_cell_layer(Area::new(3.0 * PI), Density::new(1.0));
+        layer.update_outer_radius(Length::new(1.0));
+        assert_eq!(layer.outer_radius(), Length::new(2.0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn healing_request_cannot_be_negative() {
+        CellLayer::healing_request(0, HealthDelta::new(-0.25));
+    }
+
+    #[test]
+    fn layer_costs_health_restoration() {
+        const LAYER_PARAMS: LayerParameters = LayerParameters {
+            healing_energy_delta: BioEnergyDelta::new(-3.0),
+            ..LayerParameters::DEFAULT
+        };
+        let layer = simple_cell_layer(Area::new(2.0), Density::new(1.0))
+            .with_parameters(&LAYER_PARAMS)
+            .with_health(Health::new(0.5));
+
+        let control_request = CellLayer::healing_request(0, HealthDelta::new(0.25));
+        let costed_request = layer.cost_control_request(&control_request);
+
+        assert_eq!(
+            costed_request,
+            CostedControlRequest::unlimited(&control_request, BioEnergyDelta::new(-1.5))
+        );
+    }
+
+    #[test]
+    fn healing_costs_more_where_warm() {
+        const LAYER_PARAMS: LayerParameters = LayerParameters {
+            healing_energy_delta: BioEnergyDelta::new(-3.0),
+            healing_cost_temperature_factor: 0.5,
+            ..LayerParameters::DEFAULT
+        };
+        let mut layer = simple_cell_layer(Area::new(2.0), Density::new(1.0))
+            .with_parameters(&LAYER_PARAMS)
+            .with_health(Health::new(0.5));
+        let mut env = LocalEnvironment::new();
+        env.add_temperature(2.0);
+        layer.calculate_automatic_changes(&env, &mut CellChanges::new(1, false), 0);
+
+        let control_request = CellLayer::healing_request(0, HealthDelta::new(0.25));
+        let costed_request = layer.cost_control_request(&control_request);
+
+        assert_eq!(
+            costed_request,
+            CostedControlRequest::unlimited(&control_request, BioEnergyDelta::new(-3.0))
+        );
+    }
+
+    #[test]
+    fn layer_health_restoration_is_limited_by_budgeted_fraction() {
+        let layer =
+            simple_cell_layer(Area::new(1.0), Density::new(1.0)).with_health(Health::new(0.5));
+        let mut changes = CellChanges::new(1, false);
+        layer.execute_control_request(
+            &budgeted(
+                &CellLayer::healing_request(0, HealthDelta::new(0.5)),
+                BioEnergyDelta::ZERO,
+                Fraction::new(0.5),
+            ),
+            &mut changes,
+        );
+        assert_eq!(changes.layers[0].health, HealthDelta::new(0.25));
+    }
+
+    #[test]
+    fn layer_healing_records_energy_change() {
+        let layer =
+            simple_cell_layer(Area::new(1.0), Density::new(1.0)).with_health(Health::new(0.5));
+        let mut changes = CellChanges::new(1, false);
+        layer.execute_control_request(
+            &budgeted(
+                &CellLayer::healing_request(0, HealthDelta::new(0.5)),
                 BioEnergyDelta::new(-10.0),
                 Fraction::new(0.5),
             ),
@@ -1003,6 +1762,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn mass_energy_coupled_layer_costs_resizing_by_density() {
+        const LAYER_PARAMS: LayerParameters = LayerParameters {
+            mass_per_energy: 2.0,
+            ..LayerParameters::DEFAULT
+        };
+        let layer =
+            simple_cell_layer(Area::new(4.0), Density::new(3.0)).with_parameters(&LAYER_PARAMS);
+
+        let growth_request = CellLayer::resize_request(0, AreaDelta::new(1.0));
+        let shrinkage_request = CellLayer::resize_request(0, AreaDelta::new(-2.0));
+
+        assert_eq!(
+            layer.cost_control_request(&growth_request),
+            CostedControlRequest::limited(&growth_request, 1.0, BioEnergyDelta::new(-1.5))
+        );
+        assert_eq!(
+            layer.cost_control_request(&shrinkage_request),
+            CostedControlRequest::limited(&shrinkage_request, -2.0, BioEnergyDelta::new(3.0))
+        );
+    }
+
+    #[test]
+    fn mass_energy_coupling_excludes_growth_costs() {
+        let mut parameters = LayerParameters::DEFAULT;
+        parameters.set("mass_per_energy", 2.0).unwrap();
+        assert!(parameters.check_mass_energy_coupling().is_ok());
+
+        parameters.set("growth_energy_delta", -1.0).unwrap();
+        assert!(parameters.check_mass_energy_coupling().is_err());
+    }
+
     #[test]
     fn layer_growth_with_unlimited_rate_is_still_limited_by_health() {
         let layer =
@@ -1064,6 +1855,27 @@ mod tests {
         assert_eq!(changes.energy, BioEnergyDelta::new(-1.5));
     }
 
+    #[test]
+    fn living_layer_undergoes_aging_damage() {
+        const LAYER_PARAMS: LayerParameters = LayerParameters {
+            aging_damage_health_delta: HealthDelta::new(-0.01),
+            ..LayerParameters::DEFAULT
+        };
+
+        let mut layer =
+            simple_cell_layer(Area::new(1.0), Density::new(1.0)).with_parameters(&LAYER_PARAMS);
+        let mut changes = CellChanges::new(1, false);
+        layer.calculate_aging_damage(20, &mut changes, 0);
+
+        assert_eq!(changes.layers[0].health, HealthDelta::new(-0.2));
+
+        layer.die();
+        let mut changes = CellChanges::new(1, false);
+        layer.calculate_aging_damage(20, &mut changes, 0);
+
+        assert_eq!(changes.layers[0].health, HealthDelta::ZERO);
+    }
+
     #[test]
     fn layer_undergoes_entropic_damage() {
         const LAYER_PARAMS: LayerParameters = LayerParameters {
@@ -1084,6 +1896,48 @@ mod tests {
         assert_eq!(layer.health(), Health::new(0.75));
     }
 
+    #[test]
+    fn entropic_damage_scales_with_temperature() {
+        const LAYER_PARAMS: LayerParameters = LayerParameters {
+            entropic_damage_health_delta: HealthDelta::new(-0.25),
+            entropic_damage_temperature_factor: 0.5,
+            ..LayerParameters::DEFAULT
+        };
+
+        let mut layer =
+            simple_cell_layer(Area::new(1.0), Density::new(1.0)).with_parameters(&LAYER_PARAMS);
+
+        let mut env = LocalEnvironment::new();
+        env.add_temperature(-1.0);
+        let mut changes = CellChanges::new(1, false);
+        layer.calculate_automatic_changes(&env, &mut changes, 0);
+        assert_eq!(changes.layers[0].health, HealthDelta::new(-0.125));
+
+        env.add_temperature(-2.0);
+        let mut changes = CellChanges::new(1, false);
+        layer.calculate_automatic_changes(&env, &mut changes, 0);
+        assert_eq!(changes.layers[0].health, HealthDelta::ZERO);
+    }
+
+    #[test]
+    fn closing_overlap_does_impact_damage() {
+        const LAYER_PARAMS: LayerParameters = LayerParameters {
+            impact_damage_health_delta: HealthDelta::new(-0.1),
+            ..LayerParameters::DEFAULT
+        };
+
+        let mut layer =
+            simple_cell_layer(Area::new(1.0), Density::new(1.0)).with_parameters(&LAYER_PARAMS);
+
+        let mut env = LocalEnvironment::new();
+        env.add_overlap(Overlap::new(Displacement::new(0.5, 0.0), 1.0).with_closing_speed(2.0));
+        env.add_overlap(Overlap::new(Displacement::new(0.5, 0.0), 1.0).with_closing_speed(-3.0));
+        let mut changes = CellChanges::new(1, false);
+        layer.calculate_automatic_changes(&env, &mut changes, 0);
+
+        assert_eq!(changes.layers[0].health, HealthDelta::new(-0.2));
+    }
+
     #[test]
     fn overlap_damages_layer() {
         const LAYER_PARAMS: LayerParameters = LayerParameters {
@@ -1175,6 +2029,16 @@ mod tests {
         assert_eq!(changes.layers[0].area, AreaDelta::new(0.0));
     }
 
+    #[test]
+    fn shrinking_past_zero_area_leaves_zero_area() {
+        let mut layer = simple_cell_layer(Area::new(2.0), Density::new(1.0));
+        let mut changes = CellLayerChanges::new(false);
+        changes.area = AreaDelta::new(-3.0);
+        layer.apply_changes(&changes);
+        assert_eq!(layer.area(), Area::ZERO);
+        assert_eq!(layer.mass(), Mass::ZERO);
+    }
+
     #[test]
     fn dead_layer_decays() {
         const LAYER_PARAMS: LayerParameters = LayerParameters {
@@ -1223,6 +2087,100 @@ mod tests {
         assert!(!layer.is_intact());
     }
 
+    #[test]
+    fn layer_stretched_past_rest_radius_is_stressed() {
+        let mut layer =
+            simple_cell_layer_with_radii(Length::new(1.0), Length::new(0.5), Density::new(1.0));
+        layer.update_outer_radius(Length::new(0.5));
+        assert_eq!(layer.wall_stress(), 0.0);
+
+        layer.update_outer_radius(Length::new(0.6));
+        assert!(layer.wall_stress() > 0.0);
+    }
+
+    #[test]
+    fn thinner_layer_is_stressed_more_by_same_stretch() {
+        let mut thick =
+            simple_cell_layer_with_radii(Length::new(1.0), Length::new(0.5), Density::new(1.0));
+        let mut thin =
+            simple_cell_layer_with_radii(Length::new(0.6), Length::new(0.5), Density::new(1.0));
+        thick.update_outer_radius(Length::new(0.6));
+        thin.update_outer_radius(Length::new(0.6));
+        assert!(thin.wall_stress() > thick.wall_stress());
+    }
+
+    #[test]
+    fn wall_stress_relaxes_at_relaxation_rate() {
+        const LAYER_PARAMS: LayerParameters = LayerParameters {
+            wall_relaxation_rate: Fraction::unchecked(0.5),
+            ..LayerParameters::DEFAULT
+        };
+
+        let mut layer =
+            simple_cell_layer_with_radii(Length::new(1.0), Length::new(0.5), Density::new(1.0))
+                .with_parameters(&LAYER_PARAMS);
+        layer.update_outer_radius(Length::new(0.7));
+        let first_stress = layer.wall_stress();
+        layer.update_outer_radius(Length::new(0.7));
+        assert!(layer.wall_stress() > 0.0);
+        assert!(layer.wall_stress() < first_stress);
+    }
+
+    #[test]
+    fn overstressed_living_layer_is_not_intact() {
+        const LAYER_PARAMS: LayerParameters = LayerParameters {
+            max_wall_stress: Positive::unchecked(0.5),
+            ..LayerParameters::DEFAULT
+        };
+
+        let mut layer =
+            simple_cell_layer_with_radii(Length::new(0.6), Length::new(0.5), Density::new(1.0))
+                .with_parameters(&LAYER_PARAMS);
+        assert!(layer.is_intact());
+        layer.update_outer_radius(Length::new(0.6));
+        assert!(!layer.is_intact());
+    }
+
+    #[test]
+    fn stressed_layer_takes_pressure_damage() {
+        const LAYER_PARAMS: LayerParameters = LayerParameters {
+            pressure_damage_health_delta: HealthDelta::new(-0.1),
+            ..LayerParameters::DEFAULT
+        };
+
+        let mut layer =
+            simple_cell_layer_with_radii(Length::new(1.0), Length::new(0.5), Density::new(1.0))
+                .with_parameters(&LAYER_PARAMS);
+        layer.update_outer_radius(Length::new(0.6));
+
+        let env = LocalEnvironment::new();
+        let mut changes = CellChanges::new(1, false);
+        layer.calculate_automatic_changes(&env, &mut changes, 0);
+
+        assert_eq!(
+            changes.layers[0].health,
+            HealthDelta::new(-0.1 * layer.wall_stress())
+        );
+    }
+
+    #[test]
+    fn stressed_zero_thickness_layer_survives_default_parameters() {
+        let mut layer =
+            simple_cell_layer_with_radii(Length::new(0.5), Length::new(0.5), Density::new(1.0));
+        layer.update_outer_radius(Length::new(0.5));
+        layer.update_outer_radius(Length::new(0.6));
+        assert!(layer.wall_stress().is_finite());
+        assert!(layer.wall_stress() > 0.0);
+
+        let env = LocalEnvironment::new();
+        let mut changes = CellChanges::new(1, false);
+        layer.calculate_automatic_changes(&env, &mut changes, 0);
+        assert_eq!(changes.layers[0].health, HealthDelta::ZERO);
+
+        layer.apply_changes(&changes.layers[0]);
+        assert!(layer.is_alive());
+    }
+
     #[test]
     fn photo_layer_adds_energy_based_on_area_and_efficiency_and_duration() {
         let mut layer = CellLayer::new(
@@ -1241,6 +2199,58 @@ mod tests {
         assert_eq!(changes.energy, BioEnergyDelta::new(20.0));
     }
 
+    #[test]
+    fn chemo_layer_absorbs_nutrient_based_on_area_and_efficiency() {
+        let mut layer = CellLayer::new(
+            Area::new(4.0),
+            Density::new(1.0),
+            Tissue::Chemosynthetic,
+            Box::new(ChemoCellLayerSpecialty::new(Fraction::new(0.5))),
+        );
+
+        let mut env = LocalEnvironment::new();
+        env.add_nutrient_concentration(3.0);
+
+        let mut changes = CellChanges::new(1, false);
+        layer.calculate_automatic_changes(&env, &mut changes, 0);
+
+        assert_eq!(changes.nutrient_uptake, 6.0);
+        assert_eq!(changes.energy, BioEnergyDelta::new(6.0));
+    }
+
+    #[test]
+    fn eater_layer_bites_overlapping_cells_based_on_overlap_and_health() {
+        const LAYER_PARAMS: EaterLayerParameters = EaterLayerParameters {
+            bite_energy_per_unit_area: BioEnergy::unchecked(2.0),
+            bite_damage_health_delta: HealthDelta::new(-0.1),
+        };
+        let mut layer = CellLayer::new(
+            Area::new(1.0),
+            Density::new(1.0),
+            Tissue::Predatory,
+            Box::new(EaterCellLayerSpecialty::new().with_parameters(&LAYER_PARAMS)),
+        )
+        .with_health(Health::new(0.5));
+
+        let mut env = LocalEnvironment::new();
+        env.add_cell_overlap(
+            Handle::new(3),
+            Overlap::new(Displacement::new(0.5, 0.0), 2.0),
+        );
+
+        let mut changes = CellChanges::new(1, false);
+        layer.calculate_automatic_changes(&env, &mut changes, 0);
+
+        assert_eq!(
+            changes.bites,
+            vec![Bite {
+                victim: Handle::new(3),
+                energy: BioEnergy::new(1.0),
+                damage: HealthDelta::new(-0.025),
+            }]
+        );
+    }
+
     #[test]
     fn photo_layer_energy_is_limited_by_health() {
         let mut layer = CellLayer::new(
@@ -1351,6 +2361,10 @@ mod tests {
             changes.bond_requests[0].donation_energy,
             BioEnergy::new(1.25)
         );
+        assert_eq!(
+            changes.bond_requests[0].donation_tax,
+            BioEnergy::new(0.3125)
+        );
         assert_eq!(changes.energy, BioEnergyDelta::new(-1.5625));
     }
 
@@ -1379,6 +2393,23 @@ mod tests {
         assert_eq!(changes.thrust, Force::new(1.0, -1.0));
     }
 
+    #[test]
+    fn thruster_layer_offsets_thrust_within_its_radius() {
+        let layer = CellLayer::new(
+            Area::new(PI * 4.0),
+            Density::new(1.0),
+            Tissue::Photosynthetic,
+            Box::new(ThrusterCellLayerSpecialty::new()),
+        );
+        let mut changes = CellChanges::new(1, false);
+        layer.execute_control_request(
+            &fully_budgeted(&ThrusterCellLayerSpecialty::offset_request(0, -3.0)),
+            &mut changes,
+        );
+
+        assert_eq!(changes.thrust_offset, -2.0);
+    }
+
     #[test]
     fn thruster_layer_force_is_limited_by_budget() {
         let mut layer = CellLayer::new(
@@ -1438,6 +2469,224 @@ mod tests {
         assert_eq!(changes.thrust, Force::new(0.5, -0.5));
     }
 
+    #[test]
+    fn grip_layer_adds_grip() {
+        let layer = CellLayer::new(
+            Area::new(1.0),
+            Density::new(1.0),
+            Tissue::CellWall,
+            Box::new(GripCellLayerSpecialty::new()),
+        )
+        .with_health(Health::new(0.5));
+        let mut changes = CellChanges::new(1, false);
+        layer.execute_control_request(
+            &fully_budgeted(&GripCellLayerSpecialty::grip_request(0, 3.0)),
+            &mut changes,
+        );
+
+        assert_eq!(changes.grip, 1.5);
+    }
+
+    #[test]
+    fn grip_layer_ignores_negative_grip() {
+        let layer = CellLayer::new(
+            Area::new(1.0),
+            Density::new(1.0),
+            Tissue::CellWall,
+            Box::new(GripCellLayerSpecialty::new()),
+        );
+        let mut changes = CellChanges::new(1, false);
+        layer.execute_control_request(
+            &fully_budgeted(&GripCellLayerSpecialty::grip_request(0, -3.0)),
+            &mut changes,
+        );
+
+        assert_eq!(changes.grip, 0.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn anchoring_cannot_pay_energy() {
+        const LAYER_PARAMS: AnchorLayerParameters = AnchorLayerParameters {
+            anchoring_energy_delta: BioEnergyDelta::new(0.5),
+        };
+        AnchorCellLayerSpecialty::new().with_parameters(&LAYER_PARAMS);
+    }
+
+    #[test]
+    fn grip_layer_bounds_and_costs_grip() {
+        const LAYER_PARAMS: GripLayerParameters = GripLayerParameters {
+            max_grip: 2.0,
+            grip_energy_delta: BioEnergyDelta::new(-0.25),
+        };
+
+        let layer = CellLayer::new(
+            Area::new(1.0),
+            Density::new(1.0),
+            Tissue::CellWall,
+            Box::new(GripCellLayerSpecialty::new().with_parameters(&LAYER_PARAMS)),
+        );
+        let control_request = GripCellLayerSpecialty::grip_request(0, 3.0);
+
+        let costed_request = layer.cost_control_request(&control_request);
+        assert_eq!(
+            costed_request,
+            CostedControlRequest::limited(&control_request, 2.0, BioEnergyDelta::new(-0.5))
+        );
+
+        let mut changes = CellChanges::new(1, false);
+        layer.execute_control_request(
+            &BudgetedControlRequest::new(&costed_request, Fraction::ONE),
+            &mut changes,
+        );
+        assert_eq!(changes.grip, 2.0);
+        assert_eq!(changes.energy, BioEnergyDelta::new(-0.5));
+    }
+
+    #[test]
+    fn anchor_layer_costs_and_requests_anchor() {
+        const LAYER_PARAMS: AnchorLayerParameters = AnchorLayerParameters {
+            anchoring_energy_delta: BioEnergyDelta::new(-0.5),
+        };
+
+        let layer = CellLayer::new(
+            Area::new(1.0),
+            Density::new(1.0),
+            Tissue::CellWall,
+            Box::new(AnchorCellLayerSpecialty::new().with_parameters(&LAYER_PARAMS)),
+        );
+        let control_request = AnchorCellLayerSpecialty::anchor_request(0, true);
+
+        let costed_request = layer.cost_control_request(&control_request);
+        assert_eq!(
+            costed_request,
+            CostedControlRequest::unlimited(&control_request, BioEnergyDelta::new(-0.5))
+        );
+
+        let mut changes = CellChanges::new(1, false);
+        changes.can_anchor = true;
+        layer.execute_control_request(
+            &BudgetedControlRequest::new(&costed_request, Fraction::ONE),
+            &mut changes,
+        );
+        assert!(changes.anchor);
+        assert_eq!(changes.energy, BioEnergyDelta::new(-0.5));
+    }
+
+    #[test]
+    fn anchor_layer_does_not_charge_when_cell_cannot_anchor() {
+        const LAYER_PARAMS: AnchorLayerParameters = AnchorLayerParameters {
+            anchoring_energy_delta: BioEnergyDelta::new(-0.5),
+        };
+
+        let layer = CellLayer::new(
+            Area::new(1.0),
+            Density::new(1.0),
+            Tissue::CellWall,
+            Box::new(AnchorCellLayerSpecialty::new().with_parameters(&LAYER_PARAMS)),
+        );
+        let costed_request =
+            layer.cost_control_request(&AnchorCellLayerSpecialty::anchor_request(0, true));
+
+        let mut changes = CellChanges::new(1, false);
+        layer.execute_control_request(
+            &BudgetedControlRequest::new(&costed_request, Fraction::ONE),
+            &mut changes,
+        );
+        assert!(changes.anchor);
+        assert_eq!(changes.energy, BioEnergyDelta::ZERO);
+    }
+
+    #[test]
+    fn anchor_layer_without_full_budget_does_not_anchor() {
+        let layer = CellLayer::new(
+            Area::new(1.0),
+            Density::new(1.0),
+            Tissue::CellWall,
+            Box::new(AnchorCellLayerSpecialty::new()),
+        );
+        let mut changes = CellChanges::new(1, false);
+        layer.execute_control_request(
+            &budgeted(
+                &AnchorCellLayerSpecialty::anchor_request(0, true),
+                BioEnergyDelta::new(-1.0),
+                Fraction::new(0.5),
+            ),
+            &mut changes,
+        );
+        assert!(!changes.anchor);
+    }
+
+    const FLOAT_PARAMS: FloatLayerParameters = FloatLayerParameters {
+        min_density: Density::unchecked(0.5),
+        max_density: Density::unchecked(2.0),
+        max_pumping_rate: 0.25,
+        pumping_energy_delta: BioEnergyDelta::new(-2.0),
+    };
+
+    #[test]
+    fn float_layer_bounds_and_costs_pumping_by_rate() {
+        let layer = CellLayer::new(
+            Area::new(3.0),
+            Density::new(1.0),
+            Tissue::AirBubble,
+            Box::new(FloatCellLayerSpecialty::new().with_parameters(&FLOAT_PARAMS)),
+        )
+        .with_health(Health::new(0.5));
+        let control_request = FloatCellLayerSpecialty::density_request(0, -1.0);
+
+        let costed_request = layer.cost_control_request(&control_request);
+        assert_eq!(
+            costed_request,
+            CostedControlRequest::limited(&control_request, -0.25, BioEnergyDelta::new(-1.5))
+        );
+    }
+
+    #[test]
+    fn float_layer_pumping_stops_at_density_bounds() {
+        let mut layer = CellLayer::new(
+            Area::new(1.0),
+            Density::new(1.875),
+            Tissue::AirBubble,
+            Box::new(FloatCellLayerSpecialty::new().with_parameters(&FLOAT_PARAMS)),
+        );
+        let control_request = FloatCellLayerSpecialty::density_request(0, 0.25);
+        let costed_request = layer.cost_control_request(&control_request);
+        assert_eq!(costed_request.allowed_value(), 0.125);
+
+        // a second request in the same tick can't pump past the bound either
+        let mut changes = CellChanges::new(1, false);
+        let budgeted_request = BudgetedControlRequest::new(&costed_request, Fraction::ONE);
+        layer.execute_control_request(&budgeted_request, &mut changes);
+        layer.execute_control_request(&budgeted_request, &mut changes);
+        layer.apply_changes(&changes.layers[0]);
+
+        assert_eq!(layer.density(), Density::new(2.0));
+        assert_eq!(layer.mass(), Mass::new(2.0));
+    }
+
+    #[test]
+    fn float_layer_pumping_changes_density_and_mass() {
+        let mut layer = CellLayer::new(
+            Area::new(2.0),
+            Density::new(1.0),
+            Tissue::AirBubble,
+            Box::new(FloatCellLayerSpecialty::new().with_parameters(&FLOAT_PARAMS)),
+        );
+        let mut changes = CellChanges::new(1, false);
+        let costed_request =
+            layer.cost_control_request(&FloatCellLayerSpecialty::density_request(0, -0.25));
+        layer.execute_control_request(
+            &BudgetedControlRequest::new(&costed_request, Fraction::new(0.5)),
+            &mut changes,
+        );
+        layer.apply_changes(&changes.layers[0]);
+
+        assert_eq!(layer.density(), Density::new(0.875));
+        assert_eq!(layer.mass(), Mass::new(1.75));
+        assert_eq!(changes.energy, BioEnergyDelta::new(-0.5));
+    }
+
     fn simple_cell_layer(area: Area, density: Density) -> CellLayer {
         CellLayer::new(
             area,
@@ -0,0 +1,164 @@
+use std::fmt::Write;
+
+// Who budded from whom over a run, to tell whether a trait arose once or many times.
+// Cells are identified by their cell IDs, which World hands out from 1 up, so the tree
+// is stored as a list indexed by ID. A cell added to the world directly is a founder,
+// the root of its own lineage.
+#[derive(Clone, Debug, Default)]
+pub struct Phylogeny {
+    nodes: Vec<PhylogenyNode>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PhylogenyNode {
+    pub parent_id: Option<u64>,
+    pub birth_tick: u64,
+    pub death_tick: Option<u64>,
+}
+
+impl Phylogeny {
+    pub fn new() -> Self {
+        Phylogeny::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn node(&self, cell_id: u64) -> Option<&PhylogenyNode> {
+        self.nodes.get((cell_id as usize).checked_sub(1)?)
+    }
+
+    // Records a newly added cell, whose ID must be the next one.
+    pub fn add(&mut self, cell_id: u64, parent_id: Option<u64>, tick: u64) {
+        assert_eq!(cell_id as usize, self.nodes.len() + 1);
+        self.nodes.push(PhylogenyNode {
+            parent_id,
+            birth_tick: tick,
+            death_tick: None,
+        });
+    }
+
+    pub fn record_death(&mut self, cell_id: u64, tick: u64) {
+        let index = (cell_id as usize).checked_sub(1);
+        if let Some(node) = index.and_then(|index| self.nodes.get_mut(index)) {
+            node.death_tick = Some(tick);
+        }
+    }
+
+    fn children_by_id(&self) -> Vec<Vec<u64>> {
+        let mut children = vec![vec![]; self.nodes.len() + 1];
+        for (index, node) in self.nodes.iter().enumerate() {
+            if let Some(parent_id) = node.parent_id {
+                children[parent_id as usize].push(index as u64 + 1);
+            }
+        }
+        children
+    }
+
+    fn founder_ids(&self) -> impl Iterator<Item = u64> + '_ {
+        self.nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.parent_id.is_none())
+            .map(|(index, _)| index as u64 + 1)
+    }
+
+    // The tree in Newick format, with cells labeled c<ID> and branch lengths in ticks
+    // from the parent's birth. Several founders become children of an unlabeled root.
+    // Built without recursion, since lineages can be thousands of generations deep.
+    pub fn to_newick(&self) -> String {
+        let children = self.children_by_id();
+        let mut subtrees: Vec<Option<String>> = vec![None; self.nodes.len() + 1];
+        let mut stack = self.founder_ids().map(|id| (id, false)).collect::<Vec<_>>();
+        while let Some((id, children_done)) = stack.pop() {
+            if !children_done {
+                stack.push((id, true));
+                stack.extend(children[id as usize].iter().map(|child| (*child, false)));
+                continue;
+            }
+            let mut subtree = String::new();
+            if !children[id as usize].is_empty() {
+                let child_subtrees = children[id as usize]
+                    .iter()
+                    .map(|child| subtrees[*child as usize].take().unwrap())
+                    .collect::<Vec<_>>();
+                write!(subtree, "({})", child_subtrees.join(",")).unwrap();
+            }
+            write!(subtree, "c{}", id).unwrap();
+            let node = &self.nodes[id as usize - 1];
+            if let Some(parent_id) = node.parent_id {
+                let parent = &self.nodes[parent_id as usize - 1];
+                write!(subtree, ":{}", node.birth_tick - parent.birth_tick).unwrap();
+            }
+            subtrees[id as usize] = Some(subtree);
+        }
+
+        let trees = self
+            .founder_ids()
+            .map(|id| subtrees[id as usize].take().unwrap())
+            .collect::<Vec<_>>();
+        if trees.len() == 1 {
+            format!("{};", trees[0])
+        } else {
+            format!("({});", trees.join(","))
+        }
+    }
+
+    // The tree as a GraphViz digraph, with edges from parent to child and each cell
+    // labeled with its ID and birth and death ticks.
+    pub fn to_graphviz(&self) -> String {
+        let mut dot = "digraph phylogeny {\n".to_string();
+        for (index, node) in self.nodes.iter().enumerate() {
+            let id = index + 1;
+            let death = node
+                .death_tick
+                .map_or(String::new(), |tick| tick.to_string());
+            writeln!(
+                dot,
+                "  c{} [label=\"c{}\\n{}-{}\"];",
+                id, id, node.birth_tick, death
+            )
+            .unwrap();
+            if let Some(parent_id) = node.parent_id {
+                writeln!(dot, "  c{} -> c{};", parent_id, id).unwrap();
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newick_nests_children_with_branch_lengths() {
+        let mut phylogeny = Phylogeny::new();
+        phylogeny.add(1, None, 0);
+        phylogeny.add(2, Some(1), 5);
+        phylogeny.add(3, Some(2), 8);
+        phylogeny.add(4, Some(1), 9);
+        phylogeny.add(5, None, 0);
+
+        assert_eq!(phylogeny.to_newick(), "(((c3:3)c2:5,c4:9)c1,c5);");
+    }
+
+    #[test]
+    fn graphviz_has_edge_per_birth() {
+        let mut phylogeny = Phylogeny::new();
+        phylogeny.add(1, None, 0);
+        phylogeny.add(2, Some(1), 5);
+        phylogeny.record_death(1, 7);
+
+        assert_eq!(
+            phylogeny.to_graphviz(),
+            "digraph phylogeny {\n  c1 [label=\"c1\\n0-7\"];\n  c2 [label=\"c2\\n5-\"];\n  c1 -> c2;\n}\n"
+        );
+    }
+}
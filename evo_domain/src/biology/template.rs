@@ -0,0 +1,250 @@
+use crate::biology::cell::Cell;
+use crate::biology::genome::{Coefficient, TransferFn, VecIndex};
+use crate::biology::layers::Tissue;
+use crate::physics::quantities::*;
+use std::fmt;
+
+// A cell's state saved as text, so that an interesting evolved cell can be the founder
+// of a later run. Layer specialties and control wiring are code rather than data, so a
+// template is applied to a cell built by the same program (see Cell::apply_template),
+// restoring its energy, layer areas and health, genome biases, weights, and transfer
+// functions, and what its normalized inputs have adapted to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CellTemplate {
+    pub energy: BioEnergy,
+    pub layers: Vec<LayerTemplate>,
+    pub genome_coefficients: Option<Vec<Coefficient>>,
+    // (node, transfer function) for each node with a named transfer function; other
+    // nodes keep the ones the program gave them
+    pub genome_transfer_fns: Vec<(VecIndex, TransferFn)>,
+    // (mean, variance) per normalized input
+    pub input_statistics: Vec<(Value1D, Value1D)>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayerTemplate {
+    pub tissue: Tissue,
+    pub area: Area,
+    pub health: Health,
+}
+
+impl CellTemplate {
+    pub fn from_cell(cell: &Cell) -> Self {
+        CellTemplate {
+            energy: cell.energy(),
+            layers: cell
+                .layers()
+                .iter()
+                .map(|layer| LayerTemplate {
+                    tissue: layer.tissue(),
+                    area: layer.area(),
+                    health: layer.health(),
+                })
+                .collect(),
+            genome_coefficients: cell.genome().map(|genome| genome.coefficients()),
+            genome_transfer_fns: cell
+                .genome()
+                .map(|genome| genome.transfer_fns())
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|(_, transfer_fn)| transfer_fn.name().is_some())
+                .collect(),
+            input_statistics: cell.input_statistics(),
+        }
+    }
+
+    // Reads the text written by Display. Lines starting with # are comments.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut energy = None;
+        let mut layers = vec![];
+        let mut genome_coefficients = None;
+        let mut genome_transfer_fns = vec![];
+        let mut input_statistics = vec![];
+        for line in text.lines() {
+            let mut words = line.split_whitespace();
+            match words.next() {
+                None => {}
+                Some(word) if word.starts_with('#') => {}
+                Some("energy") => energy = Some(BioEnergy::new(Self::parse_number(words.next())?)),
+                Some("layer") => layers.push(Self::parse_layer(words)?),
+                Some("genome") => {
+                    genome_coefficients = Some(
+                        words
+                            .map(|word| Self::parse_number(Some(word)).map(|c| c as Coefficient))
+                            .collect::<Result<Vec<_>, _>>()?,
+                    )
+                }
+                Some("transfer") => genome_transfer_fns = Self::parse_transfer_fns(words)?,
+                Some("inputs") => {
+                    let numbers = words
+                        .map(|word| Self::parse_number(Some(word)))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    if numbers.len() % 2 != 0 {
+                        return Err("Cell template inputs must be mean-variance pairs".to_string());
+                    }
+                    input_statistics = numbers.chunks(2).map(|pair| (pair[0], pair[1])).collect();
+                }
+                Some(word) => return Err(format!("Unexpected cell template line: {}", word)),
+            }
+        }
+        if layers.is_empty() {
+            return Err("Cell template has no layers".to_string());
+        }
+        Ok(CellTemplate {
+            energy: energy.ok_or("Cell template has no energy")?,
+            layers,
+            genome_coefficients,
+            genome_transfer_fns,
+            input_statistics,
+        })
+    }
+
+    fn parse_transfer_fns<'a, I>(words: I) -> Result<Vec<(VecIndex, TransferFn)>, String>
+    where
+        I: Iterator<Item = &'a str>,
+    {
+        let words = words.collect::<Vec<_>>();
+        if words.len() % 2 != 0 {
+            return Err("Cell template transfer functions must be node-name pairs".to_string());
+        }
+        words
+            .chunks(2)
+            .map(|pair| {
+                let node = pair[0]
+                    .parse::<VecIndex>()
+                    .map_err(|_| format!("Not a node index: {}", pair[0]))?;
+                let transfer_fn = TransferFn::from_name(pair[1])
+                    .ok_or(format!("Unknown transfer function: {}", pair[1]))?;
+                Ok((node, transfer_fn))
+            })
+            .collect()
+    }
+
+    fn parse_layer<'a, I>(mut words: I) -> Result<LayerTemplate, String>
+    where
+        I: Iterator<Item = &'a str>,
+    {
+        let tissue_name = words.next().unwrap_or("");
+        let tissue = Tissue::ALL
+            .iter()
+            .find(|tissue| format!("{:?}", tissue) == tissue_name)
+            .ok_or(format!("Unknown tissue: {}", tissue_name))?;
+        let area = Self::parse_number(words.next())?;
+        let health = Self::parse_number(words.next())?;
+        if area < 0.0 || !(0.0..=1.0).contains(&health) {
+            return Err(format!("Invalid layer area or health: {} {}", area, health));
+        }
+        Ok(LayerTemplate {
+            tissue: *tissue,
+            area: Area::new(area),
+            health: Health::new(health),
+        })
+    }
+
+    fn parse_number(word: Option<&str>) -> Result<f64, String> {
+        let word = word.unwrap_or("");
+        match word.parse::<f64>() {
+            Ok(number) if number.is_finite() => Ok(number),
+            _ => Err(format!("Not a number: {}", word)),
+        }
+    }
+
+    // Whether the template can be applied to the cell: the same tissues in the same
+    // order, a genome if and only if the cell has one, and the same number of
+    // normalized inputs.
+    pub fn check_matches(&self, cell: &Cell) -> Result<(), String> {
+        let tissues = cell.layers().iter().map(|layer| layer.tissue());
+        if !tissues.eq(self.layers.iter().map(|layer| layer.tissue)) {
+            return Err("Cell template layers don't match the cell's".to_string());
+        }
+        if cell.genome().is_some() != self.genome_coefficients.is_some()
+            || (cell.genome().is_none() && !self.genome_transfer_fns.is_empty())
+        {
+            return Err("Cell template genome doesn't match the cell's control".to_string());
+        }
+        if cell.input_statistics().len() != self.input_statistics.len() {
+            return Err("Cell template inputs don't match the cell's control".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for CellTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "energy {}", self.energy.value())?;
+        for layer in &self.layers {
+            writeln!(
+                f,
+                "layer {:?} {} {}",
+                layer.tissue,
+                layer.area.value(),
+                layer.health.value()
+            )?;
+        }
+        if let Some(coefficients) = &self.genome_coefficients {
+            write!(f, "genome")?;
+            for coefficient in coefficients {
+                write!(f, " {}", coefficient)?;
+            }
+            writeln!(f)?;
+        }
+        if !self.genome_transfer_fns.is_empty() {
+            write!(f, "transfer")?;
+            for (node, transfer_fn) in &self.genome_transfer_fns {
+                write!(f, " {} {:?}", node, transfer_fn)?;
+            }
+            writeln!(f)?;
+        }
+        if !self.input_statistics.is_empty() {
+            write!(f, "inputs")?;
+            for (mean, variance) in &self.input_statistics {
+                write!(f, " {} {}", mean, variance)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn template_text_round_trip() {
+        let template = CellTemplate {
+            energy: BioEnergy::new(12.5),
+            layers: vec![
+                LayerTemplate {
+                    tissue: Tissue::Photosynthetic,
+                    area: Area::new(3.25),
+                    health: Health::new(0.5),
+                },
+                LayerTemplate {
+                    tissue: Tissue::CellWall,
+                    area: Area::new(1.0),
+                    health: Health::FULL,
+                },
+            ],
+            genome_coefficients: Some(vec![1.5, -0.25]),
+            genome_transfer_fns: vec![(2, TransferFn::RELU), (3, TransferFn::TANH)],
+            input_statistics: vec![(100.0, 6.25), (-0.5, 0.125)],
+        };
+
+        let text = format!("# a comment\n{}", template);
+
+        assert_eq!(CellTemplate::parse(&text), Ok(template));
+    }
+
+    #[test]
+    fn parse_rejects_bad_templates() {
+        assert!(CellTemplate::parse("energy 1").is_err());
+        assert!(CellTemplate::parse("energy 1\nlayer Bone 1 1").is_err());
+        assert!(CellTemplate::parse("energy 1\nlayer CellWall 1 2").is_err());
+        assert!(CellTemplate::parse("layer CellWall 1 1").is_err());
+        assert!(CellTemplate::parse("energy x\nlayer CellWall 1 1").is_err());
+        assert!(CellTemplate::parse("energy 1\nlayer CellWall 1 1\ninputs 1 2 3").is_err());
+        assert!(CellTemplate::parse("energy 1\nlayer CellWall 1 1\ntransfer 1 cube").is_err());
+        assert!(CellTemplate::parse("energy 1\nlayer CellWall 1 1\ntransfer 1").is_err());
+    }
+}
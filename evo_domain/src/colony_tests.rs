@@ -0,0 +1,118 @@
+// Grows the budding colony from test_support, one founder budding into a line of bonded
+// cells, and checks it at each milestone. Besides guarding the multi-cell machinery, it
+// shows how its parts fit together: a cell control asks its bonding layer to keep bonds,
+// to bud on a bond that doesn't exist yet, and to give the bud energy; the world turns
+// those requests into new cells and bonds at the end of the tick and reports them to its
+// hooks; and the phylogeny and energy audit record who came from whom and where the
+// energy went.
+
+use crate::biology::cell::Cell;
+use crate::energy_audit::*;
+use crate::physics::node_graph::{GraphEdge, GraphNode};
+use crate::physics::quantities::*;
+use crate::physics::shapes::Circle;
+use crate::test_support::*;
+use crate::world::World;
+use std::sync::{Arc, Mutex};
+
+const TICKS: u64 = 300;
+const FULL_SIZE: usize = 1 + 2 * ColonyControl::GENERATIONS as usize;
+
+#[test]
+fn colony_grows_a_generation_at_a_time() {
+    let mut world = budding_colony();
+    let mut sizes = vec![(0, world.cells().len())];
+
+    for tick in 1..=TICKS {
+        world.tick();
+        if world.cells().len() != sizes.last().unwrap().1 {
+            sizes.push((tick, world.cells().len()));
+        }
+    }
+
+    // the founder buds both ways at once, and the two sides keep pace
+    assert_eq!(
+        sizes.iter().map(|(_, size)| *size).collect::<Vec<_>>(),
+        vec![1, 3, 5, 7, FULL_SIZE]
+    );
+    // the founder starts with no energy, and each bud starts with less than it needs
+    for ((tick1, _), (tick2, _)) in sizes.iter().zip(&sizes[1..]) {
+        assert!(tick2 - tick1 > 10, "{:?}", sizes);
+    }
+}
+
+#[test]
+fn colony_is_a_line_of_bonded_parents_and_children() {
+    let mut world = budding_colony().with_phylogeny();
+
+    tick_colony(&mut world);
+
+    assert_eq!(world.cells().len(), FULL_SIZE);
+    assert_eq!(world.bonds().len(), FULL_SIZE - 1);
+    let phylogeny = world.phylogeny().unwrap();
+    for bond in world.bonds() {
+        let cell1 = world.cell(bond.node1_handle());
+        let cell2 = world.cell(bond.node2_handle());
+        // the world gives the parent's bond to the child as the child's bond 0
+        assert_eq!(
+            phylogeny.node(cell2.cell_id()).unwrap().parent_id,
+            Some(cell1.cell_id())
+        );
+        assert_eq!(cell2.edge_handle(0), bond.edge_handle());
+    }
+    let mut line = world.cells().iter().collect::<Vec<_>>();
+    line.sort_by(|cell1, cell2| cell1.center().x().partial_cmp(&cell2.center().x()).unwrap());
+    for (cell1, cell2) in line.iter().zip(&line[1..]) {
+        assert!(are_bonded(&world, cell1, cell2));
+        assert!(cell1.center().y().abs() < 0.01);
+    }
+    assert_eq!(line[FULL_SIZE / 2].cell_id(), 1);
+}
+
+#[test]
+fn buds_get_their_energy_from_their_parents() {
+    let mut world = budding_colony().with_energy_audit_on_imbalance(OnImbalance::Panic);
+    let births = Arc::new(Mutex::new(vec![]));
+    let hook_births = Arc::clone(&births);
+    world.on_birth(move |parent, child| {
+        hook_births
+            .lock()
+            .unwrap()
+            .push((parent.energy(), child.energy()));
+    });
+    let budding_energy = Arc::new(Mutex::new(0.0));
+    let hook_budding_energy = Arc::clone(&budding_energy);
+    world.on_tick_end(move |world| {
+        let report = world.energy_audit().unwrap().last_report().unwrap();
+        *hook_budding_energy.lock().unwrap() += report.booked(EnergyAudit::BUDDING).value();
+    });
+
+    tick_colony(&mut world);
+
+    let births = births.lock().unwrap();
+    assert_eq!(births.len(), FULL_SIZE - 1);
+    for (parent_energy, child_energy) in births.iter() {
+        assert_eq!(*child_energy, ColonyControl::BUD_ENERGY);
+        // a parent buds once it has saved up enough, and pays for the bud
+        assert!(*parent_energy < ColonyControl::BUDDING_THRESHOLD);
+    }
+    assert_eq!(
+        *budding_energy.lock().unwrap(),
+        (FULL_SIZE - 1) as Value1D * ColonyControl::BUD_ENERGY.value()
+    );
+    assert_eq!(world.energy_audit().unwrap().imbalanced_tick_count(), 0);
+}
+
+fn tick_colony(world: &mut World) {
+    for _ in 0..TICKS {
+        world.tick();
+    }
+}
+
+fn are_bonded(world: &World, cell1: &Cell, cell2: &Cell) -> bool {
+    world.bonds().iter().any(|bond| {
+        (bond.node1_handle() == cell1.node_handle() && bond.node2_handle() == cell2.node_handle())
+            || (bond.node1_handle() == cell2.node_handle()
+                && bond.node2_handle() == cell1.node_handle())
+    })
+}
@@ -0,0 +1,238 @@
+use crate::physics::quantities::*;
+use std::fmt;
+
+// Books every change to the cells' energy during a tick, by kind, and checks at the end
+// of the tick that the books balance: the cells must end the tick with the energy they
+// started with plus everything booked, and every bit of energy a donor paid for its
+// donations must have reached a recipient, a new child, or the tax collector. Likewise,
+// the energy that mass-energy coupled layers spent growing must all have become their
+// mass, and the energy they got back from shrinking must all have come from it.
+#[derive(Debug)]
+pub struct EnergyAudit {
+    on_imbalance: OnImbalance,
+    opening_energy: BioEnergy,
+    ledger: Vec<(&'static str, BioEnergyDelta)>,
+    last_report: Option<EnergyAuditReport>,
+    imbalanced_tick_count: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OnImbalance {
+    Log,
+    Panic,
+}
+
+impl EnergyAudit {
+    // the ledger label that the bonding layer gives donations, and the ones the world
+    // uses for where they went
+    pub const DONATED: &'static str = "donated";
+    pub const DONATION_RECEIVED: &'static str = "donation received";
+    pub const BUDDING: &'static str = "budding";
+    pub const TAX: &'static str = "tax";
+    // the label that mass-energy coupled layers give their growth and shrinkage, and the
+    // one the cells give the energy equivalent of the mass those layers gained or shed
+    pub const BIOMASS_CONVERSION: &'static str = "biomass conversion";
+    pub const BIOMASS: &'static str = "biomass";
+
+    // Floating-point sums of the same changes in a different order differ a little, so
+    // differences this small, relative to the energy involved, are not leaks.
+    const RELATIVE_TOLERANCE: f64 = 1e-9;
+
+    pub fn new(on_imbalance: OnImbalance) -> Self {
+        EnergyAudit {
+            on_imbalance,
+            opening_energy: BioEnergy::ZERO,
+            ledger: vec![],
+            last_report: None,
+            imbalanced_tick_count: 0,
+        }
+    }
+
+    pub fn last_report(&self) -> Option<&EnergyAuditReport> {
+        self.last_report.as_ref()
+    }
+
+    pub fn imbalanced_tick_count(&self) -> u64 {
+        self.imbalanced_tick_count
+    }
+
+    pub fn open(&mut self, total_cell_energy: BioEnergy) {
+        self.opening_energy = total_cell_energy;
+        self.ledger.clear();
+    }
+
+    // The tax and biomass aren't changes to any cell's energy, so they are only used to
+    // check the donations and biomass conversions.
+    pub fn book(&mut self, label: &'static str, delta: BioEnergyDelta) {
+        match self.ledger.iter_mut().find(|(l, _)| *l == label) {
+            Some((_, total)) => *total += delta,
+            None => self.ledger.push((label, delta)),
+        }
+    }
+
+    pub fn close(&mut self, tick: u64, total_cell_energy: BioEnergy) -> &EnergyAuditReport {
+        let report = EnergyAuditReport {
+            tick,
+            opening_energy: self.opening_energy,
+            closing_energy: total_cell_energy,
+            ledger: std::mem::take(&mut self.ledger),
+        };
+        if !report.is_balanced() {
+            self.imbalanced_tick_count += 1;
+            match self.on_imbalance {
+                OnImbalance::Log => eprintln!("{}", report),
+                OnImbalance::Panic => panic!("{}", report),
+            }
+        }
+        self.last_report = Some(report);
+        self.last_report.as_ref().unwrap()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnergyAuditReport {
+    pub tick: u64,
+    pub opening_energy: BioEnergy,
+    pub closing_energy: BioEnergy,
+    // total change of each kind, in the order first booked
+    pub ledger: Vec<(&'static str, BioEnergyDelta)>,
+}
+
+impl EnergyAuditReport {
+    pub fn booked(&self, label: &str) -> BioEnergyDelta {
+        self.ledger
+            .iter()
+            .find(|(l, _)| *l == label)
+            .map_or(BioEnergyDelta::ZERO, |(_, delta)| *delta)
+    }
+
+    // How much more energy the cells ended with than the books say they should have.
+    pub fn unbooked_energy(&self) -> f64 {
+        let booked = self
+            .ledger
+            .iter()
+            .filter(|(label, _)| *label != EnergyAudit::TAX && *label != EnergyAudit::BIOMASS)
+            .map(|(_, delta)| delta.value())
+            .sum::<f64>();
+        self.closing_energy.value() - self.opening_energy.value() - booked
+    }
+
+    // How much of what donors paid went nowhere.
+    pub fn lost_donation_energy(&self) -> f64 {
+        -(self.booked(EnergyAudit::DONATED).value()
+            + self.booked(EnergyAudit::DONATION_RECEIVED).value()
+            + self.booked(EnergyAudit::BUDDING).value()
+            + self.booked(EnergyAudit::TAX).value())
+    }
+
+    // How much of the energy that coupled layers converted didn't match their change in
+    // mass: positive if mass appeared from nowhere, negative if energy did.
+    pub fn unconverted_biomass_energy(&self) -> f64 {
+        self.booked(EnergyAudit::BIOMASS).value()
+            + self.booked(EnergyAudit::BIOMASS_CONVERSION).value()
+    }
+
+    pub fn is_balanced(&self) -> bool {
+        let scale = self.opening_energy.value()
+            + self.closing_energy.value()
+            + self
+                .ledger
+                .iter()
+                .map(|(_, delta)| delta.value().abs())
+                .sum::<f64>();
+        let tolerance = EnergyAudit::RELATIVE_TOLERANCE * scale.max(1.0);
+        self.unbooked_energy().abs() <= tolerance
+            && self.lost_donation_energy().abs() <= tolerance
+            && self.unconverted_biomass_energy().abs() <= tolerance
+    }
+}
+
+impl fmt::Display for EnergyAuditReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Energy audit, tick {}: {}",
+            self.tick,
+            if self.is_balanced() {
+                "balanced"
+            } else {
+                "IMBALANCED"
+            }
+        )?;
+        writeln!(f, "  opening: {:.6}", self.opening_energy.value())?;
+        for (label, delta) in &self.ledger {
+            writeln!(f, "  {}: {:+.6}", label, delta.value())?;
+        }
+        writeln!(f, "  closing: {:.6}", self.closing_energy.value())?;
+        writeln!(f, "  unbooked: {:+.6e}", self.unbooked_energy())?;
+        writeln!(f, "  lost donations: {:+.6e}", self.lost_donation_energy())?;
+        write!(
+            f,
+            "  unconverted biomass: {:+.6e}",
+            self.unconverted_biomass_energy()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn booked_changes_balance() {
+        let mut audit = EnergyAudit::new(OnImbalance::Panic);
+
+        audit.open(BioEnergy::new(10.0));
+        audit.book("photo", BioEnergyDelta::new(2.0));
+        audit.book(EnergyAudit::DONATED, BioEnergyDelta::new(-1.25));
+        audit.book(EnergyAudit::DONATION_RECEIVED, BioEnergyDelta::new(1.0));
+        audit.book(EnergyAudit::TAX, BioEnergyDelta::new(0.25));
+        audit.book("photo", BioEnergyDelta::new(1.0));
+        let report = audit.close(1, BioEnergy::new(12.75));
+
+        assert!(report.is_balanced());
+        assert_eq!(report.booked("photo"), BioEnergyDelta::new(3.0));
+        assert_eq!(audit.imbalanced_tick_count(), 0);
+    }
+
+    #[test]
+    fn unbooked_energy_is_imbalance() {
+        let mut audit = EnergyAudit::new(OnImbalance::Log);
+
+        audit.open(BioEnergy::new(10.0));
+        audit.book("photo", BioEnergyDelta::new(2.0));
+        let report = audit.close(1, BioEnergy::new(11.0));
+
+        assert!(!report.is_balanced());
+        assert_eq!(report.unbooked_energy(), -1.0);
+        assert_eq!(audit.imbalanced_tick_count(), 1);
+    }
+
+    #[test]
+    fn biomass_conversion_without_matching_mass_is_imbalance() {
+        let mut audit = EnergyAudit::new(OnImbalance::Log);
+
+        audit.open(BioEnergy::new(10.0));
+        audit.book(EnergyAudit::BIOMASS_CONVERSION, BioEnergyDelta::new(-2.0));
+        audit.book(EnergyAudit::BIOMASS, BioEnergyDelta::new(1.5));
+        let report = audit.close(1, BioEnergy::new(8.0));
+
+        assert_eq!(report.unbooked_energy(), 0.0);
+        assert_eq!(report.unconverted_biomass_energy(), -0.5);
+        assert!(!report.is_balanced());
+    }
+
+    #[test]
+    fn donation_that_goes_nowhere_is_imbalance() {
+        let mut audit = EnergyAudit::new(OnImbalance::Log);
+
+        audit.open(BioEnergy::new(10.0));
+        audit.book(EnergyAudit::DONATED, BioEnergyDelta::new(-2.0));
+        audit.book(EnergyAudit::DONATION_RECEIVED, BioEnergyDelta::new(1.5));
+        let report = audit.close(1, BioEnergy::new(9.5));
+
+        assert_eq!(report.unbooked_energy(), 0.0);
+        assert_eq!(report.lost_donation_energy(), 0.5);
+        assert!(!report.is_balanced());
+    }
+}
@@ -1,2 +1,5 @@
+pub mod chemical_field;
+pub mod force_registry;
 pub mod influences;
 pub mod local_environment;
+pub mod tax_recycling;
@@ -0,0 +1,205 @@
+use crate::physics::quantities::*;
+
+// The concentration of a nutrient over the world, on a grid of patches, each uniform
+// inside. Like sunlight, it feeds cells, but it varies in both dimensions, is used up
+// where cells absorb it, and spreads each tick by diffusing into neighboring patches,
+// so cells can find food by moving up its gradient.
+#[derive(Clone, Debug)]
+pub struct ChemicalField {
+    min_corner: Position,
+    patch_width: Value1D,
+    patch_height: Value1D,
+    columns: usize,
+    rows: usize,
+    concentrations: Vec<Value1D>,
+    diffusion_rate: Fraction,
+}
+
+impl ChemicalField {
+    // A diffusion rate of 1 evens out neighboring patches the fastest without making the
+    // concentrations oscillate.
+    pub fn new(
+        min_corner: Position,
+        max_corner: Position,
+        columns: usize,
+        rows: usize,
+        diffusion_rate: Fraction,
+    ) -> Self {
+        assert!(columns > 0 && rows > 0);
+        ChemicalField {
+            min_corner,
+            patch_width: (max_corner.x() - min_corner.x()) / columns as Value1D,
+            patch_height: (max_corner.y() - min_corner.y()) / rows as Value1D,
+            columns,
+            rows,
+            concentrations: vec![0.0; columns * rows],
+            diffusion_rate,
+        }
+    }
+
+    pub fn with_uniform_concentration(mut self, concentration: Value1D) -> Self {
+        assert!(concentration >= 0.0);
+        self.concentrations = vec![concentration; self.columns * self.rows];
+        self
+    }
+
+    // Sets the concentration of the patch containing the position.
+    pub fn with_concentration_at(mut self, position: Position, concentration: Value1D) -> Self {
+        assert!(concentration >= 0.0);
+        let index = self.patch_index(position);
+        self.concentrations[index] = concentration;
+        self
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    // row 0 is the bottom of the world
+    pub fn concentrations(&self) -> &[Value1D] {
+        &self.concentrations
+    }
+
+    // Replaces all the concentrations, in the same order concentrations() returns them.
+    pub fn set_concentrations(&mut self, concentrations: &[Value1D]) -> Result<(), String> {
+        if concentrations.len() != self.concentrations.len() {
+            return Err(format!(
+                "Chemical field has {} patches, not {}",
+                self.concentrations.len(),
+                concentrations.len()
+            ));
+        }
+        if !concentrations
+            .iter()
+            .all(|&concentration| concentration >= 0.0)
+        {
+            return Err("Chemical concentrations must be nonnegative".to_string());
+        }
+        self.concentrations = concentrations.to_vec();
+        Ok(())
+    }
+
+    pub fn concentration_at(&self, position: Position) -> Value1D {
+        self.concentrations[self.patch_index(position)]
+    }
+
+    // The amount of nutrient in the whole field, i.e. concentration times area.
+    pub fn total_amount(&self) -> Value1D {
+        self.concentrations.iter().sum::<Value1D>() * self.patch_width * self.patch_height
+    }
+
+    // Takes an amount of nutrient out of the patch containing the position, spreading it
+    // over the patch's area. A patch can't go below zero, so an amount larger than the
+    // patch holds takes only what is there.
+    pub fn remove_amount_at(&mut self, position: Position, amount: Value1D) {
+        let index = self.patch_index(position);
+        let concentration = &mut self.concentrations[index];
+        *concentration =
+            (*concentration - amount / (self.patch_width * self.patch_height)).max(0.0);
+    }
+
+    // Adds an amount of nutrient to the patch containing the position, spread over the
+    // patch's area.
+    pub fn add_amount_at(&mut self, position: Position, amount: Value1D) {
+        let index = self.patch_index(position);
+        self.concentrations[index] += amount / (self.patch_width * self.patch_height);
+    }
+
+    // Moves nutrient between each pair of side-by-side patches in proportion to their
+    // difference in concentration. Nothing leaves through the edges of the world.
+    pub fn diffuse(&mut self) {
+        let rate = self.diffusion_rate.value() / 4.0;
+        let old = self.concentrations.clone();
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                let index = row * self.columns + column;
+                let mut flow = 0.0;
+                if column > 0 {
+                    flow += old[index - 1] - old[index];
+                }
+                if column + 1 < self.columns {
+                    flow += old[index + 1] - old[index];
+                }
+                if row > 0 {
+                    flow += old[index - self.columns] - old[index];
+                }
+                if row + 1 < self.rows {
+                    flow += old[index + self.columns] - old[index];
+                }
+                self.concentrations[index] = old[index] + rate * flow;
+            }
+        }
+    }
+
+    // Positions outside the world count as in the nearest edge patch.
+    fn patch_index(&self, position: Position) -> usize {
+        let column = Self::clamped_patch(
+            (position.x() - self.min_corner.x()) / self.patch_width,
+            self.columns,
+        );
+        let row = Self::clamped_patch(
+            (position.y() - self.min_corner.y()) / self.patch_height,
+            self.rows,
+        );
+        row * self.columns + column
+    }
+
+    fn clamped_patch(offset: Value1D, count: usize) -> usize {
+        (offset.floor().max(0.0) as usize).min(count - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn concentration_is_per_patch() {
+        let field = field(Fraction::ONE).with_concentration_at(Position::new(2.5, -0.5), 3.0);
+
+        assert_eq!(field.concentration_at(Position::new(2.1, -0.9)), 3.0);
+        assert_eq!(field.concentration_at(Position::new(1.9, -0.9)), 0.0);
+        assert_eq!(field.concentration_at(Position::new(2.5, 5.0)), 3.0);
+        assert_eq!(field.concentrations()[3 * 4 + 2], 3.0);
+    }
+
+    #[test]
+    fn diffusion_spreads_to_neighbors_and_conserves_total() {
+        let mut field =
+            field(Fraction::new(0.5)).with_concentration_at(Position::new(1.5, -2.5), 8.0);
+
+        field.diffuse();
+
+        assert_eq!(field.concentration_at(Position::new(1.5, -2.5)), 4.0);
+        assert_eq!(field.concentration_at(Position::new(0.5, -2.5)), 1.0);
+        assert_eq!(field.concentration_at(Position::new(1.5, -1.5)), 1.0);
+        assert_eq!(field.concentration_at(Position::new(0.5, -1.5)), 0.0);
+        assert_eq!(field.total_amount(), 8.0);
+    }
+
+    #[test]
+    fn remove_amount_never_goes_negative() {
+        let mut field = field(Fraction::ONE).with_uniform_concentration(1.0);
+
+        field.remove_amount_at(Position::new(0.5, -0.5), 0.25);
+        field.remove_amount_at(Position::new(1.5, -0.5), 5.0);
+
+        assert_eq!(field.concentration_at(Position::new(0.5, -0.5)), 0.75);
+        assert_eq!(field.concentration_at(Position::new(1.5, -0.5)), 0.0);
+    }
+
+    // 4x4 unit patches
+    fn field(diffusion_rate: Fraction) -> ChemicalField {
+        ChemicalField::new(
+            Position::new(0.0, -4.0),
+            Position::new(4.0, 0.0),
+            4,
+            4,
+            diffusion_rate,
+        )
+    }
+}
@@ -0,0 +1,250 @@
+use crate::environment::influences::*;
+use crate::physics::quantities::*;
+use std::cell::RefCell;
+use std::collections::HashSet;
+
+pub type ForceFactory =
+    Box<dyn Fn(&ForceArgs) -> Result<Box<dyn SimpleInfluenceForce>, String> + Send + Sync>;
+
+// Makes SimpleInfluenceForces by name, so that a run can be given forces without
+// recompiling, e.g. from the command line, and so that other crates can add their own
+// forces, such as tides or attractors, without changing this one. A force is asked for
+// with a spec such as "weight:gravity=-0.05" or "constant:x=0,y=-0.1".
+pub struct ForceRegistry {
+    factories: Vec<(String, ForceFactory)>,
+}
+
+impl ForceRegistry {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        ForceRegistry { factories: vec![] }
+    }
+
+    pub fn with_standard_forces() -> Self {
+        ForceRegistry::new()
+            .with_force("constant", |args| {
+                Ok(Box::new(ConstantForce::new(Force::new(
+                    args.get("x", 0.0),
+                    args.get("y", 0.0),
+                ))))
+            })
+            .with_force("weight", |args| {
+                Ok(Box::new(WeightForce::new(args.require("gravity")?)))
+            })
+            .with_force("buoyancy", |args| {
+                Ok(Box::new(BuoyancyForce::new(
+                    args.require("gravity")?,
+                    args.require("fluid_density")?,
+                )))
+            })
+            .with_force("drag", |args| {
+                Ok(Box::new(DragForce::new(args.require("viscosity")?)))
+            })
+    }
+
+    pub fn with_force<F>(mut self, name: &str, factory: F) -> Self
+    where
+        F: Fn(&ForceArgs) -> Result<Box<dyn SimpleInfluenceForce>, String> + Send + Sync + 'static,
+    {
+        self.register(name, factory);
+        self
+    }
+
+    // Replaces any force already registered under the name.
+    pub fn register<F>(&mut self, name: &str, factory: F)
+    where
+        F: Fn(&ForceArgs) -> Result<Box<dyn SimpleInfluenceForce>, String> + Send + Sync + 'static,
+    {
+        self.factories
+            .retain(|(factory_name, _)| factory_name != name);
+        self.factories.push((name.to_string(), Box::new(factory)));
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        let mut names = self
+            .factories
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        names
+    }
+
+    pub fn create(&self, spec: &str) -> Result<Box<dyn SimpleInfluenceForce>, String> {
+        let (name, args) = Self::parse_spec(spec)?;
+        let factory = self
+            .factories
+            .iter()
+            .find(|(factory_name, _)| *factory_name == name)
+            .map(|(_, factory)| factory)
+            .ok_or_else(|| {
+                format!(
+                    "Unknown force: {} (known: {})",
+                    name,
+                    self.names().join(", ")
+                )
+            })?;
+        let force = factory(&args).map_err(|message| format!("{}: {}", spec, message))?;
+        args.check_all_read()
+            .map_err(|message| format!("{}: {}", spec, message))?;
+        Ok(force)
+    }
+
+    pub fn create_influence(&self, spec: &str) -> Result<Box<dyn PerCellInfluence>, String> {
+        Ok(Box::new(SimpleForceInfluence::new(self.create(spec)?)))
+    }
+
+    // e.g. "drag" or "buoyancy:gravity=-0.05,fluid_density=0.001"
+    fn parse_spec(spec: &str) -> Result<(&str, ForceArgs), String> {
+        let mut parts = spec.splitn(2, ':');
+        let name = parts.next().unwrap_or("").trim();
+        if name.is_empty() {
+            return Err(format!("No force name in \"{}\"", spec));
+        }
+        let values = match parts.next() {
+            None => vec![],
+            Some(args) => args
+                .split(',')
+                .map(|arg| {
+                    let mut arg_parts = arg.splitn(2, '=');
+                    match (
+                        arg_parts.next().map(str::trim),
+                        arg_parts.next().map(|value| value.trim().parse::<f64>()),
+                    ) {
+                        (Some(arg_name), Some(Ok(value)))
+                            if !arg_name.is_empty() && value.is_finite() =>
+                        {
+                            Ok((arg_name.to_string(), value))
+                        }
+                        _ => Err(format!(
+                            "Not a name=value pair in \"{}\": \"{}\"",
+                            spec, arg
+                        )),
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+        };
+        Ok((name, ForceArgs::new(values)))
+    }
+}
+
+// The name=value pairs of a force spec. A value that its force doesn't read is an
+// error rather than silently ignored, so that typos show up.
+pub struct ForceArgs {
+    values: Vec<(String, f64)>,
+    names_read: RefCell<HashSet<String>>,
+}
+
+impl ForceArgs {
+    pub fn new(values: Vec<(String, f64)>) -> Self {
+        ForceArgs {
+            values,
+            names_read: RefCell::new(HashSet::new()),
+        }
+    }
+
+    // The last value given for the name, or else the default.
+    pub fn get(&self, name: &str, default_value: f64) -> f64 {
+        self.find(name).unwrap_or(default_value)
+    }
+
+    pub fn require(&self, name: &str) -> Result<f64, String> {
+        self.find(name)
+            .ok_or_else(|| format!("Missing value for {}", name))
+    }
+
+    fn find(&self, name: &str) -> Option<f64> {
+        self.names_read.borrow_mut().insert(name.to_string());
+        self.values
+            .iter()
+            .rev()
+            .find(|(value_name, _)| value_name == name)
+            .map(|(_, value)| *value)
+    }
+
+    fn check_all_read(&self) -> Result<(), String> {
+        let names_read = self.names_read.borrow();
+        match self
+            .values
+            .iter()
+            .find(|(name, _)| !names_read.contains(name))
+        {
+            Some((name, _)) => {
+                let mut known_names = names_read.iter().cloned().collect::<Vec<_>>();
+                known_names.sort();
+                Err(format!(
+                    "Unknown value: {} (known: {})",
+                    name,
+                    known_names.join(", ")
+                ))
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biology::cell::Cell;
+    use crate::physics::shapes::Circle;
+
+    #[test]
+    fn creates_standard_force_from_spec() {
+        let registry = ForceRegistry::with_standard_forces();
+        let cell = Cell::ball(
+            Length::new(1.0),
+            Mass::new(2.0),
+            Position::ORIGIN,
+            Velocity::ZERO,
+        );
+
+        let force = registry.create("weight:gravity=-0.5").unwrap();
+
+        assert_eq!(force.calc_force(&cell), Force::new(0.0, -1.0));
+        assert_eq!(force.label(), "weight");
+    }
+
+    #[test]
+    fn creates_registered_force() {
+        struct Tide {
+            strength: Value1D,
+        }
+        impl SimpleInfluenceForce for Tide {
+            fn calc_force(&self, cell: &Cell) -> Force {
+                Force::new(-self.strength * cell.center().x(), 0.0)
+            }
+
+            fn label(&self) -> &'static str {
+                "tide"
+            }
+        }
+        let registry = ForceRegistry::with_standard_forces().with_force("tide", |args| {
+            Ok(Box::new(Tide {
+                strength: args.get("strength", 1.0),
+            }))
+        });
+        let cell = Cell::ball(
+            Length::new(1.0),
+            Mass::new(1.0),
+            Position::new(2.0, 0.0),
+            Velocity::ZERO,
+        );
+
+        let force = registry.create("tide:strength=0.25").unwrap();
+
+        assert_eq!(force.calc_force(&cell), Force::new(-0.5, 0.0));
+        assert!(registry.names().contains(&"tide"));
+    }
+
+    #[test]
+    fn rejects_bad_specs() {
+        let registry = ForceRegistry::with_standard_forces();
+
+        assert!(registry.create("gravity").is_err());
+        assert!(registry.create("weight").is_err());
+        assert!(registry.create("weight:gravity").is_err());
+        assert!(registry.create("drag:viscosity=0.1,visocsity=0.2").is_err());
+        assert!(registry.create("constant:y=-1").is_ok());
+    }
+}
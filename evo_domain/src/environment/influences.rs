@@ -1,14 +1,19 @@
 use crate::biology::cell::Cell;
 use crate::environment::local_environment::*;
 use crate::physics::bond::*;
+use crate::physics::handles::Handle;
 use crate::physics::newtonian::*;
 use crate::physics::node_graph::*;
 use crate::physics::overlap::*;
 use crate::physics::quantities::*;
 use crate::physics::shapes::Circle;
+use crate::physics::shards::find_pair_overlaps_sharded;
 use crate::physics::util::*;
+use rand::SeedableRng;
+use rand_distr::{Distribution, Normal};
+use rand_pcg::Pcg64Mcg;
 
-pub trait CrossCellInfluence {
+pub trait CrossCellInfluence: Send {
     fn apply_to(
         &self,
         cell_graph: &mut NodeGraph<Cell, Bond<Cell>, AngleGusset>,
@@ -29,7 +34,14 @@ impl WallCollisions {
     }
 
     fn add_overlap_and_force(&self, cell: &mut Cell, overlap: Overlap) {
-        cell.environment_mut().add_overlap(overlap);
+        let closing_speed = -cell
+            .velocity()
+            .value()
+            .dot(overlap.incursion().value().to_unit_vector());
+        cell.environment_mut()
+            .add_overlap(overlap.with_closing_speed(closing_speed));
+        cell.environment_mut()
+            .add_wall_contact_normal(overlap.incursion().value().to_unit_vector());
         let force = Self::collision_force(cell.mass(), cell.velocity(), -overlap.incursion());
         cell.net_force_mut()
             .add_dominant_force(force, "wall collision");
@@ -70,17 +82,36 @@ impl CrossCellInfluence for WallCollisions {
 #[derive(Debug)]
 pub struct PairCollisions {
     force_adjustment_factor: Fraction,
+    shard_count: usize,
 }
 
 impl PairCollisions {
     pub fn new(force_adjustment_factor: Fraction) -> Self {
         PairCollisions {
             force_adjustment_factor,
+            shard_count: 1,
         }
     }
 
-    fn add_overlap(cell: &mut Cell, overlap: Overlap) {
-        cell.environment_mut().add_overlap(overlap);
+    // Experimental: finds the overlapping pairs in this many vertical strips of the world
+    // at once, on separate threads. See find_pair_overlaps_sharded.
+    pub fn with_shards(mut self, shard_count: usize) -> Self {
+        self.shard_count = shard_count;
+        self
+    }
+
+    fn closing_speed(cell1: &Cell, cell2: &Cell, overlap1: Overlap) -> Value1D {
+        if overlap1.incursion() == Displacement::ZERO {
+            return 0.0;
+        }
+        let relative_velocity1 = cell1.velocity() - cell2.velocity();
+        -relative_velocity1
+            .value()
+            .dot(overlap1.incursion().value().to_unit_vector())
+    }
+
+    fn add_overlap(cell: &mut Cell, other_cell: Handle<Cell>, overlap: Overlap) {
+        cell.environment_mut().add_cell_overlap(other_cell, overlap);
     }
 
     fn add_forces(&self, cell1: &mut Cell, cell2: &mut Cell, overlap1: Overlap) {
@@ -154,10 +185,24 @@ impl CrossCellInfluence for PairCollisions {
         cell_graph: &mut NodeGraph<Cell, Bond<Cell>, AngleGusset>,
         circle_handles: &mut SortableHandles<Cell>,
     ) {
-        let overlaps = find_pair_overlaps(cell_graph, circle_handles);
+        let overlaps = if self.shard_count > 1 {
+            find_pair_overlaps_sharded(cell_graph, self.shard_count)
+        } else {
+            find_pair_overlaps(cell_graph, circle_handles)
+        };
         for ((handle1, overlap1), (handle2, overlap2)) in overlaps {
-            Self::add_overlap(cell_graph.node_mut(handle1), overlap1);
-            Self::add_overlap(cell_graph.node_mut(handle2), overlap2);
+            let closing_speed =
+                Self::closing_speed(cell_graph.node(handle1), cell_graph.node(handle2), overlap1);
+            Self::add_overlap(
+                cell_graph.node_mut(handle1),
+                handle2,
+                overlap1.with_closing_speed(closing_speed),
+            );
+            Self::add_overlap(
+                cell_graph.node_mut(handle2),
+                handle1,
+                overlap2.with_closing_speed(closing_speed),
+            );
 
             if overlap1.incursion() == Displacement::ZERO {
                 continue;
@@ -185,6 +230,18 @@ impl BondForces {
         Self::update_net_force(cell2, -cell1_velocity_force, -cell1_strain_force);
     }
 
+    // So that the cell's control can sense the strain on each of its bonds.
+    fn record_strain(cell: &mut Cell, bond_handle: EdgeHandle, strain: BondStrain) {
+        if let Some(bond_index) = cell
+            .edge_handles()
+            .iter()
+            .position(|handle| *handle == Some(bond_handle))
+        {
+            cell.environment_mut()
+                .set_bond_strain(bond_index, strain.strain().length().value());
+        }
+    }
+
     fn cell1_forces(cell1: &Cell, cell2: &Cell, strain1: BondStrain) -> (Force, Force) {
         let velocity_force = Self::body1_stop_velocity_force(
             cell1.mass(),
@@ -233,9 +290,18 @@ impl CrossCellInfluence for BondForces {
         _cell_handles: &mut SortableHandles<Cell>,
     ) {
         let strains = calc_bond_strains(cell_graph);
-        for ((handle1, strain1), (handle2, _strain2)) in strains {
+        let bond_handles = cell_graph
+            .edges()
+            .iter()
+            .map(|bond| bond.edge_handle())
+            .collect::<Vec<_>>();
+        for (((handle1, strain1), (handle2, _strain2)), bond_handle) in
+            strains.into_iter().zip(bond_handles)
+        {
             cell_graph.with_nodes(handle1, handle2, |cell1, cell2| {
                 Self::add_forces(cell1, cell2, strain1);
+                Self::record_strain(cell1, bond_handle, strain1);
+                Self::record_strain(cell2, bond_handle, strain1);
             });
         }
     }
@@ -257,17 +323,103 @@ impl CrossCellInfluence for BondAngleForces {
         cell_graph: &mut NodeGraph<Cell, Bond<Cell>, AngleGusset>,
         _cell_handles: &mut SortableHandles<Cell>,
     ) {
-        let forces = calc_bond_angle_forces(cell_graph);
+        let (forces, torques) = calc_bond_angle_forces(cell_graph);
         for (handle, force) in forces {
             let cell = cell_graph.node_mut(handle);
             cell.net_force_mut()
                 .add_non_dominant_force(force, "bond angle");
         }
+        for (handle, torque) in torques {
+            cell_graph
+                .node_mut(handle)
+                .net_force_mut()
+                .add_torque(torque);
+        }
+    }
+}
+
+// Lets organisms assemble themselves from cells that meet, rather than only by budding.
+// Bonds each pair of overlapping cells whose controls both asked, in their last ticks,
+// to retain a bond in an empty slot, in the first such slot of each. Also snaps any bond
+// strained past the breaking strain, which the world then breaks even if its cells
+// retain it. Must come after the pair collisions, which find the overlaps.
+#[derive(Debug)]
+pub struct BondFormation {
+    breaking_strain: Length,
+}
+
+impl BondFormation {
+    pub fn new(breaking_strain: Length) -> Self {
+        BondFormation { breaking_strain }
+    }
+
+    fn snap_overstrained_bonds(&self, cell_graph: &mut NodeGraph<Cell, Bond<Cell>, AngleGusset>) {
+        let overstrained_bonds = cell_graph
+            .edges()
+            .iter()
+            .filter(|bond| {
+                let strain = calc_bond_strain(
+                    cell_graph.node(bond.node1_handle()),
+                    cell_graph.node(bond.node2_handle()),
+                );
+                strain.strain().length() > self.breaking_strain
+            })
+            .map(|bond| (bond.edge_handle(), bond.node1_handle(), bond.node2_handle()))
+            .collect::<Vec<_>>();
+        for (bond_handle, cell1_handle, cell2_handle) in overstrained_bonds {
+            for cell_handle in [cell1_handle, cell2_handle] {
+                let cell = cell_graph.node_mut(cell_handle);
+                if let Some(index) = cell.graph_node_data().index_of_edge_handle(bond_handle) {
+                    cell.snap_bond(index);
+                }
+            }
+        }
+    }
+
+    fn form_bonds(cell_graph: &mut NodeGraph<Cell, Bond<Cell>, AngleGusset>) {
+        let candidate_pairs = cell_graph
+            .nodes()
+            .iter()
+            .filter(|cell| cell.open_bond_slot().is_some())
+            .flat_map(|cell| {
+                let handle = cell.node_handle();
+                cell.environment()
+                    .cell_overlaps()
+                    .iter()
+                    .filter(move |(other_handle, _)| *other_handle > handle)
+                    .map(move |(other_handle, _)| (handle, *other_handle))
+            })
+            .collect::<Vec<_>>();
+        for (handle1, handle2) in candidate_pairs {
+            let cell1 = cell_graph.node(handle1);
+            let cell2 = cell_graph.node(handle2);
+            // an earlier pair may have taken one of the slots
+            if let (Some(slot1), Some(slot2)) = (cell1.open_bond_slot(), cell2.open_bond_slot()) {
+                let bond = Bond::new(cell1, cell2);
+                cell_graph.add_edge(bond, slot1, slot2);
+            }
+        }
+    }
+}
+
+impl CrossCellInfluence for BondFormation {
+    fn apply_to(
+        &self,
+        cell_graph: &mut NodeGraph<Cell, Bond<Cell>, AngleGusset>,
+        _cell_handles: &mut SortableHandles<Cell>,
+    ) {
+        self.snap_overstrained_bonds(cell_graph);
+        Self::form_bonds(cell_graph);
     }
 }
 
 pub trait PerCellInfluence: Send + Sync {
     fn apply_to(&self, cell: &mut Cell);
+
+    // the light this influence gives a cell centered at the position, if it gives any
+    fn light_intensity_at(&self, _position: Position) -> Value1D {
+        0.0
+    }
 }
 
 pub struct SimpleForceInfluence {
@@ -283,14 +435,20 @@ impl SimpleForceInfluence {
 impl PerCellInfluence for SimpleForceInfluence {
     fn apply_to(&self, cell: &mut Cell) {
         let force = self.influence_force.calc_force(cell);
-        cell.net_force_mut()
-            .add_non_dominant_force(force, self.influence_force.label());
+        let torque = self.influence_force.calc_torque(cell);
+        let net_force = cell.net_force_mut();
+        net_force.add_non_dominant_force(force, self.influence_force.label());
+        net_force.add_torque(torque);
     }
 }
 
 pub trait SimpleInfluenceForce: Send + Sync {
     fn calc_force(&self, cell: &Cell) -> Force;
 
+    fn calc_torque(&self, _cell: &Cell) -> Torque {
+        Torque::ZERO
+    }
+
     fn label(&self) -> &'static str;
 }
 
@@ -388,6 +546,22 @@ impl DragForce {
     fn abs_drag_that_will_stop_the_cell(mass: Mass, velocity: Value1D) -> Value1D {
         mass.value() * velocity.abs()
     }
+
+    // The drag on the cell's rim, at the speed it is spinning, about the cell's center.
+    fn calc_spin_drag(
+        &self,
+        moment_of_inertia: MomentOfInertia,
+        radius: Length,
+        angular_velocity: AngularVelocity,
+    ) -> Torque {
+        let radians = angular_velocity.radians();
+        let instantaneous_abs_drag =
+            self.instantaneous_abs_drag(radius, radians * radius.value()) * radius.value();
+        let abs_drag_that_will_stop_the_cell = moment_of_inertia.value() * radians.abs();
+        Torque::new(
+            -radians.signum() * instantaneous_abs_drag.min(abs_drag_that_will_stop_the_cell),
+        )
+    }
 }
 
 impl SimpleInfluenceForce for DragForce {
@@ -398,11 +572,72 @@ impl SimpleInfluenceForce for DragForce {
         )
     }
 
+    fn calc_torque(&self, cell: &Cell) -> Torque {
+        self.calc_spin_drag(
+            cell.moment_of_inertia(),
+            cell.radius(),
+            cell.angular_velocity(),
+        )
+    }
+
     fn label(&self) -> &'static str {
         "drag"
     }
 }
 
+// Small random kicks, like Brownian motion, to break up perfectly symmetric equilibria
+// and to model small-scale turbulence. Each force component is normally distributed with
+// the magnitude as its standard deviation. The randomness comes from the seed and the
+// cell's ID and own state, not a shared generator, so cells can be kicked in parallel
+// and runs are still reproducible, and cells in the same state are kicked differently.
+#[derive(Debug)]
+pub struct ThermalNoiseForce {
+    distribution: Normal<Value1D>,
+    seed: u64,
+}
+
+impl ThermalNoiseForce {
+    pub fn new(magnitude: Value1D, seed: u64) -> Self {
+        ThermalNoiseForce {
+            distribution: Normal::new(0.0, magnitude).expect("Invalid thermal noise magnitude"),
+            seed,
+        }
+    }
+
+    fn cell_seed(&self, cell: &Cell) -> u64 {
+        let position = cell.position();
+        let velocity = cell.velocity();
+        [
+            cell.cell_id(),
+            position.x().to_bits(),
+            position.y().to_bits(),
+            velocity.x().to_bits(),
+            velocity.y().to_bits(),
+            cell.ticks_lived(),
+        ]
+        .iter()
+        .fold(self.seed, |hash, bits| {
+            (hash ^ bits)
+                .wrapping_mul(0x9E37_79B9_7F4A_7C15)
+                .rotate_left(31)
+        })
+    }
+}
+
+impl SimpleInfluenceForce for ThermalNoiseForce {
+    fn calc_force(&self, cell: &Cell) -> Force {
+        let mut rng = Pcg64Mcg::seed_from_u64(self.cell_seed(cell));
+        Force::new(
+            self.distribution.sample(&mut rng),
+            self.distribution.sample(&mut rng),
+        )
+    }
+
+    fn label(&self) -> &'static str {
+        "noise"
+    }
+}
+
 #[derive(Debug)]
 pub struct UniversalOverlap {
     overlap: Overlap,
@@ -420,6 +655,80 @@ impl PerCellInfluence for UniversalOverlap {
     }
 }
 
+// Friction from the bottom wall. Resists sideways motion of cells touching the
+// bottom, up to a limit proportional to the cell's mass plus whatever grip the cell
+// is exerting. Add it after any other per-cell forces so it can resist them too.
+#[derive(Debug)]
+pub struct SubstrateFriction {
+    friction_coefficient: Value1D,
+}
+
+impl SubstrateFriction {
+    pub fn new(friction_coefficient: Value1D) -> Self {
+        assert!(
+            friction_coefficient >= 0.0 && friction_coefficient.is_finite(),
+            "Invalid friction coefficient: {}",
+            friction_coefficient
+        );
+        SubstrateFriction {
+            friction_coefficient,
+        }
+    }
+
+    fn calc_friction(&self, cell: &Cell) -> Value1D {
+        let max_friction = self.friction_coefficient * cell.mass().value() + cell.grip();
+        let force_that_will_stop_the_cell =
+            -(cell.mass().value() * cell.velocity().x() + cell.net_force().net_force().x());
+        force_that_will_stop_the_cell.clamp(-max_friction, max_friction)
+    }
+}
+
+impl PerCellInfluence for SubstrateFriction {
+    fn apply_to(&self, cell: &mut Cell) {
+        if cell.environment().is_touching_bottom_wall() {
+            let friction = self.calc_friction(cell);
+            cell.net_force_mut()
+                .add_non_dominant_force(Force::new(friction, 0.0), "substrate friction");
+        }
+    }
+}
+
+// Holds anchored cells at their anchor points on the bottom wall, the way a bond
+// holds a cell to an immovable partner. Breaks any anchor stretched beyond the
+// breaking strain.
+#[derive(Debug)]
+pub struct AnchorForces {
+    breaking_strain: Length,
+}
+
+impl AnchorForces {
+    pub fn new(breaking_strain: Length) -> Self {
+        AnchorForces { breaking_strain }
+    }
+
+    fn anchor_force(cell: &Cell, strain: Displacement) -> Force {
+        let mass = cell.mass().value();
+        Force::new(
+            -mass * (cell.velocity().x() + strain.x()),
+            -mass * (cell.velocity().y() + strain.y()),
+        )
+    }
+}
+
+impl PerCellInfluence for AnchorForces {
+    fn apply_to(&self, cell: &mut Cell) {
+        if let Some(anchor) = cell.anchor() {
+            let strain = cell.bottom_point() - anchor;
+            if strain.length() > self.breaking_strain {
+                cell.break_anchor();
+            } else {
+                let force = Self::anchor_force(cell, strain);
+                cell.net_force_mut().add_non_dominant_force(force, "anchor");
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Sunlight {
     slope: Value1D,
@@ -443,19 +752,70 @@ impl Sunlight {
     fn calc_light_intensity(&self, y: Value1D) -> Value1D {
         (self.slope * y + self.intercept).max(0.0)
     }
+
+    // Straight up or down by the slope, except in the dark, where there's no telling
+    // which way is brighter.
+    fn calc_light_gradient(&self, intensity: Value1D) -> Value2D {
+        if intensity > 0.0 {
+            Value2D::new(0.0, self.slope)
+        } else {
+            Value2D::ZERO
+        }
+    }
 }
 
 impl PerCellInfluence for Sunlight {
     fn apply_to(&self, cell: &mut Cell) {
-        let y = cell.center().y();
-        cell.environment_mut()
-            .add_light_intensity(self.calc_light_intensity(y));
+        let intensity = self.light_intensity_at(cell.center());
+        let environment = cell.environment_mut();
+        environment.add_light_intensity(intensity);
+        environment.add_light_gradient(self.calc_light_gradient(intensity));
+    }
+
+    fn light_intensity_at(&self, position: Position) -> Value1D {
+        self.calc_light_intensity(position.y())
+    }
+}
+
+// Temperature that changes linearly with depth, e.g. a warm surface over cold depths.
+// Unlike light, temperature can be negative: zero is the temperature at which layers
+// wear and heal at their base rates (see LayerParameters).
+#[derive(Debug)]
+pub struct ThermalGradient {
+    slope: Value1D,
+    intercept: Value1D,
+}
+
+impl ThermalGradient {
+    pub fn new(
+        min_y: Value1D,
+        max_y: Value1D,
+        min_temperature: Value1D,
+        max_temperature: Value1D,
+    ) -> Self {
+        let slope = (max_temperature - min_temperature) / (max_y - min_y);
+        ThermalGradient {
+            slope,
+            intercept: max_temperature - slope * max_y,
+        }
+    }
+
+    fn calc_temperature(&self, y: Value1D) -> Value1D {
+        self.slope * y + self.intercept
+    }
+}
+
+impl PerCellInfluence for ThermalGradient {
+    fn apply_to(&self, cell: &mut Cell) {
+        let temperature = self.calc_temperature(cell.center().y());
+        cell.environment_mut().add_temperature(temperature);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::biology::control::*;
     use crate::biology::layers::*;
     use std::f64::consts::PI;
 
@@ -479,6 +839,28 @@ mod tests {
         assert_ne!(ball.net_force().net_force().y(), 0.0);
     }
 
+    #[test]
+    fn wall_collisions_add_contact_normal() {
+        let mut cell_graph = NodeGraph::new();
+        let wall_collisions =
+            WallCollisions::new(Position::new(-10.0, -10.0), Position::new(10.0, 10.0));
+        let ball_handle = cell_graph.add_node(Cell::ball(
+            Length::new(1.0),
+            Mass::new(1.0),
+            Position::new(0.0, -9.5),
+            Velocity::ZERO,
+        ));
+
+        wall_collisions.apply_to(&mut cell_graph, &mut SortableHandles::new());
+
+        let ball = cell_graph.node(ball_handle);
+        assert_eq!(
+            ball.environment().wall_contact_normals(),
+            &[Value2D::new(0.0, 1.0)]
+        );
+        assert!(ball.environment().is_touching_bottom_wall());
+    }
+
     #[test]
     fn no_walls_collision_force() {
         assert_eq!(
@@ -570,6 +952,10 @@ mod tests {
         assert_eq!(cell2.environment().overlaps().len(), 1);
         assert_ne!(cell2.net_force().net_force().x(), 0.0);
         assert_ne!(cell2.net_force().net_force().y(), 0.0);
+
+        let closing_speed = 2.0 * 2.0_f64.sqrt();
+        assert!((cell1.environment().overlaps()[0].closing_speed() - closing_speed).abs() < 1e-9);
+        assert!((cell2.environment().overlaps()[0].closing_speed() - closing_speed).abs() < 1e-9);
     }
 
     // TODO fn pair_not_in_collision_adds_no_force()
@@ -739,6 +1125,35 @@ mod tests {
         assert_ne!(ball2.net_force().net_force().y(), 0.0);
     }
 
+    #[test]
+    fn bond_forces_record_strain_in_bond_slots() {
+        let mut cell_graph = NodeGraph::new();
+        let ball1_handle = cell_graph.add_node(Cell::ball(
+            Length::new(1.0),
+            Mass::new(1.0),
+            Position::new(0.0, 0.0),
+            Velocity::ZERO,
+        ));
+        let ball2_handle = cell_graph.add_node(Cell::ball(
+            Length::new(1.0),
+            Mass::new(1.0),
+            Position::new(2.5, 0.0),
+            Velocity::ZERO,
+        ));
+        let bond = Bond::new(cell_graph.node(ball1_handle), cell_graph.node(ball2_handle));
+        cell_graph.add_edge(bond, 1, 0);
+
+        BondForces::new().apply_to(&mut cell_graph, &mut SortableHandles::new());
+
+        let strains1 = cell_graph.node(ball1_handle).environment().bond_strains();
+        assert_eq!(strains1[0], 0.0);
+        assert_eq!(strains1[1], 0.5);
+        assert_eq!(
+            cell_graph.node(ball2_handle).environment().bond_strains()[0],
+            0.5
+        );
+    }
+
     #[test]
     fn bond_with_no_velocity_and_no_strain_adds_no_force() {
         let mut cell1 = Cell::ball(
@@ -928,6 +1343,29 @@ mod tests {
         assert_eq!(drag.calc_force(&ball), Force::new(-4.0, 9.0));
     }
 
+    #[test]
+    fn spin_drag_opposes_spin() {
+        let drag = DragForce::new(0.5);
+        let torque = drag.calc_spin_drag(
+            MomentOfInertia::new(100.0),
+            Length::new(2.0),
+            AngularVelocity::new(0.5),
+        );
+        // drag 0.5 * 2 * (0.5 * 2)^2 on the rim, 2 from the center
+        assert_eq!(torque, Torque::new(-2.0));
+    }
+
+    #[test]
+    fn spin_drag_is_limited_to_torque_that_will_stop_spin() {
+        let drag = DragForce::new(0.5);
+        let torque = drag.calc_spin_drag(
+            MomentOfInertia::new(0.1),
+            Length::new(2.0),
+            AngularVelocity::new(-0.5),
+        );
+        assert_eq!(torque, Torque::new(0.05));
+    }
+
     #[test]
     fn drag_force_is_limited_to_force_that_will_stop_cell() {
         let drag = DragForce::new(0.5);
@@ -940,6 +1378,168 @@ mod tests {
         assert_eq!(drag.calc_force(&ball), Force::new(-0.1, 0.1));
     }
 
+    #[test]
+    fn thermal_noise_is_reproducible_and_varies_by_cell() {
+        let noise = ThermalNoiseForce::new(0.1, 7);
+        let ball1 = Cell::ball(
+            Length::new(1.0),
+            Mass::new(1.0),
+            Position::ORIGIN,
+            Velocity::ZERO,
+        );
+        let ball2 = Cell::ball(
+            Length::new(1.0),
+            Mass::new(1.0),
+            Position::new(1.0, 0.0),
+            Velocity::ZERO,
+        );
+
+        let force1 = noise.calc_force(&ball1);
+
+        assert_ne!(force1, Force::ZERO);
+        assert_eq!(ThermalNoiseForce::new(0.1, 7).calc_force(&ball1), force1);
+        assert_ne!(noise.calc_force(&ball2), force1);
+        assert_ne!(ThermalNoiseForce::new(0.1, 8).calc_force(&ball1), force1);
+    }
+
+    #[test]
+    fn thermal_noise_varies_by_cell_id() {
+        let noise = ThermalNoiseForce::new(0.1, 7);
+        let ball = |cell_id| {
+            let mut ball = Cell::ball(
+                Length::new(1.0),
+                Mass::new(1.0),
+                Position::ORIGIN,
+                Velocity::ZERO,
+            );
+            ball.set_cell_id(cell_id);
+            ball
+        };
+
+        assert_ne!(noise.calc_force(&ball(1)), noise.calc_force(&ball(2)));
+    }
+
+    #[test]
+    fn substrate_friction_stops_slow_cell_on_bottom() {
+        let mut ball = Cell::ball(
+            Length::new(1.0),
+            Mass::new(2.0),
+            Position::ORIGIN,
+            Velocity::new(0.5, 0.0),
+        );
+        ball.environment_mut()
+            .add_wall_contact_normal(Value2D::new(0.0, 1.0));
+        let substrate = SubstrateFriction::new(1.0);
+
+        substrate.apply_to(&mut ball);
+
+        assert_eq!(ball.net_force().net_force(), Force::new(-1.0, 0.0));
+    }
+
+    #[test]
+    fn substrate_friction_is_limited_by_mass() {
+        let mut ball = Cell::ball(
+            Length::new(1.0),
+            Mass::new(2.0),
+            Position::ORIGIN,
+            Velocity::new(-5.0, 0.0),
+        );
+        ball.environment_mut()
+            .add_wall_contact_normal(Value2D::new(0.0, 1.0));
+        let substrate = SubstrateFriction::new(0.5);
+
+        substrate.apply_to(&mut ball);
+
+        assert_eq!(ball.net_force().net_force(), Force::new(1.0, 0.0));
+    }
+
+    #[test]
+    fn substrate_friction_resists_other_forces() {
+        let mut ball = Cell::ball(
+            Length::new(1.0),
+            Mass::new(2.0),
+            Position::ORIGIN,
+            Velocity::ZERO,
+        );
+        ball.environment_mut()
+            .add_wall_contact_normal(Value2D::new(0.0, 1.0));
+        ball.net_force_mut()
+            .add_non_dominant_force(Force::new(0.5, 0.0), "current");
+        let substrate = SubstrateFriction::new(1.0);
+
+        substrate.apply_to(&mut ball);
+
+        assert_eq!(ball.net_force().net_force(), Force::ZERO);
+    }
+
+    #[test]
+    fn substrate_friction_does_not_affect_cell_off_bottom() {
+        let mut ball = Cell::ball(
+            Length::new(1.0),
+            Mass::new(2.0),
+            Position::ORIGIN,
+            Velocity::new(0.5, 0.0),
+        );
+        ball.environment_mut()
+            .add_wall_contact_normal(Value2D::new(-1.0, 0.0));
+        let substrate = SubstrateFriction::new(1.0);
+
+        substrate.apply_to(&mut ball);
+
+        assert_eq!(ball.net_force().net_force(), Force::ZERO);
+    }
+
+    #[test]
+    #[should_panic]
+    fn substrate_friction_rejects_negative_coefficient() {
+        SubstrateFriction::new(-1.0);
+    }
+
+    #[test]
+    fn anchor_forces_pull_cell_back_to_anchor() {
+        let mut cell = anchored_ball(Position::new(1.0, 0.0), Velocity::new(0.5, 0.0));
+        let anchor_forces = AnchorForces::new(Length::new(2.0));
+
+        anchor_forces.apply_to(&mut cell);
+
+        assert!(cell.anchor().is_some());
+        assert_eq!(cell.net_force().net_force(), Force::new(-3.0, 0.0));
+    }
+
+    #[test]
+    fn anchor_forces_break_overstretched_anchor() {
+        let mut cell = anchored_ball(Position::new(3.0, 0.0), Velocity::ZERO);
+        let anchor_forces = AnchorForces::new(Length::new(2.0));
+
+        anchor_forces.apply_to(&mut cell);
+
+        assert_eq!(cell.anchor(), None);
+        assert_eq!(cell.net_force().net_force(), Force::ZERO);
+    }
+
+    fn anchored_ball(position: Position, velocity: Velocity) -> Cell {
+        let mut cell = Cell::new(
+            Position::new(0.0, 1.0),
+            Velocity::ZERO,
+            vec![CellLayer::new(
+                Area::new(PI),
+                Density::new(2.0 / PI),
+                Tissue::CellWall,
+                Box::new(AnchorCellLayerSpecialty::new()),
+            )],
+        )
+        .with_control(Box::new(ContinuousRequestsControl::new(vec![
+            AnchorCellLayerSpecialty::anchor_request(0, true),
+        ])));
+        cell.environment_mut()
+            .add_wall_contact_normal(Value2D::new(0.0, 1.0));
+        cell.tick();
+        assert_eq!(cell.anchor(), Some(Position::ORIGIN));
+        cell.set_initial_position(Position::new(position.x(), position.y() + 1.0));
+        cell.set_initial_velocity(velocity);
+        cell
+    }
+
     #[test]
     fn sunlight_adds_light() {
         let sunlight = Sunlight::new(-10.0, 10.0, 10.0, 20.0);
@@ -949,6 +1549,7 @@ mod tests {
         sunlight.apply_to(&mut cell);
 
         assert_eq!(cell.environment().light_intensity(), 15.0);
+        assert_eq!(cell.environment().light_gradient(), Value2D::new(0.0, 0.5));
     }
 
     #[test]
@@ -961,6 +1562,39 @@ mod tests {
         sunlight.apply_to(&mut cell);
 
         assert_eq!(cell.environment().light_intensity(), 0.0);
+        assert_eq!(cell.environment().light_gradient(), Value2D::ZERO);
+    }
+
+    #[test]
+    fn sunlight_query_matches_light_given_to_cell() {
+        let sunlight = Sunlight::new(-10.0, 0.0, 0.0, 10.0);
+        let mut cell =
+            simple_layered_cell(vec![simple_cell_layer(Area::new(1.0), Density::new(1.0))])
+                .with_initial_position(Position::new(0.0, -4.0));
+
+        sunlight.apply_to(&mut cell);
+
+        assert_eq!(
+            sunlight.light_intensity_at(Position::new(0.0, -4.0)),
+            cell.environment().light_intensity()
+        );
+        assert_eq!(
+            SimpleForceInfluence::new(Box::new(WeightForce::new(-1.0)))
+                .light_intensity_at(Position::ORIGIN),
+            0.0
+        );
+    }
+
+    #[test]
+    fn thermal_gradient_adds_temperature() {
+        let gradient = ThermalGradient::new(-10.0, 0.0, -5.0, 15.0);
+        let mut cell =
+            simple_layered_cell(vec![simple_cell_layer(Area::new(1.0), Density::new(1.0))])
+                .with_initial_position(Position::new(0.0, -9.0));
+
+        gradient.apply_to(&mut cell);
+
+        assert_eq!(cell.environment().temperature(), -3.0);
     }
 
     fn simple_layered_cell(layers: Vec<CellLayer>) -> Cell {
@@ -1,4 +1,8 @@
+use crate::biology::cell::Cell;
+use crate::biology::control::{BondStrains, NO_BOND_STRAINS};
+use crate::physics::handles::Handle;
 use crate::physics::overlap::*;
+use crate::physics::quantities::*;
 
 pub trait HasLocalEnvironment {
     fn environment(&self) -> &LocalEnvironment;
@@ -9,7 +13,18 @@ pub trait HasLocalEnvironment {
 #[derive(Clone, Debug)]
 pub struct LocalEnvironment {
     overlaps: Vec<Overlap>, // TODO smallvec?
-    light_intensity: f64,   // TODO non-zero type?
+    // the overlaps with other cells, with those cells' handles
+    cell_overlaps: Vec<(Handle<Cell>, Overlap)>,
+    wall_contact_normals: Vec<Value2D>,
+    bond_strains: BondStrains,
+    light_intensity: f64, // TODO non-zero type?
+    // how fast the light intensity increases per unit of distance in each direction, so
+    // it points toward brighter light
+    light_gradient: Value2D,
+    nutrient_concentration: f64,
+    // from the clouds of nutrients left by burst cells
+    cloud_concentration: f64,
+    temperature: f64,
 }
 
 impl LocalEnvironment {
@@ -17,7 +32,14 @@ impl LocalEnvironment {
     pub fn new() -> Self {
         LocalEnvironment {
             overlaps: vec![],
+            cell_overlaps: vec![],
+            wall_contact_normals: vec![],
+            bond_strains: NO_BOND_STRAINS,
             light_intensity: 0.0,
+            light_gradient: Value2D::ZERO,
+            nutrient_concentration: 0.0,
+            cloud_concentration: 0.0,
+            temperature: 0.0,
         }
     }
 
@@ -29,6 +51,38 @@ impl LocalEnvironment {
         &self.overlaps
     }
 
+    pub fn add_cell_overlap(&mut self, other_cell: Handle<Cell>, overlap: Overlap) {
+        self.overlaps.push(overlap);
+        self.cell_overlaps.push((other_cell, overlap));
+    }
+
+    pub fn cell_overlaps(&self) -> &[(Handle<Cell>, Overlap)] {
+        &self.cell_overlaps
+    }
+
+    // Unit vectors pointing away from the walls the cell is touching.
+    pub fn add_wall_contact_normal(&mut self, normal: Value2D) {
+        self.wall_contact_normals.push(normal);
+    }
+
+    pub fn wall_contact_normals(&self) -> &[Value2D] {
+        &self.wall_contact_normals
+    }
+
+    pub fn is_touching_bottom_wall(&self) -> bool {
+        self.wall_contact_normals
+            .iter()
+            .any(|normal| normal.y() > 0.0)
+    }
+
+    pub fn set_bond_strain(&mut self, bond_index: usize, strain_magnitude: Value1D) {
+        self.bond_strains[bond_index] = strain_magnitude;
+    }
+
+    pub fn bond_strains(&self) -> &BondStrains {
+        &self.bond_strains
+    }
+
     pub fn add_light_intensity(&mut self, light_intensity: f64) {
         self.light_intensity += light_intensity;
     }
@@ -37,16 +91,54 @@ impl LocalEnvironment {
         self.light_intensity
     }
 
+    pub fn add_light_gradient(&mut self, light_gradient: Value2D) {
+        self.light_gradient += light_gradient;
+    }
+
+    pub fn light_gradient(&self) -> Value2D {
+        self.light_gradient
+    }
+
+    pub fn add_nutrient_concentration(&mut self, concentration: f64) {
+        self.nutrient_concentration += concentration;
+    }
+
+    pub fn nutrient_concentration(&self) -> f64 {
+        self.nutrient_concentration
+    }
+
+    pub fn add_cloud_concentration(&mut self, concentration: f64) {
+        self.cloud_concentration += concentration;
+    }
+
+    pub fn cloud_concentration(&self) -> f64 {
+        self.cloud_concentration
+    }
+
+    pub fn add_temperature(&mut self, temperature: f64) {
+        self.temperature += temperature;
+    }
+
+    pub fn temperature(&self) -> f64 {
+        self.temperature
+    }
+
     pub fn clear(&mut self) {
         self.overlaps.clear();
+        self.cell_overlaps.clear();
+        self.wall_contact_normals.clear();
+        self.bond_strains = NO_BOND_STRAINS;
         self.light_intensity = 0.0;
+        self.light_gradient = Value2D::ZERO;
+        self.nutrient_concentration = 0.0;
+        self.cloud_concentration = 0.0;
+        self.temperature = 0.0;
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::physics::quantities::*;
 
     #[test]
     fn add_overlap() {
@@ -64,15 +156,30 @@ mod tests {
         assert_eq!(2.0, env.light_intensity());
     }
 
+    #[test]
+    fn wall_contact_normal_with_upward_component_is_bottom_wall() {
+        let mut env = LocalEnvironment::new();
+        env.add_wall_contact_normal(Value2D::new(-1.0, 0.0));
+        assert!(!env.is_touching_bottom_wall());
+        env.add_wall_contact_normal(Value2D::new(0.0, 1.0));
+        assert!(env.is_touching_bottom_wall());
+    }
+
     #[test]
     fn clear_local_environment() {
         let mut env = LocalEnvironment::new();
         env.add_overlap(Overlap::new(Displacement::new(1.0, 1.0), 1.0));
+        env.add_wall_contact_normal(Value2D::new(0.0, 1.0));
         env.add_light_intensity(1.0);
+        env.add_light_gradient(Value2D::new(0.0, 1.0));
+        env.add_nutrient_concentration(1.0);
 
         env.clear();
 
         assert!(env.overlaps().is_empty());
+        assert!(env.wall_contact_normals().is_empty());
         assert_eq!(0.0, env.light_intensity());
+        assert_eq!(Value2D::ZERO, env.light_gradient());
+        assert_eq!(0.0, env.nutrient_concentration());
     }
 }
@@ -0,0 +1,250 @@
+use crate::biology::cloud::Cloud;
+use crate::environment::chemical_field::ChemicalField;
+use crate::physics::quantities::*;
+use crate::seeding::DensityMap;
+use rand::SeedableRng;
+use rand_pcg::Pcg64Mcg;
+
+// Where the tax that donors pay on top of their donations goes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DonationTaxDestination {
+    // out of the world
+    Destroyed,
+    // Into a world-level pool. Each time the pool holds energy_per_cloud, a nutrient
+    // cloud of cloud_radius appears at a random place in the world, chosen with the seed,
+    // and the energy goes into the chemical field there, for chemosynthetic layers to
+    // take up. Clouds hold no energy themselves, so without a chemical field the energy
+    // is destroyed.
+    CloudPool {
+        energy_per_cloud: BioEnergy,
+        cloud_radius: Length,
+        seed: u64,
+    },
+    // Into the chemical field as nutrient, at the donor's position. Destroyed if the
+    // world has no chemical field.
+    Local,
+}
+
+// Sends each tick's donation taxes to their destination, and keeps the totals, so that
+// an energy audit can account for every bit of the tax.
+#[derive(Debug)]
+pub struct DonationTaxRecycler {
+    destination: DonationTaxDestination,
+    rng: Pcg64Mcg,
+    pool: BioEnergy,
+    total_paid: BioEnergy,
+    total_destroyed: BioEnergy,
+}
+
+impl DonationTaxRecycler {
+    pub fn new(destination: DonationTaxDestination) -> Self {
+        let seed = match destination {
+            DonationTaxDestination::CloudPool { seed, .. } => seed,
+            _ => 0,
+        };
+        DonationTaxRecycler {
+            destination,
+            rng: Pcg64Mcg::seed_from_u64(seed),
+            pool: BioEnergy::ZERO,
+            total_paid: BioEnergy::ZERO,
+            total_destroyed: BioEnergy::ZERO,
+        }
+    }
+
+    pub fn destination(&self) -> DonationTaxDestination {
+        self.destination
+    }
+
+    // The tax paid so far that is waiting in the pool for the next cloud.
+    pub fn pool(&self) -> BioEnergy {
+        self.pool
+    }
+
+    pub fn set_pool(&mut self, pool: BioEnergy) {
+        self.pool = pool;
+    }
+
+    pub fn total_paid(&self) -> BioEnergy {
+        self.total_paid
+    }
+
+    pub fn total_destroyed(&self) -> BioEnergy {
+        self.total_destroyed
+    }
+
+    // Each tax is the donor's position and the tax it paid. Returns the clouds that the
+    // pool released.
+    pub fn recycle(
+        &mut self,
+        taxes: &[(Position, BioEnergy)],
+        chemical_field: Option<&mut ChemicalField>,
+        world_min_corner: Position,
+        world_max_corner: Position,
+    ) -> Vec<Cloud> {
+        for (_, tax) in taxes {
+            self.total_paid += *tax;
+        }
+        match (self.destination, chemical_field) {
+            (DonationTaxDestination::Destroyed, _) | (DonationTaxDestination::Local, None) => {
+                for (_, tax) in taxes {
+                    self.total_destroyed += *tax;
+                }
+                vec![]
+            }
+            (DonationTaxDestination::Local, Some(field)) => {
+                for (position, tax) in taxes {
+                    field.add_amount_at(*position, tax.value());
+                }
+                vec![]
+            }
+            (
+                DonationTaxDestination::CloudPool {
+                    energy_per_cloud,
+                    cloud_radius,
+                    ..
+                },
+                mut chemical_field,
+            ) => {
+                for (_, tax) in taxes {
+                    self.pool += *tax;
+                }
+                let mut clouds = vec![];
+                while energy_per_cloud.value() > 0.0 && self.pool >= energy_per_cloud {
+                    self.pool = self.pool - energy_per_cloud;
+                    let position = Position::new(
+                        DensityMap::random_coordinate(
+                            &mut self.rng,
+                            world_min_corner.x(),
+                            world_max_corner.x(),
+                        ),
+                        DensityMap::random_coordinate(
+                            &mut self.rng,
+                            world_min_corner.y(),
+                            world_max_corner.y(),
+                        ),
+                    );
+                    match &mut chemical_field {
+                        Some(field) => field.add_amount_at(position, energy_per_cloud.value()),
+                        None => self.total_destroyed += energy_per_cloud,
+                    }
+                    clouds.push(Cloud::new(position, cloud_radius));
+                }
+                clouds
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::shapes::Circle;
+
+    fn min_corner() -> Position {
+        Position::new(0.0, 0.0)
+    }
+
+    fn max_corner() -> Position {
+        Position::new(10.0, 10.0)
+    }
+
+    #[test]
+    fn destroyed_tax_is_counted() {
+        let mut recycler = DonationTaxRecycler::new(DonationTaxDestination::Destroyed);
+
+        let clouds = recycler.recycle(
+            &[(Position::ORIGIN, BioEnergy::new(1.0))],
+            None,
+            min_corner(),
+            max_corner(),
+        );
+
+        assert!(clouds.is_empty());
+        assert_eq!(recycler.total_paid(), BioEnergy::new(1.0));
+        assert_eq!(recycler.total_destroyed(), BioEnergy::new(1.0));
+    }
+
+    #[test]
+    fn pool_releases_cloud_when_full() {
+        let mut recycler = DonationTaxRecycler::new(DonationTaxDestination::CloudPool {
+            energy_per_cloud: BioEnergy::new(2.0),
+            cloud_radius: Length::new(3.0),
+            seed: 0,
+        });
+
+        let clouds1 = recycler.recycle(
+            &[(Position::ORIGIN, BioEnergy::new(1.5))],
+            None,
+            min_corner(),
+            max_corner(),
+        );
+        let clouds2 = recycler.recycle(
+            &[(Position::ORIGIN, BioEnergy::new(1.0))],
+            None,
+            min_corner(),
+            max_corner(),
+        );
+
+        assert!(clouds1.is_empty());
+        assert_eq!(clouds2.len(), 1);
+        assert_eq!(recycler.pool(), BioEnergy::new(0.5));
+        // no chemical field to take it
+        assert_eq!(recycler.total_destroyed(), BioEnergy::new(2.0));
+    }
+
+    #[test]
+    fn pool_puts_released_energy_into_chemical_field() {
+        let mut field = ChemicalField::new(min_corner(), max_corner(), 2, 2, Fraction::ZERO);
+        let mut recycler = DonationTaxRecycler::new(DonationTaxDestination::CloudPool {
+            energy_per_cloud: BioEnergy::new(2.0),
+            cloud_radius: Length::new(3.0),
+            seed: 0,
+        });
+
+        let clouds = recycler.recycle(
+            &[(Position::ORIGIN, BioEnergy::new(5.0))],
+            Some(&mut field),
+            min_corner(),
+            max_corner(),
+        );
+
+        assert_eq!(clouds.len(), 2);
+        assert_eq!(field.total_amount(), 4.0);
+        assert_eq!(recycler.total_destroyed(), BioEnergy::ZERO);
+    }
+
+    #[test]
+    fn pool_places_clouds_in_flat_world() {
+        let mut recycler = DonationTaxRecycler::new(DonationTaxDestination::CloudPool {
+            energy_per_cloud: BioEnergy::new(1.0),
+            cloud_radius: Length::new(1.0),
+            seed: 0,
+        });
+
+        let clouds = recycler.recycle(
+            &[(Position::ORIGIN, BioEnergy::new(1.0))],
+            None,
+            Position::new(0.0, 0.0),
+            Position::new(10.0, 0.0),
+        );
+
+        assert_eq!(clouds.len(), 1);
+        assert_eq!(clouds[0].center().y(), 0.0);
+    }
+
+    #[test]
+    fn local_tax_becomes_nutrient_at_donor() {
+        let mut field = ChemicalField::new(min_corner(), max_corner(), 2, 1, Fraction::ZERO);
+        let mut recycler = DonationTaxRecycler::new(DonationTaxDestination::Local);
+
+        recycler.recycle(
+            &[(Position::new(7.0, 5.0), BioEnergy::new(10.0))],
+            Some(&mut field),
+            min_corner(),
+            max_corner(),
+        );
+
+        assert_eq!(field.concentrations(), &[0.0, 10.0 / 50.0]);
+        assert_eq!(field.total_amount(), 10.0);
+    }
+}
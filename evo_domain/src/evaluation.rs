@@ -0,0 +1,146 @@
+use crate::biology::cell::Cell;
+use crate::physics::quantities::*;
+use crate::world::World;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+// How the founder cells of a world fared over a fixed number of ticks. Running each
+// saved genome as the founder of the same fresh world gives numbers that can be
+// compared across genomes, e.g. from a hall of fame, without the competition and
+// chance events of the run that evolved them.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FitnessReport {
+    // the longest any founder lived, which is all the ticks if one is still alive
+    pub survival_ticks: u64,
+    // taken in by the founders from light, nutrients, and bites
+    pub energy_gathered: BioEnergy,
+    // budded by the founders themselves, not by their descendants
+    pub offspring: u32,
+}
+
+impl FitnessReport {
+    pub const CSV_HEADER: &'static str = "survival_ticks,energy_gathered,offspring";
+
+    pub fn to_csv_row(&self) -> String {
+        format!(
+            "{},{:.4},{}",
+            self.survival_ticks,
+            self.energy_gathered.value(),
+            self.offspring
+        )
+    }
+
+    fn add_founder(&mut self, cell: &Cell) {
+        self.survival_ticks = self.survival_ticks.max(cell.ticks_lived());
+        self.energy_gathered += cell.energy_gathered();
+        self.offspring += cell.offspring_count();
+    }
+}
+
+impl Default for FitnessReport {
+    fn default() -> Self {
+        FitnessReport {
+            survival_ticks: 0,
+            energy_gathered: BioEnergy::ZERO,
+            offspring: 0,
+        }
+    }
+}
+
+// Ticks the world until the ticks are done or all the cells it started with are dead.
+// Uses a death hook, so the world shouldn't be reused afterward.
+pub fn evaluate_founders(world: &mut World, ticks: u64) -> FitnessReport {
+    let founder_ids = world
+        .cells()
+        .iter()
+        .map(|cell| cell.cell_id())
+        .collect::<HashSet<_>>();
+    let report = Arc::new(Mutex::new(FitnessReport::default()));
+    let living_founders = Arc::new(Mutex::new(founder_ids));
+    let report_clone = Arc::clone(&report);
+    let living_founders_clone = Arc::clone(&living_founders);
+    world.on_death(move |cell| {
+        if living_founders_clone
+            .lock()
+            .unwrap()
+            .remove(&cell.cell_id())
+        {
+            report_clone.lock().unwrap().add_founder(cell);
+        }
+    });
+
+    for _ in 0..ticks {
+        if living_founders.lock().unwrap().is_empty() {
+            break;
+        }
+        world.tick();
+    }
+
+    let mut report = *report.lock().unwrap();
+    let living_founders = living_founders.lock().unwrap();
+    for cell in world.cells() {
+        if living_founders.contains(&cell.cell_id()) {
+            report.add_founder(cell);
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biology::layers::*;
+
+    #[test]
+    fn living_founder_survives_all_ticks_and_gathers_light() {
+        let mut world = World::new(Position::new(0.0, -10.0), Position::new(10.0, 0.0))
+            .with_sunlight(0.0, 1.0)
+            .with_cell(photo_cell());
+
+        let report = evaluate_founders(&mut world, 5);
+
+        assert_eq!(report.survival_ticks, 5);
+        assert!(report.energy_gathered > BioEnergy::ZERO);
+        assert_eq!(report.offspring, 0);
+    }
+
+    #[test]
+    fn evaluation_stops_when_founders_die() {
+        const THIN_LAYER_PARAMS: LayerParameters = LayerParameters {
+            minimum_intact_thickness: Fraction::unchecked(0.5),
+            ..LayerParameters::DEFAULT
+        };
+        let mut world =
+            World::new(Position::new(0.0, -10.0), Position::new(10.0, 0.0)).with_cell(Cell::new(
+                Position::new(5.0, -5.0),
+                Velocity::ZERO,
+                vec![
+                    photo_layer(),
+                    photo_layer().with_parameters(&THIN_LAYER_PARAMS).dead(),
+                ],
+            ));
+
+        let report = evaluate_founders(&mut world, 100);
+
+        assert_eq!(world.tick_count(), 1);
+        assert_eq!(report.survival_ticks, 1);
+        assert_eq!(report.offspring, 0);
+    }
+
+    fn photo_cell() -> Cell {
+        Cell::new(
+            Position::new(5.0, -5.0),
+            Velocity::ZERO,
+            vec![photo_layer()],
+        )
+    }
+
+    fn photo_layer() -> CellLayer {
+        CellLayer::new(
+            Area::new(1.0),
+            Density::new(1.0),
+            Tissue::Photosynthetic,
+            Box::new(PhotoCellLayerSpecialty::new(Fraction::ONE)),
+        )
+    }
+}
@@ -0,0 +1,151 @@
+use std::fmt;
+
+// Identifies the run that produced an output, so results can be traced back to the
+// code, configuration, and seed that made them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExperimentMetadata {
+    pub name: String,
+    pub description: String,
+    pub git_commit: Option<String>,
+    pub config_hash: u64,
+    pub overrides: Vec<(String, f64)>,
+    pub seed: u64,
+}
+
+impl ExperimentMetadata {
+    pub fn new(name: &str, seed: u64) -> Self {
+        ExperimentMetadata {
+            name: name.to_string(),
+            description: String::new(),
+            git_commit: None,
+            config_hash: 0,
+            overrides: vec![],
+            seed,
+        }
+    }
+
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = description.to_string();
+        self
+    }
+
+    pub fn with_git_commit(mut self, git_commit: Option<String>) -> Self {
+        self.git_commit = git_commit;
+        self
+    }
+
+    // Any config whose Debug text changes whenever its values do.
+    pub fn with_config<C: fmt::Debug>(mut self, config: &C) -> Self {
+        self.config_hash = Self::hash_config(config);
+        self
+    }
+
+    // Parameter values changed for this run without changing the code.
+    pub fn with_overrides(mut self, overrides: &[(String, f64)]) -> Self {
+        self.overrides = overrides.to_vec();
+        self
+    }
+
+    // FNV-1a rather than the std hasher, whose output may change between Rust releases.
+    fn hash_config<C: fmt::Debug>(config: &C) -> u64 {
+        format!("{:?}", config)
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+            })
+    }
+
+    // Comment lines for the top of text outputs such as CSV files.
+    pub fn header_lines(&self) -> Vec<String> {
+        let mut lines = vec![format!("# experiment: {}", self.name)];
+        if !self.description.is_empty() {
+            lines.push(format!("# description: {}", self.description));
+        }
+        lines.push(format!("# git commit: {}", self.git_commit_or_unknown()));
+        lines.push(format!("# config hash: {:016x}", self.config_hash));
+        if !self.overrides.is_empty() {
+            let overrides = self
+                .overrides
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, value))
+                .collect::<Vec<_>>();
+            lines.push(format!("# overrides: {}", overrides.join(" ")));
+        }
+        lines.push(format!("# seed: {}", self.seed));
+        lines
+    }
+
+    // A prefix for output file names, e.g. "duckweed-s42-1a2b3c4".
+    pub fn file_stem(&self) -> String {
+        let name = self
+            .name
+            .chars()
+            .map(|ch| if ch.is_alphanumeric() { ch } else { '_' })
+            .collect::<String>();
+        format!("{}-s{}-{}", name, self.seed, self.git_commit_or_unknown())
+    }
+
+    fn git_commit_or_unknown(&self) -> &str {
+        match &self.git_commit {
+            Some(commit) => commit,
+            None => "unknown",
+        }
+    }
+}
+
+impl fmt::Display for ExperimentMetadata {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for line in self.header_lines() {
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_lines_identify_the_run() {
+        let metadata = ExperimentMetadata::new("duckweed", 42)
+            .with_description("wider world")
+            .with_git_commit(Some("1a2b3c4".to_string()));
+
+        assert_eq!(
+            metadata.header_lines(),
+            vec![
+                "# experiment: duckweed",
+                "# description: wider world",
+                "# git commit: 1a2b3c4",
+                "# config hash: 0000000000000000",
+                "# seed: 42",
+            ]
+        );
+    }
+
+    #[test]
+    fn header_lines_list_overrides() {
+        let metadata = ExperimentMetadata::new("duckweed", 42)
+            .with_overrides(&[("gravity".to_string(), -0.1), ("drag".to_string(), 0.01)]);
+
+        assert!(metadata
+            .header_lines()
+            .contains(&"# overrides: gravity=-0.1 drag=0.01".to_string()));
+    }
+
+    #[test]
+    fn file_stem_is_safe_for_file_names() {
+        let metadata = ExperimentMetadata::new("big run/2", 7);
+        assert_eq!(metadata.file_stem(), "big_run_2-s7-unknown");
+    }
+
+    #[test]
+    fn config_hash_follows_config_values() {
+        let hash1 = ExperimentMetadata::new("x", 0).with_config(&(1.0, 2.0));
+        let hash2 = ExperimentMetadata::new("x", 0).with_config(&(1.0, 2.0));
+        let hash3 = ExperimentMetadata::new("x", 0).with_config(&(1.0, 2.5));
+        assert_eq!(hash1.config_hash, hash2.config_hash);
+        assert_ne!(hash1.config_hash, hash3.config_hash);
+    }
+}
@@ -0,0 +1,244 @@
+use crate::biology::cell::Cell;
+use crate::physics::handles::Handle;
+use crate::physics::newtonian::*;
+use crate::physics::node_graph::GraphNode;
+use crate::physics::quantities::*;
+use std::fmt;
+
+// Watches for "physics explosions," where a bad force or a too-large time step flings
+// cells apart, and reports the cells involved and the forces that moved them in the tick
+// it happened, so that the cause can be found instead of guessed at.
+#[derive(Debug)]
+pub struct ExplosionWatchdog {
+    parameters: ExplosionWatchdogParameters,
+    last_kinetic_energy: Option<Value1D>,
+    last_report: Option<ExplosionReport>,
+    exploded_last_tick: bool,
+    explosion_count: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ExplosionWatchdogParameters {
+    // any cell faster than this, in distance per tick, is an explosion
+    pub max_speed: Value1D,
+    // so is the cells' total kinetic energy growing by more than this factor in a tick...
+    pub max_kinetic_energy_growth: Value1D,
+    // ...as long as it ends above this, so that cells starting from rest don't count
+    pub min_kinetic_energy: Value1D,
+    // the report lists this many of the cells with the most kinetic energy
+    pub max_reported_cells: usize,
+    // whether a run that can pause, e.g. one with a window, should pause at an explosion
+    pub pause: bool,
+    // whether to print each report to stderr, as well as keeping it as the last report
+    pub log: bool,
+}
+
+impl ExplosionWatchdogParameters {
+    pub const DEFAULT: ExplosionWatchdogParameters = ExplosionWatchdogParameters {
+        max_speed: 100.0,
+        max_kinetic_energy_growth: 10.0,
+        min_kinetic_energy: 1.0,
+        max_reported_cells: 5,
+        pause: false,
+        log: false,
+    };
+}
+
+impl ExplosionWatchdog {
+    pub fn new(parameters: ExplosionWatchdogParameters) -> Self {
+        ExplosionWatchdog {
+            parameters,
+            last_kinetic_energy: None,
+            last_report: None,
+            exploded_last_tick: false,
+            explosion_count: 0,
+        }
+    }
+
+    pub fn parameters(&self) -> &ExplosionWatchdogParameters {
+        &self.parameters
+    }
+
+    pub fn last_report(&self) -> Option<&ExplosionReport> {
+        self.last_report.as_ref()
+    }
+
+    pub fn exploded_last_tick(&self) -> bool {
+        self.exploded_last_tick
+    }
+
+    // The number of ticks, over the run, that had an explosion.
+    pub fn explosion_count(&self) -> u64 {
+        self.explosion_count
+    }
+
+    // Returns the report, if the cells exploded.
+    pub fn check(&mut self, tick: u64, cells: &[Cell]) -> Option<&ExplosionReport> {
+        let kinetic_energy = cells.iter().map(kinetic_energy).sum::<Value1D>();
+        let previous_kinetic_energy = self.last_kinetic_energy.replace(kinetic_energy);
+        let max_speed = cells
+            .iter()
+            .map(|cell| cell.velocity().value().length())
+            .fold(0.0, Value1D::max);
+
+        let too_fast = max_speed > self.parameters.max_speed;
+        let spiked = previous_kinetic_energy.is_some_and(|previous| {
+            kinetic_energy > self.parameters.min_kinetic_energy
+                && kinetic_energy > previous * self.parameters.max_kinetic_energy_growth
+        });
+        self.exploded_last_tick = too_fast || spiked;
+        if !self.exploded_last_tick {
+            return None;
+        }
+
+        self.explosion_count += 1;
+        let report = ExplosionReport {
+            tick,
+            kinetic_energy,
+            previous_kinetic_energy,
+            max_speed,
+            cells: self.reported_cells(cells),
+        };
+        if self.parameters.log {
+            eprintln!("{}", report);
+        }
+        self.last_report = Some(report);
+        self.last_report.as_ref()
+    }
+
+    fn reported_cells(&self, cells: &[Cell]) -> Vec<ExplodingCell> {
+        let mut cells = cells.iter().collect::<Vec<_>>();
+        cells.sort_by(|cell1, cell2| {
+            kinetic_energy(cell2)
+                .partial_cmp(&kinetic_energy(cell1))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        cells
+            .into_iter()
+            .take(self.parameters.max_reported_cells)
+            .map(|cell| ExplodingCell {
+                node_handle: cell.node_handle(),
+                cell_id: cell.cell_id(),
+                position: cell.position(),
+                velocity: cell.velocity(),
+                kinetic_energy: kinetic_energy(cell),
+                net_force: cell.last_net_force().clone(),
+            })
+            .collect()
+    }
+}
+
+fn kinetic_energy(cell: &Cell) -> Value1D {
+    0.5 * cell.mass().value() * cell.velocity().value().length_squared()
+}
+
+#[derive(Clone, Debug)]
+pub struct ExplosionReport {
+    pub tick: u64,
+    pub kinetic_energy: Value1D,
+    // None on the first tick watched
+    pub previous_kinetic_energy: Option<Value1D>,
+    pub max_speed: Value1D,
+    // the cells with the most kinetic energy, most first
+    pub cells: Vec<ExplodingCell>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ExplodingCell {
+    pub node_handle: Handle<Cell>,
+    pub cell_id: u64,
+    pub position: Position,
+    pub velocity: Velocity,
+    pub kinetic_energy: Value1D,
+    // the forces that moved the cell in the tick, one by one
+    pub net_force: NetForce,
+}
+
+impl fmt::Display for ExplosionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Physics explosion, tick {}:", self.tick)?;
+        match self.previous_kinetic_energy {
+            Some(previous) => writeln!(
+                f,
+                "  kinetic energy: {:.6} -> {:.6}",
+                previous, self.kinetic_energy
+            )?,
+            None => writeln!(f, "  kinetic energy: {:.6}", self.kinetic_energy)?,
+        }
+        write!(f, "  max speed: {:.6}", self.max_speed)?;
+        for cell in &self.cells {
+            write!(
+                f,
+                "\n  cell {} ({}): position {}, velocity {}, kinetic energy {:.6}",
+                cell.node_handle, cell.cell_id, cell.position, cell.velocity, cell.kinetic_energy
+            )?;
+            let net_force = &cell.net_force;
+            write!(
+                f,
+                "\n    {} x {:.4}",
+                net_force.dominant_x_force_label(),
+                net_force.dominant_x_force()
+            )?;
+            write!(
+                f,
+                "\n    {} y {:.4}",
+                net_force.dominant_y_force_label(),
+                net_force.dominant_y_force()
+            )?;
+            for addition in net_force.non_dominant_force_additions().iter().flatten() {
+                if addition.force != Force::ZERO {
+                    write!(f, "\n    {} {:+.4}", addition.label, addition.force)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fast_cell_is_explosion() {
+        let mut watchdog = ExplosionWatchdog::new(ExplosionWatchdogParameters {
+            max_speed: 10.0,
+            ..ExplosionWatchdogParameters::DEFAULT
+        });
+
+        assert!(watchdog
+            .check(1, &[ball(Velocity::new(6.0, 8.0))])
+            .is_none());
+        let report = watchdog.check(2, &[ball(Velocity::new(6.0, 8.5))]).unwrap();
+
+        assert_eq!(report.tick, 2);
+        assert_eq!(report.cells.len(), 1);
+        assert!(watchdog.exploded_last_tick());
+        assert_eq!(watchdog.explosion_count(), 1);
+    }
+
+    #[test]
+    fn kinetic_energy_spike_is_explosion() {
+        let mut watchdog = ExplosionWatchdog::new(ExplosionWatchdogParameters {
+            max_kinetic_energy_growth: 10.0,
+            min_kinetic_energy: 1.0,
+            ..ExplosionWatchdogParameters::DEFAULT
+        });
+        let cells = [ball(Velocity::new(1.0, 0.0)), ball(Velocity::new(0.0, 5.0))];
+
+        // from rest, but not past the minimum
+        watchdog.check(1, &[ball(Velocity::ZERO), ball(Velocity::ZERO)]);
+        assert!(watchdog
+            .check(2, &[ball(Velocity::new(1.0, 0.0)), ball(Velocity::ZERO)])
+            .is_none());
+        let report = watchdog.check(3, &cells).unwrap();
+
+        assert_eq!(report.previous_kinetic_energy, Some(0.5));
+        assert_eq!(report.kinetic_energy, 13.0);
+        assert_eq!(report.cells[0].kinetic_energy, 12.5);
+    }
+
+    fn ball(velocity: Velocity) -> Cell {
+        Cell::ball(Length::new(1.0), Mass::new(1.0), Position::ORIGIN, velocity)
+    }
+}
@@ -0,0 +1,247 @@
+use crate::biology::cell::Cell;
+use crate::biology::genome::SparseNeuralNetGenome;
+use crate::physics::quantities::*;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+
+// The genomes of a run's standout cells, taken every so many ticks, so that a long run
+// keeps its most interesting intermediate champions after they and their lines have
+// died out, instead of ending with whatever happens to be alive. Holds the most recent
+// entries, up to its capacity.
+#[derive(Debug)]
+pub struct HallOfFame {
+    interval: u64,
+    capacity: usize,
+    entries: VecDeque<HallOfFameEntry>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Distinction {
+    // the cell that has lived the most ticks
+    LongestLived,
+    // the cell with the most energy in the lineage whose cells have the most energy
+    // between them
+    RichestLineage,
+}
+
+impl Distinction {
+    pub const ALL: [Distinction; 2] = [Distinction::LongestLived, Distinction::RichestLineage];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Distinction::LongestLived => "longest-lived",
+            Distinction::RichestLineage => "richest-lineage",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HallOfFameEntry {
+    pub distinction: Distinction,
+    pub cell_id: u64,
+    pub lineage_id: u64,
+    // the ticks at which the cell was first and last found to hold the distinction
+    pub first_tick: u64,
+    pub last_tick: u64,
+    // as of the last tick: the ticks lived, or the lineage's total energy
+    pub score: Value1D,
+    pub genome: SparseNeuralNetGenome,
+}
+
+impl HallOfFame {
+    pub const DEFAULT_CAPACITY: usize = 50;
+
+    pub fn new(interval: u64, capacity: usize) -> Self {
+        assert!(interval > 0);
+        assert!(capacity > 0);
+        HallOfFame {
+            interval,
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub fn interval(&self) -> u64 {
+        self.interval
+    }
+
+    // oldest first
+    pub fn entries(&self) -> &VecDeque<HallOfFameEntry> {
+        &self.entries
+    }
+
+    // Every interval ticks, takes the genome of the living champion of each
+    // distinction. A champion still holding its distinction from last time only has its
+    // entry brought up to date, so that one long-lived cell doesn't fill the hall.
+    pub fn record(&mut self, tick: u64, cells: &[Cell]) {
+        if !tick.is_multiple_of(self.interval) {
+            return;
+        }
+        for distinction in Distinction::ALL {
+            if let Some((cell, score)) = Self::champion(distinction, cells) {
+                self.induct(tick, distinction, cell, score);
+            }
+        }
+    }
+
+    // Ties go to the earliest cell.
+    fn champion(distinction: Distinction, cells: &[Cell]) -> Option<(&Cell, Value1D)> {
+        let candidates = || cells.iter().filter(|cell| cell.genome().is_some());
+        match distinction {
+            Distinction::LongestLived => candidates()
+                .max_by_key(|cell| (cell.ticks_lived(), Reverse(cell.cell_id())))
+                .map(|cell| (cell, cell.ticks_lived() as Value1D)),
+            Distinction::RichestLineage => {
+                let mut lineage_energies = BTreeMap::new();
+                for cell in candidates() {
+                    *lineage_energies.entry(cell.lineage_id()).or_insert(0.0) +=
+                        cell.energy().value();
+                }
+                let (lineage_id, lineage_energy) = lineage_energies
+                    .into_iter()
+                    .rev()
+                    .max_by(|(_, energy1), (_, energy2)| energy1.total_cmp(energy2))?;
+                candidates()
+                    .filter(|cell| cell.lineage_id() == lineage_id)
+                    .rev()
+                    .max_by(|cell1, cell2| {
+                        cell1.energy().value().total_cmp(&cell2.energy().value())
+                    })
+                    .map(|cell| (cell, lineage_energy))
+            }
+        }
+    }
+
+    fn induct(&mut self, tick: u64, distinction: Distinction, cell: &Cell, score: Value1D) {
+        let latest = self
+            .entries
+            .iter_mut()
+            .rev()
+            .find(|entry| entry.distinction == distinction);
+        if let Some(entry) = latest {
+            if entry.cell_id == cell.cell_id() {
+                entry.last_tick = tick;
+                entry.score = score;
+                return;
+            }
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(HallOfFameEntry {
+            distinction,
+            cell_id: cell.cell_id(),
+            lineage_id: cell.lineage_id(),
+            first_tick: tick,
+            last_tick: tick,
+            score,
+            genome: cell.genome().unwrap().clone(),
+        });
+    }
+}
+
+impl fmt::Display for HallOfFameEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ticks {}-{}: {} cell {} of lineage {} ({:.4})",
+            self.first_tick,
+            self.last_tick,
+            self.distinction.name(),
+            self.cell_id,
+            self.lineage_id,
+            self.score
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biology::control::*;
+    use crate::biology::genome::*;
+    use crate::biology::layers::*;
+    use crate::world::World;
+
+    #[test]
+    fn records_champions_every_interval() {
+        let mut world = world_with_energies(&[10.0, 30.0, 20.0]).with_hall_of_fame(2, 10);
+
+        world.tick();
+        assert!(world.hall_of_fame().unwrap().entries().is_empty());
+        world.tick();
+
+        let entries = world.hall_of_fame().unwrap().entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].distinction, Distinction::LongestLived);
+        assert_eq!(entries[0].cell_id, 1);
+        assert_eq!(entries[0].score, 2.0);
+        assert_eq!(entries[1].distinction, Distinction::RichestLineage);
+        assert_eq!(entries[1].cell_id, 2);
+        assert_eq!(entries[1].score, 30.0);
+        assert_eq!(entries[1].genome, *world.cells()[1].genome().unwrap());
+    }
+
+    #[test]
+    fn continuing_champion_updates_its_entry() {
+        let mut world = world_with_energies(&[10.0, 30.0]).with_hall_of_fame(1, 10);
+
+        for _ in 0..3 {
+            world.tick();
+        }
+
+        let entries = world.hall_of_fame().unwrap().entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries[0].to_string(),
+            "ticks 1-3: longest-lived cell 1 of lineage 1 (3.0000)"
+        );
+    }
+
+    #[test]
+    fn full_hall_drops_oldest() {
+        let mut world = world_with_energies(&[10.0, 30.0]).with_hall_of_fame(1, 1);
+
+        world.tick();
+
+        let entries = world.hall_of_fame().unwrap().entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].distinction, Distinction::RichestLineage);
+    }
+
+    fn world_with_energies(energies: &[Value1D]) -> World {
+        World::new(Position::new(-100.0, -100.0), Position::new(100.0, 100.0)).with_cells(
+            energies
+                .iter()
+                .enumerate()
+                .map(|(index, energy)| {
+                    Cell::new(
+                        Position::new(index as Value1D * 10.0, 0.0),
+                        Velocity::ZERO,
+                        vec![CellLayer::new(
+                            Area::new(1.0),
+                            Density::new(1.0),
+                            Tissue::CellWall,
+                            Box::new(NullCellLayerSpecialty::new()),
+                        )],
+                    )
+                    .with_initial_energy(BioEnergy::new(*energy))
+                    .with_control(Box::new(net_control()))
+                })
+                .collect(),
+        )
+    }
+
+    fn net_control() -> NeuralNetControl {
+        let mut builder = NeuralNetControlBuilder::new(TransferFn::IDENTITY);
+        let energy_index = builder.add_input_node("energy", |cell_state| cell_state.energy.value());
+        builder.add_output_node("resize", &[(energy_index, 0.0)], 0.0, |value| {
+            CellLayer::resize_request(0, AreaDelta::new(value))
+        });
+        builder.build(SeededMutationRandomness::new(
+            0,
+            &MutationParameters::NO_MUTATION,
+        ))
+    }
+}
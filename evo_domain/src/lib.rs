@@ -1,40 +1,147 @@
+pub mod benchmark;
 pub mod biology;
+#[cfg(test)]
+mod colony_tests;
+pub mod energy_audit;
 pub mod environment;
+pub mod evaluation;
+pub mod experiment;
+pub mod explosion_watchdog;
+pub mod hall_of_fame;
+pub mod phase_times;
 pub mod physics;
+pub mod render_frame;
+pub mod scenario;
+pub mod seeding;
+pub mod soak;
+pub mod speciation;
+pub mod state_hash;
+pub mod stats;
+#[cfg(test)]
+mod stress_tests;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
+pub mod warnings;
 pub mod world;
+pub mod world_events;
+pub mod world_state;
 
 use crate::biology::cloud::CloudParameters;
-use crate::physics::quantities::Area;
+use crate::physics::quantities::{Area, BioEnergy, Fraction, Positive};
 use std::f64::consts::PI;
+#[cfg(feature = "timing")]
 use std::time;
 
 #[derive(Debug, Clone, Copy)]
 pub struct Parameters {
     pub initial_layer_area: Area,
     pub cloud_params: CloudParameters,
+    // what each nonzero weight of a cell's neural net costs it in energy every tick, so
+    // that bigger nets must earn their keep
+    pub genome_weight_upkeep: BioEnergy,
+    pub units: UnitScales,
 }
 
 impl Parameters {
     pub const DEFAULT: Parameters = Parameters {
         initial_layer_area: Area::unchecked(10.0 * PI),
         cloud_params: CloudParameters::DEFAULT,
+        genome_weight_upkeep: BioEnergy::ZERO,
+        units: UnitScales::DEFAULT,
     };
 
+    // Names accepted by set, e.g. for overrides given on the command line.
+    pub const NAMES: [&'static str; 7] = [
+        "initial_layer_area",
+        "cloud_resize_factor",
+        "cloud_minimum_concentration",
+        "genome_weight_upkeep",
+        "pixels_per_unit",
+        "energy_display_factor",
+        "mass_display_factor",
+    ];
+
     // pub fn validate(&self) {
     //     self.cloud_params.validate();
     // }
+
+    pub fn set(&mut self, name: &str, value: f64) -> Result<(), String> {
+        let valid = match name {
+            "initial_layer_area" | "cloud_resize_factor" | "genome_weight_upkeep" => value >= 0.0,
+            // display scales, which zero would collapse and negatives would flip
+            "pixels_per_unit" | "energy_display_factor" | "mass_display_factor" => value > 0.0,
+            "cloud_minimum_concentration" => (0.0..=1.0).contains(&value),
+            _ => true,
+        };
+        if !valid || !value.is_finite() {
+            return Err(format!("Invalid value for {}: {}", name, value));
+        }
+        match name {
+            "initial_layer_area" => self.initial_layer_area = Area::new(value),
+            "cloud_resize_factor" => self.cloud_params.resize_factor = Positive::new(value),
+            "cloud_minimum_concentration" => {
+                self.cloud_params.minimum_concentration = Fraction::new(value)
+            }
+            "genome_weight_upkeep" => self.genome_weight_upkeep = BioEnergy::new(value),
+            "pixels_per_unit" => self.units.pixels_per_unit = Some(value),
+            "energy_display_factor" => self.units.energy_display_factor = value,
+            "mass_display_factor" => self.units.mass_display_factor = value,
+            _ => return Err(format!("Unknown parameter: {}", name)),
+        }
+        Ok(())
+    }
+}
+
+// How to present a world's quantities, so that worlds built at very different
+// magnitudes draw at a sensible size and print readable numbers. None of these
+// affect the simulation itself.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnitScales {
+    // initial window pixels per world unit; None fits the world to the screen
+    pub pixels_per_unit: Option<f64>,
+    // printed energies and masses are multiplied by these
+    pub energy_display_factor: f64,
+    pub mass_display_factor: f64,
+}
+
+impl UnitScales {
+    pub const DEFAULT: UnitScales = UnitScales {
+        pixels_per_unit: None,
+        energy_display_factor: 1.0,
+        mass_display_factor: 1.0,
+    };
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum UserAction {
+    AddCellAt { x: f64, y: f64 },
     DebugPrint,
     Exit,
+    ExportSelected,
+    ExportSelectedGenome,
     FastForwardToggle,
+    FollowSelectionToggle,
+    GridToggle,
+    NetDiagramToggle,
+    OverlayToggle,
     PlayToggle,
-    SelectCellToggle { x: f64, y: f64 },
+    PopulationGraphToggle,
+    RemoveCellAt { x: f64, y: f64, tolerance: f64 },
+    Screenshot,
+    SelectCellToggle { x: f64, y: f64, tolerance: f64 },
     SingleTick,
+    SlowDown,
+    SpeedUp,
+    StepBack,
+    ViewBack,
+    WarningPanelToggle,
+    ZoomIn,
+    ZoomOut,
+    ZoomToFit,
+    ZoomToSelection,
 }
 
+#[cfg(feature = "timing")]
 pub struct ElapsedTimeProbe {
     name: &'static str,
     reporting_interval: time::Duration,
@@ -44,6 +151,7 @@ pub struct ElapsedTimeProbe {
     probes_duration: time::Duration,
 }
 
+#[cfg(feature = "timing")]
 impl ElapsedTimeProbe {
     pub fn new(name: &'static str, reporting_interval: time::Duration) -> Self {
         ElapsedTimeProbe {
@@ -88,3 +196,31 @@ impl ElapsedTimeProbe {
         self.next_reporting_time = time::Instant::now() + self.reporting_interval;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_parameter_by_name() {
+        let mut parameters = Parameters::DEFAULT;
+        parameters.set("cloud_resize_factor", 1.5).unwrap();
+        parameters.set("pixels_per_unit", 2.0).unwrap();
+        parameters.set("genome_weight_upkeep", 0.01).unwrap();
+        assert_eq!(parameters.cloud_params.resize_factor, Positive::new(1.5));
+        assert_eq!(parameters.genome_weight_upkeep, BioEnergy::new(0.01));
+        assert_eq!(parameters.units.pixels_per_unit, Some(2.0));
+    }
+
+    #[test]
+    fn set_rejects_unknown_names_and_invalid_values() {
+        let mut parameters = Parameters::DEFAULT;
+        assert!(parameters.set("gravity", -1.0).is_err());
+        assert!(parameters.set("cloud_minimum_concentration", 1.5).is_err());
+        assert!(parameters.set("initial_layer_area", -1.0).is_err());
+        assert!(parameters.set("mass_display_factor", f64::NAN).is_err());
+        assert!(parameters.set("pixels_per_unit", 0.0).is_err());
+        assert!(parameters.set("energy_display_factor", 0.0).is_err());
+        assert!(parameters.set("mass_display_factor", -1.0).is_err());
+    }
+}
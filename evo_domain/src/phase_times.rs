@@ -0,0 +1,106 @@
+use std::time::Duration;
+#[cfg(feature = "timing")]
+use std::time::Instant;
+
+// How long each phase of a world's ticks took, added up over the ticks since timing was
+// enabled, for finding where the time goes and comparing it across changes and machines.
+// Without the "timing" feature the phases are all zero.
+#[derive(Clone, Debug, Default)]
+pub struct PhaseTimes {
+    ticks: u64,
+    // in tick order
+    phases: Vec<(&'static str, Duration)>,
+}
+
+impl PhaseTimes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    pub fn phases(&self) -> &[(&'static str, Duration)] {
+        &self.phases
+    }
+
+    pub fn total(&self) -> Duration {
+        self.phases.iter().map(|(_, duration)| *duration).sum()
+    }
+
+    pub(crate) fn add_tick(&mut self, clock: PhaseClock) {
+        self.ticks += 1;
+        for (phase, duration) in clock.laps {
+            match self.phases.iter_mut().find(|(name, _)| *name == phase) {
+                Some((_, total)) => *total += duration,
+                None => self.phases.push((phase, duration)),
+            }
+        }
+    }
+}
+
+// Times the phases of one tick, or does nothing if not running, so that a world without
+// phase timing doesn't keep laps.
+pub(crate) struct PhaseClock {
+    running: bool,
+    #[cfg(feature = "timing")]
+    last_lap_end: Instant,
+    laps: Vec<(&'static str, Duration)>,
+}
+
+impl PhaseClock {
+    pub fn start(running: bool) -> Self {
+        PhaseClock {
+            running,
+            #[cfg(feature = "timing")]
+            last_lap_end: Instant::now(),
+            laps: vec![],
+        }
+    }
+
+    // Ends the named phase, which started when the last one ended.
+    pub fn lap(&mut self, phase: &'static str) {
+        if !self.running {
+            return;
+        }
+        #[cfg(feature = "timing")]
+        let duration = {
+            let now = Instant::now();
+            let duration = now - self.last_lap_end;
+            self.last_lap_end = now;
+            duration
+        };
+        #[cfg(not(feature = "timing"))]
+        let duration = Duration::ZERO;
+        self.laps.push((phase, duration));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adds_up_phases_in_order() {
+        let mut times = PhaseTimes::new();
+        for _ in 0..2 {
+            let mut clock = PhaseClock::start(true);
+            clock.laps = vec![
+                ("first", Duration::from_millis(1)),
+                ("second", Duration::from_millis(3)),
+            ];
+            times.add_tick(clock);
+        }
+
+        assert_eq!(times.ticks(), 2);
+        assert_eq!(
+            times.phases(),
+            &[
+                ("first", Duration::from_millis(2)),
+                ("second", Duration::from_millis(6))
+            ]
+        );
+        assert_eq!(times.total(), Duration::from_millis(8));
+    }
+}
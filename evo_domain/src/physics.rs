@@ -1,9 +1,11 @@
 pub mod bond;
+pub mod bounds;
 pub mod handles;
 pub mod newtonian;
 pub mod node_graph;
 pub mod overlap;
 pub mod quantities;
 pub mod shapes;
+pub mod shards;
 pub mod simple_graph_elements;
 pub mod util;
@@ -130,19 +130,28 @@ impl AngleGusset {
     }
 }
 
+// The forces that straighten each gusset's outer nodes toward its angle, and the
+// torques they exert back on its vertex node through the bonds.
+#[allow(clippy::type_complexity)]
 pub fn calc_bond_angle_forces<C>(
     graph: &NodeGraph<C, Bond<C>, AngleGusset>,
-) -> Vec<(Handle<C>, Force)>
+) -> (Vec<(Handle<C>, Force)>, Vec<(Handle<C>, Torque)>)
 where
     C: Circle + GraphNode<C>,
 {
     let mut forces: Vec<(Handle<C>, Force)> = Vec::with_capacity(graph.meta_edges().len() * 2);
+    let mut torques: Vec<(Handle<C>, Torque)> = Vec::with_capacity(graph.meta_edges().len());
     for gusset in graph.meta_edges() {
         let force_pair = calc_bond_angle_force_pair(gusset, graph);
+        torques.push(calc_bond_angle_vertex_torque(
+            gusset,
+            graph,
+            &[force_pair.0, force_pair.1],
+        ));
         forces.push(force_pair.0);
         forces.push(force_pair.1);
     }
-    forces
+    (forces, torques)
 }
 
 fn calc_bond_angle_force_pair<C>(
@@ -178,6 +187,32 @@ where
     )
 }
 
+// The outer nodes push back on the vertex node where their bonds meet its surface. The
+// torques cancel when the bonds are the same length, but spin the vertex node when not.
+fn calc_bond_angle_vertex_torque<C>(
+    gusset: &AngleGusset,
+    graph: &NodeGraph<C, Bond<C>, AngleGusset>,
+    outer_forces: &[(Handle<C>, Force)],
+) -> (Handle<C>, Torque)
+where
+    C: Circle + GraphNode<C>,
+{
+    let node0 = graph.node(graph.edge(gusset.edge1_handle()).node2_handle());
+    let torque = outer_forces
+        .iter()
+        .map(|(handle, force)| {
+            let bond_offset = graph.node(*handle).center() - node0.center();
+            let bond_length = bond_offset.length().value();
+            if bond_length == 0.0 {
+                return Torque::ZERO;
+            }
+            let attachment_offset = bond_offset * (node0.radius().value() / bond_length);
+            Torque::of_force_at(attachment_offset, -*force)
+        })
+        .fold(Torque::ZERO, |sum, torque| sum + torque);
+    (node0.node_handle(), torque)
+}
+
 fn calc_bond_angle(origin: Position, point1: Position, point2: Position) -> Angle {
     let angle1 = point1.to_polar_angle(origin);
     let angle2 = point2.to_polar_angle(origin);
@@ -292,6 +327,43 @@ mod tests {
         assert!((force_pair.1).1.x() < 0.0);
     }
 
+    #[test]
+    fn unequal_bonds_spin_gusset_vertex() {
+        let mut graph: NodeGraph<SimpleCircleNode, Bond<SimpleCircleNode>, AngleGusset> =
+            NodeGraph::new();
+        let node1 = add_simple_circle_node(&mut graph, (0.0, 2.0), 1.0);
+        let node2 = add_simple_circle_node(&mut graph, (0.0, 0.0), 1.0);
+        let node3 = add_simple_circle_node(&mut graph, (0.1, -4.0), 1.0);
+        let bond1 = add_bond(&mut graph, node1, node2);
+        let bond2 = add_bond(&mut graph, node2, node3);
+        let gusset = add_angle_gusset(&mut graph, bond1, bond2, PI);
+        graph.add_meta_edge(gusset);
+
+        let (forces, torques) = calc_bond_angle_forces(&graph);
+
+        assert_eq!(forces.len(), 2);
+        assert_eq!(torques.len(), 1);
+        assert_eq!(node2, torques[0].0);
+        assert!(torques[0].1.value() < 0.0);
+    }
+
+    #[test]
+    fn equal_bonds_do_not_spin_gusset_vertex() {
+        let mut graph: NodeGraph<SimpleCircleNode, Bond<SimpleCircleNode>, AngleGusset> =
+            NodeGraph::new();
+        let node1 = add_simple_circle_node(&mut graph, (0.0, 2.0), 1.0);
+        let node2 = add_simple_circle_node(&mut graph, (0.0, 0.0), 1.0);
+        let node3 = add_simple_circle_node(&mut graph, (2.0, 0.0), 1.0);
+        let bond1 = add_bond(&mut graph, node1, node2);
+        let bond2 = add_bond(&mut graph, node2, node3);
+        let gusset = add_angle_gusset(&mut graph, bond1, bond2, PI);
+        graph.add_meta_edge(gusset);
+
+        let (_, torques) = calc_bond_angle_forces(&graph);
+
+        assert!(torques[0].1.value().abs() < 1e-12);
+    }
+
     #[test]
     fn three_quarter_right_angle_off_origin() {
         let origin = Position::new(1.0, 1.0);
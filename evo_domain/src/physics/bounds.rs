@@ -0,0 +1,302 @@
+use crate::physics::handles::*;
+use crate::physics::node_graph::*;
+use crate::physics::shapes::*;
+
+// The bounding boxes of a graph's nodes and of its colonies, the groups of nodes joined
+// by edges, kept up to date by the graph's owner, so that their users, such as a
+// renderer culling what is out of view or a tool selecting a region, don't each
+// recompute them. Nodes joining only merges colonies; removing nodes or edges, which
+// changes handles and can split colonies, makes the next update find them anew.
+#[derive(Debug)]
+pub struct BoundingBoxes<N: ObjectWithHandle<N>> {
+    // by node index
+    node_boxes: Vec<Rectangle>,
+    // colony index by node index
+    node_colonies: Vec<usize>,
+    colonies: Vec<Colony<N>>,
+    colonies_valid: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct Colony<N: ObjectWithHandle<N>> {
+    node_handles: Vec<Handle<N>>,
+    bounds: Rectangle,
+}
+
+impl<N: ObjectWithHandle<N>> Colony<N> {
+    pub fn node_handles(&self) -> &[Handle<N>] {
+        &self.node_handles
+    }
+
+    pub fn bounds(&self) -> Rectangle {
+        self.bounds
+    }
+}
+
+impl<N: GraphNode<N> + Circle> BoundingBoxes<N> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        BoundingBoxes {
+            node_boxes: vec![],
+            node_colonies: vec![],
+            colonies: vec![],
+            colonies_valid: true,
+        }
+    }
+
+    // For a node just added to the graph, which starts a colony of its own.
+    pub fn add_node(&mut self, node: &N) {
+        debug_assert_eq!(node.node_handle().index(), self.node_boxes.len());
+        let bounds = node.to_bounding_box();
+        self.node_boxes.push(bounds);
+        self.node_colonies.push(self.colonies.len());
+        self.colonies.push(Colony {
+            node_handles: vec![node.node_handle()],
+            bounds,
+        });
+    }
+
+    // For an edge just added to the graph, which merges its nodes' colonies, the smaller
+    // into the larger.
+    pub fn join(&mut self, handle1: Handle<N>, handle2: Handle<N>) {
+        if !self.colonies_valid {
+            return;
+        }
+        let colony1 = self.node_colonies[handle1.index()];
+        let colony2 = self.node_colonies[handle2.index()];
+        if colony1 == colony2 {
+            return;
+        }
+        let (mut kept, absorbed) = if self.colonies[colony1].node_handles.len()
+            >= self.colonies[colony2].node_handles.len()
+        {
+            (colony1, colony2)
+        } else {
+            (colony2, colony1)
+        };
+        let absorbed_colony = self.colonies.swap_remove(absorbed);
+        if absorbed < self.colonies.len() {
+            // the last colony moved into the absorbed one's place
+            if kept == self.colonies.len() {
+                kept = absorbed;
+            }
+            for handle in &self.colonies[absorbed].node_handles {
+                self.node_colonies[handle.index()] = absorbed;
+            }
+        }
+        for handle in &absorbed_colony.node_handles {
+            self.node_colonies[handle.index()] = kept;
+        }
+        let colony = &mut self.colonies[kept];
+        colony.bounds = colony.bounds.union(absorbed_colony.bounds);
+        colony.node_handles.extend(absorbed_colony.node_handles);
+    }
+
+    // For nodes or edges about to be removed from the graph.
+    pub fn invalidate_colonies(&mut self) {
+        self.colonies_valid = false;
+    }
+
+    // Recomputes the boxes from where the nodes are now, and the colonies too if they
+    // were invalidated.
+    pub fn update<E: GraphEdge<N>, ME: GraphMetaEdge>(&mut self, graph: &NodeGraph<N, E, ME>) {
+        self.node_boxes.clear();
+        self.node_boxes
+            .extend(graph.nodes().iter().map(Circle::to_bounding_box));
+        if !self.colonies_valid {
+            self.find_colonies(graph);
+        }
+        for colony in &mut self.colonies {
+            let node_boxes = &self.node_boxes;
+            colony.bounds = colony
+                .node_handles
+                .iter()
+                .map(|handle| node_boxes[handle.index()])
+                .fold(
+                    node_boxes[colony.node_handles[0].index()],
+                    |bounds, node_box| bounds.union(node_box),
+                );
+        }
+    }
+
+    // Union-find over the edges, with the colonies in order of their first nodes.
+    fn find_colonies<E: GraphEdge<N>, ME: GraphMetaEdge>(&mut self, graph: &NodeGraph<N, E, ME>) {
+        fn root(parents: &mut [usize], mut index: usize) -> usize {
+            while parents[index] != index {
+                parents[index] = parents[parents[index]];
+                index = parents[index];
+            }
+            index
+        }
+
+        let nodes = graph.nodes();
+        let mut parents = (0..nodes.len()).collect::<Vec<_>>();
+        for edge in graph.edges() {
+            let root1 = root(&mut parents, edge.node1_handle().index());
+            let root2 = root(&mut parents, edge.node2_handle().index());
+            parents[root1.max(root2)] = root1.min(root2);
+        }
+
+        self.colonies.clear();
+        self.node_colonies.clear();
+        let mut root_colonies = vec![usize::MAX; nodes.len()];
+        for (index, node) in nodes.iter().enumerate() {
+            let root = root(&mut parents, index);
+            if root_colonies[root] == usize::MAX {
+                root_colonies[root] = self.colonies.len();
+                self.colonies.push(Colony {
+                    node_handles: vec![],
+                    bounds: self.node_boxes[index],
+                });
+            }
+            self.node_colonies.push(root_colonies[root]);
+            self.colonies[root_colonies[root]]
+                .node_handles
+                .push(node.node_handle());
+        }
+        self.colonies_valid = true;
+    }
+
+    pub fn node_bounds(&self, handle: Handle<N>) -> Rectangle {
+        self.node_boxes[handle.index()]
+    }
+
+    pub fn colonies(&self) -> &[Colony<N>] {
+        &self.colonies
+    }
+
+    pub fn colony_of(&self, handle: Handle<N>) -> &Colony<N> {
+        &self.colonies[self.node_colonies[handle.index()]]
+    }
+
+    // The box around every node, or None if there are none.
+    pub fn overall_bounds(&self) -> Option<Rectangle> {
+        self.colonies
+            .iter()
+            .map(Colony::bounds)
+            .reduce(|bounds1, bounds2| bounds1.union(bounds2))
+    }
+
+    // The indexes of the nodes whose boxes overlap the region, in order.
+    pub fn node_indexes_overlapping(&self, region: Rectangle) -> Vec<usize> {
+        self.node_boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, node_box)| node_box.overlaps(region))
+            .map(|(index, _)| index)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::quantities::*;
+    use crate::physics::simple_graph_elements::*;
+
+    type Graph =
+        NodeGraph<SimpleCircleNode, SimpleGraphEdge<SimpleCircleNode>, SimpleGraphMetaEdge>;
+
+    #[test]
+    fn joined_nodes_share_colony_bounds() {
+        let mut graph = Graph::new();
+        let mut boxes = BoundingBoxes::new();
+        let handles = add_nodes(
+            &mut graph,
+            &mut boxes,
+            &[(0.0, 0.0), (10.0, 0.0), (20.0, 5.0)],
+        );
+
+        join(&mut graph, &mut boxes, handles[2], handles[1]);
+        join(&mut graph, &mut boxes, handles[0], handles[1]);
+
+        assert_eq!(boxes.colonies().len(), 1);
+        assert_eq!(
+            boxes.colony_of(handles[0]).bounds(),
+            Rectangle::new(Position::new(-1.0, -1.0), Position::new(21.0, 6.0))
+        );
+        assert_eq!(boxes.colony_of(handles[0]).node_handles().len(), 3);
+    }
+
+    #[test]
+    fn update_follows_moves_and_splits() {
+        let mut graph = Graph::new();
+        let mut boxes = BoundingBoxes::new();
+        let handles = add_nodes(
+            &mut graph,
+            &mut boxes,
+            &[(0.0, 0.0), (10.0, 0.0), (20.0, 0.0)],
+        );
+        join(&mut graph, &mut boxes, handles[0], handles[1]);
+        join(&mut graph, &mut boxes, handles[1], handles[2]);
+
+        graph
+            .node_mut(handles[2])
+            .set_center(Position::new(30.0, 0.0));
+        boxes.invalidate_colonies();
+        let edge_handle = graph.node(handles[2]).edge_handle(0);
+        graph.remove_edges(&[edge_handle]);
+        boxes.update(&graph);
+
+        assert_eq!(boxes.colonies().len(), 2);
+        assert_eq!(
+            boxes.colony_of(handles[2]).bounds(),
+            Rectangle::new(Position::new(29.0, -1.0), Position::new(31.0, 1.0))
+        );
+        assert_eq!(
+            boxes.overall_bounds(),
+            Some(Rectangle::new(
+                Position::new(-1.0, -1.0),
+                Position::new(31.0, 1.0)
+            ))
+        );
+        assert_eq!(
+            boxes.node_indexes_overlapping(Rectangle::new(
+                Position::new(5.0, -5.0),
+                Position::new(29.5, 5.0)
+            )),
+            vec![1, 2]
+        );
+    }
+
+    fn add_nodes(
+        graph: &mut Graph,
+        boxes: &mut BoundingBoxes<SimpleCircleNode>,
+        centers: &[(f64, f64)],
+    ) -> Vec<Handle<SimpleCircleNode>> {
+        centers
+            .iter()
+            .map(|(x, y)| {
+                let handle = graph.add_node(SimpleCircleNode::new(
+                    Position::new(*x, *y),
+                    Length::new(1.0),
+                ));
+                boxes.add_node(graph.node(handle));
+                handle
+            })
+            .collect()
+    }
+
+    fn join(
+        graph: &mut Graph,
+        boxes: &mut BoundingBoxes<SimpleCircleNode>,
+        handle1: Handle<SimpleCircleNode>,
+        handle2: Handle<SimpleCircleNode>,
+    ) {
+        let edge = SimpleGraphEdge::new(graph.node(handle1), graph.node(handle2));
+        let edge_index1 = graph
+            .node(handle1)
+            .edge_handles()
+            .iter()
+            .filter(|h| h.is_some())
+            .count();
+        let edge_index2 = graph
+            .node(handle2)
+            .edge_handles()
+            .iter()
+            .filter(|h| h.is_some())
+            .count();
+        graph.add_edge(edge, edge_index1, edge_index2);
+        boxes.join(handle1, handle2);
+    }
+}
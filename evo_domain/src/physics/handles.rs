@@ -141,7 +141,7 @@ impl<T: ObjectWithHandle<T>> Handle<T> {
         }
     }
 
-    fn index(self) -> usize {
+    pub fn index(self) -> usize {
         self.index as usize
     }
 }
@@ -10,17 +10,33 @@ use std::ops::Neg;
 pub struct Overlap {
     incursion: Displacement,
     width: Value1D,
+    closing_speed: Value1D,
 }
 
 impl Overlap {
     pub fn new(incursion: Displacement, width: Value1D) -> Self {
-        Overlap { incursion, width }
+        Overlap {
+            incursion,
+            width,
+            closing_speed: 0.0,
+        }
+    }
+
+    // How fast the overlap is getting deeper, e.g. in a crash; negative if the
+    // overlapping objects are separating.
+    pub fn with_closing_speed(mut self, closing_speed: Value1D) -> Self {
+        self.closing_speed = closing_speed;
+        self
     }
 
     pub fn incursion(&self) -> Displacement {
         self.incursion
     }
 
+    pub fn closing_speed(&self) -> Value1D {
+        self.closing_speed
+    }
+
     pub fn area(&self) -> Value1D {
         self.depth() * self.width
     }
@@ -34,7 +50,7 @@ impl Neg for Overlap {
     type Output = Overlap;
 
     fn neg(self) -> Self::Output {
-        Overlap::new(-self.incursion, self.width)
+        Overlap::new(-self.incursion, self.width).with_closing_speed(self.closing_speed)
     }
 }
 
@@ -93,7 +109,7 @@ impl Walls {
     }
 }
 
-type PairOverlap<C> = ((Handle<C>, Overlap), (Handle<C>, Overlap));
+pub type PairOverlap<C> = ((Handle<C>, Overlap), (Handle<C>, Overlap));
 
 pub fn find_pair_overlaps<C, E, ME>(
     graph: &mut NodeGraph<C, E, ME>,
@@ -1444,6 +1444,11 @@ pub struct Density {
 }
 
 impl Density {
+    pub const ZERO: Density = Density { value: 0.0 };
+    pub const MAX: Density = Density {
+        value: f64::INFINITY,
+    };
+
     pub fn new(value: Value1D) -> Self {
         if value < 0.0 {
             panic!("Invalid density: {}", value);
@@ -1452,6 +1457,10 @@ impl Density {
         Density { value }
     }
 
+    pub const fn unchecked(value: Value1D) -> Self {
+        Density { value }
+    }
+
     #[allow(dead_code)]
     pub fn value(self) -> Value1D {
         self.value
@@ -1585,6 +1594,37 @@ impl Torque {
     }
 }
 
+impl Torque {
+    pub const ZERO: Torque = Torque { value: 0.0 };
+
+    // The torque about a body's center of a force applied at the offset from it.
+    pub fn of_force_at(offset: Displacement, force: Force) -> Self {
+        Torque::new(offset.x() * force.y() - offset.y() * force.x())
+    }
+}
+
+impl Add for Torque {
+    type Output = Torque;
+
+    fn add(self, rhs: Torque) -> Self::Output {
+        Torque::new(self.value + rhs.value)
+    }
+}
+
+impl AddAssign for Torque {
+    fn add_assign(&mut self, rhs: Torque) {
+        self.value += rhs.value;
+    }
+}
+
+impl Mul<Duration> for Torque {
+    type Output = AngularImpulse;
+
+    fn mul(self, rhs: Duration) -> Self::Output {
+        AngularImpulse::new(self.value * rhs.value)
+    }
+}
+
 impl Neg for Torque {
     type Output = Torque;
 
@@ -1593,6 +1633,139 @@ impl Neg for Torque {
     }
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, PartialOrd, Serialize)]
+pub struct AngularImpulse {
+    value: Value1D,
+}
+
+impl AngularImpulse {
+    pub fn new(value: Value1D) -> Self {
+        AngularImpulse { value }
+    }
+
+    #[allow(dead_code)]
+    pub fn value(self) -> Value1D {
+        self.value
+    }
+}
+
+impl Div<MomentOfInertia> for AngularImpulse {
+    type Output = AngularVelocity;
+
+    fn div(self, rhs: MomentOfInertia) -> Self::Output {
+        AngularVelocity::new(self.value / rhs.value)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, PartialOrd, Serialize)]
+pub struct MomentOfInertia {
+    value: Value1D,
+}
+
+impl MomentOfInertia {
+    pub const ZERO: MomentOfInertia = MomentOfInertia { value: 0.0 };
+
+    pub fn new(value: Value1D) -> Self {
+        MomentOfInertia { value }
+    }
+
+    // Of a uniform ring, or a disk if the inner radius is zero, spinning about its center.
+    pub fn of_annulus(mass: Mass, inner_radius: Length, outer_radius: Length) -> Self {
+        MomentOfInertia::new(mass.value * (inner_radius.sqr() + outer_radius.sqr()).value() / 2.0)
+    }
+
+    #[allow(dead_code)]
+    pub fn value(self) -> Value1D {
+        self.value
+    }
+}
+
+impl Add for MomentOfInertia {
+    type Output = MomentOfInertia;
+
+    fn add(self, rhs: MomentOfInertia) -> Self::Output {
+        MomentOfInertia::new(self.value + rhs.value)
+    }
+}
+
+// In radians per tick, counterclockwise.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, PartialOrd, Serialize)]
+pub struct AngularVelocity {
+    radians: Value1D,
+}
+
+impl AngularVelocity {
+    pub const ZERO: AngularVelocity = AngularVelocity { radians: 0.0 };
+
+    pub fn new(radians: Value1D) -> Self {
+        AngularVelocity { radians }
+    }
+
+    pub fn radians(self) -> Value1D {
+        self.radians
+    }
+}
+
+impl Add for AngularVelocity {
+    type Output = AngularVelocity;
+
+    fn add(self, rhs: AngularVelocity) -> Self::Output {
+        AngularVelocity::new(self.radians + rhs.radians)
+    }
+}
+
+impl Mul<Duration> for AngularVelocity {
+    type Output = Deflection;
+
+    fn mul(self, rhs: Duration) -> Self::Output {
+        Deflection::from_radians(self.radians * rhs.value)
+    }
+}
+
+// Plain (x, y) tuples and [x, y] arrays, for FFI and telemetry code that would otherwise
+// convert field by field.
+macro_rules! impl_2d_conversions {
+    ($($quantity:ident),*) => {
+        $(
+            impl From<(Value1D, Value1D)> for $quantity {
+                fn from((x, y): (Value1D, Value1D)) -> Self {
+                    $quantity { x, y }
+                }
+            }
+
+            impl From<[Value1D; 2]> for $quantity {
+                fn from([x, y]: [Value1D; 2]) -> Self {
+                    $quantity { x, y }
+                }
+            }
+
+            impl From<$quantity> for (Value1D, Value1D) {
+                fn from(quantity: $quantity) -> Self {
+                    (quantity.x, quantity.y)
+                }
+            }
+
+            impl From<$quantity> for [Value1D; 2] {
+                fn from(quantity: $quantity) -> Self {
+                    [quantity.x, quantity.y]
+                }
+            }
+        )*
+    };
+}
+
+impl_2d_conversions!(
+    Value2D,
+    Position,
+    Displacement,
+    Velocity,
+    Acceleration,
+    DeltaV,
+    Momentum,
+    Impulse,
+    Force
+);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1875,4 +2048,38 @@ mod tests {
     fn negate_torque() {
         assert_eq!(Torque::new(-0.75), -Torque::new(0.75));
     }
+
+    #[test]
+    fn torque_of_off_center_force() {
+        assert_eq!(
+            Torque::of_force_at(Displacement::new(2.0, 0.0), Force::new(1.0, 0.5)),
+            Torque::new(1.0)
+        );
+        assert_eq!(
+            Torque::of_force_at(Displacement::new(0.0, 2.0), Force::new(0.5, 1.0)),
+            Torque::new(-1.0)
+        );
+    }
+
+    #[test]
+    fn torque_to_angular_velocity() {
+        assert_eq!(
+            Torque::new(1.5) * Duration::new(0.5) / MomentOfInertia::new(3.0),
+            AngularVelocity::new(0.25)
+        );
+        assert_eq!(
+            AngularVelocity::new(0.25) * Duration::new(2.0),
+            Deflection::from_radians(0.5)
+        );
+    }
+
+    #[test]
+    fn convert_2d_quantities_to_and_from_plain_values() {
+        assert_eq!(Position::from((1.5, -2.0)), Position::new(1.5, -2.0));
+        assert_eq!(Velocity::from([1.5, -2.0]), Velocity::new(1.5, -2.0));
+        let tuple: (Value1D, Value1D) = Force::new(0.25, 4.0).into();
+        assert_eq!(tuple, (0.25, 4.0));
+        let array: [Value1D; 2] = Displacement::new(0.25, 4.0).into();
+        assert_eq!(array, [0.25, 4.0]);
+    }
 }
@@ -63,6 +63,20 @@ impl Rectangle {
         let other_y_range = FloatRange::new(other.min_corner.y(), other.max_corner.y());
         self_x_range.overlaps(other_x_range) && self_y_range.overlaps(other_y_range)
     }
+
+    // The smallest rectangle that covers both.
+    pub fn union(&self, other: Rectangle) -> Rectangle {
+        Rectangle::new(
+            Position::new(
+                self.min_corner.x().min(other.min_corner.x()),
+                self.min_corner.y().min(other.min_corner.y()),
+            ),
+            Position::new(
+                self.max_corner.x().max(other.max_corner.x()),
+                self.max_corner.y().max(other.max_corner.y()),
+            ),
+        )
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -0,0 +1,278 @@
+use crate::physics::node_graph::*;
+use crate::physics::overlap::{calc_overlap, PairOverlap};
+use crate::physics::quantities::*;
+use crate::physics::shapes::*;
+use rayon::prelude::*;
+use std::cmp::Ordering;
+
+// Experimental: finds the same overlapping pairs as find_pair_overlaps, but splits the
+// world into vertical strips ("shards") and sweeps each one on its own thread, for very
+// large worlds where the serial sweep dominates the tick.
+//
+// Each cell belongs to the shard that holds its center. At the border between shards,
+// each shard also sweeps copies of the cells from the shards to its right whose centers
+// are closer to its right edge than the widest possible overlap, twice the largest
+// radius. Then every overlapping pair is seen whole by the shard that owns the pair's
+// leftmost cell, and only that shard reports it, so no pair is missed or reported
+// twice. Bonded pairs are skipped, as in find_pair_overlaps, by looking up the bonds,
+// which the shards only read.
+//
+// The pairs come out grouped by shard, in a different order than from
+// find_pair_overlaps. Collision forces keep the dominant force on each cell, so a cell
+// caught between several others can end up pushed differently than in an unsharded run.
+pub fn find_pair_overlaps_sharded<C, E, ME>(
+    graph: &NodeGraph<C, E, ME>,
+    shard_count: usize,
+) -> Vec<PairOverlap<C>>
+where
+    C: Circle + GraphNode<C> + Send + Sync,
+    E: GraphEdge<C> + Sync,
+    ME: GraphMetaEdge + Sync,
+{
+    let shards = Shards::new(graph.nodes(), shard_count.max(1));
+    let shard_members = shards.members(graph.nodes());
+    shard_members
+        .par_iter()
+        .enumerate()
+        .map(|(shard, members)| shards.shard_pair_overlaps(graph, shard, members))
+        .collect::<Vec<_>>()
+        .concat()
+}
+
+struct Shards {
+    min_x: Value1D,
+    width: Value1D,
+    count: usize,
+    border_width: Value1D,
+}
+
+impl Shards {
+    fn new<C: Circle>(circles: &[C], count: usize) -> Self {
+        let (min_x, max_x, max_radius) = circles.iter().fold(
+            (Value1D::INFINITY, Value1D::NEG_INFINITY, 0.0 as Value1D),
+            |(min_x, max_x, max_radius), circle| {
+                (
+                    min_x.min(circle.center().x()),
+                    max_x.max(circle.center().x()),
+                    max_radius.max(circle.radius().value()),
+                )
+            },
+        );
+        Shards {
+            min_x,
+            width: (max_x - min_x) / count as Value1D,
+            count,
+            border_width: 2.0 * max_radius,
+        }
+    }
+
+    fn owner(&self, x: Value1D) -> usize {
+        if self.width > 0.0 {
+            (((x - self.min_x) / self.width) as usize).min(self.count - 1)
+        } else {
+            0
+        }
+    }
+
+    fn right_edge(&self, shard: usize) -> Value1D {
+        self.min_x + (shard + 1) as Value1D * self.width
+    }
+
+    // The indexes of the circles each shard sweeps: its own, then copies of its
+    // neighbors' from across its right border.
+    fn members<C: Circle>(&self, circles: &[C]) -> Vec<Vec<usize>> {
+        let mut members = vec![vec![]; self.count];
+        for (index, circle) in circles.iter().enumerate() {
+            let x = circle.center().x();
+            let owner = self.owner(x);
+            members[owner].push(index);
+            for shard in (0..owner).rev() {
+                if x >= self.right_edge(shard) + self.border_width {
+                    break;
+                }
+                members[shard].push(index);
+            }
+        }
+        members
+    }
+
+    fn shard_pair_overlaps<C, E, ME>(
+        &self,
+        graph: &NodeGraph<C, E, ME>,
+        shard: usize,
+        members: &[usize],
+    ) -> Vec<PairOverlap<C>>
+    where
+        C: Circle + GraphNode<C>,
+        E: GraphEdge<C>,
+        ME: GraphMetaEdge,
+    {
+        let circles = graph.nodes();
+        let mut sorted = members.to_vec();
+        sorted.sort_by(|&index1, &index2| {
+            circles[index1]
+                .min_x()
+                .partial_cmp(&circles[index2].min_x())
+                .unwrap()
+                .then(index1.cmp(&index2))
+        });
+
+        let mut overlaps = vec![];
+        for (i, &index1) in sorted.iter().enumerate() {
+            let circle1 = &circles[index1];
+            for &index2 in &sorted[(i + 1)..] {
+                let circle2 = &circles[index2];
+                if circle2.min_x() >= circle1.max_x() {
+                    break;
+                }
+                if self.owner(Self::leftmost(circle1, index1, circle2, index2)) != shard {
+                    continue;
+                }
+                if graph.have_edge(circle1, circle2) {
+                    continue;
+                }
+                if let Some(overlap) = calc_overlap(circle1, circle2) {
+                    overlaps.push((
+                        (circle1.node_handle(), overlap),
+                        (circle2.node_handle(), -overlap),
+                    ));
+                }
+            }
+        }
+        overlaps
+    }
+
+    // the center x of the pair's leftmost circle, by center and then by index
+    fn leftmost<C: Circle>(circle1: &C, index1: usize, circle2: &C, index2: usize) -> Value1D {
+        let x1 = circle1.center().x();
+        let x2 = circle2.center().x();
+        match x1.partial_cmp(&x2).unwrap().then(index1.cmp(&index2)) {
+            Ordering::Greater => x2,
+            _ => x1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::handles::Handle;
+    use crate::physics::overlap::{find_pair_overlaps, SortableHandle, SortableHandles};
+    use crate::physics::simple_graph_elements::*;
+    use rand::{Rng, SeedableRng};
+    use rand_pcg::Pcg64Mcg;
+    use std::time::Instant;
+
+    type SimpleGraph =
+        NodeGraph<SimpleCircleNode, SimpleGraphEdge<SimpleCircleNode>, SimpleGraphMetaEdge>;
+
+    #[test]
+    fn sharded_overlaps_match_unsharded_overlaps() {
+        for seed in 0..5 {
+            let (mut graph, mut handles) = crowded_graph(300, seed);
+            let expected = sorted_pairs(&find_pair_overlaps(&mut graph, &mut handles));
+
+            for shard_count in 1..=6 {
+                assert_eq!(
+                    sorted_pairs(&find_pair_overlaps_sharded(&graph, shard_count)),
+                    expected,
+                    "seed {}, {} shards",
+                    seed,
+                    shard_count
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn pair_straddling_shard_border_is_found_once() {
+        let mut graph = SimpleGraph::new();
+        graph.add_node(SimpleCircleNode::new(
+            Position::new(0.0, 0.0),
+            Length::new(1.0),
+        ));
+        graph.add_node(SimpleCircleNode::new(
+            Position::new(4.9, 0.0),
+            Length::new(1.0),
+        ));
+        graph.add_node(SimpleCircleNode::new(
+            Position::new(5.1, 0.0),
+            Length::new(1.0),
+        ));
+        graph.add_node(SimpleCircleNode::new(
+            Position::new(10.0, 0.0),
+            Length::new(1.0),
+        ));
+
+        let overlaps = find_pair_overlaps_sharded(&graph, 2);
+
+        assert_eq!(
+            sorted_pairs(&overlaps),
+            vec![(Handle::new(1), Handle::new(2))]
+        );
+    }
+
+    #[test]
+    fn one_circle_or_none_has_no_overlaps() {
+        let mut graph = SimpleGraph::new();
+        assert!(find_pair_overlaps_sharded(&graph, 4).is_empty());
+
+        graph.add_node(SimpleCircleNode::new(Position::ORIGIN, Length::new(1.0)));
+        assert!(find_pair_overlaps_sharded(&graph, 4).is_empty());
+    }
+
+    // cargo test --release -p evo_domain sharded_overlaps_benchmark -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn sharded_overlaps_benchmark() {
+        const REPEATS: u32 = 10;
+        let (mut graph, mut handles) = crowded_graph(100_000, 0);
+        // sort the handles, as they mostly are from tick to tick
+        find_pair_overlaps(&mut graph, &mut handles);
+
+        let start = Instant::now();
+        for _ in 0..REPEATS {
+            find_pair_overlaps(&mut graph, &mut handles);
+        }
+        println!("unsharded: {:?}", start.elapsed() / REPEATS);
+
+        for &shard_count in &[1, 2, 4, 8, 16, 32] {
+            let start = Instant::now();
+            for _ in 0..REPEATS {
+                find_pair_overlaps_sharded(&graph, shard_count);
+            }
+            println!("{} shards: {:?}", shard_count, start.elapsed() / REPEATS);
+        }
+    }
+
+    // unit circles scattered over a square too small for them, so many overlap
+    fn crowded_graph(count: usize, seed: u64) -> (SimpleGraph, SortableHandles<SimpleCircleNode>) {
+        let mut rng = Pcg64Mcg::seed_from_u64(seed);
+        let half_width = (count as Value1D).sqrt();
+        let mut graph = SimpleGraph::new();
+        let mut handles = SortableHandles::new();
+        for _ in 0..count {
+            let handle = graph.add_node(SimpleCircleNode::new(
+                Position::new(
+                    rng.gen_range(-half_width, half_width),
+                    rng.gen_range(-half_width, half_width),
+                ),
+                Length::new(rng.gen_range(0.5, 1.5)),
+            ));
+            handles.add_handle(SortableHandle::GraphNode(handle));
+        }
+        (graph, handles)
+    }
+
+    // each pair's handles, lower first, in order
+    fn sorted_pairs(
+        overlaps: &[PairOverlap<SimpleCircleNode>],
+    ) -> Vec<(Handle<SimpleCircleNode>, Handle<SimpleCircleNode>)> {
+        let mut pairs = overlaps
+            .iter()
+            .map(|&((handle1, _), (handle2, _))| (handle1.min(handle2), handle1.max(handle2)))
+            .collect::<Vec<_>>();
+        pairs.sort_unstable();
+        pairs
+    }
+}
@@ -0,0 +1,207 @@
+use crate::biology::cell::Cell;
+use crate::biology::cloud::Cloud;
+use crate::biology::layers::Tissue;
+use crate::physics::bond::Bond;
+use crate::physics::newtonian::NewtonianBody;
+use crate::physics::node_graph::GraphEdge;
+use crate::physics::quantities::*;
+use crate::physics::shapes::Circle;
+use crate::world::World;
+
+// What a front end needs to draw a world's cells, bonds, and clouds, copied out of the
+// world and tied to no renderer, so that every front end turns cells into sprites from
+// the same extraction instead of walking the world its own way.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RenderFrame {
+    pub tick: u64,
+    pub cells: Vec<CellFrame>,
+    pub bonds: Vec<BondFrame>,
+    // each anchored cell's tether to its anchor point, drawn like a bond
+    pub anchors: Vec<BondFrame>,
+    pub clouds: Vec<CloudFrame>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CellFrame {
+    pub cell_id: u64,
+    pub center: Position,
+    pub velocity: Velocity,
+    pub radius: Length,
+    pub energy: BioEnergy,
+    pub selected: bool,
+    // innermost first
+    pub layers: Vec<LayerFrame>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LayerFrame {
+    pub outer_radius: Length,
+    pub health: Health,
+    pub tissue: Tissue,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BondFrame {
+    pub end1: Position,
+    pub end2: Position,
+    pub radius1: Length,
+    pub radius2: Length,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CloudFrame {
+    pub center: Position,
+    pub radius: Length,
+    pub concentration: Fraction,
+}
+
+impl RenderFrame {
+    pub fn new(world: &World) -> Self {
+        RenderFrame {
+            tick: world.tick_count(),
+            cells: world.cells().iter().map(CellFrame::new).collect(),
+            bonds: world
+                .bonds()
+                .iter()
+                .map(|bond| BondFrame::for_bond(bond, world))
+                .collect(),
+            anchors: world
+                .cells()
+                .iter()
+                .filter_map(BondFrame::for_anchor)
+                .collect(),
+            clouds: world.clouds().iter().map(CloudFrame::new).collect(),
+        }
+    }
+
+    pub fn total_cell_energy(&self) -> BioEnergy {
+        self.cells
+            .iter()
+            .fold(BioEnergy::ZERO, |total, cell| total + cell.energy)
+    }
+}
+
+impl CellFrame {
+    pub fn new(cell: &Cell) -> Self {
+        CellFrame {
+            cell_id: cell.cell_id(),
+            center: cell.center(),
+            velocity: cell.velocity(),
+            radius: cell.radius(),
+            energy: cell.energy(),
+            selected: cell.is_selected(),
+            layers: cell
+                .layers()
+                .iter()
+                .map(|layer| LayerFrame {
+                    outer_radius: layer.outer_radius(),
+                    health: layer.health(),
+                    tissue: layer.tissue(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl BondFrame {
+    fn for_bond(bond: &Bond<Cell>, world: &World) -> Self {
+        let cell1 = world.cell(bond.node1_handle());
+        let cell2 = world.cell(bond.node2_handle());
+        BondFrame {
+            end1: cell1.center(),
+            end2: cell2.center(),
+            radius1: cell1.radius(),
+            radius2: cell2.radius(),
+        }
+    }
+
+    // from the cell to its anchor point, narrowing to a quarter of the cell's radius
+    fn for_anchor(cell: &Cell) -> Option<Self> {
+        cell.anchor().map(|anchor| BondFrame {
+            end1: cell.center(),
+            end2: anchor,
+            radius1: cell.radius(),
+            radius2: cell.radius() * 0.25,
+        })
+    }
+}
+
+impl CloudFrame {
+    fn new(cloud: &Cloud) -> Self {
+        CloudFrame {
+            center: cloud.center(),
+            radius: cloud.radius(),
+            concentration: cloud.concentration(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biology::layers::*;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn frame_copies_cells_and_bonds_out_of_world() {
+        let world = World::new(Position::new(-10.0, -10.0), Position::new(10.0, 10.0))
+            .with_cells(vec![
+                Cell::new(
+                    Position::new(-1.0, 0.0),
+                    Velocity::new(2.0, 0.0),
+                    vec![
+                        CellLayer::new(
+                            Area::new(PI),
+                            Density::new(1.0),
+                            Tissue::Photosynthetic,
+                            Box::new(NullCellLayerSpecialty::new()),
+                        ),
+                        CellLayer::new(
+                            Area::new(3.0 * PI),
+                            Density::new(1.0),
+                            Tissue::CellWall,
+                            Box::new(NullCellLayerSpecialty::new()),
+                        ),
+                    ],
+                )
+                .with_initial_energy(BioEnergy::new(5.0)),
+                Cell::ball(
+                    Length::new(1.0),
+                    Mass::new(1.0),
+                    Position::new(3.0, 0.0),
+                    Velocity::ZERO,
+                )
+                .with_initial_energy(BioEnergy::new(1.0)),
+            ])
+            .with_bonds(vec![(0, 1)]);
+
+        let frame = RenderFrame::new(&world);
+
+        assert_eq!(frame.cells.len(), 2);
+        let cell = &frame.cells[0];
+        assert_eq!(cell.center, Position::new(-1.0, 0.0));
+        assert_eq!(cell.velocity, Velocity::new(2.0, 0.0));
+        assert_eq!(cell.radius, Length::new(2.0));
+        assert_eq!(
+            cell.layers
+                .iter()
+                .map(|layer| (layer.outer_radius, layer.tissue))
+                .collect::<Vec<_>>(),
+            vec![
+                (Length::new(1.0), Tissue::Photosynthetic),
+                (Length::new(2.0), Tissue::CellWall)
+            ]
+        );
+        assert_eq!(frame.total_cell_energy(), BioEnergy::new(6.0));
+        assert_eq!(
+            frame.bonds,
+            vec![BondFrame {
+                end1: Position::new(-1.0, 0.0),
+                end2: Position::new(3.0, 0.0),
+                radius1: Length::new(2.0),
+                radius2: Length::new(1.0),
+            }]
+        );
+        assert!(frame.anchors.is_empty());
+    }
+}
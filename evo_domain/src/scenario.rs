@@ -0,0 +1,883 @@
+use crate::biology::cell::Cell;
+use crate::biology::control::CellControl;
+use crate::biology::layers::*;
+use crate::environment::force_registry::ForceRegistry;
+use crate::physics::quantities::*;
+use crate::world::World;
+use crate::Parameters;
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg64Mcg;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+// A world described in a TOML or RON file instead of in code: its corners, parameters,
+// influences, the layers its cells are made of, and where its cells start. Numbers can
+// be named in a [variables] table and used elsewhere as "$name", including inside force
+// specs, so that a run can change them with --set, e.g.
+//
+//   min_corner = [0.0, -400.0]
+//   max_corner = [1200.0, 0.0]
+//   forces = ["weight:gravity=$gravity", "drag:viscosity=0.005"]
+//
+//   [variables]
+//   gravity = -0.05
+//
+//   [layers.photo]
+//   tissue = "Photosynthetic"
+//   area = 15.7
+//   density = 0.002
+//   specialty = { kind = "photo", efficiency = 0.1 }
+//   parameters = { healing_energy_delta = -1.0, max_growth_rate = 10.0 }
+//
+//   [[cells]]
+//   layers = ["photo"]
+//   position = [200.0, -50.0]
+//   energy = 50.0
+//   count = "$founders"
+//   spread = [150.0, 40.0]
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ScenarioSpec {
+    pub min_corner: [Value1D; 2],
+    pub max_corner: [Value1D; 2],
+    // by Parameters::NAMES
+    #[serde(default)]
+    pub parameters: BTreeMap<String, Value1D>,
+    #[serde(default = "default_standard_influences")]
+    pub standard_influences: bool,
+    // [min intensity, max intensity]
+    #[serde(default)]
+    pub sunlight: Option<[Value1D; 2]>,
+    #[serde(default)]
+    pub thermal_noise: Value1D,
+    // if given, overlapping cells that ask to bond are bonded, and bonds strained past
+    // this are broken; see BondFormation
+    #[serde(default)]
+    pub bond_breaking_strain: Option<Value1D>,
+    // if given, cells on the bottom wall are held back by friction with this
+    // coefficient, plus their grip; see SubstrateFriction
+    #[serde(default)]
+    pub substrate_friction: Option<Value1D>,
+    // by ForceRegistry spec, e.g. "drag:viscosity=0.005"
+    #[serde(default)]
+    pub forces: Vec<String>,
+    // by name, for the cells' layer stacks
+    #[serde(default)]
+    pub layers: BTreeMap<String, LayerSpec>,
+    #[serde(default)]
+    pub cells: Vec<CellSpec>,
+    // the cell that a right-click adds; its position is ignored
+    #[serde(default)]
+    pub placeable_cell: Option<CellSpec>,
+}
+
+fn default_standard_influences() -> bool {
+    true
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct LayerSpec {
+    pub tissue: Tissue,
+    pub area: Value1D,
+    pub density: Value1D,
+    #[serde(default)]
+    pub specialty: SpecialtySpec,
+    // by LayerParameters::NAMES, over LayerParameters::DEFAULT
+    #[serde(default)]
+    pub parameters: BTreeMap<String, Value1D>,
+}
+
+// Any parameter left out keeps its default.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+pub enum SpecialtySpec {
+    #[default]
+    Null,
+    Photo {
+        efficiency: Value1D,
+    },
+    Chemo {
+        efficiency: Value1D,
+    },
+    Bonding {
+        max_donation_energy_per_unit_area: Option<Value1D>,
+        donation_energy_tax_rate: Option<Value1D>,
+    },
+    Eater {
+        bite_energy_per_unit_area: Option<Value1D>,
+        bite_damage_health_delta: Option<Value1D>,
+    },
+    Thruster,
+    Grip {
+        max_grip: Option<Value1D>,
+        grip_energy_delta: Option<Value1D>,
+    },
+    Anchor {
+        anchoring_energy_delta: Option<Value1D>,
+    },
+    Float {
+        min_density: Option<Value1D>,
+        max_density: Option<Value1D>,
+        max_pumping_rate: Option<Value1D>,
+        pumping_energy_delta: Option<Value1D>,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CellSpec {
+    // by name from the scenario's layers, innermost first
+    pub layers: Vec<String>,
+    // by ControlRegistry name; none for no control
+    #[serde(default)]
+    pub control: Option<String>,
+    #[serde(default)]
+    pub position: [Value1D; 2],
+    #[serde(default)]
+    pub velocity: [Value1D; 2],
+    #[serde(default)]
+    pub energy: Value1D,
+    // How many founder cells to make from the spec: the first at the position and the
+    // rest scattered at random within the spread of it, each with its own control seed,
+    // so that a run doesn't hang on one founder surviving. Ignored for placeable_cell.
+    #[serde(default = "default_count")]
+    pub count: usize,
+    // the farthest the rest can be from the position in x and y
+    #[serde(default)]
+    pub spread: [Value1D; 2],
+    // whether the rest start with their controls spawned once, i.e. with their genomes
+    // mutated, rather than all alike
+    #[serde(default)]
+    pub mutate: bool,
+}
+
+fn default_count() -> usize {
+    1
+}
+
+impl ScenarioSpec {
+    // Builds the world, with forces made by the registry and controls made by name.
+    // A control factory is given the seed, e.g. for mutation randomness.
+    pub fn build_world(
+        &self,
+        seed: u64,
+        forces: &ForceRegistry,
+        controls: &ControlRegistry,
+    ) -> Result<World, String> {
+        let mut parameters = Parameters::DEFAULT;
+        for (name, value) in &self.parameters {
+            parameters.set(name, *value)?;
+        }
+        let layers = self
+            .layers
+            .iter()
+            .map(|(name, spec)| Ok((name.as_str(), LayerTemplate::new(name, spec)?)))
+            .collect::<Result<BTreeMap<_, _>, String>>()?;
+
+        let mut world = World::new(to_position(self.min_corner), to_position(self.max_corner))
+            .with_parameters(parameters);
+        if self.standard_influences {
+            world = world.with_standard_influences();
+        }
+        if let Some([min_intensity, max_intensity]) = self.sunlight {
+            world = world.with_sunlight(min_intensity, max_intensity);
+        }
+        for spec in &self.forces {
+            world = world.with_per_cell_influence(forces.create_influence(spec)?);
+        }
+        check(
+            "thermal_noise",
+            self.thermal_noise,
+            self.thermal_noise >= 0.0,
+        )?;
+        world = world.with_thermal_noise(self.thermal_noise, seed);
+        // after the other per-cell forces, so that it resists them too
+        if let Some(friction_coefficient) = self.substrate_friction {
+            check(
+                "substrate_friction",
+                friction_coefficient,
+                friction_coefficient >= 0.0,
+            )?;
+            world = world.with_bottom_substrate(friction_coefficient);
+        }
+        if let Some(breaking_strain) = self.bond_breaking_strain {
+            check(
+                "bond_breaking_strain",
+                breaking_strain,
+                breaking_strain >= 0.0,
+            )?;
+            world = world.with_bond_formation(Length::new(breaking_strain));
+        }
+        let mut rng = Pcg64Mcg::seed_from_u64(seed);
+        for spec in &self.cells {
+            world = world.with_cells(Self::build_founders(
+                spec, &layers, seed, &mut rng, controls,
+            )?);
+        }
+        if let Some(spec) = &self.placeable_cell {
+            world = world.with_placeable_cell(Self::build_cell(
+                spec,
+                &layers,
+                to_position(spec.position),
+                seed,
+                false,
+                controls,
+            )?);
+        }
+        Ok(world)
+    }
+
+    // The spec's count of cells, the first as the spec says and the rest scattered,
+    // seeded and maybe mutated by the rng.
+    fn build_founders(
+        spec: &CellSpec,
+        layers: &BTreeMap<&str, LayerTemplate>,
+        seed: u64,
+        rng: &mut Pcg64Mcg,
+        controls: &ControlRegistry,
+    ) -> Result<Vec<Cell>, String> {
+        for &distance in &spec.spread {
+            check("spread", distance, distance >= 0.0)?;
+        }
+        let position = to_position(spec.position);
+        let mut founders = Vec::with_capacity(spec.count);
+        for index in 0..spec.count {
+            founders.push(if index == 0 {
+                Self::build_cell(spec, layers, position, seed, false, controls)?
+            } else {
+                let offset = Displacement::new(
+                    spec.spread[0] * rng.gen_range(-1.0, 1.0),
+                    spec.spread[1] * rng.gen_range(-1.0, 1.0),
+                );
+                Self::build_cell(
+                    spec,
+                    layers,
+                    position + offset,
+                    rng.gen(),
+                    spec.mutate,
+                    controls,
+                )?
+            });
+        }
+        Ok(founders)
+    }
+
+    fn build_cell(
+        spec: &CellSpec,
+        layers: &BTreeMap<&str, LayerTemplate>,
+        position: Position,
+        seed: u64,
+        mutate: bool,
+        controls: &ControlRegistry,
+    ) -> Result<Cell, String> {
+        if spec.layers.is_empty() {
+            return Err("A cell needs at least one layer".to_string());
+        }
+        let cell_layers = spec
+            .layers
+            .iter()
+            .map(|name| {
+                layers
+                    .get(name.as_str())
+                    .map(LayerTemplate::create)
+                    .ok_or_else(|| format!("Unknown layer: {}", name))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        check("energy", spec.energy, spec.energy >= 0.0)?;
+        check("velocity", spec.velocity[0], true)?;
+        check("velocity", spec.velocity[1], true)?;
+        let mut cell = Cell::new(
+            position,
+            Velocity::new(spec.velocity[0], spec.velocity[1]),
+            cell_layers,
+        )
+        .with_initial_energy(BioEnergy::new(spec.energy));
+        if let Some(name) = &spec.control {
+            let mut control = controls.create(name, seed)?;
+            if mutate {
+                control = control.spawn();
+            }
+            cell = cell.with_control(control);
+        }
+        Ok(cell)
+    }
+}
+
+fn to_position(xy: [Value1D; 2]) -> Position {
+    Position::new(xy[0], xy[1])
+}
+
+// A layer spec with its parameters checked and made 'static, as CellLayer wants them,
+// once per world rather than once per cell. They are leaked, but a run builds its
+// worlds only once.
+struct LayerTemplate {
+    area: Area,
+    density: Density,
+    tissue: Tissue,
+    parameters: &'static LayerParameters,
+    specialty: SpecialtyTemplate,
+}
+
+enum SpecialtyTemplate {
+    Null,
+    Photo(Fraction),
+    Chemo(Fraction),
+    Bonding(&'static BondingLayerParameters),
+    Eater(&'static EaterLayerParameters),
+    Thruster,
+    Grip(&'static GripLayerParameters),
+    Anchor(&'static AnchorLayerParameters),
+    Float(&'static FloatLayerParameters),
+}
+
+impl LayerTemplate {
+    fn new(name: &str, spec: &LayerSpec) -> Result<Self, String> {
+        let in_layer = |message: String| format!("Layer {}: {}", name, message);
+        check("area", spec.area, spec.area >= 0.0).map_err(in_layer)?;
+        check("density", spec.density, spec.density >= 0.0).map_err(in_layer)?;
+        let mut parameters = LayerParameters::DEFAULT;
+        for (parameter_name, value) in &spec.parameters {
+            parameters.set(parameter_name, *value).map_err(in_layer)?;
+        }
+        parameters.check_mass_energy_coupling().map_err(in_layer)?;
+        let specialty = SpecialtyTemplate::new(&spec.specialty).map_err(in_layer)?;
+        // see FloatCellLayerSpecialty
+        if let SpecialtyTemplate::Float(_) = specialty {
+            if parameters.is_mass_energy_coupled() {
+                return Err(in_layer(
+                    "A float layer can't have mass_per_energy".to_string(),
+                ));
+            }
+        }
+        Ok(LayerTemplate {
+            area: Area::new(spec.area),
+            density: Density::new(spec.density),
+            tissue: spec.tissue,
+            parameters: Box::leak(Box::new(parameters)),
+            specialty,
+        })
+    }
+
+    fn create(&self) -> CellLayer {
+        CellLayer::new(
+            self.area,
+            self.density,
+            self.tissue,
+            self.specialty.create(),
+        )
+        .with_parameters(self.parameters)
+    }
+}
+
+impl SpecialtyTemplate {
+    fn new(spec: &SpecialtySpec) -> Result<Self, String> {
+        Ok(match *spec {
+            SpecialtySpec::Null => SpecialtyTemplate::Null,
+            SpecialtySpec::Photo { efficiency } => {
+                check_fraction("efficiency", efficiency)?;
+                SpecialtyTemplate::Photo(Fraction::new(efficiency))
+            }
+            SpecialtySpec::Chemo { efficiency } => {
+                check_fraction("efficiency", efficiency)?;
+                SpecialtyTemplate::Chemo(Fraction::new(efficiency))
+            }
+            SpecialtySpec::Bonding {
+                max_donation_energy_per_unit_area,
+                donation_energy_tax_rate,
+            } => {
+                let mut parameters = BondingLayerParameters::DEFAULT;
+                if let Some(value) = max_donation_energy_per_unit_area {
+                    check("max_donation_energy_per_unit_area", value, value >= 0.0)?;
+                    parameters.max_donation_energy_per_unit_area = BioEnergy::new(value);
+                }
+                if let Some(value) = donation_energy_tax_rate {
+                    check_fraction("donation_energy_tax_rate", value)?;
+                    parameters.donation_energy_tax_rate = Fraction::new(value);
+                }
+                SpecialtyTemplate::Bonding(Box::leak(Box::new(parameters)))
+            }
+            SpecialtySpec::Eater {
+                bite_energy_per_unit_area,
+                bite_damage_health_delta,
+            } => {
+                let mut parameters = EaterLayerParameters::DEFAULT;
+                if let Some(value) = bite_energy_per_unit_area {
+                    check("bite_energy_per_unit_area", value, value >= 0.0)?;
+                    parameters.bite_energy_per_unit_area = BioEnergy::new(value);
+                }
+                if let Some(value) = bite_damage_health_delta {
+                    check("bite_damage_health_delta", value, value <= 0.0)?;
+                    parameters.bite_damage_health_delta = HealthDelta::new(value);
+                }
+                SpecialtyTemplate::Eater(Box::leak(Box::new(parameters)))
+            }
+            SpecialtySpec::Thruster => SpecialtyTemplate::Thruster,
+            SpecialtySpec::Grip {
+                max_grip,
+                grip_energy_delta,
+            } => {
+                let mut parameters = GripLayerParameters::DEFAULT;
+                if let Some(value) = max_grip {
+                    check("max_grip", value, value >= 0.0)?;
+                    parameters.max_grip = value;
+                }
+                if let Some(value) = grip_energy_delta {
+                    check("grip_energy_delta", value, value <= 0.0)?;
+                    parameters.grip_energy_delta = BioEnergyDelta::new(value);
+                }
+                SpecialtyTemplate::Grip(Box::leak(Box::new(parameters)))
+            }
+            SpecialtySpec::Anchor {
+                anchoring_energy_delta,
+            } => {
+                let mut parameters = AnchorLayerParameters::DEFAULT;
+                if let Some(value) = anchoring_energy_delta {
+                    check("anchoring_energy_delta", value, value <= 0.0)?;
+                    parameters.anchoring_energy_delta = BioEnergyDelta::new(value);
+                }
+                SpecialtyTemplate::Anchor(Box::leak(Box::new(parameters)))
+            }
+            SpecialtySpec::Float {
+                min_density,
+                max_density,
+                max_pumping_rate,
+                pumping_energy_delta,
+            } => {
+                let mut parameters = FloatLayerParameters::DEFAULT;
+                if let Some(value) = min_density {
+                    check("min_density", value, value >= 0.0)?;
+                    parameters.min_density = Density::new(value);
+                }
+                if let Some(value) = max_density {
+                    check(
+                        "max_density",
+                        value,
+                        value >= parameters.min_density.value(),
+                    )?;
+                    parameters.max_density = Density::new(value);
+                }
+                if let Some(value) = max_pumping_rate {
+                    check("max_pumping_rate", value, value >= 0.0)?;
+                    parameters.max_pumping_rate = value;
+                }
+                if let Some(value) = pumping_energy_delta {
+                    check("pumping_energy_delta", value, value <= 0.0)?;
+                    parameters.pumping_energy_delta = BioEnergyDelta::new(value);
+                }
+                SpecialtyTemplate::Float(Box::leak(Box::new(parameters)))
+            }
+        })
+    }
+
+    fn create(&self) -> Box<dyn CellLayerSpecialty> {
+        match *self {
+            SpecialtyTemplate::Null => Box::new(NullCellLayerSpecialty::new()),
+            SpecialtyTemplate::Photo(efficiency) => {
+                Box::new(PhotoCellLayerSpecialty::new(efficiency))
+            }
+            SpecialtyTemplate::Chemo(efficiency) => {
+                Box::new(ChemoCellLayerSpecialty::new(efficiency))
+            }
+            SpecialtyTemplate::Bonding(parameters) => {
+                Box::new(BondingCellLayerSpecialty::new().with_parameters(parameters))
+            }
+            SpecialtyTemplate::Eater(parameters) => {
+                Box::new(EaterCellLayerSpecialty::new().with_parameters(parameters))
+            }
+            SpecialtyTemplate::Thruster => Box::new(ThrusterCellLayerSpecialty::new()),
+            SpecialtyTemplate::Grip(parameters) => {
+                Box::new(GripCellLayerSpecialty::new().with_parameters(parameters))
+            }
+            SpecialtyTemplate::Anchor(parameters) => {
+                Box::new(AnchorCellLayerSpecialty::new().with_parameters(parameters))
+            }
+            SpecialtyTemplate::Float(parameters) => {
+                Box::new(FloatCellLayerSpecialty::new().with_parameters(parameters))
+            }
+        }
+    }
+}
+
+fn check(name: &str, value: Value1D, valid: bool) -> Result<(), String> {
+    if valid && value.is_finite() {
+        Ok(())
+    } else {
+        Err(format!("Invalid value for {}: {}", name, value))
+    }
+}
+
+fn check_fraction(name: &str, value: Value1D) -> Result<(), String> {
+    check(name, value, (0.0..=1.0).contains(&value))
+}
+
+pub type ControlFactory = Box<dyn Fn(u64) -> Box<dyn CellControl> + Send + Sync>;
+
+// Makes cell controls by name for scenario files, since a control such as a neural net
+// wired to particular layers is code, not data.
+pub struct ControlRegistry {
+    factories: Vec<(String, ControlFactory)>,
+}
+
+impl ControlRegistry {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        ControlRegistry { factories: vec![] }
+    }
+
+    pub fn with_control<F>(mut self, name: &str, factory: F) -> Self
+    where
+        F: Fn(u64) -> Box<dyn CellControl> + Send + Sync + 'static,
+    {
+        self.factories
+            .retain(|(factory_name, _)| factory_name != name);
+        self.factories.push((name.to_string(), Box::new(factory)));
+        self
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        let mut names = self
+            .factories
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        names
+    }
+
+    pub fn create(&self, name: &str, seed: u64) -> Result<Box<dyn CellControl>, String> {
+        self.factories
+            .iter()
+            .find(|(factory_name, _)| factory_name == name)
+            .map(|(_, factory)| factory(seed))
+            .ok_or_else(|| {
+                format!(
+                    "Unknown control: {} (known: {})",
+                    name,
+                    self.names().join(", ")
+                )
+            })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ScenarioFormat {
+    Toml,
+    Ron,
+}
+
+impl ScenarioFormat {
+    // By the file's extension, .toml or .ron.
+    pub fn of_path(path: &Path) -> Result<Self, String> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Ok(ScenarioFormat::Toml),
+            Some("ron") => Ok(ScenarioFormat::Ron),
+            _ => Err(format!(
+                "Not a .toml or .ron scenario file: {}",
+                path.display()
+            )),
+        }
+    }
+}
+
+// A scenario file as read, before its variables are filled in to make a ScenarioSpec.
+#[derive(Clone, Debug)]
+pub struct ScenarioFile {
+    variables: BTreeMap<String, Value1D>,
+    body: Value,
+}
+
+impl ScenarioFile {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|error| format!("Cannot read {}: {}", path.display(), error))?;
+        Self::parse(&text, ScenarioFormat::of_path(path)?)
+            .map_err(|message| format!("{}: {}", path.display(), message))
+    }
+
+    pub fn parse(text: &str, format: ScenarioFormat) -> Result<Self, String> {
+        let body: Value = match format {
+            ScenarioFormat::Toml => toml::from_str(text).map_err(|error| error.to_string())?,
+            ScenarioFormat::Ron => ron::from_str(text).map_err(|error| error.to_string())?,
+        };
+        let mut body = match body {
+            Value::Object(map) => map,
+            _ => return Err("A scenario must be a table of fields".to_string()),
+        };
+        let variables = match body.remove("variables") {
+            Some(variables) => serde_json::from_value(variables)
+                .map_err(|error| format!("variables: {}", error))?,
+            None => BTreeMap::new(),
+        };
+        Ok(ScenarioFile {
+            variables,
+            body: Value::Object(body),
+        })
+    }
+
+    // The variables and their values in the file.
+    pub fn variables(&self) -> &BTreeMap<String, Value1D> {
+        &self.variables
+    }
+
+    // The spec, with each variable's value from value_of, given the variable's name and
+    // its value in the file, e.g. to let the command line override it.
+    pub fn spec<F>(&self, value_of: F) -> Result<ScenarioSpec, String>
+    where
+        F: Fn(&str, Value1D) -> Value1D,
+    {
+        let values = self
+            .variables
+            .iter()
+            .map(|(name, value)| (name.as_str(), value_of(name, *value)))
+            .collect::<BTreeMap<_, _>>();
+        let mut body = self.body.clone();
+        substitute_variables(&mut body, &values)?;
+        serde_json::from_value(body).map_err(|error| error.to_string())
+    }
+}
+
+// Replaces each "$name" string with the variable's number, as an integer if it's whole
+// so that it can be a count, and each "$name" inside a longer string, such as a force
+// spec, with the number written out.
+fn substitute_variables(value: &mut Value, values: &BTreeMap<&str, Value1D>) -> Result<(), String> {
+    match value {
+        Value::String(text) if text.contains('$') => {
+            let variable_value = match text.strip_prefix('$') {
+                Some(name) if values.contains_key(name) => Some(values[name]),
+                _ => None,
+            };
+            *value = match variable_value {
+                Some(number) if number.fract() == 0.0 && number.abs() < 1e15 => {
+                    Value::from(number as i64)
+                }
+                Some(number) => serde_json::Number::from_f64(number)
+                    .map(Value::Number)
+                    .ok_or_else(|| format!("Invalid value for {}: {}", text, number))?,
+                None => Value::String(substitute_in_text(text, values)?),
+            };
+        }
+        Value::Array(elements) => {
+            for element in elements {
+                substitute_variables(element, values)?;
+            }
+        }
+        Value::Object(fields) => {
+            for field in fields.values_mut() {
+                substitute_variables(field, values)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn substitute_in_text(text: &str, values: &BTreeMap<&str, Value1D>) -> Result<String, String> {
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('$') {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let end = after
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(after.len());
+        let name = &after[..end];
+        let value = values
+            .get(name)
+            .ok_or_else(|| format!("Unknown variable in \"{}\": ${}", text, name))?;
+        result.push_str(&value.to_string());
+        rest = &after[end..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biology::control::NullControl;
+    use crate::physics::newtonian::NewtonianBody;
+    use crate::physics::shapes::Circle;
+
+    const SCENARIO: &str = r#"
+        min_corner = [0.0, -100.0]
+        max_corner = [200.0, 0.0]
+        sunlight = [0.0, 1.0]
+        bond_breaking_strain = 5.0
+        forces = ["weight:gravity=$gravity", "drag:viscosity=0.01"]
+
+        [variables]
+        gravity = -0.05
+        founders = 1
+
+        [parameters]
+        cloud_resize_factor = 1.5
+
+        [layers.float]
+        tissue = "AirBubble"
+        area = 10.0
+        density = 0.0001
+        specialty = { kind = "float", max_density = 0.004, pumping_energy_delta = -1.0 }
+
+        [layers.photo]
+        tissue = "Photosynthetic"
+        area = 5.0
+        density = 0.002
+        specialty = { kind = "photo", efficiency = 0.1 }
+        parameters = { healing_energy_delta = -1.0, max_growth_rate = 10.0 }
+
+        [[cells]]
+        layers = ["float", "photo"]
+        control = "none"
+        position = [50.0, -50.0]
+        velocity = [1.0, 0.0]
+        energy = 20.0
+    "#;
+
+    #[test]
+    fn builds_world_from_toml() {
+        let spec = ScenarioFile::parse(SCENARIO, ScenarioFormat::Toml)
+            .unwrap()
+            .spec(|_name, value| value)
+            .unwrap();
+
+        let world = spec
+            .build_world(0, &ForceRegistry::with_standard_forces(), &controls())
+            .unwrap();
+
+        assert_eq!(spec.bond_breaking_strain, Some(5.0));
+        assert_eq!(world.max_corner(), Position::new(200.0, 0.0));
+        assert_eq!(world.parameters().cloud_params.resize_factor.value(), 1.5);
+        let cell = &world.cells()[0];
+        assert_eq!(cell.center(), Position::new(50.0, -50.0));
+        assert_eq!(cell.velocity(), Velocity::new(1.0, 0.0));
+        assert_eq!(cell.energy(), BioEnergy::new(20.0));
+        assert_eq!(cell.layers().len(), 2);
+        assert_eq!(cell.layers()[1].area(), Area::new(5.0));
+        assert!(cell.mass().value() > 0.0);
+    }
+
+    #[test]
+    fn substitutes_variables() {
+        let file = ScenarioFile::parse(SCENARIO, ScenarioFormat::Toml).unwrap();
+
+        let spec = file
+            .spec(|name, value| if name == "gravity" { -0.1 } else { value })
+            .unwrap();
+
+        assert_eq!(file.variables()["gravity"], -0.05);
+        assert_eq!(spec.forces[0], "weight:gravity=-0.1");
+    }
+
+    #[test]
+    fn seeds_scattered_founders() {
+        let spec = ScenarioFile::parse(
+            &SCENARIO.replace(
+                "energy = 20.0",
+                "energy = 20.0\ncount = \"$founders\"\nspread = [40.0, 20.0]",
+            ),
+            ScenarioFormat::Toml,
+        )
+        .unwrap()
+        .spec(|name, value| if name == "founders" { 5.0 } else { value })
+        .unwrap();
+
+        let world = spec
+            .build_world(0, &ForceRegistry::with_standard_forces(), &controls())
+            .unwrap();
+
+        assert_eq!(world.cells().len(), 5);
+        assert_eq!(world.cells()[0].center(), Position::new(50.0, -50.0));
+        for cell in &world.cells()[1..] {
+            assert_ne!(cell.center(), Position::new(50.0, -50.0));
+            assert!((cell.center().x() - 50.0).abs() <= 40.0);
+            assert!((cell.center().y() + 50.0).abs() <= 20.0);
+        }
+    }
+
+    #[test]
+    fn reads_ron() {
+        let spec = ScenarioFile::parse(
+            r#"(
+                min_corner: (0.0, -100.0),
+                max_corner: (200.0, 0.0),
+                thermal_noise: "$noise",
+                variables: { "noise": 0.5 },
+                layers: {
+                    "wall": (tissue: "CellWall", area: 5.0, density: 0.002),
+                },
+                cells: [(layers: ["wall"], energy: 10.0)],
+            )"#,
+            ScenarioFormat::Ron,
+        )
+        .unwrap()
+        .spec(|_name, value| value)
+        .unwrap();
+
+        assert_eq!(spec.thermal_noise, 0.5);
+        assert_eq!(spec.layers["wall"].specialty, SpecialtySpec::Null);
+        assert_eq!(spec.cells[0].layers, vec!["wall".to_string()]);
+    }
+
+    #[test]
+    fn rejects_bad_scenarios() {
+        let build = |text: &str| {
+            ScenarioFile::parse(text, ScenarioFormat::Toml)?
+                .spec(|_name, value| value)?
+                .build_world(0, &ForceRegistry::with_standard_forces(), &controls())
+        };
+        let corners = "min_corner = [0.0, -1.0]\nmax_corner = [1.0, 0.0]\n";
+
+        assert!(build(corners).is_ok());
+        assert!(build(&format!("{}typo = 1.0", corners)).is_err());
+        assert!(build(&format!("{}forces = [\"weight:gravity=$g\"]", corners)).is_err());
+        assert!(build(&format!("{}[[cells]]\nlayers = [\"missing\"]", corners)).is_err());
+        assert!(build(&format!("{}bond_breaking_strain = -1.0", corners)).is_err());
+        assert!(build(&format!("{}thermal_noise = -1.0", corners)).is_err());
+        assert!(build(&format!("{}thermal_noise = nan", corners)).is_err());
+        assert!(build(&format!("{}substrate_friction = 0.5", corners)).is_ok());
+        assert!(build(&format!("{}substrate_friction = -0.5", corners)).is_err());
+        let wall_cell = "[layers.wall]\ntissue = \"CellWall\"\narea = 1.0\ndensity = 1.0\n[[cells]]\nlayers = [\"wall\"]\n";
+        assert!(build(&format!("{}{}", corners, wall_cell)).is_ok());
+        assert!(build(&format!("{}{}energy = nan", corners, wall_cell)).is_err());
+        assert!(build(&format!("{}{}velocity = [inf, 0.0]", corners, wall_cell)).is_err());
+        assert!(build(&format!(
+            "{}[layers.wall]\ntissue = \"CellWall\"\narea = 1.0\ndensity = 1.0\n[[cells]]\nlayers = [\"wall\"]\ncount = 2\nspread = [-1.0, 0.0]",
+            corners
+        ))
+        .is_err());
+        assert!(build(&format!(
+            "{}[layers.wall]\ntissue = \"CellWall\"\narea = 1.0\ndensity = 1.0\nparameters = {{ decay_rate = 2.0 }}",
+            corners
+        ))
+        .is_err());
+        assert!(build(&format!(
+            "{}[layers.grip]\ntissue = \"CellWall\"\narea = 1.0\ndensity = 1.0\nspecialty = {{ kind = \"grip\" }}",
+            corners
+        ))
+        .is_ok());
+        assert!(build(&format!(
+            "{}[layers.grip]\ntissue = \"CellWall\"\narea = 1.0\ndensity = 1.0\nspecialty = {{ kind = \"grip\", grip_energy_delta = 1.0 }}",
+            corners
+        ))
+        .is_err());
+        let float_layer = "[layers.float]\ntissue = \"AirBubble\"\narea = 1.0\ndensity = 1.0\n";
+        assert!(build(&format!(
+            "{}{}specialty = {{ kind = \"float\", min_density = 2.0, max_density = 1.0 }}",
+            corners, float_layer
+        ))
+        .is_err());
+        assert!(build(&format!(
+            "{}{}specialty = {{ kind = \"float\" }}\nparameters = {{ mass_per_energy = 1.0 }}",
+            corners, float_layer
+        ))
+        .is_err());
+    }
+
+    fn controls() -> ControlRegistry {
+        ControlRegistry::new().with_control("none", |_seed| Box::new(NullControl::new()))
+    }
+}
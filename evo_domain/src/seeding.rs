@@ -0,0 +1,609 @@
+use crate::physics::quantities::*;
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg64Mcg;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::fs;
+use std::path::Path;
+
+// A grid of values in [0, 1] stretched over a world rectangle, used to bias where
+// initial cells are placed. Row 0 is the top of the world, as in an image.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DensityMap {
+    width: usize,
+    height: usize,
+    values: Vec<Value1D>,
+}
+
+impl DensityMap {
+    pub fn new(width: usize, height: usize, values: Vec<Value1D>) -> Self {
+        assert!(width > 0 && height > 0);
+        assert_eq!(values.len(), width * height);
+        assert!(values.iter().all(|value| (0.0..=1.0).contains(value)));
+        DensityMap {
+            width,
+            height,
+            values,
+        }
+    }
+
+    pub fn uniform() -> Self {
+        Self::new(1, 1, vec![1.0])
+    }
+
+    pub fn from_pgm_file(path: &Path) -> Result<Self, String> {
+        let bytes = fs::read(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+        Self::from_pgm(&bytes)
+    }
+
+    // Parses a grayscale image in binary (P5) or plain (P2) PGM format.
+    pub fn from_pgm(bytes: &[u8]) -> Result<Self, String> {
+        let mut parser = PgmParser::new(bytes);
+        let magic = parser.next_token()?;
+        let width = parser.next_number()?;
+        let height = parser.next_number()?;
+        let max_value = parser.next_number()?;
+        if width == 0 || height == 0 {
+            return Err("PGM image is empty".to_string());
+        }
+        if max_value == 0 || max_value > 65535 {
+            return Err(format!("Invalid PGM max value: {}", max_value));
+        }
+
+        let num_pixels = width
+            .checked_mul(height)
+            .ok_or_else(|| format!("PGM image is too big: {} x {}", width, height))?;
+        let raw_values = match magic.as_str() {
+            "P2" => (0..num_pixels)
+                .map(|_| parser.next_number())
+                .collect::<Result<Vec<usize>, String>>()?,
+            "P5" => parser.binary_values(num_pixels, max_value > 255)?,
+            _ => return Err(format!("Not a grayscale PGM image: {}", magic)),
+        };
+
+        let values = raw_values
+            .iter()
+            .map(|&raw| (raw.min(max_value) as Value1D) / (max_value as Value1D))
+            .collect();
+        Ok(Self::new(width, height, values))
+    }
+
+    // Classic 2D gradient (Perlin) noise, sampled on a width x height grid and
+    // rescaled to [0, 1]. Larger scales give broader features.
+    pub fn perlin_noise(width: usize, height: usize, scale: Value1D, seed: u64) -> Self {
+        assert!(scale > 0.0);
+        let noise = PerlinNoise::new(seed);
+        let raw_values: Vec<Value1D> = (0..height)
+            .flat_map(|row| (0..width).map(move |col| (row, col)))
+            .map(|(row, col)| noise.value(col as Value1D / scale, row as Value1D / scale))
+            .collect();
+        Self::new(width, height, Self::normalize(raw_values))
+    }
+
+    fn normalize(raw_values: Vec<Value1D>) -> Vec<Value1D> {
+        let min = raw_values
+            .iter()
+            .cloned()
+            .fold(Value1D::INFINITY, Value1D::min);
+        let max = raw_values
+            .iter()
+            .cloned()
+            .fold(Value1D::NEG_INFINITY, Value1D::max);
+        let range = max - min;
+        raw_values
+            .iter()
+            .map(|value| {
+                if range > 0.0 {
+                    (value - min) / range
+                } else {
+                    1.0
+                }
+            })
+            .collect()
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn value(&self, col: usize, row: usize) -> Value1D {
+        self.values[row * self.width + col]
+    }
+
+    pub fn density_at(&self, min_corner: Position, max_corner: Position, pos: Position) -> Value1D {
+        let x_fraction = (pos.x() - min_corner.x()) / (max_corner.x() - min_corner.x());
+        let y_fraction = (max_corner.y() - pos.y()) / (max_corner.y() - min_corner.y());
+        self.value(
+            Self::fraction_to_index(x_fraction, self.width),
+            Self::fraction_to_index(y_fraction, self.height),
+        )
+    }
+
+    fn fraction_to_index(fraction: Value1D, size: usize) -> usize {
+        ((fraction * size as Value1D).floor().max(0.0) as usize).min(size - 1)
+    }
+
+    // Rejection-samples positions so that the local number of cells is proportional
+    // to the map's density. Gives up on a position after a bounded number of tries,
+    // so an all-black map yields fewer positions rather than looping forever.
+    pub fn sample_positions(
+        &self,
+        min_corner: Position,
+        max_corner: Position,
+        count: usize,
+        seed: u64,
+    ) -> Vec<Position> {
+        const MAX_TRIES_PER_POSITION: usize = 1000;
+
+        let mut rng = Pcg64Mcg::seed_from_u64(seed);
+        let mut positions = Vec::with_capacity(count);
+        for _ in 0..count {
+            for _ in 0..MAX_TRIES_PER_POSITION {
+                let pos = Position::new(
+                    Self::random_coordinate(&mut rng, min_corner.x(), max_corner.x()),
+                    Self::random_coordinate(&mut rng, min_corner.y(), max_corner.y()),
+                );
+                if rng.gen::<Value1D>() < self.density_at(min_corner, max_corner, pos) {
+                    positions.push(pos);
+                    break;
+                }
+            }
+        }
+        positions
+    }
+
+    // Like sample_positions, but keeps circles with the given radii from overlapping each
+    // other or coming within wall_margin of the world's edges, so the world doesn't start
+    // with a burst of collisions. Places larger circles first. A circle that finds no
+    // clear spot goes where it overlaps its neighbors least, so every circle gets a position.
+    pub fn sample_non_overlapping_positions(
+        &self,
+        min_corner: Position,
+        max_corner: Position,
+        radii: &[Length],
+        wall_margin: Length,
+        seed: u64,
+    ) -> Vec<Position> {
+        const MAX_TRIES_PER_POSITION: usize = 1000;
+
+        let mut rng = Pcg64Mcg::seed_from_u64(seed);
+        let max_radius = radii
+            .iter()
+            .map(|radius| radius.value())
+            .fold(0.0, Value1D::max);
+        let mut placed = PlacedCircles::new(2.0 * max_radius);
+        let mut positions = vec![Position::ORIGIN; radii.len()];
+        let mut largest_first = (0..radii.len()).collect::<Vec<_>>();
+        largest_first.sort_by(|a, b| radii[*b].value().partial_cmp(&radii[*a].value()).unwrap());
+        for index in largest_first {
+            let radius = radii[index].value();
+            let inset = radius + wall_margin.value();
+            // (dense enough, clearance) of the best spot found so far
+            let mut best: Option<(bool, Value1D, Position)> = None;
+            for _ in 0..MAX_TRIES_PER_POSITION {
+                let pos = Position::new(
+                    Self::random_coordinate(
+                        &mut rng,
+                        min_corner.x() + inset,
+                        max_corner.x() - inset,
+                    ),
+                    Self::random_coordinate(
+                        &mut rng,
+                        min_corner.y() + inset,
+                        max_corner.y() - inset,
+                    ),
+                );
+                let dense_enough =
+                    rng.gen::<Value1D>() < self.density_at(min_corner, max_corner, pos);
+                let clearance = placed.clearance(pos, radius);
+                let is_best = match best {
+                    Some((best_dense_enough, best_clearance, _)) => {
+                        (dense_enough, clearance) > (best_dense_enough, best_clearance)
+                    }
+                    None => true,
+                };
+                if is_best {
+                    best = Some((dense_enough, clearance, pos));
+                }
+                if dense_enough && clearance >= 0.0 {
+                    break;
+                }
+            }
+            let (_, _, pos) = best.unwrap();
+            placed.add(pos, radius);
+            positions[index] = pos;
+        }
+        positions
+    }
+
+    pub(crate) fn random_coordinate(rng: &mut Pcg64Mcg, low: Value1D, high: Value1D) -> Value1D {
+        if low < high {
+            rng.gen_range(low, high)
+        } else {
+            // no room between the walls, so center it
+            (low + high) / 2.0
+        }
+    }
+}
+
+// Nudges hand-placed positions by up to max_offset in a random direction, so demo
+// layouts look organic but come out the same for the same seed. Each position's
+// offset depends only on the seed and the position's index, so adding a cell to
+// a scenario doesn't move the others.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PlacementJitter {
+    max_offset: Length,
+    seed: u64,
+}
+
+impl PlacementJitter {
+    pub fn new(max_offset: Length, seed: u64) -> Self {
+        PlacementJitter { max_offset, seed }
+    }
+
+    pub fn offset(&self, index: usize) -> Displacement {
+        let mut rng = Pcg64Mcg::seed_from_u64(
+            self.seed
+                .wrapping_add((index as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15)),
+        );
+        // sqrt spreads the offsets evenly over the disk instead of bunching them at the center
+        let radius = self.max_offset.value() * rng.gen::<Value1D>().sqrt();
+        let angle = rng.gen_range(0.0, 2.0 * PI);
+        Displacement::from_polar(Length::new(radius), Angle::from_radians(angle))
+    }
+
+    pub fn apply(&self, index: usize, pos: Position) -> Position {
+        pos + self.offset(index)
+    }
+}
+
+// Circles bucketed into square grid cells at least as wide as the largest diameter,
+// so any overlapping circle is in the same or an adjacent grid cell.
+struct PlacedCircles {
+    grid_size: Value1D,
+    grid: HashMap<(i64, i64), Vec<(Position, Value1D)>>,
+}
+
+impl PlacedCircles {
+    fn new(grid_size: Value1D) -> Self {
+        PlacedCircles {
+            grid_size: grid_size.max(1.0),
+            grid: HashMap::new(),
+        }
+    }
+
+    fn add(&mut self, pos: Position, radius: Value1D) {
+        self.grid
+            .entry(self.grid_key(pos))
+            .or_default()
+            .push((pos, radius));
+    }
+
+    // Distance to the edge of the nearest overlapping or adjacent circle,
+    // negative if overlapping.
+    fn clearance(&self, pos: Position, radius: Value1D) -> Value1D {
+        let (col, row) = self.grid_key(pos);
+        let mut clearance = Value1D::INFINITY;
+        for neighbor_col in (col - 1)..=(col + 1) {
+            for neighbor_row in (row - 1)..=(row + 1) {
+                if let Some(circles) = self.grid.get(&(neighbor_col, neighbor_row)) {
+                    for (other_pos, other_radius) in circles {
+                        let gap = (pos - *other_pos).length().value() - radius - other_radius;
+                        clearance = clearance.min(gap);
+                    }
+                }
+            }
+        }
+        clearance
+    }
+
+    fn grid_key(&self, pos: Position) -> (i64, i64) {
+        (
+            (pos.x() / self.grid_size).floor() as i64,
+            (pos.y() / self.grid_size).floor() as i64,
+        )
+    }
+}
+
+struct PgmParser<'a> {
+    bytes: &'a [u8],
+    index: usize,
+}
+
+impl<'a> PgmParser<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        PgmParser { bytes, index: 0 }
+    }
+
+    fn next_token(&mut self) -> Result<String, String> {
+        self.skip_whitespace_and_comments();
+        let start = self.index;
+        while self.index < self.bytes.len() && !self.bytes[self.index].is_ascii_whitespace() {
+            self.index += 1;
+        }
+        if start == self.index {
+            return Err("Unexpected end of PGM data".to_string());
+        }
+        Ok(String::from_utf8_lossy(&self.bytes[start..self.index]).to_string())
+    }
+
+    fn next_number(&mut self) -> Result<usize, String> {
+        let token = self.next_token()?;
+        token
+            .parse::<usize>()
+            .map_err(|_| format!("Invalid number in PGM data: {}", token))
+    }
+
+    fn skip_whitespace_and_comments(&mut self) {
+        while self.index < self.bytes.len() {
+            if self.bytes[self.index] == b'#' {
+                while self.index < self.bytes.len() && self.bytes[self.index] != b'\n' {
+                    self.index += 1;
+                }
+            } else if self.bytes[self.index].is_ascii_whitespace() {
+                self.index += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn binary_values(&mut self, count: usize, two_bytes: bool) -> Result<Vec<usize>, String> {
+        // exactly one whitespace byte separates the header from the raster
+        self.index += 1;
+        let bytes_per_value = if two_bytes { 2 } else { 1 };
+        let raster = count
+            .checked_mul(bytes_per_value)
+            .and_then(|len| self.index.checked_add(len))
+            .and_then(|end| self.bytes.get(self.index..end))
+            .ok_or_else(|| "PGM raster is truncated".to_string())?;
+        Ok(raster
+            .chunks(bytes_per_value)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .fold(0_usize, |value, &byte| (value << 8) | byte as usize)
+            })
+            .collect())
+    }
+}
+
+struct PerlinNoise {
+    permutation: [u8; 512],
+}
+
+impl PerlinNoise {
+    fn new(seed: u64) -> Self {
+        let mut rng = Pcg64Mcg::seed_from_u64(seed);
+        let mut shuffled: Vec<u8> = (0..=255).collect();
+        for i in (1..shuffled.len()).rev() {
+            shuffled.swap(i, rng.gen_range(0, i + 1));
+        }
+        let mut permutation = [0_u8; 512];
+        for i in 0..512 {
+            permutation[i] = shuffled[i % 256];
+        }
+        PerlinNoise { permutation }
+    }
+
+    // Returns a value in roughly [-1, 1].
+    fn value(&self, x: Value1D, y: Value1D) -> Value1D {
+        let x_floor = x.floor();
+        let y_floor = y.floor();
+        let xi = (x_floor as i64 & 255) as usize;
+        let yi = (y_floor as i64 & 255) as usize;
+        let xf = x - x_floor;
+        let yf = y - y_floor;
+        let u = Self::fade(xf);
+        let v = Self::fade(yf);
+
+        let p = &self.permutation;
+        let aa = p[p[xi] as usize + yi];
+        let ab = p[p[xi] as usize + yi + 1];
+        let ba = p[p[xi + 1] as usize + yi];
+        let bb = p[p[xi + 1] as usize + yi + 1];
+
+        Self::lerp(
+            v,
+            Self::lerp(
+                u,
+                Self::gradient(aa, xf, yf),
+                Self::gradient(ba, xf - 1.0, yf),
+            ),
+            Self::lerp(
+                u,
+                Self::gradient(ab, xf, yf - 1.0),
+                Self::gradient(bb, xf - 1.0, yf - 1.0),
+            ),
+        )
+    }
+
+    fn fade(t: Value1D) -> Value1D {
+        t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+    }
+
+    fn lerp(t: Value1D, a: Value1D, b: Value1D) -> Value1D {
+        a + t * (b - a)
+    }
+
+    fn gradient(hash: u8, x: Value1D, y: Value1D) -> Value1D {
+        match hash & 3 {
+            0 => x + y,
+            1 => -x + y,
+            2 => x - y,
+            _ => -x - y,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_plain_pgm() {
+        let map = DensityMap::from_pgm(b"P2\n# comment\n2 2\n4\n0 1\n2 4\n").unwrap();
+        assert_eq!(map.width(), 2);
+        assert_eq!(map.height(), 2);
+        assert_eq!(map.value(0, 0), 0.0);
+        assert_eq!(map.value(1, 0), 0.25);
+        assert_eq!(map.value(0, 1), 0.5);
+        assert_eq!(map.value(1, 1), 1.0);
+    }
+
+    #[test]
+    fn parses_binary_pgm() {
+        let mut bytes = b"P5 2 1 255\n".to_vec();
+        bytes.extend_from_slice(&[0, 255]);
+        let map = DensityMap::from_pgm(&bytes).unwrap();
+        assert_eq!(map.value(0, 0), 0.0);
+        assert_eq!(map.value(1, 0), 1.0);
+    }
+
+    #[test]
+    fn rejects_non_grayscale_pgm() {
+        assert!(DensityMap::from_pgm(b"P6 1 1 255\n").is_err());
+    }
+
+    #[test]
+    fn rejects_truncated_binary_pgm() {
+        assert!(DensityMap::from_pgm(b"P5 2 2 255\n\x00").is_err());
+    }
+
+    #[test]
+    fn rejects_pgm_too_big_to_count() {
+        let header = format!("P5 {} 2 255\n", usize::MAX);
+        assert_eq!(
+            DensityMap::from_pgm(header.as_bytes()).unwrap_err(),
+            format!("PGM image is too big: {} x 2", usize::MAX)
+        );
+        let header = format!("P5 {} 1 65535\n", usize::MAX / 2 + 1);
+        assert!(DensityMap::from_pgm(header.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn density_at_maps_top_row_to_max_y() {
+        let map = DensityMap::new(1, 2, vec![1.0, 0.0]);
+        let min_corner = Position::new(0.0, -10.0);
+        let max_corner = Position::new(10.0, 0.0);
+        assert_eq!(
+            map.density_at(min_corner, max_corner, Position::new(5.0, -1.0)),
+            1.0
+        );
+        assert_eq!(
+            map.density_at(min_corner, max_corner, Position::new(5.0, -9.0)),
+            0.0
+        );
+    }
+
+    #[test]
+    fn perlin_noise_is_normalized_and_deterministic() {
+        let map1 = DensityMap::perlin_noise(16, 8, 4.0, 42);
+        let map2 = DensityMap::perlin_noise(16, 8, 4.0, 42);
+        assert_eq!(map1, map2);
+        let values: Vec<Value1D> = (0..8)
+            .flat_map(|row| (0..16).map(move |col| (col, row)))
+            .map(|(col, row)| map1.value(col, row))
+            .collect();
+        assert!(values.contains(&0.0));
+        assert!(values.contains(&1.0));
+    }
+
+    #[test]
+    fn sampled_positions_avoid_empty_regions() {
+        let map = DensityMap::new(2, 1, vec![0.0, 1.0]);
+        let min_corner = Position::new(0.0, 0.0);
+        let max_corner = Position::new(10.0, 10.0);
+        let positions = map.sample_positions(min_corner, max_corner, 20, 0);
+        assert_eq!(positions.len(), 20);
+        assert!(positions.iter().all(|pos| pos.x() >= 5.0));
+    }
+
+    #[test]
+    fn non_overlapping_positions_do_not_overlap_or_touch_walls() {
+        let map = DensityMap::uniform();
+        let min_corner = Position::new(-50.0, -50.0);
+        let max_corner = Position::new(50.0, 50.0);
+        let radii = (0..100)
+            .map(|i| Length::new(1.0 + (i % 3) as f64))
+            .collect::<Vec<_>>();
+
+        let positions = map.sample_non_overlapping_positions(
+            min_corner,
+            max_corner,
+            &radii,
+            Length::new(1.0),
+            0,
+        );
+
+        assert_eq!(positions.len(), radii.len());
+        for (i, pos) in positions.iter().enumerate() {
+            let inset = radii[i].value() + 1.0;
+            assert!(pos.x() >= min_corner.x() + inset && pos.x() <= max_corner.x() - inset);
+            assert!(pos.y() >= min_corner.y() + inset && pos.y() <= max_corner.y() - inset);
+            for j in (i + 1)..positions.len() {
+                let distance = (*pos - positions[j]).length().value();
+                assert!(distance >= radii[i].value() + radii[j].value());
+            }
+        }
+    }
+
+    #[test]
+    fn non_overlapping_positions_fall_back_to_least_overlap_when_crowded() {
+        let map = DensityMap::uniform();
+        let radii = vec![Length::new(2.0); 10];
+
+        let positions = map.sample_non_overlapping_positions(
+            Position::ORIGIN,
+            Position::new(6.0, 6.0),
+            &radii,
+            Length::new(0.0),
+            0,
+        );
+
+        assert_eq!(positions.len(), 10);
+        assert!(positions.iter().all(|pos| pos.x() >= 2.0 && pos.x() <= 4.0));
+    }
+
+    #[test]
+    fn sampling_a_zero_width_region_stays_on_its_line() {
+        let map = DensityMap::uniform();
+        let positions =
+            map.sample_positions(Position::new(3.0, 0.0), Position::new(3.0, 10.0), 5, 0);
+        assert_eq!(positions.len(), 5);
+        assert!(positions.iter().all(|pos| pos.x() == 3.0));
+    }
+
+    #[test]
+    fn sampling_an_empty_map_terminates() {
+        let map = DensityMap::new(1, 1, vec![0.0]);
+        let positions = map.sample_positions(Position::ORIGIN, Position::new(1.0, 1.0), 3, 0);
+        assert!(positions.is_empty());
+    }
+
+    #[test]
+    fn jitter_stays_within_max_offset() {
+        let jitter = PlacementJitter::new(Length::new(3.0), 0);
+        for index in 0..100 {
+            assert!(jitter.offset(index).length().value() <= 3.0);
+        }
+    }
+
+    #[test]
+    fn jitter_depends_only_on_seed_and_index() {
+        let jitter = PlacementJitter::new(Length::new(3.0), 42);
+        assert_eq!(
+            jitter.offset(7),
+            PlacementJitter::new(Length::new(3.0), 42).offset(7)
+        );
+        assert_ne!(jitter.offset(7), jitter.offset(8));
+        assert_ne!(
+            jitter.offset(7),
+            PlacementJitter::new(Length::new(3.0), 43).offset(7)
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use crate::world::World;
+use std::fs;
+
+// Runs a world for a long time, sampling the process's resident memory and the world's
+// footprint (see World::footprint) every so many ticks, and fails if anything keeps
+// growing: a leak from an event log, stats, recorded forces, or dead-cell bookkeeping
+// that a short test would never notice. Something that only fluctuates peaks about as
+// high in the later half of the run as in the earlier half; something that grows without
+// bound peaks higher.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SoakParameters {
+    pub ticks: u64,
+    pub sample_interval: u64,
+    // ticks to run before the first sample, while caches and the population settle
+    pub warmup_ticks: u64,
+    // how much higher the later peak may be than the earlier one, as a fraction of it...
+    pub growth_tolerance: f64,
+    // ...plus this many items, so that parts holding only a few items can vary
+    pub footprint_slack: usize,
+    // ...or for resident memory, plus this many bytes
+    pub rss_slack_bytes: u64,
+}
+
+impl SoakParameters {
+    pub const DEFAULT: SoakParameters = SoakParameters {
+        ticks: 1_000_000,
+        sample_interval: 10_000,
+        warmup_ticks: 1_000,
+        growth_tolerance: 0.25,
+        footprint_slack: 10,
+        rss_slack_bytes: 16 << 20,
+    };
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SoakSample {
+    pub tick: u64,
+    // None where the operating system doesn't say
+    pub rss_bytes: Option<u64>,
+    pub footprint: Vec<(&'static str, usize)>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SoakReport {
+    pub samples: Vec<SoakSample>,
+    // one line per thing that kept growing
+    pub failures: Vec<String>,
+}
+
+impl SoakReport {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+// Calls on_sample with each sample as it is taken, e.g. to print progress.
+pub fn run_soak<F>(world: &mut World, parameters: &SoakParameters, mut on_sample: F) -> SoakReport
+where
+    F: FnMut(&SoakSample),
+{
+    assert!(parameters.sample_interval > 0);
+    for _ in 0..parameters.warmup_ticks {
+        world.tick();
+    }
+    let mut samples = vec![];
+    let mut ticks_run = 0;
+    loop {
+        let sample = SoakSample {
+            tick: world.tick_count(),
+            rss_bytes: resident_memory_bytes(),
+            footprint: world.footprint(),
+        };
+        on_sample(&sample);
+        samples.push(sample);
+        if ticks_run >= parameters.ticks {
+            break;
+        }
+        let ticks = parameters.sample_interval.min(parameters.ticks - ticks_run);
+        for _ in 0..ticks {
+            world.tick();
+        }
+        ticks_run += ticks;
+    }
+    let failures = find_growth(&samples, parameters);
+    SoakReport { samples, failures }
+}
+
+fn find_growth(samples: &[SoakSample], parameters: &SoakParameters) -> Vec<String> {
+    if samples.len() < 4 {
+        return vec![];
+    }
+    let (earlier, later) = samples.split_at(samples.len() / 2);
+    let peak = |samples: &[SoakSample], value: &dyn Fn(&SoakSample) -> Option<u64>| {
+        samples.iter().filter_map(value).max()
+    };
+    let exceeds = |earlier_peak: u64, later_peak: u64, slack: u64| {
+        later_peak as f64 > earlier_peak as f64 * (1.0 + parameters.growth_tolerance) + slack as f64
+    };
+
+    let mut failures = vec![];
+    let rss = |sample: &SoakSample| sample.rss_bytes;
+    if let (Some(earlier_peak), Some(later_peak)) = (peak(earlier, &rss), peak(later, &rss)) {
+        if exceeds(earlier_peak, later_peak, parameters.rss_slack_bytes) {
+            failures.push(format!(
+                "resident memory grew from a peak of {} bytes to {}",
+                earlier_peak, later_peak
+            ));
+        }
+    }
+    for (index, (part, _)) in samples[0].footprint.iter().enumerate() {
+        let count = |sample: &SoakSample| Some(sample.footprint[index].1 as u64);
+        let earlier_peak = peak(earlier, &count).unwrap();
+        let later_peak = peak(later, &count).unwrap();
+        if exceeds(earlier_peak, later_peak, parameters.footprint_slack as u64) {
+            failures.push(format!(
+                "{} grew from a peak of {} to {}",
+                part, earlier_peak, later_peak
+            ));
+        }
+    }
+    failures
+}
+
+// From the VmRSS line of /proc/self/status, so only on Linux.
+pub fn resident_memory_bytes() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kilobytes = line.split_whitespace().nth(1)?.parse::<u64>().ok()?;
+    Some(kilobytes * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::benchmark::dense_world;
+
+    #[test]
+    fn steady_world_passes() {
+        let mut world = dense_world(20, 0);
+        let parameters = SoakParameters {
+            ticks: 200,
+            sample_interval: 20,
+            warmup_ticks: 10,
+            ..SoakParameters::DEFAULT
+        };
+        let mut sample_count = 0;
+
+        let report = run_soak(&mut world, &parameters, |_| sample_count += 1);
+
+        assert_eq!(sample_count, 11);
+        assert_eq!(report.samples.len(), 11);
+        assert_eq!(report.samples.last().unwrap().tick, 210);
+        assert!(report.passed(), "{:?}", report.failures);
+    }
+
+    #[test]
+    fn steady_growth_fails() {
+        let samples = (0..10)
+            .map(|index| SoakSample {
+                tick: index * 100,
+                rss_bytes: None,
+                footprint: vec![("cells", 50), ("warnings", 100 + 20 * index as usize)],
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            find_growth(&samples, &SoakParameters::DEFAULT),
+            vec!["warnings grew from a peak of 180 to 280"]
+        );
+    }
+
+    // Takes a few minutes; run with cargo test --release -- --ignored.
+    #[test]
+    #[ignore]
+    fn dense_world_memory_is_stable_over_many_ticks() {
+        let mut world = dense_world(1000, 0);
+        let parameters = SoakParameters {
+            ticks: 100_000,
+            sample_interval: 5_000,
+            ..SoakParameters::DEFAULT
+        };
+
+        let report = run_soak(&mut world, &parameters, |_| {});
+
+        assert!(report.passed(), "{:?}", report.failures);
+    }
+}
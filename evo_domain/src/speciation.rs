@@ -0,0 +1,202 @@
+use crate::biology::genome::SparseNeuralNetGenome;
+use crate::world::World;
+use std::fmt;
+
+// Groups a population's genomes into species and reports when species appear and die
+// out, for macro-evolutionary measures such as extinction rates. A genome belongs to the
+// oldest living species whose founding genome is within the threshold distance, or else
+// founds a new species. Call update periodically, e.g. every stats interval; a species
+// is counted extinct at the first update that finds no members.
+#[derive(Clone, Debug)]
+pub struct SpeciesTracker {
+    threshold: f32,
+    living: Vec<Species>,
+    next_id: u64,
+    extinct_lifetimes: Vec<u64>,
+}
+
+#[derive(Clone, Debug)]
+struct Species {
+    id: u64,
+    representative: SparseNeuralNetGenome,
+    first_tick: u64,
+    member_count: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpeciesEvent {
+    pub tick: u64,
+    pub species_id: u64,
+    pub kind: SpeciesEventKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SpeciesEventKind {
+    Origination,
+    // with the number of ticks from origination to extinction
+    Extinction { lifetime: u64 },
+}
+
+impl SpeciesTracker {
+    pub const CSV_HEADER: &'static str = "event,species,lifetime";
+
+    pub fn new(threshold: f32) -> Self {
+        assert!(threshold > 0.0);
+        SpeciesTracker {
+            threshold,
+            living: vec![],
+            next_id: 1,
+            extinct_lifetimes: vec![],
+        }
+    }
+
+    // Counts only cells with genomes.
+    pub fn update_from_world(&mut self, world: &World) -> Vec<SpeciesEvent> {
+        let genomes = world
+            .cells()
+            .iter()
+            .filter_map(|cell| cell.genome())
+            .collect::<Vec<_>>();
+        self.update(world.tick_count(), &genomes)
+    }
+
+    pub fn update(&mut self, tick: u64, genomes: &[&SparseNeuralNetGenome]) -> Vec<SpeciesEvent> {
+        let mut events = vec![];
+        for species in &mut self.living {
+            species.member_count = 0;
+        }
+        let threshold = self.threshold;
+        for genome in genomes {
+            match self
+                .living
+                .iter_mut()
+                .find(|species| species.representative.distance(genome) <= threshold)
+            {
+                Some(species) => species.member_count += 1,
+                None => {
+                    events.push(SpeciesEvent {
+                        tick,
+                        species_id: self.next_id,
+                        kind: SpeciesEventKind::Origination,
+                    });
+                    self.living.push(Species {
+                        id: self.next_id,
+                        representative: (*genome).clone(),
+                        first_tick: tick,
+                        member_count: 1,
+                    });
+                    self.next_id += 1;
+                }
+            }
+        }
+
+        let extinct_lifetimes = &mut self.extinct_lifetimes;
+        self.living.retain(|species| {
+            if species.member_count > 0 {
+                return true;
+            }
+            let lifetime = tick - species.first_tick;
+            extinct_lifetimes.push(lifetime);
+            events.push(SpeciesEvent {
+                tick,
+                species_id: species.id,
+                kind: SpeciesEventKind::Extinction { lifetime },
+            });
+            false
+        });
+        events
+    }
+
+    pub fn living_count(&self) -> usize {
+        self.living.len()
+    }
+
+    pub fn extinct_count(&self) -> usize {
+        self.extinct_lifetimes.len()
+    }
+
+    // zero when no species has died out
+    pub fn mean_extinct_lifetime(&self) -> f64 {
+        if self.extinct_lifetimes.is_empty() {
+            0.0
+        } else {
+            self.extinct_lifetimes.iter().sum::<u64>() as f64 / self.extinct_lifetimes.len() as f64
+        }
+    }
+}
+
+impl fmt::Display for SpeciesTracker {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "Species: {} living, {} extinct, mean extinct lifetime {:.1} ticks",
+            self.living_count(),
+            self.extinct_count(),
+            self.mean_extinct_lifetime()
+        )
+    }
+}
+
+impl SpeciesEvent {
+    pub fn to_csv_row(&self) -> String {
+        match self.kind {
+            SpeciesEventKind::Origination => format!("origination,{},", self.species_id),
+            SpeciesEventKind::Extinction { lifetime } => {
+                format!("extinction,{},{}", self.species_id, lifetime)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biology::genome::TransferFn;
+
+    #[test]
+    fn distant_genome_originates_new_species() {
+        let mut tracker = SpeciesTracker::new(2.0);
+        let (g1, g2, g3) = (genome(0.0), genome(1.5), genome(5.0));
+
+        let events = tracker.update(10, &[&g1, &g2, &g3]);
+
+        assert_eq!(events, vec![origination(10, 1), origination(10, 2)]);
+        assert_eq!(tracker.living_count(), 2);
+    }
+
+    #[test]
+    fn species_without_members_goes_extinct() {
+        let mut tracker = SpeciesTracker::new(2.0);
+        let (g1, g2) = (genome(0.0), genome(5.0));
+        tracker.update(10, &[&g1, &g2]);
+        assert!(tracker.update(20, &[&g1, &g2]).is_empty());
+
+        let events = tracker.update(35, &[&g2]);
+
+        assert_eq!(
+            events,
+            vec![SpeciesEvent {
+                tick: 35,
+                species_id: 1,
+                kind: SpeciesEventKind::Extinction { lifetime: 25 }
+            }]
+        );
+        assert_eq!(events[0].to_csv_row(), "extinction,1,25");
+        assert_eq!(tracker.extinct_count(), 1);
+        assert_eq!(tracker.mean_extinct_lifetime(), 25.0);
+    }
+
+    fn origination(tick: u64, species_id: u64) -> SpeciesEvent {
+        SpeciesEvent {
+            tick,
+            species_id,
+            kind: SpeciesEventKind::Origination,
+        }
+    }
+
+    fn genome(weight: f32) -> SparseNeuralNetGenome {
+        let mut genome = SparseNeuralNetGenome::new(TransferFn::IDENTITY);
+        genome.connect_node(1, 0.0, &[(0, weight)]);
+        genome
+    }
+}
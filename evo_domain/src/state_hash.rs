@@ -0,0 +1,58 @@
+// A fast hash (64-bit FNV-1a) of numbers that make up a world's state. Two runs that
+// should be identical can log it every so often and be compared line by line to find
+// the first tick where they diverge. Unlike std's DefaultHasher, the algorithm is fixed,
+// so hashes from different builds can be compared too.
+pub struct StateHasher {
+    hash: u64,
+}
+
+impl StateHasher {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    pub fn new() -> Self {
+        StateHasher {
+            hash: Self::OFFSET_BASIS,
+        }
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        for byte in &value.to_le_bytes() {
+            self.hash ^= *byte as u64;
+            self.hash = self.hash.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    // Exact bits, so that even the smallest floating-point difference shows.
+    pub fn write_f64(&mut self, value: f64) {
+        self.write_u64(value.to_bits());
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+impl Default for StateHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_depends_on_values_and_their_order() {
+        let hash = |values: &[f64]| {
+            let mut hasher = StateHasher::new();
+            values.iter().for_each(|value| hasher.write_f64(*value));
+            hasher.finish()
+        };
+
+        assert_eq!(hash(&[1.0, 2.0]), hash(&[1.0, 2.0]));
+        assert_ne!(hash(&[1.0, 2.0]), hash(&[2.0, 1.0]));
+        assert_ne!(hash(&[1.0]), hash(&[1.0 + f64::EPSILON]));
+    }
+}
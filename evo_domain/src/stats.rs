@@ -0,0 +1,534 @@
+use crate::biology::cell::Cell;
+use crate::biology::genome::SparseNeuralNetGenome;
+use crate::biology::layers::Tissue;
+use crate::physics::quantities::BioEnergy;
+use crate::world::World;
+use crate::world_events::*;
+use std::fmt;
+use std::io::{self, Write};
+
+// How far a population's genomes have moved from the founders' and from each other,
+// to tell during a run whether anything is evolving.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GenomeDistanceStats {
+    pub population: usize,
+    // mean over cells of the distance to the nearest founder genome
+    pub mean_distance_to_founders: f32,
+    // mean distance between pairs of cells
+    pub diversity: f32,
+}
+
+impl GenomeDistanceStats {
+    pub const CSV_HEADER: &'static str = "population,mean_distance_to_founders,diversity";
+
+    // diversity compares every pair, so larger populations are sampled down to this
+    const MAX_DIVERSITY_SAMPLE: usize = 100;
+
+    pub fn founder_genomes(world: &World) -> Vec<SparseNeuralNetGenome> {
+        world
+            .cells()
+            .iter()
+            .filter_map(|cell| cell.genome().cloned())
+            .collect()
+    }
+
+    // Counts only cells with genomes.
+    pub fn measure(founders: &[SparseNeuralNetGenome], world: &World) -> Self {
+        let genomes = world
+            .cells()
+            .iter()
+            .filter_map(|cell| cell.genome())
+            .collect::<Vec<_>>();
+        Self::of_genomes(founders, &genomes)
+    }
+
+    fn of_genomes(founders: &[SparseNeuralNetGenome], genomes: &[&SparseNeuralNetGenome]) -> Self {
+        GenomeDistanceStats {
+            population: genomes.len(),
+            mean_distance_to_founders: Self::mean(genomes.iter().map(|genome| {
+                founders
+                    .iter()
+                    .map(|founder| genome.distance(founder))
+                    .fold(f32::INFINITY, f32::min)
+            })),
+            diversity: Self::diversity(genomes),
+        }
+    }
+
+    fn diversity(genomes: &[&SparseNeuralNetGenome]) -> f32 {
+        let stride = genomes.len().div_ceil(Self::MAX_DIVERSITY_SAMPLE);
+        let sample = genomes.iter().step_by(stride.max(1)).collect::<Vec<_>>();
+        Self::mean(sample.iter().enumerate().flat_map(|(i, genome1)| {
+            sample[i + 1..]
+                .iter()
+                .map(move |genome2| genome1.distance(genome2))
+        }))
+    }
+
+    // zero when there are no values
+    fn mean<I: Iterator<Item = f32>>(values: I) -> f32 {
+        let (sum, count) = values.fold((0.0, 0), |(sum, count), value| (sum + value, count + 1));
+        if count == 0 {
+            0.0
+        } else {
+            sum / count as f32
+        }
+    }
+
+    pub fn to_csv_row(&self) -> String {
+        format!(
+            "{},{},{}",
+            self.population, self.mean_distance_to_founders, self.diversity
+        )
+    }
+}
+
+// A world's population at one moment, for plotting population dynamics offline.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PopulationStats {
+    pub population: usize,
+    pub total_energy: f64,
+    // ages are ticks lived
+    pub mean_age: f64,
+    pub max_age: u64,
+    pub bond_count: usize,
+    // mean area of the layers of each tissue, in Tissue::ALL order, zero if there are none
+    pub mean_layer_areas: [f64; 6],
+}
+
+impl PopulationStats {
+    pub const CSV_HEADER: &'static str = "population,total_energy,mean_age,max_age,bonds,\
+        mean_air_bubble_area,mean_bonding_area,mean_cell_wall_area,mean_photosynthetic_area,\
+        mean_chemosynthetic_area,mean_predatory_area";
+
+    pub fn measure(world: &World) -> Self {
+        let mut stats = Self::of_cells(world.cells());
+        stats.bond_count = world.bonds().len();
+        stats
+    }
+
+    fn of_cells(cells: &[Cell]) -> Self {
+        let mut mean_layer_areas = [0.0; 6];
+        for (mean_area, tissue) in mean_layer_areas.iter_mut().zip(&Tissue::ALL) {
+            let areas = cells.iter().flat_map(|cell| {
+                cell.layers()
+                    .iter()
+                    .filter(move |layer| layer.tissue() == *tissue)
+                    .map(|layer| layer.area().value())
+            });
+            *mean_area = Self::mean(areas);
+        }
+        PopulationStats {
+            population: cells.len(),
+            total_energy: cells
+                .iter()
+                .fold(0.0, |sum, cell| sum + cell.energy().value()),
+            mean_age: Self::mean(cells.iter().map(|cell| cell.ticks_lived() as f64)),
+            max_age: cells.iter().map(Cell::ticks_lived).max().unwrap_or(0),
+            bond_count: 0,
+            mean_layer_areas,
+        }
+    }
+
+    // zero when there are no values
+    fn mean<I: Iterator<Item = f64>>(values: I) -> f64 {
+        let (sum, count) = values.fold((0.0, 0), |(sum, count), value| (sum + value, count + 1));
+        if count == 0 {
+            0.0
+        } else {
+            sum / count as f64
+        }
+    }
+
+    pub fn to_csv_row(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{},{},{},{},{}",
+            self.population,
+            self.total_energy,
+            self.mean_age,
+            self.max_age,
+            self.bond_count,
+            self.mean_layer_areas[0],
+            self.mean_layer_areas[1],
+            self.mean_layer_areas[2],
+            self.mean_layer_areas[3],
+            self.mean_layer_areas[4],
+            self.mean_layer_areas[5]
+        )
+    }
+}
+
+// What happened to a world's cells and bonds over a stretch of ticks, counted from its
+// WorldEvents.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct EventCounts {
+    pub births: usize,
+    pub deaths: usize,
+    pub bonds_formed: usize,
+    pub bonds_broken: usize,
+    pub energy_donated: f64,
+}
+
+impl EventCounts {
+    pub const CSV_HEADER: &'static str = "births,deaths,bonds_formed,bonds_broken,energy_donated";
+
+    pub fn count(&mut self, event: &WorldEvent) {
+        match event {
+            WorldEvent::CellBorn { .. } => self.births += 1,
+            WorldEvent::CellDied { .. } => self.deaths += 1,
+            WorldEvent::BondFormed { .. } => self.bonds_formed += 1,
+            WorldEvent::BondBroken { .. } => self.bonds_broken += 1,
+            WorldEvent::EnergyDonated { energy, .. } => self.energy_donated += energy.value(),
+        }
+    }
+
+    pub fn to_csv_row(&self) -> String {
+        format!(
+            "{},{},{},{},{}",
+            self.births, self.deaths, self.bonds_formed, self.bonds_broken, self.energy_donated
+        )
+    }
+}
+
+// Measures PopulationStats each time it is called, e.g. from a World::on_tick_end hook,
+// keeping the latest and optionally streaming each to CSV with its tick. Subscribed to
+// the world's events, it also counts what happened since the last call.
+#[derive(Default)]
+pub struct StatsCollector {
+    csv: Option<Box<dyn Write + Send>>,
+    latest: Option<PopulationStats>,
+    event_counts: EventCounts,
+    latest_event_counts: EventCounts,
+}
+
+impl StatsCollector {
+    pub fn new() -> Self {
+        StatsCollector::default()
+    }
+
+    // Writes the CSV column header at once, after anything already written.
+    pub fn with_csv(mut self, mut csv: Box<dyn Write + Send>) -> io::Result<Self> {
+        writeln!(
+            csv,
+            "tick,{},{}",
+            PopulationStats::CSV_HEADER,
+            EventCounts::CSV_HEADER
+        )?;
+        self.csv = Some(csv);
+        Ok(self)
+    }
+
+    pub fn collect(&mut self, world: &World) -> io::Result<()> {
+        let stats = PopulationStats::measure(world);
+        let event_counts = std::mem::take(&mut self.event_counts);
+        if let Some(csv) = &mut self.csv {
+            writeln!(
+                csv,
+                "{},{},{}",
+                world.tick_count(),
+                stats.to_csv_row(),
+                event_counts.to_csv_row()
+            )?;
+        }
+        self.latest = Some(stats);
+        self.latest_event_counts = event_counts;
+        Ok(())
+    }
+
+    pub fn latest(&self) -> Option<&PopulationStats> {
+        self.latest.as_ref()
+    }
+
+    // between the last two calls to collect
+    pub fn latest_event_counts(&self) -> &EventCounts {
+        &self.latest_event_counts
+    }
+}
+
+impl WorldEventListener for StatsCollector {
+    fn on_event(&mut self, _tick: u64, event: &WorldEvent) {
+        self.event_counts.count(event);
+    }
+}
+
+// The cells, over a whole run, that no other cell beat on lifespan, offspring count,
+// and peak energy all at once, with their genomes.
+#[derive(Clone, Debug, Default)]
+pub struct ParetoFront {
+    entries: Vec<ParetoEntry>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParetoEntry {
+    pub lifespan: u64,
+    pub offspring_count: u32,
+    pub peak_energy: BioEnergy,
+    pub genome: Option<SparseNeuralNetGenome>,
+}
+
+impl ParetoFront {
+    pub fn new() -> Self {
+        ParetoFront::default()
+    }
+
+    pub fn entries(&self) -> &[ParetoEntry] {
+        &self.entries
+    }
+
+    // Call with each cell as it dies, and at the end of the run with the living cells.
+    pub fn add_cell(&mut self, cell: &Cell) {
+        self.add(ParetoEntry::new(cell));
+    }
+
+    pub fn add(&mut self, entry: ParetoEntry) {
+        if self
+            .entries
+            .iter()
+            .any(|existing| existing.dominates(&entry) || existing.same_scores(&entry))
+        {
+            return;
+        }
+        self.entries.retain(|existing| !entry.dominates(existing));
+        self.entries.push(entry);
+    }
+}
+
+impl fmt::Display for ParetoFront {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Pareto front (lifespan, offspring, peak energy):")?;
+        for entry in &self.entries {
+            writeln!(
+                f,
+                "  {} ticks, {} offspring, {:.4} energy",
+                entry.lifespan,
+                entry.offspring_count,
+                entry.peak_energy.value()
+            )?;
+            if let Some(genome) = &entry.genome {
+                write!(f, "{}", genome.format_table(&[], &[], &[]))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ParetoEntry {
+    pub fn new(cell: &Cell) -> Self {
+        ParetoEntry {
+            lifespan: cell.ticks_lived(),
+            offspring_count: cell.offspring_count(),
+            peak_energy: cell.peak_energy(),
+            genome: cell.genome().cloned(),
+        }
+    }
+
+    fn dominates(&self, other: &ParetoEntry) -> bool {
+        self.lifespan >= other.lifespan
+            && self.offspring_count >= other.offspring_count
+            && self.peak_energy >= other.peak_energy
+            && !self.same_scores(other)
+    }
+
+    fn same_scores(&self, other: &ParetoEntry) -> bool {
+        self.lifespan == other.lifespan
+            && self.offspring_count == other.offspring_count
+            && self.peak_energy == other.peak_energy
+    }
+}
+
+// Recent stats samples kept in memory, e.g. for on-screen graphs, in bounded space however
+// long the run. When full, the older half is thinned to every k-th sample, so recent
+// history stays exact while older history gets coarser. Exact values belong in a stats
+// file streamed to disk.
+#[derive(Clone, Debug)]
+pub struct StatsHistory<T> {
+    samples: Vec<(u64, T)>,
+    capacity: usize,
+    downsampling_factor: usize,
+}
+
+impl<T> StatsHistory<T> {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity >= 2);
+        StatsHistory {
+            samples: Vec::with_capacity(capacity),
+            capacity,
+            downsampling_factor: 2,
+        }
+    }
+
+    pub fn with_downsampling_factor(mut self, factor: usize) -> Self {
+        assert!(factor >= 2);
+        self.downsampling_factor = factor;
+        self
+    }
+
+    pub fn add(&mut self, tick: u64, sample: T) {
+        if self.samples.len() >= self.capacity {
+            self.downsample();
+        }
+        self.samples.push((tick, sample));
+    }
+
+    // (tick, sample) pairs, oldest first
+    pub fn samples(&self) -> &[(u64, T)] {
+        &self.samples
+    }
+
+    fn downsample(&mut self) {
+        let older_len = self.samples.len() / 2;
+        let factor = self.downsampling_factor;
+        let mut index = 0;
+        // keep the last of every factor samples, so at least one goes
+        self.samples.retain(|_| {
+            let keep = index >= older_len || index % factor == factor - 1;
+            index += 1;
+            keep
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biology::genome::TransferFn;
+    use crate::physics::quantities::*;
+    use std::f64::consts::PI;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn measures_distance_to_nearest_founder_and_between_cells() {
+        let founders = vec![genome(0.0), genome(10.0)];
+        let (g1, g2, g3) = (genome(1.0), genome(3.0), genome(9.0));
+
+        let stats = GenomeDistanceStats::of_genomes(&founders, &[&g1, &g2, &g3]);
+
+        assert_eq!(stats.population, 3);
+        assert_eq!(stats.mean_distance_to_founders, 5.0 / 3.0);
+        assert_eq!(stats.diversity, (2.0 + 8.0 + 6.0) / 3.0);
+    }
+
+    #[test]
+    fn empty_population_has_zero_stats() {
+        let stats = GenomeDistanceStats::of_genomes(&[genome(0.0)], &[]);
+        assert_eq!(stats.to_csv_row(), "0,0,0");
+    }
+
+    #[test]
+    fn population_stats_of_cells() {
+        let mut cells = vec![
+            Cell::ball(
+                Length::new(1.0),
+                Mass::new(1.0),
+                Position::ORIGIN,
+                Velocity::ZERO,
+            )
+            .with_initial_energy(BioEnergy::new(2.0)),
+            Cell::ball(
+                Length::new(2.0),
+                Mass::new(1.0),
+                Position::ORIGIN,
+                Velocity::ZERO,
+            )
+            .with_initial_energy(BioEnergy::new(3.0)),
+        ];
+        cells[1].tick();
+        cells[1].tick();
+
+        let stats = PopulationStats::of_cells(&cells);
+
+        assert_eq!(stats.population, 2);
+        assert_eq!(stats.total_energy, 5.0);
+        assert_eq!(stats.mean_age, 1.0);
+        assert_eq!(stats.max_age, 2);
+        assert_eq!(stats.mean_layer_areas[0], 0.0);
+        assert_eq!(stats.mean_layer_areas[3], (PI + 4.0 * PI) / 2.0);
+    }
+
+    #[test]
+    fn stats_collector_streams_csv_rows() {
+        let csv = SharedBuffer::default();
+        let mut collector = StatsCollector::new()
+            .with_csv(Box::new(csv.clone()))
+            .unwrap();
+        let world = World::new(Position::ORIGIN, Position::new(10.0, 10.0));
+
+        collector.collect(&world).unwrap();
+
+        let text = String::from_utf8(csv.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(
+            text,
+            format!(
+                "tick,{},{}\n0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0\n",
+                PopulationStats::CSV_HEADER,
+                EventCounts::CSV_HEADER
+            )
+        );
+        assert_eq!(collector.latest().unwrap().population, 0);
+    }
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn pareto_front_keeps_only_undominated_entries() {
+        let mut front = ParetoFront::new();
+        front.add(entry(10, 1, 5.0));
+        front.add(entry(5, 3, 5.0));
+        front.add(entry(4, 2, 4.0));
+        front.add(entry(10, 1, 5.0));
+        assert_eq!(front.entries(), &[entry(10, 1, 5.0), entry(5, 3, 5.0)]);
+
+        front.add(entry(12, 3, 5.0));
+        assert_eq!(front.entries(), &[entry(12, 3, 5.0)]);
+    }
+
+    fn entry(lifespan: u64, offspring_count: u32, peak_energy: f64) -> ParetoEntry {
+        ParetoEntry {
+            lifespan,
+            offspring_count,
+            peak_energy: BioEnergy::new(peak_energy),
+            genome: None,
+        }
+    }
+
+    fn genome(weight: f32) -> SparseNeuralNetGenome {
+        let mut genome = SparseNeuralNetGenome::new(TransferFn::IDENTITY);
+        genome.connect_node(1, 0.0, &[(0, weight)]);
+        genome
+    }
+
+    #[test]
+    fn stats_history_stays_within_capacity() {
+        let mut history = StatsHistory::new(10);
+        for tick in 0..1000 {
+            history.add(tick, tick);
+        }
+        assert!(history.samples().len() <= 10);
+        assert_eq!(history.samples().last(), Some(&(999, 999)));
+    }
+
+    #[test]
+    fn stats_history_thins_older_half_only() {
+        let mut history = StatsHistory::new(8).with_downsampling_factor(2);
+        for tick in 0..9 {
+            history.add(tick, ());
+        }
+        let ticks = history
+            .samples()
+            .iter()
+            .map(|(tick, _)| *tick)
+            .collect::<Vec<_>>();
+        assert_eq!(ticks, vec![1, 3, 4, 5, 6, 7, 8]);
+    }
+}
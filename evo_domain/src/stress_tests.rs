@@ -0,0 +1,225 @@
+// Seeded fuzzing of worlds built to be hard on the physics and biology: crowds of
+// overlapping cells, extreme velocities, zero-area layers, and bonds between cells that
+// sit on top of each other. Each world runs a few ticks and must neither panic nor
+// break the invariants checked at the end of every tick.
+
+use crate::biology::cell::Cell;
+use crate::biology::control::ContinuousRequestsControl;
+use crate::biology::control_requests::ControlRequest;
+use crate::biology::layers::*;
+use crate::physics::bond::Bond;
+use crate::physics::node_graph::{GraphEdge, GraphNode, MAX_NODE_EDGES};
+use crate::physics::quantities::*;
+use crate::physics::shapes::Circle;
+use crate::world::World;
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg64Mcg;
+
+const SEEDS: u64 = 10;
+const TICKS: usize = 10;
+
+// layer kinds
+const PHOTO: u32 = 0;
+const BONDING: u32 = 1;
+const THRUSTER: u32 = 2;
+const GRIP: u32 = 3;
+
+#[test]
+fn adversarial_worlds_survive_ticks_with_invariants() {
+    for seed in 0..SEEDS {
+        let mut world = adversarial_world(seed);
+        for tick in 0..TICKS {
+            world.tick();
+            check_invariants(&world, seed, tick);
+        }
+    }
+}
+
+fn adversarial_world(seed: u64) -> World {
+    let mut rng = Pcg64Mcg::seed_from_u64(seed);
+    let mut world = World::new(Position::new(-50.0, -50.0), Position::new(50.0, 50.0))
+        .with_standard_influences()
+        .with_sunlight(0.0, 10.0)
+        .with_bottom_substrate(0.5);
+
+    let num_cells = rng.gen_range(100, 400);
+    for _ in 0..num_cells {
+        world.add_cell(adversarial_cell(&mut rng));
+    }
+
+    // bonds in random slots, including between cells that overlap completely
+    let mut used_slots = vec![[false; MAX_NODE_EDGES]; num_cells];
+    let mut index_pairs = vec![];
+    for _ in 0..rng.gen_range(0, num_cells) {
+        let index1 = rng.gen_range(0, num_cells);
+        let index2 = rng.gen_range(0, num_cells);
+        let slot1 = rng.gen_range(0, MAX_NODE_EDGES);
+        let slot2 = rng.gen_range(0, MAX_NODE_EDGES);
+        if index1 == index2
+            || used_slots[index1][slot1]
+            || used_slots[index2][slot2]
+            || index_pairs.contains(&(index1, index2))
+            || index_pairs.contains(&(index2, index1))
+        {
+            continue;
+        }
+        used_slots[index1][slot1] = true;
+        used_slots[index2][slot2] = true;
+        index_pairs.push((index1, index2));
+        let bond = Bond::new(&world.cells()[index1], &world.cells()[index2]);
+        world.add_bond(bond, slot1, slot2);
+    }
+    world
+}
+
+fn adversarial_cell(rng: &mut Pcg64Mcg) -> Cell {
+    // crowded into a small patch, some exactly on top of each other
+    let position = if rng.gen_bool(0.1) {
+        Position::ORIGIN
+    } else {
+        Position::new(rng.gen_range(-10.0, 10.0), rng.gen_range(-10.0, 10.0))
+    };
+    let speed = extreme_value(rng, 1.0e4);
+    let velocity = Velocity::new(
+        speed * rng.gen_range(-1.0, 1.0),
+        speed * rng.gen_range(-1.0, 1.0),
+    );
+
+    let tissues = (0..rng.gen_range(1, 4))
+        .map(|_| rng.gen_range(0, 5))
+        .collect::<Vec<_>>();
+    let layers = tissues
+        .iter()
+        .map(|kind| adversarial_layer(rng, *kind))
+        .collect();
+    let requests = (0..rng.gen_range(0, 6))
+        .map(|_| {
+            let layer_index = rng.gen_range(0, tissues.len());
+            adversarial_request(rng, layer_index, tissues[layer_index])
+        })
+        .collect();
+
+    Cell::new(position, velocity, layers)
+        .with_control(Box::new(ContinuousRequestsControl::new(requests)))
+        .with_initial_energy(BioEnergy::new(extreme_value(rng, 1.0e6).abs()))
+}
+
+fn adversarial_layer(rng: &mut Pcg64Mcg, kind: u32) -> CellLayer {
+    const LAYER_PARAMS: LayerParameters = LayerParameters {
+        minimum_intact_thickness: Fraction::unchecked(0.05),
+        healing_energy_delta: BioEnergyDelta::new(-1.0),
+        healing_cost_temperature_factor: 0.05,
+        entropic_damage_health_delta: HealthDelta::new(-0.01),
+        entropic_damage_temperature_factor: 0.1,
+        aging_damage_health_delta: HealthDelta::new(-0.0001),
+        overlap_damage_health_delta: HealthDelta::new(-0.1),
+        impact_damage_health_delta: HealthDelta::new(-0.05),
+        growth_energy_delta: BioEnergyDelta::new(-1.0),
+        max_growth_rate: Positive::unchecked(10.0),
+        shrinkage_energy_delta: BioEnergyDelta::new(0.5),
+        max_shrinkage_rate: 1.0,
+        mass_per_energy: 0.0,
+        decay_rate: Fraction::unchecked(0.5),
+        max_wall_stress: Positive::unchecked(2.0),
+        wall_relaxation_rate: Fraction::unchecked(0.5),
+        pressure_damage_health_delta: HealthDelta::new(-0.1),
+    };
+
+    let area = match rng.gen_range(0, 4) {
+        0 => Area::ZERO,
+        1 => Area::new(1.0e-9),
+        _ => Area::new(rng.gen_range(0.1, 50.0)),
+    };
+    let (tissue, specialty): (Tissue, Box<dyn CellLayerSpecialty>) = match kind {
+        PHOTO => (
+            Tissue::Photosynthetic,
+            Box::new(PhotoCellLayerSpecialty::new(Fraction::new(0.5))),
+        ),
+        BONDING => (Tissue::Bonding, Box::new(BondingCellLayerSpecialty::new())),
+        THRUSTER => (
+            Tissue::Photosynthetic,
+            Box::new(ThrusterCellLayerSpecialty::new()),
+        ),
+        GRIP => (
+            Tissue::Photosynthetic,
+            Box::new(GripCellLayerSpecialty::new()),
+        ),
+        _ => (Tissue::CellWall, Box::new(NullCellLayerSpecialty::new())),
+    };
+    let layer = CellLayer::new(
+        area,
+        Density::new(rng.gen_range(0.1, 10.0)),
+        tissue,
+        specialty,
+    );
+    let layer = if rng.gen_bool(0.5) {
+        layer.with_parameters(&LAYER_PARAMS)
+    } else {
+        layer
+    };
+    if rng.gen_bool(0.1) {
+        layer.dead()
+    } else {
+        layer
+    }
+}
+
+// Extreme values on any of the layer's control channels.
+fn adversarial_request(rng: &mut Pcg64Mcg, layer_index: usize, kind: u32) -> ControlRequest {
+    let bond_index = rng.gen_range(0, 8);
+    match (rng.gen_range(0, 3), kind) {
+        (0, _) => CellLayer::resize_request(layer_index, AreaDelta::new(extreme_value(rng, 100.0))),
+        (1, _) => {
+            CellLayer::healing_request(layer_index, HealthDelta::new(rng.gen_range(0.0, 1.0)))
+        }
+        (_, BONDING) => match rng.gen_range(0, 3) {
+            0 => BondingCellLayerSpecialty::retain_bond_request(layer_index, bond_index, true),
+            1 => BondingCellLayerSpecialty::budding_angle_request(
+                layer_index,
+                bond_index,
+                Angle::from_radians(rng.gen_range(-10.0, 10.0)),
+            ),
+            _ => BondingCellLayerSpecialty::donation_energy_request(
+                layer_index,
+                bond_index,
+                BioEnergy::new(extreme_value(rng, 1.0e6).abs()),
+            ),
+        },
+        (_, THRUSTER) => {
+            ThrusterCellLayerSpecialty::force_x_request(layer_index, extreme_value(rng, 1.0e4))
+        }
+        (_, GRIP) => GripCellLayerSpecialty::grip_request(layer_index, rng.gen_range(0.0, 1.0)),
+        _ => CellLayer::resize_request(layer_index, AreaDelta::new(extreme_value(rng, 100.0))),
+    }
+}
+
+// zero, tiny, or anywhere up to plus or minus max
+fn extreme_value(rng: &mut Pcg64Mcg, max: f64) -> f64 {
+    match rng.gen_range(0, 4) {
+        0 => 0.0,
+        1 => 1.0e-12,
+        _ => rng.gen_range(-max, max),
+    }
+}
+
+fn check_invariants(world: &World, seed: u64, tick: usize) {
+    let context = format!("seed {}, tick {}", seed, tick);
+    for cell in world.cells() {
+        assert!(cell.center().x().is_finite(), "{}: {:?}", context, cell);
+        assert!(cell.center().y().is_finite(), "{}: {:?}", context, cell);
+        assert!(cell.radius().value() >= 0.0, "{}: {:?}", context, cell);
+        assert!(cell.radius().value().is_finite(), "{}: {:?}", context, cell);
+        assert!(cell.energy().value() >= 0.0, "{}: {:?}", context, cell);
+        assert!(cell.energy().value().is_finite(), "{}: {:?}", context, cell);
+        for layer in cell.layers() {
+            assert!(layer.area().value() >= 0.0, "{}: {:?}", context, cell);
+            assert!(layer.mass().value() >= 0.0, "{}: {:?}", context, cell);
+        }
+    }
+    for bond in world.bonds() {
+        for handle in &[bond.node1_handle(), bond.node2_handle()] {
+            assert_eq!(world.cell(*handle).node_handle(), *handle, "{}", context);
+        }
+        assert_ne!(bond.node1_handle(), bond.node2_handle(), "{}", context);
+    }
+}
@@ -0,0 +1,228 @@
+// Small worlds for common test scenarios, shared by unit tests and, through the
+// "test-support" feature, by benchmarks and other crates' tests. Each returns a world
+// that the caller can extend with more with_* calls before ticking it.
+
+use crate::biology::cell::Cell;
+use crate::biology::control::*;
+use crate::biology::control_requests::*;
+use crate::biology::layers::*;
+use crate::environment::influences::*;
+use crate::physics::quantities::*;
+use crate::world::World;
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg64Mcg;
+use std::f64::consts::PI;
+
+// A ball of radius 1 and mass 1.
+pub fn unit_ball(position: Position, velocity: Velocity) -> Cell {
+    Cell::ball(Length::new(1.0), Mass::new(1.0), position, velocity)
+}
+
+// Two touching unit balls centered on the x axis, bonded to each other.
+pub fn bonded_pair() -> World {
+    colony_chain(2)
+}
+
+// Unit balls in a row along the x axis, each touching and bonded to the next.
+pub fn colony_chain(length: usize) -> World {
+    assert!(length > 0);
+    let half_width = length as Value1D + 1.0;
+    World::new(
+        Position::new(-half_width, -half_width),
+        Position::new(half_width, half_width),
+    )
+    .with_standard_influences()
+    .with_cells(
+        (0..length)
+            .map(|i| {
+                unit_ball(
+                    Position::new(2.0 * i as Value1D - (length - 1) as Value1D, 0.0),
+                    Velocity::ZERO,
+                )
+            })
+            .collect(),
+    )
+    .with_bonds((1..length).map(|i| (i - 1, i)).collect())
+}
+
+// Unit balls scattered over a patch too small for them, so many overlap, inside walls.
+pub fn crowded_cluster(count: usize, seed: u64) -> World {
+    let mut rng = Pcg64Mcg::seed_from_u64(seed);
+    let patch_half_width = (count as Value1D).sqrt();
+    World::new(Position::new(-20.0, -20.0), Position::new(20.0, 20.0))
+        .with_standard_influences()
+        .with_cells(
+            (0..count)
+                .map(|_| {
+                    unit_ball(
+                        Position::new(
+                            rng.gen_range(-patch_half_width, patch_half_width),
+                            rng.gen_range(-patch_half_width, patch_half_width),
+                        ),
+                        Velocity::ZERO,
+                    )
+                })
+                .collect(),
+        )
+}
+
+// A unit ball lighter than the fluid around it, thrown downward into a tall world with
+// gravity, buoyancy, and drag, so its fall slows and then it floats back up.
+pub fn falling_floater() -> World {
+    const GRAVITY: Value1D = -0.05;
+    World::new(Position::new(0.0, -100.0), Position::new(10.0, 0.0))
+        .with_perimeter_walls()
+        .with_per_cell_influences(vec![
+            Box::new(SimpleForceInfluence::new(Box::new(WeightForce::new(
+                GRAVITY,
+            )))),
+            Box::new(SimpleForceInfluence::new(Box::new(BuoyancyForce::new(
+                GRAVITY, 0.5,
+            )))),
+            Box::new(SimpleForceInfluence::new(Box::new(DragForce::new(0.005)))),
+        ])
+        .with_cell(unit_ball(
+            Position::new(5.0, -50.0),
+            Velocity::new(0.0, -2.0),
+        ))
+}
+
+// A single photosynthetic founder in dim, even light that grows, by budding, into a
+// line of bonded cells along the x axis; see ColonyControl.
+pub fn budding_colony() -> World {
+    World::new(Position::new(-60.0, -20.0), Position::new(60.0, 20.0))
+        .with_standard_influences()
+        .with_per_cell_influences(vec![
+            Box::new(Sunlight::new(-20.0, 20.0, 0.05, 0.05)),
+            Box::new(SimpleForceInfluence::new(Box::new(DragForce::new(0.1)))),
+        ])
+        .with_cell(
+            Cell::new(
+                Position::ORIGIN,
+                Velocity::ZERO,
+                vec![
+                    CellLayer::new(
+                        Area::new(10.0),
+                        Density::new(1.0),
+                        Tissue::Photosynthetic,
+                        Box::new(PhotoCellLayerSpecialty::new(Fraction::ONE)),
+                    ),
+                    CellLayer::new(
+                        Area::new(10.0),
+                        Density::new(1.0),
+                        Tissue::Bonding,
+                        Box::new(BondingCellLayerSpecialty::new()),
+                    ),
+                ],
+            )
+            .with_control(Box::new(ColonyControl::new(ColonyControl::GENERATIONS))),
+        )
+}
+
+// Each cell keeps all its bonds and, once it has saved up BUDDING_THRESHOLD energy, buds
+// once, giving the bud BUD_ENERGY: the founder buds on bonds 1 and 2, to its right and
+// left, and each bud buds on bond 1, away from the founder, until the colony is the
+// given number of generations deep. A bud's bond to its parent is its bond 0.
+#[derive(Clone, Debug)]
+pub struct ColonyControl {
+    generation: u32,
+    generations: u32,
+    budded: bool,
+}
+
+impl ColonyControl {
+    pub const GENERATIONS: u32 = 4;
+    pub const BUD_ENERGY: BioEnergy = BioEnergy::unchecked(10.0);
+    // enough for the founder's two buds
+    pub const BUDDING_THRESHOLD: BioEnergy = BioEnergy::unchecked(25.0);
+    const BONDING_LAYER_INDEX: usize = 1;
+
+    pub fn new(generations: u32) -> Self {
+        ColonyControl {
+            generation: 0,
+            generations,
+            budded: false,
+        }
+    }
+
+    fn bud_requests(bond_index: usize, angle: Angle) -> Vec<ControlRequest> {
+        vec![
+            BondingCellLayerSpecialty::budding_angle_request(
+                Self::BONDING_LAYER_INDEX,
+                bond_index,
+                angle,
+            ),
+            BondingCellLayerSpecialty::donation_energy_request(
+                Self::BONDING_LAYER_INDEX,
+                bond_index,
+                Self::BUD_ENERGY,
+            ),
+        ]
+    }
+}
+
+impl CellControl for ColonyControl {
+    fn run(&mut self, cell_state: &CellStateSnapshot) -> Vec<ControlRequest> {
+        let mut requests = (0..3)
+            .map(|bond_index| {
+                BondingCellLayerSpecialty::retain_bond_request(
+                    Self::BONDING_LAYER_INDEX,
+                    bond_index,
+                    true,
+                )
+            })
+            .collect::<Vec<_>>();
+        if !self.budded
+            && self.generation < self.generations
+            && cell_state.energy >= Self::BUDDING_THRESHOLD
+        {
+            self.budded = true;
+            if self.generation == 0 {
+                requests.extend(Self::bud_requests(1, Angle::from_radians(0.0)));
+                requests.extend(Self::bud_requests(2, Angle::from_radians(PI)));
+            } else if cell_state.center.x() > 0.0 {
+                requests.extend(Self::bud_requests(1, Angle::from_radians(0.0)));
+            } else {
+                requests.extend(Self::bud_requests(1, Angle::from_radians(PI)));
+            }
+        }
+        requests
+    }
+
+    fn spawn(&mut self) -> Box<dyn CellControl> {
+        Box::new(ColonyControl {
+            generation: self.generation + 1,
+            generations: self.generations,
+            budded: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::newtonian::NewtonianBody;
+
+    #[test]
+    fn colony_chain_bonds_neighbors() {
+        let world = colony_chain(4);
+        assert_eq!(world.cells().len(), 4);
+        assert_eq!(world.bonds().len(), 3);
+        assert_eq!(world.cells()[0].position(), Position::new(-3.0, 0.0));
+        assert_eq!(world.cells()[3].position(), Position::new(3.0, 0.0));
+    }
+
+    #[test]
+    fn falling_floater_slows_and_rises() {
+        let mut world = falling_floater();
+        for _ in 0..10 {
+            world.tick();
+        }
+        let velocity = world.cells()[0].velocity();
+        assert!(velocity.y() > -2.0);
+        for _ in 0..100 {
+            world.tick();
+        }
+        assert!(world.cells()[0].velocity().y() > 0.0);
+    }
+}
@@ -0,0 +1,118 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+// Non-fatal problems a world ran into, most recent last, for showing to users who never
+// look at the terminal. Warnings from the same source in consecutive ticks are merged
+// into one, with the latest message, that moves to the end, so that a problem that
+// persists doesn't push the rest out.
+#[derive(Clone, Debug)]
+pub struct WarningLog {
+    warnings: VecDeque<WorldWarning>,
+    capacity: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorldWarning {
+    pub severity: Severity,
+    // what raised it, e.g. "explosion watchdog"
+    pub source: &'static str,
+    pub message: String,
+    pub first_tick: u64,
+    pub last_tick: u64,
+}
+
+impl WarningLog {
+    pub fn new(capacity: usize) -> Self {
+        WarningLog {
+            warnings: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn warnings(&self) -> &VecDeque<WorldWarning> {
+        &self.warnings
+    }
+
+    pub fn add(&mut self, tick: u64, severity: Severity, source: &'static str, message: String) {
+        let continued = self.warnings.iter().rposition(|warning| {
+            warning.source == source
+                && warning.severity == severity
+                && tick <= warning.last_tick + 1
+        });
+        if let Some(index) = continued {
+            let mut warning = self.warnings.remove(index).unwrap();
+            warning.message = message;
+            warning.last_tick = tick;
+            self.warnings.push_back(warning);
+            return;
+        }
+        if self.warnings.len() == self.capacity {
+            self.warnings.pop_front();
+        }
+        self.warnings.push_back(WorldWarning {
+            severity,
+            source,
+            message,
+            first_tick: tick,
+            last_tick: tick,
+        });
+    }
+
+    pub fn clear(&mut self) {
+        self.warnings.clear();
+    }
+}
+
+impl fmt::Display for WorldWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.first_tick == self.last_tick {
+            write!(f, "{}", self.first_tick)?;
+        } else {
+            write!(f, "{}-{}", self.first_tick, self.last_tick)?;
+        }
+        write!(f, " {}", self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn consecutive_warnings_from_same_source_merge() {
+        let mut log = WarningLog::new(10);
+        log.add(3, Severity::Info, "capped", "capped: 2".to_string());
+        log.add(3, Severity::Error, "explosion", "flung apart".to_string());
+        log.add(4, Severity::Info, "capped", "capped: 5".to_string());
+        log.add(6, Severity::Info, "capped", "capped: 1".to_string());
+
+        let rows = log
+            .warnings()
+            .iter()
+            .map(WorldWarning::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(rows, vec!["3 flung apart", "3-4 capped: 5", "6 capped: 1"]);
+    }
+
+    #[test]
+    fn full_log_drops_oldest() {
+        let mut log = WarningLog::new(2);
+        log.add(1, Severity::Error, "a", "first".to_string());
+        log.add(1, Severity::Warning, "b", "second".to_string());
+        log.add(1, Severity::Error, "c", "third".to_string());
+
+        let messages = log
+            .warnings()
+            .iter()
+            .map(|warning| warning.message.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, vec!["second", "third"]);
+    }
+}
@@ -1,29 +1,64 @@
 use crate::biology::cell::Cell;
 use crate::biology::changes::*;
 use crate::biology::cloud::Cloud;
+use crate::biology::genome::SparseNeuralNetGenome;
+use crate::biology::phylogeny::Phylogeny;
+use crate::biology::template::CellTemplate;
+use crate::energy_audit::*;
+use crate::environment::chemical_field::ChemicalField;
 use crate::environment::influences::*;
+use crate::environment::local_environment::HasLocalEnvironment;
+use crate::environment::tax_recycling::*;
+use crate::explosion_watchdog::*;
+use crate::hall_of_fame::HallOfFame;
+use crate::phase_times::*;
 use crate::physics::bond::*;
+use crate::physics::bounds::*;
 use crate::physics::handles::*;
 use crate::physics::node_graph::*;
 use crate::physics::overlap::{SortableHandle, SortableHandles};
 use crate::physics::quantities::*;
-use crate::physics::shapes::Circle;
+use crate::physics::shapes::{Circle, Rectangle};
+use crate::state_hash::StateHasher;
+use crate::warnings::*;
+use crate::world_events::*;
+use crate::world_state::*;
 use crate::Parameters;
 use rayon::prelude::*;
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 use std::io;
 use std::io::{Result, StdoutLock, Write};
+use std::time;
 
 pub struct World {
     parameters: Parameters,
     min_corner: Position,
     max_corner: Position,
     cell_graph: NodeGraph<Cell, Bond<Cell>, AngleGusset>,
+    bounding_boxes: BoundingBoxes<Cell>,
     clouds: ObjectsWithHandles<Cloud>,
     circle_handles: SortableHandles<Cell>,
     cross_cell_influences: Vec<Box<dyn CrossCellInfluence>>,
     per_cell_influences: Vec<Box<dyn PerCellInfluence>>,
     num_selected_cells: u32,
+    tick_count: u64,
+    scheduled_parameter_changes: Vec<ParameterChange>,
+    parallelism: usize,
+    thread_pool: Option<rayon::ThreadPool>,
+    control_time_budget: Option<time::Duration>,
+    slow_control_penalty: Fraction,
+    slow_control_count: u64,
+    next_cell_id: u64,
+    phylogeny: Option<Phylogeny>,
+    hall_of_fame: Option<HallOfFame>,
+    chemical_field: Option<ChemicalField>,
+    donation_tax_recycler: DonationTaxRecycler,
+    energy_audit: Option<EnergyAudit>,
+    explosion_watchdog: Option<ExplosionWatchdog>,
+    phase_times: Option<PhaseTimes>,
+    placeable_cell: Option<Cell>,
+    warnings: WarningLog,
+    hooks: WorldHooks,
 }
 
 impl World {
@@ -33,14 +68,131 @@ impl World {
             min_corner,
             max_corner,
             cell_graph: NodeGraph::new(),
+            bounding_boxes: BoundingBoxes::new(),
             clouds: ObjectsWithHandles::new(),
             circle_handles: SortableHandles::new(),
             cross_cell_influences: vec![],
             per_cell_influences: vec![],
             num_selected_cells: 0,
+            tick_count: 0,
+            scheduled_parameter_changes: vec![],
+            parallelism: 0,
+            thread_pool: None,
+            control_time_budget: None,
+            slow_control_penalty: Fraction::ZERO,
+            slow_control_count: 0,
+            next_cell_id: 1,
+            phylogeny: None,
+            hall_of_fame: None,
+            chemical_field: None,
+            donation_tax_recycler: DonationTaxRecycler::new(DonationTaxDestination::Destroyed),
+            energy_audit: None,
+            explosion_watchdog: None,
+            phase_times: None,
+            placeable_cell: None,
+            warnings: WarningLog::new(Self::WARNING_LOG_CAPACITY),
+            hooks: WorldHooks::default(),
         }
     }
 
+    // The number of threads that tick cells: 1 ticks them in order on the calling thread,
+    // for reproducible debugging and profiling, and 0 (the default) uses rayon's global
+    // thread pool. Bonds, collisions, and other cross-cell changes are always serial.
+    pub fn with_parallelism(mut self, num_threads: usize) -> Self {
+        self.parallelism = num_threads;
+        self.thread_pool = if num_threads > 1 {
+            Some(
+                rayon::ThreadPoolBuilder::new()
+                    .num_threads(num_threads)
+                    .build()
+                    .expect("Failed to create cell tick thread pool"),
+            )
+        } else {
+            None
+        };
+        self
+    }
+
+    pub fn parallelism(&self) -> usize {
+        self.parallelism
+    }
+
+    // Counts each cell control that takes longer than the budget to run in a tick, and
+    // takes the penalty fraction of the cell's energy, so that slow controls don't
+    // dominate tick time. Wall-clock time varies from run to run, so a run with a penalty
+    // is not reproducible.
+    pub fn with_control_time_budget(
+        mut self,
+        budget: time::Duration,
+        slow_control_penalty: Fraction,
+    ) -> Self {
+        self.control_time_budget = Some(budget);
+        self.slow_control_penalty = slow_control_penalty;
+        self
+    }
+
+    // The number of times, over the run, that a cell control exceeded the time budget.
+    pub fn slow_control_count(&self) -> u64 {
+        self.slow_control_count
+    }
+
+    const WARNING_LOG_CAPACITY: usize = 100;
+
+    // The recent non-fatal problems: capped and dropped control requests, slow controls,
+    // energy audit imbalances, and physics explosions.
+    pub fn warnings(&self) -> &WarningLog {
+        &self.warnings
+    }
+
+    // How many items each part of the world is holding, such as cells, bonds, logged
+    // warnings, and recorded forces, for finding what grows in a long run.
+    pub fn footprint(&self) -> Vec<(&'static str, usize)> {
+        let cells = self.cell_graph.nodes();
+        let sum_over_cells = |count: fn(&Cell) -> usize| cells.iter().map(count).sum::<usize>();
+        vec![
+            ("cells", cells.len()),
+            ("bonds", self.cell_graph.edges().len()),
+            ("angle gussets", self.cell_graph.meta_edges().len()),
+            ("clouds", self.clouds.objects().len()),
+            ("sort handles", self.circle_handles.handles().len()),
+            ("colonies", self.bounding_boxes.colonies().len()),
+            ("scheduled changes", self.scheduled_parameter_changes.len()),
+            ("warnings", self.warnings.warnings().len()),
+            (
+                "phylogeny nodes",
+                self.phylogeny.as_ref().map_or(0, Phylogeny::len),
+            ),
+            (
+                "hall of fame entries",
+                self.hall_of_fame
+                    .as_ref()
+                    .map_or(0, |hall_of_fame| hall_of_fame.entries().len()),
+            ),
+            ("pending events", self.hooks.event_bus.pending_count()),
+            (
+                "energy audit ledger",
+                self.energy_audit
+                    .as_ref()
+                    .and_then(EnergyAudit::last_report)
+                    .map_or(0, |report| report.ledger.len()),
+            ),
+            (
+                "recorded forces",
+                sum_over_cells(|cell| {
+                    cell.last_net_force()
+                        .non_dominant_force_additions()
+                        .as_ref()
+                        .map_or(0, Vec::len)
+                }),
+            ),
+            (
+                "energy changes",
+                sum_over_cells(|cell| cell.last_energy_changes().map_or(0, <[_]>::len)),
+            ),
+            ("bites", sum_over_cells(|cell| cell.bites().len())),
+        ]
+    }
+
     pub fn with_parameters(mut self, parameters: Parameters) -> Self {
         self.parameters = parameters;
         self
@@ -65,10 +217,28 @@ impl World {
         self.with_cross_cell_influence(Box::new(PairCollisions::new(force_adjustment_factor)))
     }
 
+    // Experimental: pair collisions found in this many vertical strips of the world at
+    // once, on rayon's global thread pool, for very large worlds. Bond forces and all
+    // cross-cell changes are still serial, and cells still tick as with_parallelism says.
+    pub fn with_sharded_pair_collisions(
+        self,
+        force_adjustment_factor: Fraction,
+        shard_count: usize,
+    ) -> Self {
+        self.with_cross_cell_influence(Box::new(
+            PairCollisions::new(force_adjustment_factor).with_shards(shard_count),
+        ))
+    }
+
     pub fn with_bond_forces(self) -> Self {
         self.with_cross_cell_influence(Box::new(BondForces::new()))
     }
 
+    // After the pair collisions, e.g. after with_standard_influences.
+    pub fn with_bond_formation(self, breaking_strain: Length) -> Self {
+        self.with_cross_cell_influence(Box::new(BondFormation::new(breaking_strain)))
+    }
+
     pub fn with_sunlight(self, min_intensity: Value1D, max_intensity: Value1D) -> Self {
         let world_min_corner = self.min_corner();
         let world_max_corner = self.max_corner();
@@ -80,6 +250,35 @@ impl World {
         )))
     }
 
+    pub fn with_thermal_gradient(self, min_temperature: Value1D, max_temperature: Value1D) -> Self {
+        let world_min_corner = self.min_corner();
+        let world_max_corner = self.max_corner();
+        self.with_per_cell_influence(Box::new(ThermalGradient::new(
+            world_min_corner.y(),
+            world_max_corner.y(),
+            min_temperature,
+            max_temperature,
+        )))
+    }
+
+    pub fn with_bottom_substrate(self, friction_coefficient: Value1D) -> Self {
+        self.with_per_cell_influence(Box::new(SubstrateFriction::new(friction_coefficient)))
+    }
+
+    pub fn with_anchor_forces(self, breaking_strain: Length) -> Self {
+        self.with_per_cell_influence(Box::new(AnchorForces::new(breaking_strain)))
+    }
+
+    // Adds nothing for a magnitude of zero.
+    pub fn with_thermal_noise(self, magnitude: Value1D, seed: u64) -> Self {
+        if magnitude == 0.0 {
+            return self;
+        }
+        self.with_per_cell_influence(Box::new(SimpleForceInfluence::new(Box::new(
+            ThermalNoiseForce::new(magnitude, seed),
+        ))))
+    }
+
     pub fn with_cross_cell_influence(mut self, influence: Box<dyn CrossCellInfluence>) -> Self {
         self.cross_cell_influences.push(influence);
         self
@@ -111,6 +310,12 @@ impl World {
         self
     }
 
+    // The cell that add_cell_at copies when no cell is selected.
+    pub fn with_placeable_cell(mut self, cell: Cell) -> Self {
+        self.placeable_cell = Some(cell);
+        self
+    }
+
     pub fn with_cells(mut self, cells: Vec<Cell>) -> Self {
         for cell in cells {
             self.add_cell(cell);
@@ -148,6 +353,51 @@ impl World {
         self
     }
 
+    // Called with the parent and child cells each time a cell buds.
+    pub fn on_birth<F>(&mut self, hook: F)
+    where
+        F: 'static + FnMut(&Cell, &Cell) + Send,
+    {
+        self.hooks.birth_hooks.push(Box::new(hook));
+    }
+
+    // Called with each cell that bursts, just before it is removed from the world.
+    pub fn on_death<F>(&mut self, hook: F)
+    where
+        F: 'static + FnMut(&Cell) + Send,
+    {
+        self.hooks.death_hooks.push(Box::new(hook));
+    }
+
+    // Called with the two bonded cells each time a bond forms or breaks.
+    pub fn on_bond_change<F>(&mut self, hook: F)
+    where
+        F: 'static + FnMut(BondChange, &Cell, &Cell) + Send,
+    {
+        self.hooks.bond_change_hooks.push(Box::new(hook));
+    }
+
+    // Called with each change made by set_parameter, e.g. to annotate logs with it.
+    pub fn on_parameter_change<F>(&mut self, hook: F)
+    where
+        F: 'static + FnMut(&ParameterChange) + Send,
+    {
+        self.hooks.parameter_change_hooks.push(Box::new(hook));
+    }
+
+    pub fn on_tick_end<F>(&mut self, hook: F)
+    where
+        F: 'static + FnMut(&World) + Send,
+    {
+        self.hooks.tick_end_hooks.push(Box::new(hook));
+    }
+
+    // Reports each birth, death, bond change, and donation to the listener at the end of
+    // the tick it happened in, before the tick-end hooks are called.
+    pub fn subscribe(&mut self, listener: Box<dyn WorldEventListener>) {
+        self.hooks.event_bus.subscribe(listener);
+    }
+
     pub fn min_corner(&self) -> Position {
         self.min_corner
     }
@@ -156,8 +406,176 @@ impl World {
         self.max_corner
     }
 
+    pub fn parameters(&self) -> &Parameters {
+        &self.parameters
+    }
+
+    // Changes one of the Parameters (see Parameters::NAMES) in the middle of a run.
+    pub fn set_parameter(&mut self, name: &str, value: f64) -> std::result::Result<(), String> {
+        self.parameters.set(name, value)?;
+        let change = ParameterChange {
+            tick: self.tick_count,
+            name: name.to_string(),
+            value,
+        };
+        for hook in &mut self.hooks.parameter_change_hooks {
+            hook(&change);
+        }
+        Ok(())
+    }
+
+    // Calls set_parameter once tick_count reaches the given tick, just before the next tick.
+    pub fn schedule_parameter_change(
+        &mut self,
+        tick: u64,
+        name: &str,
+        value: f64,
+    ) -> std::result::Result<(), String> {
+        // fail now rather than mid-run
+        let mut parameters = self.parameters;
+        parameters.set(name, value)?;
+        self.scheduled_parameter_changes.push(ParameterChange {
+            tick,
+            name: name.to_string(),
+            value,
+        });
+        Ok(())
+    }
+
+    // the number of ticks run so far, including the one the tick-end hooks are called for
+    pub fn tick_count(&self) -> u64 {
+        self.tick_count
+    }
+
+    // Records who buds from whom from now on. Cells already in the world count as
+    // founders, so call this before the first tick.
+    pub fn with_phylogeny(mut self) -> Self {
+        self.enable_phylogeny();
+        self
+    }
+
+    pub fn enable_phylogeny(&mut self) {
+        let mut phylogeny = Phylogeny::new();
+        for cell_id in 1..self.next_cell_id {
+            phylogeny.add(cell_id, None, self.tick_count);
+        }
+        self.phylogeny = Some(phylogeny);
+    }
+
+    pub fn phylogeny(&self) -> Option<&Phylogeny> {
+        self.phylogeny.as_ref()
+    }
+
+    // Every interval ticks, keeps the genomes of the longest-lived cell and of the best
+    // cell of the richest lineage, up to capacity entries. See HallOfFame.
+    pub fn with_hall_of_fame(mut self, interval: u64, capacity: usize) -> Self {
+        self.enable_hall_of_fame(interval, capacity);
+        self
+    }
+
+    pub fn enable_hall_of_fame(&mut self, interval: u64, capacity: usize) {
+        self.hall_of_fame = Some(HallOfFame::new(interval, capacity));
+    }
+
+    pub fn hall_of_fame(&self) -> Option<&HallOfFame> {
+        self.hall_of_fame.as_ref()
+    }
+
+    // Checks every tick that the cells' energy changes add up, and prints the books of any
+    // tick that doesn't balance. Slows the world down, so it is only for debugging.
+    pub fn with_energy_audit(self) -> Self {
+        self.with_energy_audit_on_imbalance(OnImbalance::Log)
+    }
+
+    pub fn with_energy_audit_on_imbalance(mut self, on_imbalance: OnImbalance) -> Self {
+        self.enable_energy_audit(on_imbalance);
+        self
+    }
+
+    pub fn enable_energy_audit(&mut self, on_imbalance: OnImbalance) {
+        for cell in self.cell_graph.nodes_mut() {
+            cell.record_energy_changes();
+        }
+        self.energy_audit = Some(EnergyAudit::new(on_imbalance));
+    }
+
+    pub fn energy_audit(&self) -> Option<&EnergyAudit> {
+        self.energy_audit.as_ref()
+    }
+
+    // Checks at the end of every tick for cells flung apart by a physics explosion, and
+    // prints a report of the fastest ones and the forces on them. Records every cell's
+    // forces, so it slows the world down.
+    pub fn with_explosion_watchdog(mut self, parameters: ExplosionWatchdogParameters) -> Self {
+        self.enable_explosion_watchdog(parameters);
+        self
+    }
+
+    pub fn enable_explosion_watchdog(&mut self, parameters: ExplosionWatchdogParameters) {
+        for cell in self.cell_graph.nodes_mut() {
+            cell.record_force_additions();
+        }
+        self.explosion_watchdog = Some(ExplosionWatchdog::new(parameters));
+    }
+
+    pub fn explosion_watchdog(&self) -> Option<&ExplosionWatchdog> {
+        self.explosion_watchdog.as_ref()
+    }
+
+    // Adds up how long each phase of every tick takes, e.g. for a benchmark. Needs the
+    // "timing" feature.
+    pub fn with_phase_timing(mut self) -> Self {
+        self.enable_phase_timing();
+        self
+    }
+
+    pub fn enable_phase_timing(&mut self) {
+        self.phase_times = Some(PhaseTimes::new());
+    }
+
+    pub fn phase_times(&self) -> Option<&PhaseTimes> {
+        self.phase_times.as_ref()
+    }
+
+    // Nutrient that cells with chemosynthetic layers absorb, and that diffuses each tick.
+    pub fn with_chemical_field(mut self, field: ChemicalField) -> Self {
+        self.chemical_field = Some(field);
+        self
+    }
+
+    // By default, the tax paid on donations leaves the world.
+    pub fn with_donation_tax_destination(mut self, destination: DonationTaxDestination) -> Self {
+        self.donation_tax_recycler = DonationTaxRecycler::new(destination);
+        self
+    }
+
+    pub fn donation_tax_recycler(&self) -> &DonationTaxRecycler {
+        &self.donation_tax_recycler
+    }
+
+    pub fn chemical_field(&self) -> Option<&ChemicalField> {
+        self.chemical_field.as_ref()
+    }
+
     pub fn add_cell(&mut self, cell: Cell) -> Handle<Cell> {
+        self.add_cell_with_parent(cell, None)
+    }
+
+    fn add_cell_with_parent(&mut self, mut cell: Cell, parent_id: Option<u64>) -> Handle<Cell> {
+        let cell_id = self.next_cell_id;
+        self.next_cell_id += 1;
+        cell.set_cell_id(cell_id);
+        if self.energy_audit.is_some() {
+            cell.record_energy_changes();
+        }
+        if self.explosion_watchdog.is_some() {
+            cell.record_force_additions();
+        }
+        if let Some(phylogeny) = &mut self.phylogeny {
+            phylogeny.add(cell_id, parent_id, self.tick_count);
+        }
         let handle = self.cell_graph.add_node(cell);
+        self.bounding_boxes.add_node(self.cell_graph.node(handle));
         self.circle_handles
             .add_handle(SortableHandle::GraphNode(handle));
         handle
@@ -171,18 +589,128 @@ impl World {
         self.cell_graph.node(handle)
     }
 
+    // The cell graph and the handles sorted for overlap finding, as the cross-cell
+    // influences see them, so that benchmarks can time those parts of a tick alone.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn cell_graph_and_handles_mut(
+        &mut self,
+    ) -> (
+        &mut NodeGraph<Cell, Bond<Cell>, AngleGusset>,
+        &mut SortableHandles<Cell>,
+    ) {
+        (&mut self.cell_graph, &mut self.circle_handles)
+    }
+
     fn cell_mut(&mut self, handle: Handle<Cell>) -> &mut Cell {
         self.cell_graph.node_mut(handle)
     }
 
+    // The light a cell centered at the position would get from all light sources, e.g. for
+    // drawing and tests, so they need not re-derive it from the sunlight parameters.
+    // Nothing casts shadows yet; occluders would be accounted for here.
+    pub fn light_intensity_at(&self, position: Position) -> Value1D {
+        self.per_cell_influences
+            .iter()
+            .map(|influence| influence.light_intensity_at(position))
+            .sum()
+    }
+
+    // Kicks the cell during the next tick, e.g. for scripted events or dragging a cell.
+    // Returns false if no cell has the handle. Handles change when cells die, so look
+    // a cell up again after each tick rather than keeping its handle.
+    pub fn apply_impulse(&mut self, handle: Handle<Cell>, impulse: Impulse) -> bool {
+        if !self.cell_graph.is_valid_handle(handle) {
+            return false;
+        }
+        self.cell_mut(handle).apply_impulse(impulse);
+        true
+    }
+
+    // Like apply_impulse, with the impulse that brings the cell to the velocity.
+    pub fn set_cell_velocity(&mut self, handle: Handle<Cell>, velocity: Velocity) -> bool {
+        if !self.cell_graph.is_valid_handle(handle) {
+            return false;
+        }
+        self.cell_mut(handle).set_velocity(velocity);
+        true
+    }
+
+    // Applies the template to every cell, e.g. to start a run from a saved cell.
+    pub fn apply_cell_template(
+        &mut self,
+        template: &CellTemplate,
+    ) -> std::result::Result<(), String> {
+        for cell in self.cell_graph.nodes_mut() {
+            cell.apply_template(template)?;
+        }
+        self.bounding_boxes.update(&self.cell_graph);
+        Ok(())
+    }
+
+    // Gives the cell a copy of the genome, e.g. one exported from another run.
+    pub fn set_cell_genome(
+        &mut self,
+        handle: Handle<Cell>,
+        genome: &SparseNeuralNetGenome,
+    ) -> std::result::Result<(), String> {
+        self.cell_mut(handle).set_genome(genome.clone())
+    }
+
+    pub fn apply_genome(
+        &mut self,
+        genome: &SparseNeuralNetGenome,
+    ) -> std::result::Result<(), String> {
+        for cell in self.cell_graph.nodes_mut() {
+            cell.set_genome(genome.clone())?;
+        }
+        Ok(())
+    }
+
     pub fn add_bond(
         &mut self,
         bond: Bond<Cell>,
         bond_index_on_cell1: usize,
         bond_index_on_cell2: usize,
     ) {
+        let cell1_handle = bond.node1_handle();
+        let cell2_handle = bond.node2_handle();
         self.cell_graph
             .add_edge(bond, bond_index_on_cell1, bond_index_on_cell2);
+        self.bounding_boxes.join(cell1_handle, cell2_handle);
+        self.hooks.bond_change(
+            BondChange::Formed,
+            self.cell_graph.node(cell1_handle),
+            self.cell_graph.node(cell2_handle),
+        );
+    }
+
+    // The bounding box of the cell as of the end of the last tick, or when it was added.
+    pub fn cell_bounds(&self, handle: Handle<Cell>) -> Rectangle {
+        self.bounding_boxes.node_bounds(handle)
+    }
+
+    // The colonies, groups of cells joined by bonds, with their bounding boxes as of
+    // the end of the last tick. A cell with no bonds is a colony of one.
+    pub fn colonies(&self) -> &[Colony<Cell>] {
+        self.bounding_boxes.colonies()
+    }
+
+    pub fn colony_of(&self, handle: Handle<Cell>) -> &Colony<Cell> {
+        self.bounding_boxes.colony_of(handle)
+    }
+
+    // The bounding box of all the cells, or None if there are none.
+    pub fn cells_bounds(&self) -> Option<Rectangle> {
+        self.bounding_boxes.overall_bounds()
+    }
+
+    // The cells whose bounding boxes overlap the region, in order.
+    pub fn cells_overlapping(&self, region: Rectangle) -> Vec<&Cell> {
+        self.bounding_boxes
+            .node_indexes_overlapping(region)
+            .into_iter()
+            .map(|index| &self.cells()[index])
+            .collect()
     }
 
     pub fn bonds(&self) -> &[Bond<Cell>] {
@@ -208,96 +736,549 @@ impl World {
         self.clouds.objects()
     }
 
+    // A hash of the tick count and the cells', bonds', and clouds' current state, to
+    // compare with another run that should be identical. See StateHasher.
+    pub fn state_hash(&self) -> u64 {
+        let mut hasher = StateHasher::new();
+        hasher.write_u64(self.tick_count);
+        for cell in self.cells() {
+            cell.hash_state(&mut hasher);
+        }
+        for bond in self.bonds() {
+            hasher.write_u64(self.cell(bond.node1_handle()).cell_id());
+            hasher.write_u64(self.cell(bond.node2_handle()).cell_id());
+        }
+        for cloud in self.clouds() {
+            hasher.write_f64(cloud.center().x());
+            hasher.write_f64(cloud.center().y());
+            hasher.write_f64(cloud.radius().value());
+            hasher.write_f64(cloud.concentration().value());
+        }
+        hasher.finish()
+    }
+
+    // See WorldState for what is saved and what isn't.
+    pub fn save_state(&self) -> WorldState {
+        WorldState {
+            tick_count: self.tick_count,
+            next_cell_id: self.next_cell_id,
+            cells: self.cells().iter().map(Cell::save_state).collect(),
+            bonds: self.bond_cell_ids(),
+            clouds: self
+                .clouds()
+                .iter()
+                .map(|cloud| CloudState {
+                    position: [cloud.center().x(), cloud.center().y()],
+                    radius: cloud.radius().value(),
+                    concentration: cloud.concentration().value(),
+                })
+                .collect(),
+            chemical_concentrations: self
+                .chemical_field
+                .as_ref()
+                .map(|field| field.concentrations().to_vec()),
+            donation_tax_pool: self.donation_tax_recycler.pool().value(),
+        }
+    }
+
+    // Restores a state saved from this world, or from one built the same way, which
+    // must have the same cells, in the same order, and the same bonds.
+    pub fn load_state(&mut self, state: &WorldState) -> std::result::Result<(), String> {
+        let cell_ids: Vec<u64> = self.cells().iter().map(Cell::cell_id).collect();
+        let state_cell_ids: Vec<u64> = state.cells.iter().map(|cell| cell.cell_id).collect();
+        if cell_ids != state_cell_ids {
+            return Err("World state has different cells than the world".to_string());
+        }
+        if self.bond_cell_ids() != state.bonds {
+            return Err("World state has different bonds than the world".to_string());
+        }
+        if state.chemical_concentrations.is_some() != self.chemical_field.is_some() {
+            return Err("World state chemical field doesn't match the world's".to_string());
+        }
+        if state.donation_tax_pool.is_nan() || state.donation_tax_pool < 0.0 {
+            return Err("World state donation tax pool must be nonnegative".to_string());
+        }
+        if !state
+            .clouds
+            .iter()
+            .all(|cloud| cloud.radius >= 0.0 && (0.0..=1.0).contains(&cloud.concentration))
+        {
+            return Err("World state has an invalid cloud".to_string());
+        }
+        if let (Some(field), Some(concentrations)) =
+            (&mut self.chemical_field, &state.chemical_concentrations)
+        {
+            field.set_concentrations(concentrations)?;
+        }
+        for (cell, cell_state) in self.cell_graph.nodes_mut().iter_mut().zip(&state.cells) {
+            cell.load_state(cell_state)?;
+        }
+        self.clouds = ObjectsWithHandles::new();
+        for cloud in &state.clouds {
+            self.clouds.add(
+                Cloud::new(
+                    Position::new(cloud.position[0], cloud.position[1]),
+                    Length::new(cloud.radius),
+                )
+                .with_concentration(Fraction::new(cloud.concentration)),
+            );
+        }
+        self.donation_tax_recycler
+            .set_pool(BioEnergy::new(state.donation_tax_pool));
+        self.tick_count = state.tick_count;
+        self.next_cell_id = state.next_cell_id;
+        self.bounding_boxes.update(&self.cell_graph);
+        Ok(())
+    }
+
+    fn bond_cell_ids(&self) -> Vec<(u64, u64)> {
+        self.bonds()
+            .iter()
+            .map(|bond| {
+                (
+                    self.cell(bond.node1_handle()).cell_id(),
+                    self.cell(bond.node2_handle()).cell_id(),
+                )
+            })
+            .collect()
+    }
+
     pub fn debug_print_cells(&self) {
         println!("{:#?}", self.cell_graph);
+        for cell in self.cells() {
+            cell.print_genome_table();
+        }
     }
 
-    pub fn toggle_select_cell_at(&mut self, pos: Position) {
-        for cell in self.cell_graph.nodes_mut() {
-            if cell.overlaps(pos) {
-                if cell.is_selected() {
-                    cell.set_selected(false);
-                    self.num_selected_cells -= 1;
-                } else {
-                    cell.set_selected(true);
-                    self.num_selected_cells += 1;
-                }
-            }
+    // The cell whose edge is nearest pos, counting only cells within tolerance of it.
+    // A cell that contains pos beats one that doesn't, so tiny cells can be picked with
+    // a generous tolerance without stealing clicks from the cells around them.
+    pub fn nearest_cell(&self, pos: Position, tolerance: Length) -> Option<&Cell> {
+        let reach = tolerance.value();
+        let region = Rectangle::new(
+            Position::new(pos.x() - reach, pos.y() - reach),
+            Position::new(pos.x() + reach, pos.y() + reach),
+        );
+        self.cells_overlapping(region)
+            .into_iter()
+            .map(|cell| {
+                let edge_distance = (cell.center() - pos).length().value() - cell.radius().value();
+                (cell, edge_distance)
+            })
+            .filter(|(_, edge_distance)| *edge_distance <= tolerance.value())
+            .min_by(|(_, distance1), (_, distance2)| distance1.total_cmp(distance2))
+            .map(|(cell, _)| cell)
+    }
+
+    // Adds a copy of the first selected cell, or else of the placeable cell, with the
+    // same energy, layers, and genome, at rest at the position. Returns None if there is
+    // no cell to copy.
+    pub fn add_cell_at(&mut self, pos: Position) -> Option<Handle<Cell>> {
+        let layer_area = self.parameters.initial_layer_area;
+        let selected_handle = self
+            .cells()
+            .iter()
+            .find(|cell| cell.is_selected())
+            .map(|cell| cell.node_handle());
+        let source = match selected_handle {
+            Some(handle) => self.cell_graph.node_mut(handle),
+            None => self.placeable_cell.as_mut()?,
+        };
+        let mut cell = source.spawn(layer_area);
+        cell.apply_template(&CellTemplate::from_cell(source))
+            .unwrap();
+        cell.set_initial_position(pos);
+        Some(self.add_cell(cell))
+    }
+
+    // Removes the cell nearest the position, within the tolerance, and its bonds, as if
+    // it had burst. Returns whether there was such a cell.
+    pub fn remove_cell_at(&mut self, pos: Position, tolerance: Length) -> bool {
+        let handle = match self.nearest_cell(pos, tolerance) {
+            Some(cell) => cell.node_handle(),
+            None => return false,
+        };
+        if self.cell(handle).is_selected() {
+            self.num_selected_cells -= 1;
+        }
+        self.remove_burst_cells(&[handle]);
+        self.update_circle_handles();
+        self.bounding_boxes.update(&self.cell_graph);
+        true
+    }
+
+    pub fn toggle_select_cell_at(&mut self, pos: Position, tolerance: Length) {
+        let handle = match self.nearest_cell(pos, tolerance) {
+            Some(cell) => cell.node_handle(),
+            None => return,
+        };
+        let cell = self.cell_graph.node_mut(handle);
+        if cell.is_selected() {
+            cell.set_selected(false);
+            self.num_selected_cells -= 1;
+        } else {
+            cell.set_selected(true);
+            self.num_selected_cells += 1;
         }
     }
 
     pub fn tick(&mut self) {
+        let mut clock = PhaseClock::start(self.phase_times.is_some());
+        self.open_energy_audit();
+        self.apply_scheduled_parameter_changes();
         self.apply_cross_cell_influences();
+        clock.lap("cross-cell influences");
         let cell_bond_requests = self.tick_cells();
+        clock.lap("cells");
+        self.book_cell_energy_changes();
+        self.check_control_times();
+        self.apply_bites();
+        clock.lap("bites");
+        self.tick_chemical_field();
         self.tick_clouds();
+        clock.lap("chemicals and clouds");
         self.apply_world_changes(&cell_bond_requests);
+        clock.lap("bonds, births, and deaths");
+        self.tick_count += 1;
+        self.close_energy_audit();
+        self.check_for_explosion();
+        self.log_warnings();
+        self.record_hall_of_fame();
         self.print_end_tick_info().unwrap();
+        self.hooks.event_bus.dispatch(self.tick_count);
+        self.call_tick_end_hooks();
+        clock.lap("monitors and hooks");
+        if let Some(phase_times) = &mut self.phase_times {
+            phase_times.add_tick(clock);
+        }
     }
 
-    fn apply_cross_cell_influences(&mut self) {
-        for influence in &self.cross_cell_influences {
-            influence.apply_to(&mut self.cell_graph, &mut self.circle_handles);
-        }
+    fn total_cell_energy(&self) -> BioEnergy {
+        self.cells()
+            .iter()
+            .fold(BioEnergy::ZERO, |total, cell| total + cell.energy())
     }
 
-    fn tick_cells(&mut self) -> Vec<BondRequests> {
-        let per_cell_influences = &self.per_cell_influences;
-        self.cell_graph
-            .nodes_mut()
-            .par_iter_mut()
-            .map(|cell| {
-                for influence in per_cell_influences {
-                    influence.apply_to(cell);
-                }
-                cell.tick()
-            })
-            .collect()
+    fn open_energy_audit(&mut self) {
+        let total_cell_energy = self.total_cell_energy();
+        if let Some(audit) = &mut self.energy_audit {
+            audit.open(total_cell_energy);
+        }
     }
 
-    fn tick_clouds(&mut self) {
-        for cloud in self.clouds.objects_mut() {
-            cloud.tick(&self.parameters.cloud_params);
+    fn book_energy(&mut self, label: &'static str, delta: BioEnergyDelta) {
+        if let Some(audit) = &mut self.energy_audit {
+            audit.book(label, delta);
         }
     }
 
-    fn apply_world_changes(&mut self, cell_bond_requests: &[BondRequests]) {
-        let parameters = &self.parameters;
-        let mut donated_energy = vec![];
-        let mut new_children = vec![];
-        let mut broken_bond_handles = HashSet::new();
-        let mut burst_cell_handles = vec![];
-        self.cell_graph.for_each_node(|index, cell, edge_source| {
-            Self::execute_bond_requests(
-                parameters,
-                cell,
-                edge_source,
-                &cell_bond_requests[index],
-                &mut donated_energy,
-                &mut new_children,
-                &mut broken_bond_handles,
-            );
-            if !cell.is_intact() {
-                burst_cell_handles.push(cell.node_handle());
-            }
-        });
-        self.apply_donated_energy(donated_energy);
-        for burst_cell_handle in &burst_cell_handles {
-            if self.cell(*burst_cell_handle).is_selected() {
-                self.num_selected_cells -= 1;
+    fn book_cell_energy_changes(&mut self) {
+        let audit = match &mut self.energy_audit {
+            Some(audit) => audit,
+            None => return,
+        };
+        for cell in self.cell_graph.nodes() {
+            for change in cell.last_energy_changes().unwrap_or_default() {
+                audit.book(change.label, change.energy_delta);
             }
         }
-        self.add_clouds_for_burst_cells(&burst_cell_handles);
-        self.update_cell_graph(new_children, broken_bond_handles, burst_cell_handles);
-        self.remove_nonexistent_clouds();
-        self.update_circle_handles();
     }
 
-    fn execute_bond_requests(
-        parameters: &Parameters,
-        cell: &mut Cell,
-        edge_source: &mut EdgeSource<Cell, Bond<Cell>>,
+    fn close_energy_audit(&mut self) {
+        let total_cell_energy = self.total_cell_energy();
+        if let Some(audit) = &mut self.energy_audit {
+            audit.close(self.tick_count, total_cell_energy);
+        }
+    }
+
+    fn check_for_explosion(&mut self) {
+        if let Some(watchdog) = &mut self.explosion_watchdog {
+            watchdog.check(self.tick_count, self.cell_graph.nodes());
+        }
+    }
+
+    fn record_hall_of_fame(&mut self) {
+        if let Some(hall_of_fame) = &mut self.hall_of_fame {
+            hall_of_fame.record(self.tick_count, self.cell_graph.nodes());
+        }
+    }
+
+    fn log_warnings(&mut self) {
+        let tick = self.tick_count;
+        let cells = self.cell_graph.nodes();
+        let dropped = cells
+            .iter()
+            .map(|cell| cell.dropped_request_count())
+            .sum::<u32>();
+        if dropped > 0 {
+            self.warnings.add(
+                tick,
+                Severity::Warning,
+                "dropped requests",
+                format!("control requests not finite and dropped: {}", dropped),
+            );
+        }
+        let capped = cells
+            .iter()
+            .map(|cell| cell.capped_request_count())
+            .sum::<u32>();
+        if capped > 0 {
+            self.warnings.add(
+                tick,
+                Severity::Info,
+                "capped requests",
+                format!("control requests capped: {}", capped),
+            );
+        }
+        if let Some(budget) = self.control_time_budget {
+            let slow = cells
+                .iter()
+                .filter(|cell| cell.last_control_time() > budget)
+                .count();
+            if slow > 0 {
+                self.warnings.add(
+                    tick,
+                    Severity::Warning,
+                    "slow controls",
+                    format!("controls over budget: {}", slow),
+                );
+            }
+        }
+        if let Some(report) = self
+            .energy_audit
+            .as_ref()
+            .and_then(EnergyAudit::last_report)
+        {
+            if !report.is_balanced() {
+                self.warnings.add(
+                    tick,
+                    Severity::Warning,
+                    "energy audit",
+                    format!("energy unbalanced by {:.4}", report.unbooked_energy()),
+                );
+            }
+        }
+        if let Some(watchdog) = &self.explosion_watchdog {
+            if let Some(report) = watchdog
+                .last_report()
+                .filter(|_| watchdog.exploded_last_tick())
+            {
+                self.warnings.add(
+                    tick,
+                    Severity::Error,
+                    "explosion watchdog",
+                    format!("cells flung apart - top speed {:.1}", report.max_speed),
+                );
+            }
+        }
+    }
+
+    fn apply_scheduled_parameter_changes(&mut self) {
+        let tick_count = self.tick_count;
+        let (due, not_due): (Vec<_>, Vec<_>) =
+            std::mem::take(&mut self.scheduled_parameter_changes)
+                .into_iter()
+                .partition(|change| change.tick <= tick_count);
+        self.scheduled_parameter_changes = not_due;
+        for change in due {
+            self.set_parameter(&change.name, change.value).unwrap();
+        }
+    }
+
+    fn call_tick_end_hooks(&mut self) {
+        // the hooks need to see the whole world, so take them out of it while they run
+        let mut tick_end_hooks = std::mem::take(&mut self.hooks.tick_end_hooks);
+        for hook in &mut tick_end_hooks {
+            hook(self);
+        }
+        tick_end_hooks.append(&mut self.hooks.tick_end_hooks);
+        self.hooks.tick_end_hooks = tick_end_hooks;
+    }
+
+    // An influence may add bonds, such as BondFormation, but not remove them.
+    fn apply_cross_cell_influences(&mut self) {
+        for influence in &self.cross_cell_influences {
+            let bond_count = self.cell_graph.edges().len();
+            influence.apply_to(&mut self.cell_graph, &mut self.circle_handles);
+            for bond in &self.cell_graph.edges()[bond_count..] {
+                self.bounding_boxes
+                    .join(bond.node1_handle(), bond.node2_handle());
+                self.hooks.bond_change(
+                    BondChange::Formed,
+                    self.cell_graph.node(bond.node1_handle()),
+                    self.cell_graph.node(bond.node2_handle()),
+                );
+            }
+        }
+    }
+
+    fn tick_cells(&mut self) -> Vec<BondRequests> {
+        let per_cell_influences = &self.per_cell_influences;
+        let parameters = &self.parameters;
+        let chemical_field = &self.chemical_field;
+        let clouds = self.clouds.objects();
+        let tick_cell = |cell: &mut Cell| {
+            if let Some(field) = chemical_field {
+                let concentration = field.concentration_at(cell.center());
+                cell.environment_mut()
+                    .add_nutrient_concentration(concentration);
+            }
+            for cloud in clouds {
+                let concentration = cloud.concentration_sensed_at(cell.center(), cell.radius());
+                cell.environment_mut()
+                    .add_cloud_concentration(concentration);
+            }
+            for influence in per_cell_influences {
+                influence.apply_to(cell);
+            }
+            cell.tick_with_parameters(parameters)
+        };
+        let cells = self.cell_graph.nodes_mut();
+        match (self.parallelism, &self.thread_pool) {
+            (1, _) => cells.iter_mut().map(tick_cell).collect(),
+            (_, Some(thread_pool)) => {
+                thread_pool.install(|| cells.par_iter_mut().map(tick_cell).collect())
+            }
+            (_, None) => cells.par_iter_mut().map(tick_cell).collect(),
+        }
+    }
+
+    fn check_control_times(&mut self) {
+        let budget = match self.control_time_budget {
+            Some(budget) => budget,
+            None => return,
+        };
+        let mut penalty = BioEnergy::ZERO;
+        for cell in self.cell_graph.nodes_mut() {
+            if cell.last_control_time() > budget {
+                self.slow_control_count += 1;
+                let energy_before = cell.energy();
+                cell.lose_energy_fraction(self.slow_control_penalty);
+                penalty += energy_before - cell.energy();
+            }
+        }
+        self.book_energy("slow control", -BioEnergyDelta::from(penalty));
+    }
+
+    // Each victim loses the total of the bites on it, or all its energy if that is less,
+    // and each eater gets its bite's share of what was taken, so that eaters split a
+    // victim that runs out in proportion to their bites, whatever the order of the cells.
+    fn apply_bites(&mut self) {
+        let mut bites = self
+            .cell_graph
+            .nodes()
+            .iter()
+            .flat_map(|cell| {
+                let eater = cell.node_handle();
+                cell.bites().iter().map(move |bite| (eater, *bite))
+            })
+            .collect::<Vec<_>>();
+        // totaled smallest first, so that the sums don't depend on the order of the eaters
+        bites.sort_by(|(eater1, bite1), (eater2, bite2)| {
+            bite1
+                .victim
+                .index()
+                .cmp(&bite2.victim.index())
+                .then(bite1.energy.value().total_cmp(&bite2.energy.value()))
+                .then(
+                    self.cell(*eater1)
+                        .cell_id()
+                        .cmp(&self.cell(*eater2).cell_id()),
+                )
+        });
+        let mut start = 0;
+        while start < bites.len() {
+            let victim = bites[start].1.victim;
+            let end = start
+                + bites[start..]
+                    .iter()
+                    .take_while(|(_, bite)| bite.victim == victim)
+                    .count();
+            let victim_bites = &bites[start..end];
+            start = end;
+            let (total_energy, total_damage) = victim_bites.iter().fold(
+                (BioEnergy::ZERO, HealthDelta::ZERO),
+                |(energy, damage), (_, bite)| (energy + bite.energy, damage + bite.damage),
+            );
+            let taken = self.cell_mut(victim).take_bite(total_energy, total_damage);
+            self.book_energy("eaten", -BioEnergyDelta::from(taken));
+            if total_energy == BioEnergy::ZERO {
+                continue;
+            }
+            for (eater, bite) in victim_bites {
+                let share =
+                    BioEnergy::new(taken.value() * (bite.energy.value() / total_energy.value()));
+                self.cell_mut(*eater).add_gathered_energy(share);
+                self.book_energy("eating", share.into());
+            }
+        }
+    }
+
+    // Takes out what the cells absorbed, where they were, before spreading what is left.
+    fn tick_chemical_field(&mut self) {
+        let field = match &mut self.chemical_field {
+            Some(field) => field,
+            None => return,
+        };
+        for cell in self.cell_graph.nodes() {
+            field.remove_amount_at(cell.center(), cell.last_nutrient_uptake());
+        }
+        field.diffuse();
+    }
+
+    fn tick_clouds(&mut self) {
+        for cloud in self.clouds.objects_mut() {
+            cloud.tick(&self.parameters.cloud_params);
+        }
+    }
+
+    fn apply_world_changes(&mut self, cell_bond_requests: &[BondRequests]) {
+        let parameters = &self.parameters;
+        let mut donated_energy = vec![];
+        let mut donation_taxes = vec![];
+        let mut new_children = vec![];
+        let mut broken_bond_handles = HashSet::new();
+        let mut burst_cell_handles = vec![];
+        self.cell_graph.for_each_node(|index, cell, edge_source| {
+            Self::execute_bond_requests(
+                parameters,
+                cell,
+                edge_source,
+                &cell_bond_requests[index],
+                &mut donated_energy,
+                &mut new_children,
+                &mut broken_bond_handles,
+            );
+            for bond_request in cell_bond_requests[index].iter() {
+                if bond_request.donation_tax != BioEnergy::ZERO {
+                    donation_taxes.push((cell.center(), bond_request.donation_tax));
+                }
+            }
+            if !cell.is_intact() {
+                burst_cell_handles.push(cell.node_handle());
+            }
+        });
+        self.apply_donated_energy(donated_energy);
+        self.recycle_donation_taxes(&donation_taxes);
+        for burst_cell_handle in &burst_cell_handles {
+            if self.cell(*burst_cell_handle).is_selected() {
+                self.num_selected_cells -= 1;
+            }
+        }
+        self.add_clouds_for_burst_cells(&burst_cell_handles);
+        self.book_burst_and_budded_energy(&burst_cell_handles, &new_children);
+        self.update_cell_graph(new_children, broken_bond_handles, burst_cell_handles);
+        self.remove_nonexistent_clouds();
+        self.update_circle_handles();
+        self.bounding_boxes.update(&self.cell_graph);
+    }
+
+    fn execute_bond_requests(
+        parameters: &Parameters,
+        cell: &mut Cell,
+        edge_source: &mut EdgeSource<Cell, Bond<Cell>>,
         bond_requests: &BondRequests,
-        donated_energy: &mut Vec<(Handle<Cell>, BioEnergy)>,
+        donated_energy: &mut Vec<(u64, Handle<Cell>, BioEnergy)>,
         new_children: &mut Vec<NewChildData>,
         broken_bond_handles: &mut HashSet<EdgeHandle>,
     ) {
@@ -307,6 +1288,7 @@ impl World {
                     if cell.has_edge(index) {
                         let bond = edge_source.edge(cell.edge_handle(index));
                         donated_energy.push((
+                            cell.cell_id(),
                             bond.other_node_handle(cell.node_handle()),
                             bond_request.donation_energy,
                         ));
@@ -323,10 +1305,54 @@ impl World {
                         });
                     }
                 }
+                // a snapped bond still carries this tick's donation
+                if cell.is_bond_snapped(index) && cell.has_edge(index) {
+                    broken_bond_handles.insert(cell.edge_handle(index));
+                }
             } else if cell.has_edge(index) {
                 broken_bond_handles.insert(cell.edge_handle(index));
             }
         }
+        cell.clear_snapped_bonds();
+    }
+
+    // A burst cell takes its energy out of the world with it.
+    fn book_burst_and_budded_energy(
+        &mut self,
+        burst_cell_handles: &[Handle<Cell>],
+        new_children: &[NewChildData],
+    ) {
+        if self.energy_audit.is_none() {
+            return;
+        }
+        let burst_energy = burst_cell_handles
+            .iter()
+            .fold(BioEnergy::ZERO, |total, handle| {
+                total + self.cell(*handle).energy()
+            });
+        let budded_energy = new_children
+            .iter()
+            .fold(BioEnergy::ZERO, |total, new_child_data| {
+                total + new_child_data.child.energy()
+            });
+        self.book_energy("burst", -BioEnergyDelta::from(burst_energy));
+        self.book_energy(EnergyAudit::BUDDING, budded_energy.into());
+    }
+
+    fn recycle_donation_taxes(&mut self, donation_taxes: &[(Position, BioEnergy)]) {
+        let total_tax = donation_taxes
+            .iter()
+            .fold(BioEnergy::ZERO, |total, (_, tax)| total + *tax);
+        self.book_energy(EnergyAudit::TAX, total_tax.into());
+        let clouds = self.donation_tax_recycler.recycle(
+            donation_taxes,
+            self.chemical_field.as_mut(),
+            self.min_corner,
+            self.max_corner,
+        );
+        for cloud in clouds {
+            self.clouds.add(cloud);
+        }
     }
 
     fn add_clouds_for_burst_cells(&mut self, burst_cell_handles: &[Handle<Cell>]) {
@@ -348,27 +1374,101 @@ impl World {
     ) {
         self.add_children(new_children);
         self.remove_bonds(&broken_bond_handles);
-        self.cell_graph.remove_nodes(&burst_cell_handles);
+        self.remove_burst_cells(&burst_cell_handles);
+    }
+
+    fn remove_burst_cells(&mut self, burst_cell_handles: &[Handle<Cell>]) {
+        // a bond between two burst cells breaks only once
+        let broken_bond_handles: BTreeSet<_> = burst_cell_handles
+            .iter()
+            .flat_map(|handle| {
+                self.cell_graph
+                    .node(*handle)
+                    .edge_handles()
+                    .iter()
+                    .flatten()
+            })
+            .copied()
+            .collect();
+        for edge_handle in broken_bond_handles {
+            let bond = self.cell_graph.edge(edge_handle);
+            self.hooks.bond_change(
+                BondChange::Broken,
+                self.cell_graph.node(bond.node1_handle()),
+                self.cell_graph.node(bond.node2_handle()),
+            );
+        }
+        for handle in burst_cell_handles {
+            let cell = self.cell_graph.node(*handle);
+            self.hooks.death(cell);
+            if let Some(phylogeny) = &mut self.phylogeny {
+                phylogeny.record_death(cell.cell_id(), self.tick_count);
+            }
+        }
+        if !burst_cell_handles.is_empty() {
+            self.bounding_boxes.invalidate_colonies();
+        }
+        self.cell_graph.remove_nodes(burst_cell_handles);
     }
 
     fn add_children(&mut self, new_children: Vec<NewChildData>) {
         for new_child_data in new_children {
-            let child_handle = self.add_cell(new_child_data.child);
+            let parent_id = self.cell(new_child_data.parent).cell_id();
+            let child_handle = self.add_cell_with_parent(new_child_data.child, Some(parent_id));
+            self.hooks.birth(
+                self.cell_graph.node(new_child_data.parent),
+                self.cell_graph.node(child_handle),
+            );
             let bond = Bond::new(self.cell(new_child_data.parent), self.cell(child_handle));
             self.add_bond(bond, new_child_data.bond_index, 0);
         }
     }
 
-    fn apply_donated_energy(&mut self, donated_energy: Vec<(Handle<Cell>, BioEnergy)>) {
-        for (cell_handle, donation) in donated_energy {
-            self.cell_mut(cell_handle)
-                .add_received_donated_energy(donation);
+    // Every donor has already paid for its donations in its own tick, scaled back with
+    // its other requests if it couldn't afford them all, so the donations are settled
+    // together here: each recipient's donations are totaled first, smallest first so that
+    // the floating-point sum doesn't depend on the order of the cells, and then each
+    // recipient receives its total at once.
+    fn apply_donated_energy(&mut self, mut donated_energy: Vec<(u64, Handle<Cell>, BioEnergy)>) {
+        donated_energy.sort_by(|(donor_id1, _, donation1), (donor_id2, _, donation2)| {
+            donation1
+                .value()
+                .total_cmp(&donation2.value())
+                .then(donor_id1.cmp(donor_id2))
+        });
+        let mut received_energy = vec![BioEnergy::ZERO; self.cells().len()];
+        let mut total_donation = BioEnergy::ZERO;
+        for &(donor_id, cell_handle, donation) in &donated_energy {
+            received_energy[cell_handle.index()] += donation;
+            self.hooks.event_bus.emit(WorldEvent::EnergyDonated {
+                donor_id,
+                recipient_id: self.cell(cell_handle).cell_id(),
+                energy: donation,
+            });
+            total_donation += donation;
+        }
+        for (cell, energy) in self.cell_graph.nodes_mut().iter_mut().zip(received_energy) {
+            if energy > BioEnergy::ZERO {
+                cell.add_received_donated_energy(energy);
+            }
         }
+        self.book_energy(EnergyAudit::DONATION_RECEIVED, total_donation.into());
     }
 
     fn remove_bonds(&mut self, bond_handles: &HashSet<EdgeHandle>) {
         let mut sorted_bond_handles: Vec<EdgeHandle> = bond_handles.iter().cloned().collect();
         sorted_bond_handles.sort_unstable();
+        for bond_handle in &sorted_bond_handles {
+            let bond = self.cell_graph.edge(*bond_handle);
+            self.hooks.bond_change(
+                BondChange::Broken,
+                self.cell_graph.node(bond.node1_handle()),
+                self.cell_graph.node(bond.node2_handle()),
+            );
+        }
+        if !sorted_bond_handles.is_empty() {
+            self.bounding_boxes.invalidate_colonies();
+        }
         self.cell_graph.remove_edges(&sorted_bond_handles);
     }
 
@@ -397,7 +1497,7 @@ impl World {
     }
 
     fn print_end_tick_info(&self) -> Result<()> {
-        if self.num_selected_cells == 0 {
+        if !cfg!(feature = "console") || self.num_selected_cells == 0 {
             return Ok(());
         }
 
@@ -449,6 +1549,68 @@ impl World {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BondChange {
+    Formed,
+    Broken,
+}
+
+type BirthHooks = Vec<Box<dyn FnMut(&Cell, &Cell) + Send>>;
+type DeathHooks = Vec<Box<dyn FnMut(&Cell) + Send>>;
+type BondChangeHooks = Vec<Box<dyn FnMut(BondChange, &Cell, &Cell) + Send>>;
+type ParameterChangeHooks = Vec<Box<dyn FnMut(&ParameterChange) + Send>>;
+type TickEndHooks = Vec<Box<dyn FnMut(&World) + Send>>;
+
+#[derive(Default)]
+struct WorldHooks {
+    birth_hooks: BirthHooks,
+    death_hooks: DeathHooks,
+    bond_change_hooks: BondChangeHooks,
+    parameter_change_hooks: ParameterChangeHooks,
+    tick_end_hooks: TickEndHooks,
+    event_bus: WorldEventBus,
+}
+
+impl WorldHooks {
+    fn birth(&mut self, parent: &Cell, child: &Cell) {
+        for hook in &mut self.birth_hooks {
+            hook(parent, child);
+        }
+        self.event_bus.emit(WorldEvent::CellBorn {
+            parent_id: parent.cell_id(),
+            child_id: child.cell_id(),
+        });
+    }
+
+    fn death(&mut self, cell: &Cell) {
+        for hook in &mut self.death_hooks {
+            hook(cell);
+        }
+        self.event_bus.emit(WorldEvent::CellDied {
+            cell_id: cell.cell_id(),
+        });
+    }
+
+    fn bond_change(&mut self, change: BondChange, cell1: &Cell, cell2: &Cell) {
+        for hook in &mut self.bond_change_hooks {
+            hook(change, cell1, cell2);
+        }
+        let (cell1_id, cell2_id) = (cell1.cell_id(), cell2.cell_id());
+        self.event_bus.emit(match change {
+            BondChange::Formed => WorldEvent::BondFormed { cell1_id, cell2_id },
+            BondChange::Broken => WorldEvent::BondBroken { cell1_id, cell2_id },
+        });
+    }
+}
+
+// A change to one of the world's Parameters, made after the given number of ticks.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParameterChange {
+    pub tick: u64,
+    pub name: String,
+    pub value: f64,
+}
+
 struct NewChildData {
     parent: Handle<Cell>,
     bond_index: usize,
@@ -460,11 +1622,15 @@ mod tests {
     use super::*;
     use crate::biology::cloud::CloudParameters;
     use crate::biology::control::*;
+    use crate::biology::control_requests::ControlRequest;
+    use crate::biology::genome::*;
     use crate::biology::layers::*;
-    use crate::environment::local_environment::*;
     use crate::physics::newtonian::NewtonianBody;
     use crate::physics::overlap::Overlap;
+    use crate::stats::{EventCounts, StatsCollector};
+    use crate::test_support;
     use std::f64::consts::PI;
+    use std::sync::{Arc, Mutex};
 
     #[test]
     fn tick_moves_ball() {
@@ -482,6 +1648,51 @@ mod tests {
         assert!(ball.position().y() > 0.0);
     }
 
+    #[test]
+    fn nearest_cell_prefers_covering_cell_within_tolerance() {
+        let world = World::new(Position::ORIGIN, Position::ORIGIN)
+            .with_cell(Cell::ball(
+                Length::new(5.0),
+                Mass::new(1.0),
+                Position::ORIGIN,
+                Velocity::ZERO,
+            ))
+            .with_cell(Cell::ball(
+                Length::new(0.5),
+                Mass::new(1.0),
+                Position::new(8.0, 0.0),
+                Velocity::ZERO,
+            ));
+
+        let nearest = |x, tolerance| {
+            world
+                .nearest_cell(Position::new(x, 0.0), Length::new(tolerance))
+                .map(|cell| cell.radius().value())
+        };
+        assert_eq!(nearest(6.0, 2.0), Some(5.0));
+        assert_eq!(nearest(7.0, 2.0), Some(0.5));
+        assert_eq!(nearest(7.6, 2.0), Some(0.5));
+        assert_eq!(nearest(20.0, 2.0), None);
+    }
+
+    #[test]
+    fn nearest_cell_finds_cell_where_it_moved() {
+        let mut world = World::new(Position::ORIGIN, Position::ORIGIN).with_cell(Cell::ball(
+            Length::new(1.0),
+            Mass::new(1.0),
+            Position::ORIGIN,
+            Velocity::new(10.0, 0.0),
+        ));
+
+        world.tick();
+
+        let moved_to = world.cells()[0].center();
+        assert!(world.nearest_cell(moved_to, Length::new(0.1)).is_some());
+        assert!(world
+            .nearest_cell(Position::ORIGIN, Length::new(0.1))
+            .is_none());
+    }
+
     #[test]
     fn tick_with_force_accelerates_ball() {
         let mut world = World::new(Position::ORIGIN, Position::ORIGIN)
@@ -541,6 +1752,52 @@ mod tests {
         assert_eq!(ball.net_force().net_force(), Force::new(0.0, 0.0));
     }
 
+    #[test]
+    fn last_net_force_outlasts_tick() {
+        let mut world = World::new(Position::ORIGIN, Position::ORIGIN)
+            .with_per_cell_influence(Box::new(SimpleForceInfluence::new(Box::new(
+                ConstantForce::new(Force::new(1.0, 1.0)),
+            ))))
+            .with_cell(Cell::ball(
+                Length::new(1.0),
+                Mass::new(1.0),
+                Position::ORIGIN,
+                Velocity::ZERO,
+            ));
+
+        world.tick();
+
+        let ball = &world.cells()[0];
+        assert_eq!(ball.last_net_force().net_force(), Force::new(1.0, 1.0));
+    }
+
+    #[test]
+    fn explosion_watchdog_reports_forces_on_fast_cells() {
+        let mut world = World::new(Position::ORIGIN, Position::ORIGIN)
+            .with_per_cell_influence(Box::new(SimpleForceInfluence::new(Box::new(
+                ConstantForce::new(Force::new(10.0, 0.0)),
+            ))))
+            .with_cell(Cell::ball(
+                Length::new(1.0),
+                Mass::new(1.0),
+                Position::ORIGIN,
+                Velocity::ZERO,
+            ))
+            .with_explosion_watchdog(ExplosionWatchdogParameters {
+                max_speed: 5.0,
+                ..ExplosionWatchdogParameters::DEFAULT
+            });
+
+        world.tick();
+
+        let watchdog = world.explosion_watchdog().unwrap();
+        assert!(watchdog.exploded_last_tick());
+        let report = watchdog.last_report().unwrap();
+        assert_eq!(report.tick, 1);
+        let additions = report.cells[0].net_force.non_dominant_force_additions();
+        assert_eq!(additions.as_ref().unwrap()[0].label, "constant");
+    }
+
     #[test]
     fn cannot_bounce_off_drag_force() {
         let mut world = World::new(Position::ORIGIN, Position::ORIGIN)
@@ -678,6 +1935,38 @@ mod tests {
         assert_eq!(child.energy(), BioEnergy::new(1.0)); // 0 + 1
     }
 
+    #[test]
+    fn phylogeny_records_budding() {
+        let mut world = World::new(Position::ORIGIN, Position::ORIGIN)
+            .with_phylogeny()
+            .with_cells(vec![
+                Cell::new(
+                    Position::ORIGIN,
+                    Velocity::ZERO,
+                    vec![CellLayer::new(
+                        Area::new(1.0),
+                        Density::new(1.0),
+                        Tissue::Photosynthetic,
+                        Box::new(BondingCellLayerSpecialty::new()),
+                    )],
+                )
+                .with_control(Box::new(ContinuousRequestsControl::new(vec![
+                    BondingCellLayerSpecialty::retain_bond_request(0, 1, true),
+                    BondingCellLayerSpecialty::donation_energy_request(0, 1, BioEnergy::new(1.0)),
+                ])))
+                .with_initial_energy(BioEnergy::new(10.0)),
+                simple_layered_cell(vec![simple_cell_layer(Area::new(1.0), Density::new(1.0))]),
+            ]);
+
+        world.tick();
+
+        let child = &world.cells()[2];
+        assert_eq!(child.cell_id(), 3);
+        assert_eq!(child.lineage_id(), 1);
+        assert_eq!(world.cells()[1].lineage_id(), 2);
+        assert_eq!(world.phylogeny().unwrap().to_newick(), "((c3:0)c1,c2);");
+    }
+
     #[test]
     fn cells_can_pass_energy_through_bond() {
         let mut world = World::new(Position::ORIGIN, Position::ORIGIN)
@@ -726,25 +2015,448 @@ mod tests {
     }
 
     #[test]
-    fn world_breaks_bond_when_requested() {
-        let mut world = World::new(Position::ORIGIN, Position::ORIGIN)
+    fn received_donations_do_not_depend_on_cell_order() {
+        let recipient = || {
+            simple_layered_cell(vec![simple_cell_layer(Area::new(1.0), Density::new(1.0))])
+                .with_initial_energy(BioEnergy::new(0.3))
+        };
+        // added in the other order, 0.3 + 0.2 + 0.1 would differ from 0.3 + 0.1 + 0.2 in
+        // the last bit
+        let mut world1 = World::new(Position::ORIGIN, Position::ORIGIN)
             .with_cells(vec![
-                simple_layered_cell(vec![CellLayer::new(
-                    Area::new(1.0),
-                    Density::new(1.0),
-                    Tissue::Photosynthetic,
-                    Box::new(BondingCellLayerSpecialty::new()),
-                )])
-                .with_control(Box::new(ContinuousRequestsControl::new(vec![
-                    BondingCellLayerSpecialty::retain_bond_request(0, 1, false),
-                ]))),
-                simple_layered_cell(vec![simple_cell_layer(Area::new(1.0), Density::new(1.0))]),
+                recipient(),
+                donor_cell(0, BioEnergy::new(0.1)),
+                donor_cell(1, BioEnergy::new(0.2)),
             ])
-            .with_bonds(vec![(0, 1)]);
+            .with_bonds(vec![(0, 1), (2, 0)]);
+        let mut world2 = World::new(Position::ORIGIN, Position::ORIGIN)
+            .with_cells(vec![
+                donor_cell(1, BioEnergy::new(0.2)),
+                donor_cell(0, BioEnergy::new(0.1)),
+                recipient(),
+            ])
+            .with_bonds(vec![(2, 1), (0, 2)]);
+
+        world1.tick();
+        world2.tick();
+
+        assert_eq!(world1.cells()[0].energy(), world2.cells()[2].energy());
+    }
+
+    #[test]
+    fn recipient_receives_total_of_its_donations() {
+        let mut world = World::new(Position::ORIGIN, Position::ORIGIN)
+            .with_cells(vec![
+                simple_layered_cell(vec![simple_cell_layer(Area::new(1.0), Density::new(1.0))])
+                    .with_initial_energy(BioEnergy::new(0.3)),
+                donor_cell(0, BioEnergy::new(0.1)),
+                donor_cell(1, BioEnergy::new(0.7)),
+            ])
+            .with_bonds(vec![(0, 1), (2, 0)]);
+
+        world.tick();
+
+        // 0.3 + 0.1 + 0.7 would be 1.1
+        assert_eq!(world.cells()[0].energy(), BioEnergy::new(0.3 + (0.1 + 0.7)));
+    }
+
+    #[test]
+    fn donation_tax_goes_to_pool() {
+        const LAYER_PARAMS: BondingLayerParameters = BondingLayerParameters {
+            donation_energy_tax_rate: Fraction::unchecked(0.25),
+            ..BondingLayerParameters::DEFAULT
+        };
+        let donor = Cell::new(
+            Position::ORIGIN,
+            Velocity::ZERO,
+            vec![CellLayer::new(
+                Area::new(1.0),
+                Density::new(1.0),
+                Tissue::Photosynthetic,
+                Box::new(BondingCellLayerSpecialty::new().with_parameters(&LAYER_PARAMS)),
+            )],
+        )
+        .with_control(Box::new(ContinuousRequestsControl::new(vec![
+            BondingCellLayerSpecialty::retain_bond_request(0, 0, true),
+            BondingCellLayerSpecialty::donation_energy_request(0, 0, BioEnergy::new(2.0)),
+        ])))
+        .with_initial_energy(BioEnergy::new(10.0));
+        let mut world = World::new(Position::ORIGIN, Position::new(10.0, 10.0))
+            .with_donation_tax_destination(DonationTaxDestination::CloudPool {
+                energy_per_cloud: BioEnergy::new(100.0),
+                cloud_radius: Length::new(1.0),
+                seed: 0,
+            })
+            .with_cells(vec![
+                donor,
+                simple_layered_cell(vec![simple_cell_layer(Area::new(1.0), Density::new(1.0))]),
+            ])
+            .with_bonds(vec![(0, 1)]);
+
+        world.tick();
+
+        assert_eq!(world.cells()[0].energy(), BioEnergy::new(7.5));
+        assert_eq!(world.donation_tax_recycler().pool(), BioEnergy::new(0.5));
+        assert_eq!(
+            world.donation_tax_recycler().total_destroyed(),
+            BioEnergy::ZERO
+        );
+    }
+
+    #[test]
+    fn energy_audit_balances_donations_and_budding() {
+        const LAYER_PARAMS: BondingLayerParameters = BondingLayerParameters {
+            donation_energy_tax_rate: Fraction::unchecked(0.25),
+            ..BondingLayerParameters::DEFAULT
+        };
+        let taxed_donor = Cell::new(
+            Position::ORIGIN,
+            Velocity::ZERO,
+            vec![CellLayer::new(
+                Area::new(1.0),
+                Density::new(1.0),
+                Tissue::Photosynthetic,
+                Box::new(BondingCellLayerSpecialty::new().with_parameters(&LAYER_PARAMS)),
+            )],
+        )
+        .with_control(Box::new(ContinuousRequestsControl::new(vec![
+            BondingCellLayerSpecialty::retain_bond_request(0, 1, true),
+            BondingCellLayerSpecialty::donation_energy_request(0, 1, BioEnergy::new(1.0)),
+        ])))
+        .with_initial_energy(BioEnergy::new(10.0));
+        let mut world = World::new(Position::ORIGIN, Position::new(10.0, 10.0))
+            .with_energy_audit_on_imbalance(OnImbalance::Panic)
+            .with_cells(vec![
+                taxed_donor,
+                simple_layered_cell(vec![simple_cell_layer(Area::new(1.0), Density::new(1.0))]),
+                donor_cell(1, BioEnergy::new(2.0)),
+            ])
+            .with_bonds(vec![(0, 1)]);
+
+        world.tick();
+
+        let report = world.energy_audit().unwrap().last_report().unwrap();
+        assert_eq!(
+            report.booked(EnergyAudit::DONATED),
+            BioEnergyDelta::new(-3.25)
+        );
+        assert_eq!(
+            report.booked(EnergyAudit::DONATION_RECEIVED),
+            BioEnergyDelta::new(1.0)
+        );
+        assert_eq!(
+            report.booked(EnergyAudit::BUDDING),
+            BioEnergyDelta::new(2.0)
+        );
+        assert_eq!(report.booked(EnergyAudit::TAX), BioEnergyDelta::new(0.25));
+        assert_eq!(world.cells().len(), 4);
+        assert_eq!(world.energy_audit().unwrap().imbalanced_tick_count(), 0);
+    }
+
+    #[test]
+    fn energy_audit_catches_donation_over_dropped_bond() {
+        let donor = simple_layered_cell(vec![CellLayer::new(
+            Area::new(1.0),
+            Density::new(1.0),
+            Tissue::Photosynthetic,
+            Box::new(BondingCellLayerSpecialty::new()),
+        )])
+        .with_control(Box::new(ContinuousRequestsControl::new(vec![
+            BondingCellLayerSpecialty::donation_energy_request(0, 0, BioEnergy::new(2.0)),
+        ])))
+        .with_initial_energy(BioEnergy::new(10.0));
+        let mut world = World::new(Position::ORIGIN, Position::ORIGIN)
+            .with_energy_audit()
+            .with_cells(vec![
+                donor,
+                simple_layered_cell(vec![simple_cell_layer(Area::new(1.0), Density::new(1.0))]),
+            ])
+            .with_bonds(vec![(0, 1)]);
+
+        world.tick();
+
+        let report = world.energy_audit().unwrap().last_report().unwrap();
+        assert_eq!(report.unbooked_energy(), 0.0);
+        assert_eq!(report.lost_donation_energy(), 2.0);
+        assert_eq!(world.energy_audit().unwrap().imbalanced_tick_count(), 1);
+    }
+
+    #[test]
+    fn mass_energy_coupled_resizing_balances_in_energy_audit() {
+        const LAYER_PARAMS: LayerParameters = LayerParameters {
+            mass_per_energy: 2.0,
+            ..LayerParameters::DEFAULT
+        };
+        let resizing_cell = |x, delta_area| {
+            Cell::new(
+                Position::new(x, 0.0),
+                Velocity::ZERO,
+                vec![simple_cell_layer(Area::new(4.0), Density::new(3.0))
+                    .with_parameters(&LAYER_PARAMS)],
+            )
+            .with_control(Box::new(ContinuousResizeControl::new(
+                0,
+                AreaDelta::new(delta_area),
+            )))
+            .with_initial_energy(BioEnergy::new(10.0))
+        };
+        let mut world = World::new(Position::new(-10.0, -10.0), Position::new(10.0, 10.0))
+            .with_energy_audit_on_imbalance(OnImbalance::Panic)
+            .with_cells(vec![resizing_cell(-5.0, 1.0), resizing_cell(5.0, -2.0)]);
+
+        world.tick();
+
+        assert_eq!(world.cells()[0].energy(), BioEnergy::new(8.5));
+        assert_eq!(world.cells()[1].energy(), BioEnergy::new(13.0));
+        let report = world.energy_audit().unwrap().last_report().unwrap();
+        assert_eq!(
+            report.booked(EnergyAudit::BIOMASS_CONVERSION),
+            BioEnergyDelta::new(1.5)
+        );
+        assert_eq!(
+            report.booked(EnergyAudit::BIOMASS),
+            BioEnergyDelta::new(-1.5)
+        );
+    }
+
+    fn donor_cell(bond_index: usize, donation: BioEnergy) -> Cell {
+        Cell::new(
+            Position::ORIGIN,
+            Velocity::ZERO,
+            vec![CellLayer::new(
+                Area::new(1.0),
+                Density::new(1.0),
+                Tissue::Photosynthetic,
+                Box::new(BondingCellLayerSpecialty::new()),
+            )],
+        )
+        .with_control(Box::new(ContinuousRequestsControl::new(vec![
+            BondingCellLayerSpecialty::retain_bond_request(0, bond_index, true),
+            BondingCellLayerSpecialty::donation_energy_request(0, bond_index, donation),
+        ])))
+        .with_initial_energy(BioEnergy::new(10.0))
+    }
+
+    #[test]
+    fn add_cell_at_copies_selected_cell() {
+        let mut world = World::new(Position::new(-10.0, -10.0), Position::new(10.0, 10.0))
+            .with_cell(
+                simple_layered_cell(vec![
+                    simple_cell_layer(Area::new(2.0), Density::new(1.0)),
+                    simple_cell_layer(Area::new(3.0), Density::new(1.0)),
+                ])
+                .with_initial_energy(BioEnergy::new(5.0)),
+            );
+        world.toggle_select_cell_at(Position::ORIGIN, Length::new(0.0));
+
+        let handle = world.add_cell_at(Position::new(5.0, 0.0)).unwrap();
+
+        assert_eq!(world.cells().len(), 2);
+        let copy = world.cell(handle);
+        assert_eq!(copy.center(), Position::new(5.0, 0.0));
+        assert_eq!(copy.energy(), BioEnergy::new(5.0));
+        assert_eq!(copy.radius(), world.cells()[0].radius());
+        assert!(!copy.is_selected());
+    }
+
+    #[test]
+    fn add_cell_at_copies_placeable_cell_when_none_is_selected() {
+        let mut world = World::new(Position::new(-10.0, -10.0), Position::new(10.0, 10.0));
+        assert_eq!(world.add_cell_at(Position::ORIGIN), None);
+        let mut world = world.with_placeable_cell(
+            simple_layered_cell(vec![simple_cell_layer(Area::new(2.0), Density::new(1.0))])
+                .with_initial_energy(BioEnergy::new(3.0)),
+        );
+
+        world.add_cell_at(Position::new(1.0, 2.0));
+        world.add_cell_at(Position::new(-1.0, 2.0));
+
+        assert_eq!(world.cells().len(), 2);
+        assert_eq!(world.cells()[1].center(), Position::new(-1.0, 2.0));
+        assert_eq!(world.cells()[1].energy(), BioEnergy::new(3.0));
+    }
+
+    #[test]
+    fn remove_cell_at_removes_cell_and_its_bonds() {
+        let mut world = World::new(Position::new(-10.0, -10.0), Position::new(10.0, 10.0))
+            .with_cells(vec![
+                Cell::ball(
+                    Length::new(1.0),
+                    Mass::new(1.0),
+                    Position::new(-1.0, 0.0),
+                    Velocity::ZERO,
+                ),
+                Cell::ball(
+                    Length::new(1.0),
+                    Mass::new(1.0),
+                    Position::new(1.0, 0.0),
+                    Velocity::ZERO,
+                ),
+            ])
+            .with_bonds(vec![(0, 1)]);
+        world.toggle_select_cell_at(Position::new(-1.0, 0.0), Length::new(0.0));
+
+        assert!(!world.remove_cell_at(Position::new(5.0, 5.0), Length::new(0.5)));
+        assert!(world.remove_cell_at(Position::new(-2.2, 0.0), Length::new(0.5)));
+
+        assert_eq!(world.cells().len(), 1);
+        assert_eq!(world.cells()[0].center(), Position::new(1.0, 0.0));
+        assert_eq!(world.bonds().len(), 0);
+        world.tick();
+    }
+
+    #[test]
+    fn colonies_follow_bonds_and_moves() {
+        let ball = |x| {
+            Cell::ball(
+                Length::new(1.0),
+                Mass::new(1.0),
+                Position::new(x, 0.0),
+                Velocity::new(1.0, 0.0),
+            )
+        };
+        let mut world = World::new(Position::new(-10.0, -10.0), Position::new(10.0, 10.0))
+            .with_cells(vec![ball(-4.0), ball(-2.0), ball(0.0), ball(4.0)])
+            .with_bonds(vec![(0, 1), (1, 2)]);
+        assert_eq!(world.colonies().len(), 2);
+
+        world.tick();
+        assert_eq!(
+            world.colony_of(world.cells()[0].node_handle()).bounds(),
+            Rectangle::new(Position::new(-4.0, -1.0), Position::new(2.0, 1.0))
+        );
+        assert_eq!(
+            world.cells_bounds(),
+            Some(Rectangle::new(
+                Position::new(-4.0, -1.0),
+                Position::new(6.0, 1.0)
+            ))
+        );
+
+        assert!(world.remove_cell_at(Position::new(-1.0, 0.0), Length::new(0.0)));
+        assert_eq!(world.colonies().len(), 3);
+        let in_region = world
+            .cells_overlapping(Rectangle::new(
+                Position::new(-1.0, -1.0),
+                Position::new(10.0, 1.0),
+            ))
+            .iter()
+            .map(|cell| cell.center().x())
+            .collect::<Vec<_>>();
+        assert_eq!(in_region, vec![5.0, 1.0]);
+    }
+
+    #[test]
+    fn world_breaks_bond_when_requested() {
+        let mut world = World::new(Position::ORIGIN, Position::ORIGIN)
+            .with_cells(vec![
+                simple_layered_cell(vec![CellLayer::new(
+                    Area::new(1.0),
+                    Density::new(1.0),
+                    Tissue::Photosynthetic,
+                    Box::new(BondingCellLayerSpecialty::new()),
+                )])
+                .with_control(Box::new(ContinuousRequestsControl::new(vec![
+                    BondingCellLayerSpecialty::retain_bond_request(0, 1, false),
+                ]))),
+                simple_layered_cell(vec![simple_cell_layer(Area::new(1.0), Density::new(1.0))]),
+            ])
+            .with_bonds(vec![(0, 1)]);
+
+        world.tick();
+
+        assert_eq!(world.bonds().len(), 0);
+    }
+
+    #[test]
+    fn world_bonds_overlapping_cells_that_ask_to_bond() {
+        let mut world = World::new(Position::new(-10.0, -10.0), Position::new(10.0, 10.0))
+            // overlaps without pushing the cells apart
+            .with_pair_collisions(Fraction::ZERO)
+            .with_bond_formation(Length::new(10.0))
+            .with_cells(vec![
+                Cell::ball(
+                    Length::new(1.0),
+                    Mass::new(1.0),
+                    Position::new(-0.9, 0.0),
+                    Velocity::ZERO,
+                ),
+                Cell::ball(
+                    Length::new(1.0),
+                    Mass::new(1.0),
+                    Position::new(0.9, 0.0),
+                    Velocity::ZERO,
+                ),
+            ]);
+        let bond_changes = Arc::new(Mutex::new(vec![]));
+        let bond_changes_clone = Arc::clone(&bond_changes);
+        world.on_bond_change(move |change, _, _| bond_changes_clone.lock().unwrap().push(change));
+
+        // the cells first ask to bond during their first tick
+        world.tick();
+        assert_eq!(world.bonds().len(), 0);
+        world.tick();
+
+        assert_eq!(world.bonds().len(), 1);
+        assert_eq!(world.colonies().len(), 1);
+        assert_eq!(*bond_changes.lock().unwrap(), vec![BondChange::Formed]);
+        assert!(world.cells()[0].has_edge(0));
+        assert!(world.cells()[1].has_edge(0));
+    }
+
+    #[test]
+    fn world_does_not_bond_cell_that_does_not_ask_to_bond() {
+        let mut world = World::new(Position::new(-10.0, -10.0), Position::new(10.0, 10.0))
+            // overlaps without pushing the cells apart
+            .with_pair_collisions(Fraction::ZERO)
+            .with_bond_formation(Length::new(10.0))
+            .with_cells(vec![
+                Cell::ball(
+                    Length::new(1.0),
+                    Mass::new(1.0),
+                    Position::new(-0.9, 0.0),
+                    Velocity::ZERO,
+                ),
+                Cell::ball(
+                    Length::new(1.0),
+                    Mass::new(1.0),
+                    Position::new(0.9, 0.0),
+                    Velocity::ZERO,
+                )
+                .with_control(Box::new(NullControl::new())),
+            ]);
+
+        world.tick();
+        world.tick();
+
+        assert_eq!(world.bonds().len(), 0);
+    }
+
+    #[test]
+    fn world_breaks_overstrained_bond_even_if_retained() {
+        let mut world = World::new(Position::new(-10.0, -10.0), Position::new(10.0, 10.0))
+            .with_standard_influences()
+            .with_bond_formation(Length::new(1.0))
+            .with_cells(vec![
+                Cell::ball(
+                    Length::new(1.0),
+                    Mass::new(1.0),
+                    Position::new(-2.5, 0.0),
+                    Velocity::ZERO,
+                ),
+                Cell::ball(
+                    Length::new(1.0),
+                    Mass::new(1.0),
+                    Position::new(2.5, 0.0),
+                    Velocity::ZERO,
+                ),
+            ])
+            .with_bonds(vec![(0, 1)]);
 
         world.tick();
 
         assert_eq!(world.bonds().len(), 0);
+        assert_eq!(world.colonies().len(), 2);
     }
 
     #[test]
@@ -830,6 +2542,812 @@ mod tests {
         assert_eq!(world.clouds().len(), 0);
     }
 
+    #[test]
+    fn cell_controls_sense_clouds_around_them() {
+        let mut world = World::new(Position::new(-20.0, -20.0), Position::new(20.0, 20.0))
+            .with_clouds(vec![Cloud::new(
+                Position::new(-10.0, 0.0),
+                Length::new(5.0),
+            )])
+            .with_cells(vec![
+                cloud_sensing_cell(Position::new(-10.0, 0.0)),
+                cloud_sensing_cell(Position::new(10.0, 0.0)),
+            ]);
+
+        world.tick();
+
+        assert_eq!(world.cells()[0].layers()[0].area(), Area::new(2.0));
+        assert_eq!(world.cells()[1].layers()[0].area(), Area::new(1.0));
+    }
+
+    #[test]
+    fn identical_runs_have_identical_state_hashes() {
+        let create_world = |speed: Value1D| {
+            World::new(Position::new(-20.0, -20.0), Position::new(20.0, 20.0)).with_cells(vec![
+                Cell::new(
+                    Position::ORIGIN,
+                    Velocity::new(speed, 0.0),
+                    vec![simple_cell_layer(Area::new(1.0), Density::new(1.0))],
+                ),
+            ])
+        };
+        let mut world1 = create_world(1.0);
+        let mut world2 = create_world(1.0);
+        // big enough to survive f32-physics
+        let mut diverged_world = create_world(1.0 + 1e-3);
+
+        for _ in 0..3 {
+            world1.tick();
+            world2.tick();
+            diverged_world.tick();
+        }
+
+        assert_eq!(world1.state_hash(), world2.state_hash());
+        assert_ne!(world1.state_hash(), diverged_world.state_hash());
+    }
+
+    #[test]
+    fn cell_can_steer_toward_the_light() {
+        let mut builder = NeuralNetControlBuilder::new(TransferFn::IDENTITY);
+        let (gradient_x_index, gradient_y_index) = builder.add_light_gradient_inputs();
+        builder.add_output_node("thrust x", &[(gradient_x_index, 10.0)], 0.0, |value| {
+            ThrusterCellLayerSpecialty::force_x_request(0, value)
+        });
+        builder.add_output_node("thrust y", &[(gradient_y_index, 10.0)], 0.0, |value| {
+            ThrusterCellLayerSpecialty::force_y_request(0, value)
+        });
+        let control = builder.build(SeededMutationRandomness::new(
+            0,
+            &MutationParameters::NO_MUTATION,
+        ));
+        let mut world = World::new(Position::new(-10.0, -10.0), Position::new(10.0, 10.0))
+            .with_sunlight(0.0, 20.0)
+            .with_cell(
+                Cell::new(
+                    Position::ORIGIN,
+                    Velocity::ZERO,
+                    vec![CellLayer::new(
+                        Area::new(PI),
+                        Density::new(1.0),
+                        Tissue::CellWall,
+                        Box::new(ThrusterCellLayerSpecialty::new()),
+                    )],
+                )
+                .with_control(Box::new(control)),
+            );
+
+        for _ in 0..5 {
+            world.tick();
+        }
+
+        let cell = &world.cells()[0];
+        assert_eq!(cell.position().x(), 0.0);
+        assert!(cell.position().y() > 0.0);
+    }
+
+    // A hill climber over the weights from a cell's touch inputs to its thruster, scored
+    // by how far the cell presses into a wall that a current pushes it against.
+    #[test]
+    fn cell_can_evolve_to_avoid_touching_a_wall() {
+        const MUTATION: MutationParameters = MutationParameters {
+            weight_mutation_probability: 0.5,
+            weight_mutation_stdev: 2.0,
+            ..MutationParameters::NO_MUTATION
+        };
+        let touch_control = |mutation: &'static MutationParameters| {
+            let mut builder = NeuralNetControlBuilder::new(TransferFn::IDENTITY);
+            let (touch_indexes, _total_index) = builder.add_touch_inputs();
+            // pushing the wrong way to start with, but not zero, since a zero weight
+            // can't mutate
+            let weights = touch_indexes
+                .iter()
+                .map(|touch_index| (*touch_index, 1.0))
+                .collect::<Vec<_>>();
+            builder.add_output_node("thrust x", &weights, 0.0, |value| {
+                ThrusterCellLayerSpecialty::force_x_request(0, value)
+            });
+            builder.build(SeededMutationRandomness::new(0, mutation))
+        };
+        let wall_contact = |genome: &SparseNeuralNetGenome| {
+            let mut control = touch_control(&MutationParameters::NO_MUTATION);
+            control.set_genome(genome.clone()).unwrap();
+            let mut world = World::new(Position::new(-10.0, -10.0), Position::new(10.0, 10.0))
+                .with_perimeter_walls()
+                .with_per_cell_influence(Box::new(SimpleForceInfluence::new(Box::new(
+                    ConstantForce::new(Force::new(0.5, 0.0)),
+                ))))
+                .with_cell(
+                    Cell::new(
+                        Position::new(8.0, 0.0),
+                        Velocity::ZERO,
+                        vec![CellLayer::new(
+                            Area::new(PI),
+                            Density::new(1.0),
+                            Tissue::CellWall,
+                            Box::new(ThrusterCellLayerSpecialty::new()),
+                        )],
+                    )
+                    .with_control(Box::new(control)),
+                );
+            let mut contact = 0.0;
+            for _ in 0..30 {
+                world.tick();
+                // the environment's overlaps are gone by the end of the tick
+                let cell = &world.cells()[0];
+                contact += (cell.center().x() + cell.radius().value() - 10.0).max(0.0);
+            }
+            contact
+        };
+
+        let mut parent = touch_control(&MUTATION);
+        let initial_contact = wall_contact(parent.genome().unwrap());
+        let mut best_contact = initial_contact;
+        for _ in 0..50 {
+            let child = parent.spawn();
+            let child_genome = child.genome().unwrap().clone();
+            let contact = wall_contact(&child_genome);
+            if contact < best_contact {
+                best_contact = contact;
+                parent.set_genome(child_genome).unwrap();
+            }
+        }
+
+        assert!(initial_contact > 0.0);
+        assert!(best_contact < 0.25 * initial_contact);
+        // it thrusts away from the wall it touches on its east side
+        let east_touch_weight = parent
+            .genome()
+            .unwrap()
+            .connections()
+            .into_iter()
+            .find(|(from_index, _, _)| *from_index == 0)
+            .unwrap()
+            .2;
+        assert!(east_touch_weight < 0.0);
+    }
+
+    // grows its layer by the cloud concentration it senses
+    fn cloud_sensing_cell(position: Position) -> Cell {
+        let mut builder = NeuralNetControlBuilder::new(TransferFn::IDENTITY);
+        let concentration_index = builder.add_cloud_concentration_input();
+        builder.add_output_node("resize", &[(concentration_index, 1.0)], 0.0, |value| {
+            CellLayer::resize_request(0, AreaDelta::new(value))
+        });
+        let control = builder.build(SeededMutationRandomness::new(
+            0,
+            &MutationParameters::NO_MUTATION,
+        ));
+        simple_layered_cell(vec![simple_cell_layer(Area::new(1.0), Density::new(1.0))])
+            .with_initial_position(position)
+            .with_control(Box::new(control))
+    }
+
+    #[test]
+    fn gripping_cell_on_bottom_resists_current() {
+        let mut world = World::new(Position::new(-10.0, -10.0), Position::new(10.0, 10.0))
+            .with_perimeter_walls()
+            .with_per_cell_influence(Box::new(SimpleForceInfluence::new(Box::new(
+                ConstantForce::new(Force::new(1.0, -0.1)),
+            ))))
+            .with_bottom_substrate(0.1)
+            .with_cell(
+                Cell::new(
+                    Position::new(0.0, -9.0),
+                    Velocity::ZERO,
+                    vec![CellLayer::new(
+                        Area::new(PI),
+                        Density::new(1.0),
+                        Tissue::CellWall,
+                        Box::new(GripCellLayerSpecialty::new()),
+                    )],
+                )
+                .with_control(Box::new(ContinuousRequestsControl::new(vec![
+                    GripCellLayerSpecialty::grip_request(0, 10.0),
+                ]))),
+            );
+
+        for _ in 0..10 {
+            world.tick();
+        }
+
+        assert!(world.cells()[0].position().x().abs() < 0.5);
+    }
+
+    #[test]
+    fn world_can_tick_on_another_thread() {
+        let world = World::new(Position::new(-10.0, -10.0), Position::new(10.0, 10.0))
+            .with_standard_influences()
+            .with_cell(Cell::ball(
+                Length::new(1.0),
+                Mass::new(1.0),
+                Position::ORIGIN,
+                Velocity::new(1.0, 1.0),
+            ));
+
+        let world = std::thread::spawn(move || {
+            let mut world = world;
+            world.tick();
+            world
+        })
+        .join()
+        .unwrap();
+
+        let ball = &world.cells()[0];
+        assert!(ball.position().x() > 0.0);
+        assert!(ball.position().y() > 0.0);
+    }
+
+    #[test]
+    fn world_calls_birth_and_bond_hooks_for_new_cell() {
+        let mut world = World::new(Position::ORIGIN, Position::ORIGIN).with_cell(
+            Cell::new(
+                Position::ORIGIN,
+                Velocity::ZERO,
+                vec![CellLayer::new(
+                    Area::new(1.0),
+                    Density::new(1.0),
+                    Tissue::Photosynthetic,
+                    Box::new(BondingCellLayerSpecialty::new()),
+                )],
+            )
+            .with_control(Box::new(ContinuousRequestsControl::new(vec![
+                BondingCellLayerSpecialty::retain_bond_request(0, 1, true),
+                BondingCellLayerSpecialty::donation_energy_request(0, 1, BioEnergy::new(1.0)),
+            ])))
+            .with_initial_energy(BioEnergy::new(10.0)),
+        );
+        let births = Arc::new(Mutex::new(vec![]));
+        let births_clone = Arc::clone(&births);
+        world.on_birth(move |parent, child| {
+            births_clone
+                .lock()
+                .unwrap()
+                .push((parent.node_handle(), child.node_handle()))
+        });
+        let bond_changes = Arc::new(Mutex::new(vec![]));
+        let bond_changes_clone = Arc::clone(&bond_changes);
+        world.on_bond_change(move |change, _, _| bond_changes_clone.lock().unwrap().push(change));
+
+        world.tick();
+
+        let births = births.lock().unwrap();
+        assert_eq!(births.len(), 1);
+        assert_eq!(births[0].0, world.cells()[0].node_handle());
+        assert_eq!(births[0].1, world.cells()[1].node_handle());
+        assert_eq!(*bond_changes.lock().unwrap(), vec![BondChange::Formed]);
+    }
+
+    #[test]
+    fn world_reports_events_to_subscribers_at_tick_end() {
+        let mut world = World::new(Position::ORIGIN, Position::ORIGIN)
+            .with_cells(vec![
+                simple_layered_cell(vec![simple_cell_layer(Area::new(1.0), Density::new(1.0))]),
+                donor_cell(0, BioEnergy::new(0.25)),
+            ])
+            .with_bonds(vec![(0, 1)]);
+        let collector = Arc::new(Mutex::new(StatsCollector::new()));
+        world.subscribe(Box::new(Arc::clone(&collector)));
+        let collector_clone = Arc::clone(&collector);
+        world.on_tick_end(move |world| collector_clone.lock().unwrap().collect(world).unwrap());
+
+        world.tick();
+
+        assert_eq!(
+            *collector.lock().unwrap().latest_event_counts(),
+            // the recipient has no control to retain the bond, but the donation still
+            // goes through
+            EventCounts {
+                bonds_broken: 1,
+                energy_donated: 0.25,
+                ..EventCounts::default()
+            }
+        );
+    }
+
+    #[test]
+    fn world_calls_bond_hook_for_broken_bond() {
+        let mut world = World::new(Position::ORIGIN, Position::ORIGIN)
+            .with_cells(vec![
+                simple_layered_cell(vec![CellLayer::new(
+                    Area::new(1.0),
+                    Density::new(1.0),
+                    Tissue::Photosynthetic,
+                    Box::new(BondingCellLayerSpecialty::new()),
+                )])
+                .with_control(Box::new(ContinuousRequestsControl::new(vec![
+                    BondingCellLayerSpecialty::retain_bond_request(0, 1, false),
+                ]))),
+                simple_layered_cell(vec![simple_cell_layer(Area::new(1.0), Density::new(1.0))]),
+            ])
+            .with_bonds(vec![(0, 1)]);
+        let bond_changes = Arc::new(Mutex::new(vec![]));
+        let bond_changes_clone = Arc::clone(&bond_changes);
+        world.on_bond_change(move |change, _, _| bond_changes_clone.lock().unwrap().push(change));
+
+        world.tick();
+
+        assert_eq!(*bond_changes.lock().unwrap(), vec![BondChange::Broken]);
+    }
+
+    #[test]
+    fn world_calls_death_and_bond_hooks_for_burst_cell() {
+        const LAYER_PARAMS: LayerParameters = LayerParameters {
+            minimum_intact_thickness: Fraction::unchecked(0.5),
+            ..LayerParameters::DEFAULT
+        };
+
+        let mut world = World::new(Position::ORIGIN, Position::ORIGIN)
+            .with_cells(vec![
+                Cell::new(
+                    Position::new(3.5, -1.5),
+                    Velocity::ZERO,
+                    vec![
+                        simple_cell_layer(Area::new(1.0), Density::new(1.0)),
+                        simple_cell_layer(Area::new(0.1), Density::new(1.0))
+                            .with_parameters(&LAYER_PARAMS)
+                            .dead(),
+                    ],
+                ),
+                simple_layered_cell(vec![simple_cell_layer(Area::new(1.0), Density::new(1.0))]),
+            ])
+            .with_bonds(vec![(0, 1)]);
+        let deaths = Arc::new(Mutex::new(vec![]));
+        let deaths_clone = Arc::clone(&deaths);
+        world.on_death(move |cell| deaths_clone.lock().unwrap().push(cell.position()));
+        let bond_changes = Arc::new(Mutex::new(vec![]));
+        let bond_changes_clone = Arc::clone(&bond_changes);
+        world.on_bond_change(move |change, _, _| bond_changes_clone.lock().unwrap().push(change));
+
+        world.tick();
+
+        assert_eq!(*deaths.lock().unwrap(), vec![Position::new(3.5, -1.5)]);
+        assert_eq!(*bond_changes.lock().unwrap(), vec![BondChange::Broken]);
+    }
+
+    #[test]
+    fn world_breaks_bond_once_when_both_bonded_cells_burst() {
+        const LAYER_PARAMS: LayerParameters = LayerParameters {
+            minimum_intact_thickness: Fraction::unchecked(0.5),
+            ..LayerParameters::DEFAULT
+        };
+
+        let burst_cell = |x| {
+            Cell::new(
+                Position::new(x, 0.0),
+                Velocity::ZERO,
+                vec![
+                    simple_cell_layer(Area::new(1.0), Density::new(1.0)),
+                    simple_cell_layer(Area::new(0.1), Density::new(1.0))
+                        .with_parameters(&LAYER_PARAMS)
+                        .dead(),
+                ],
+            )
+        };
+        let mut world = World::new(Position::ORIGIN, Position::ORIGIN)
+            .with_cells(vec![burst_cell(-1.5), burst_cell(1.5)])
+            .with_bonds(vec![(0, 1)]);
+        let deaths = Arc::new(Mutex::new(0));
+        let deaths_clone = Arc::clone(&deaths);
+        world.on_death(move |_| *deaths_clone.lock().unwrap() += 1);
+        let bond_changes = Arc::new(Mutex::new(vec![]));
+        let bond_changes_clone = Arc::clone(&bond_changes);
+        world.on_bond_change(move |change, _, _| bond_changes_clone.lock().unwrap().push(change));
+
+        world.tick();
+
+        assert_eq!(*deaths.lock().unwrap(), 2);
+        assert_eq!(*bond_changes.lock().unwrap(), vec![BondChange::Broken]);
+        assert!(world.cells().is_empty());
+    }
+
+    #[test]
+    fn world_calls_tick_end_hook_after_each_tick() {
+        let mut world = World::new(Position::ORIGIN, Position::ORIGIN).with_cell(Cell::ball(
+            Length::new(1.0),
+            Mass::new(1.0),
+            Position::ORIGIN,
+            Velocity::new(1.0, 0.0),
+        ));
+        let positions = Arc::new(Mutex::new(vec![]));
+        let positions_clone = Arc::clone(&positions);
+        world.on_tick_end(move |world| {
+            positions_clone
+                .lock()
+                .unwrap()
+                .push((world.tick_count(), world.cells()[0].position()))
+        });
+
+        world.tick();
+        world.tick();
+
+        assert_eq!(
+            *positions.lock().unwrap(),
+            vec![(1, Position::new(1.0, 0.0)), (2, Position::new(2.0, 0.0))]
+        );
+    }
+
+    #[test]
+    fn light_intensity_query_sums_light_sources() {
+        let world = World::new(Position::new(0.0, -10.0), Position::new(10.0, 0.0))
+            .with_sunlight(0.0, 10.0)
+            .with_sunlight(1.0, 1.0);
+
+        assert_eq!(world.light_intensity_at(Position::new(5.0, -4.0)), 7.0);
+        assert_eq!(world.light_intensity_at(Position::new(5.0, 0.0)), 11.0);
+    }
+
+    #[test]
+    fn light_intensity_query_without_light_sources() {
+        let world = World::new(Position::ORIGIN, Position::new(10.0, 10.0));
+        assert_eq!(world.light_intensity_at(Position::new(5.0, 5.0)), 0.0);
+    }
+
+    #[test]
+    fn parallel_tick_matches_serial_tick() {
+        let new_world = |num_threads| {
+            test_support::crowded_cluster(8, 0)
+                .with_per_cell_influence(Box::new(SimpleForceInfluence::new(Box::new(
+                    WeightForce::new(-1.0),
+                ))))
+                .with_parallelism(num_threads)
+        };
+        let mut serial_world = new_world(1);
+        let mut parallel_world = new_world(3);
+
+        for _ in 0..20 {
+            serial_world.tick();
+            parallel_world.tick();
+        }
+
+        assert_eq!(parallel_world.parallelism(), 3);
+        for (serial_cell, parallel_cell) in serial_world.cells().iter().zip(parallel_world.cells())
+        {
+            assert_eq!(serial_cell.center(), parallel_cell.center());
+            assert_eq!(serial_cell.velocity(), parallel_cell.velocity());
+        }
+    }
+
+    #[test]
+    fn sharded_collisions_match_unsharded_collisions_of_separate_pairs() {
+        let new_world = |shard_count| {
+            let world = World::new(Position::new(-20.0, -20.0), Position::new(20.0, 20.0));
+            let world = if shard_count > 1 {
+                world.with_sharded_pair_collisions(Fraction::ONE, shard_count)
+            } else {
+                world.with_pair_collisions(Fraction::ONE)
+            };
+            // pairs that straddle the borders between four shards, and pairs that don't
+            world.with_cells(
+                [-15.0, -13.5, -8.0, -6.5, 0.0, 1.5, 5.1, 6.6, 14.0, 15.5]
+                    .iter()
+                    .map(|&x| test_support::unit_ball(Position::new(x, 0.0), Velocity::ZERO))
+                    .collect(),
+            )
+        };
+        let mut unsharded_world = new_world(1);
+        let mut sharded_world = new_world(4);
+
+        unsharded_world.tick();
+        sharded_world.tick();
+
+        for (unsharded_cell, sharded_cell) in
+            unsharded_world.cells().iter().zip(sharded_world.cells())
+        {
+            assert_ne!(sharded_cell.velocity(), Velocity::ZERO);
+            assert_eq!(sharded_cell.velocity(), unsharded_cell.velocity());
+        }
+    }
+
+    #[cfg(feature = "timing")]
+    #[test]
+    fn slow_control_is_counted_and_penalized() {
+        let mut world = World::new(Position::ORIGIN, Position::ORIGIN)
+            .with_control_time_budget(time::Duration::from_millis(1), Fraction::new(0.5))
+            .with_cells(vec![
+                simple_layered_cell(vec![simple_cell_layer(Area::new(1.0), Density::new(1.0))])
+                    .with_control(Box::new(SlowControl {}))
+                    .with_initial_energy(BioEnergy::new(10.0)),
+                simple_layered_cell(vec![simple_cell_layer(Area::new(1.0), Density::new(1.0))])
+                    .with_control(Box::new(NullControl::new()))
+                    .with_initial_energy(BioEnergy::new(10.0)),
+            ]);
+
+        world.tick();
+
+        assert_eq!(world.slow_control_count(), 1);
+        assert_eq!(world.cells()[0].energy(), BioEnergy::new(5.0));
+        assert_eq!(world.cells()[1].energy(), BioEnergy::new(10.0));
+    }
+
+    #[test]
+    fn capped_and_dropped_requests_are_logged_as_merged_warnings() {
+        let mut world = World::new(Position::ORIGIN, Position::ORIGIN).with_cell(
+            simple_layered_cell(vec![
+                simple_cell_layer(Area::new(1.0), Density::new(1.0)).with_health(Health::new(0.5))
+            ])
+            .with_control(Box::new(ContinuousRequestsControl::new(vec![
+                CellLayer::resize_request(0, AreaDelta::new(0.1)),
+                CellLayer::resize_request(0, AreaDelta::new(f64::NAN)),
+            ])))
+            .with_initial_energy(BioEnergy::new(10.0)),
+        );
+
+        world.tick();
+        world.tick();
+
+        let warnings = world.warnings().warnings();
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].severity, Severity::Warning);
+        assert_eq!(
+            warnings[0].to_string(),
+            "1-2 control requests not finite and dropped: 1"
+        );
+        assert_eq!(warnings[1].severity, Severity::Info);
+        assert_eq!(warnings[1].to_string(), "1-2 control requests capped: 1");
+    }
+
+    #[cfg(feature = "timing")]
+    #[derive(Debug)]
+    struct SlowControl {}
+
+    #[cfg(feature = "timing")]
+    impl CellControl for SlowControl {
+        fn run(&mut self, _cell_state: &CellStateSnapshot) -> Vec<ControlRequest> {
+            std::thread::sleep(time::Duration::from_millis(5));
+            vec![]
+        }
+
+        fn spawn(&mut self) -> Box<dyn CellControl> {
+            Box::new(SlowControl {})
+        }
+    }
+
+    #[test]
+    fn impulse_kicks_cell_on_next_tick() {
+        let mut world = World::new(Position::ORIGIN, Position::ORIGIN).with_cell(Cell::ball(
+            Length::new(1.0),
+            Mass::new(2.0),
+            Position::ORIGIN,
+            Velocity::ZERO,
+        ));
+        let handle = world.cells()[0].node_handle();
+        world.cell_mut(handle).set_selected(true);
+
+        assert!(world.apply_impulse(handle, Impulse::new(4.0, 0.0)));
+        world.tick();
+
+        let ball = &world.cells()[0];
+        assert_eq!(ball.velocity(), Velocity::new(2.0, 0.0));
+        let additions = ball.last_net_force().non_dominant_force_additions();
+        assert_eq!(additions.as_ref().unwrap()[0].label, "impulse");
+    }
+
+    #[test]
+    fn set_cell_velocity_takes_effect_on_next_tick() {
+        let mut world = World::new(Position::ORIGIN, Position::ORIGIN).with_cell(Cell::ball(
+            Length::new(1.0),
+            Mass::new(2.0),
+            Position::ORIGIN,
+            Velocity::new(1.0, 1.0),
+        ));
+        let handle = world.cells()[0].node_handle();
+
+        assert!(world.set_cell_velocity(handle, Velocity::new(-1.0, 0.5)));
+        world.tick();
+
+        assert_eq!(world.cells()[0].velocity(), Velocity::new(-1.0, 0.5));
+        assert_eq!(world.cells()[0].position(), Position::new(-1.0, 0.5));
+    }
+
+    #[test]
+    fn impulse_on_missing_cell_is_refused() {
+        let mut world = World::new(Position::ORIGIN, Position::ORIGIN);
+        assert!(!world.apply_impulse(Handle::new(0), Impulse::new(1.0, 0.0)));
+        assert!(!world.set_cell_velocity(Handle::new(0), Velocity::ZERO));
+    }
+
+    #[test]
+    fn scheduled_parameter_change_applies_before_its_tick_and_calls_hook() {
+        let mut world = World::new(Position::ORIGIN, Position::ORIGIN);
+        world
+            .schedule_parameter_change(1, "cloud_resize_factor", 1.5)
+            .unwrap();
+        let changes = Arc::new(Mutex::new(vec![]));
+        let changes_clone = Arc::clone(&changes);
+        world.on_parameter_change(move |change| changes_clone.lock().unwrap().push(change.clone()));
+
+        world.tick();
+        assert_eq!(world.parameters().cloud_params.resize_factor.value(), 1.0);
+        world.tick();
+
+        assert_eq!(world.parameters().cloud_params.resize_factor.value(), 1.5);
+        assert_eq!(
+            *changes.lock().unwrap(),
+            vec![ParameterChange {
+                tick: 1,
+                name: "cloud_resize_factor".to_string(),
+                value: 1.5
+            }]
+        );
+    }
+
+    #[test]
+    fn scheduling_invalid_parameter_change_fails() {
+        let mut world = World::new(Position::ORIGIN, Position::ORIGIN);
+        assert!(world.schedule_parameter_change(1, "gravity", 1.5).is_err());
+    }
+
+    #[test]
+    fn chemosynthetic_cell_depletes_chemical_field() {
+        let field = ChemicalField::new(
+            Position::ORIGIN,
+            Position::new(4.0, 4.0),
+            4,
+            4,
+            Fraction::ZERO,
+        )
+        .with_uniform_concentration(1.0);
+        let mut world = World::new(Position::ORIGIN, Position::new(4.0, 4.0))
+            .with_chemical_field(field)
+            .with_cell(Cell::new(
+                Position::new(0.5, 0.5),
+                Velocity::ZERO,
+                vec![CellLayer::new(
+                    Area::new(0.5),
+                    Density::new(1.0),
+                    Tissue::Chemosynthetic,
+                    Box::new(ChemoCellLayerSpecialty::new(Fraction::ONE)),
+                )],
+            ));
+
+        world.tick();
+
+        assert_eq!(world.cells()[0].energy(), BioEnergy::new(0.5));
+        let field = world.chemical_field().unwrap();
+        assert_eq!(field.concentration_at(Position::new(0.5, 0.5)), 0.5);
+        assert_eq!(field.concentration_at(Position::new(1.5, 0.5)), 1.0);
+    }
+
+    #[test]
+    fn eater_takes_no_more_energy_than_victim_has() {
+        const EATER_PARAMS: EaterLayerParameters = EaterLayerParameters {
+            bite_energy_per_unit_area: BioEnergy::unchecked(100.0),
+            bite_damage_health_delta: HealthDelta::new(-0.1),
+        };
+        let mut world = World::new(Position::new(-10.0, -10.0), Position::new(10.0, 10.0))
+            .with_pair_collisions(Fraction::ONE)
+            .with_cells(vec![
+                Cell::new(
+                    Position::ORIGIN,
+                    Velocity::ZERO,
+                    vec![CellLayer::new(
+                        Area::new(PI),
+                        Density::new(1.0),
+                        Tissue::Predatory,
+                        Box::new(EaterCellLayerSpecialty::new().with_parameters(&EATER_PARAMS)),
+                    )],
+                ),
+                simple_layered_cell(vec![simple_cell_layer(Area::new(PI), Density::new(1.0))])
+                    .with_initial_position(Position::new(1.5, 0.0))
+                    .with_initial_energy(BioEnergy::new(0.25)),
+            ]);
+
+        world.tick();
+
+        assert_eq!(world.cells()[0].energy(), BioEnergy::new(0.25));
+        assert_eq!(world.cells()[1].energy(), BioEnergy::ZERO);
+        assert!(world.cells()[1].layers()[0].health() < Health::FULL);
+    }
+
+    #[test]
+    fn eaters_split_victim_in_proportion_to_their_bites() {
+        const EATER_PARAMS: EaterLayerParameters = EaterLayerParameters {
+            bite_energy_per_unit_area: BioEnergy::unchecked(100.0),
+            bite_damage_health_delta: HealthDelta::new(-0.1),
+        };
+        let eater = |x| {
+            Cell::new(
+                Position::new(x, 0.0),
+                Velocity::ZERO,
+                vec![CellLayer::new(
+                    Area::new(PI),
+                    Density::new(1.0),
+                    Tissue::Predatory,
+                    Box::new(EaterCellLayerSpecialty::new().with_parameters(&EATER_PARAMS)),
+                )],
+            )
+        };
+        let mut world = World::new(Position::new(-10.0, -10.0), Position::new(10.0, 10.0))
+            .with_pair_collisions(Fraction::ONE)
+            .with_cells(vec![
+                eater(-1.5),
+                simple_layered_cell(vec![simple_cell_layer(Area::new(PI), Density::new(1.0))])
+                    .with_initial_energy(BioEnergy::new(0.3)),
+                eater(1.75),
+            ]);
+
+        world.tick();
+
+        let bigger_share = world.cells()[0].energy().value();
+        let smaller_share = world.cells()[2].energy().value();
+        assert_eq!(world.cells()[1].energy(), BioEnergy::ZERO);
+        assert!(smaller_share > 0.0);
+        assert!(bigger_share > smaller_share);
+        assert!((bigger_share + smaller_share - 0.3).abs() < 1e-12);
+    }
+
+    #[test]
+    fn identical_worlds_stay_identical_for_1000_ticks() {
+        let mut world1 = crowded_world();
+        let mut world2 = crowded_world();
+
+        for _ in 0..500 {
+            world1.tick();
+            world2.tick();
+        }
+        let json = world1.save_state().to_json().unwrap();
+        let mut loaded_world = crowded_world();
+        loaded_world
+            .load_state(&WorldState::from_json(&json).unwrap())
+            .unwrap();
+        assert_eq!(loaded_world.save_state(), world1.save_state());
+        assert_eq!(loaded_world.state_hash(), world1.state_hash());
+        for _ in 500..1000 {
+            world1.tick();
+            world2.tick();
+            loaded_world.tick();
+        }
+
+        assert_eq!(world1.tick_count(), 1000);
+        assert_eq!(world2.state_hash(), world1.state_hash());
+        assert_eq!(loaded_world.state_hash(), world1.state_hash());
+        assert_eq!(world1.cells().len(), world2.cells().len());
+        for (cell1, cell2) in world1.cells().iter().zip(world2.cells()) {
+            assert_eq!(cell1.position(), cell2.position());
+            assert_eq!(cell1.velocity(), cell2.velocity());
+            assert_eq!(cell1.energy(), cell2.energy());
+        }
+    }
+
+    #[test]
+    fn world_state_only_loads_into_world_with_same_cells() {
+        let mut world = crowded_world();
+        world.tick();
+        let state = world.save_state();
+
+        let mut other_world = World::new(Position::new(-20.0, -20.0), Position::new(20.0, 20.0))
+            .with_cell(Cell::ball(
+                Length::new(1.0),
+                Mass::new(1.0),
+                Position::ORIGIN,
+                Velocity::ZERO,
+            ));
+
+        assert!(other_world.load_state(&state).is_err());
+    }
+
+    fn crowded_world() -> World {
+        let mut world = World::new(Position::new(-20.0, -20.0), Position::new(20.0, 20.0))
+            .with_standard_influences()
+            .with_sunlight(0.0, 1.0);
+        for i in 0..40 {
+            let x = -15.0 + (i % 8) as f64 * 4.0;
+            let y = -15.0 + (i / 8) as f64 * 6.0;
+            let velocity = Velocity::new(((i * 7) % 5) as f64 - 2.0, ((i * 3) % 5) as f64 - 2.0);
+            world.add_cell(Cell::new(
+                Position::new(x, y),
+                velocity,
+                vec![CellLayer::new(
+                    Area::new(PI),
+                    Density::new(1.0),
+                    Tissue::Photosynthetic,
+                    Box::new(PhotoCellLayerSpecialty::new(Fraction::new(0.5))),
+                )],
+            ));
+        }
+        world
+    }
+
     fn simple_layered_cell(layers: Vec<CellLayer>) -> Cell {
         Cell::new(Position::ORIGIN, Velocity::ZERO, layers)
     }
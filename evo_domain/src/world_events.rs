@@ -0,0 +1,127 @@
+use crate::physics::quantities::*;
+use std::sync::{Arc, Mutex};
+
+// The things that happen to a world's cells and bonds, by cell ID, for listeners
+// subscribed with World::subscribe. Unlike the on_birth and similar hooks, which see the
+// cells themselves in the middle of the tick, events are only reported, in the order
+// they happened, once the tick has ended.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WorldEvent {
+    CellBorn {
+        parent_id: u64,
+        child_id: u64,
+    },
+    CellDied {
+        cell_id: u64,
+    },
+    BondFormed {
+        cell1_id: u64,
+        cell2_id: u64,
+    },
+    BondBroken {
+        cell1_id: u64,
+        cell2_id: u64,
+    },
+    // across a bond, net of the tax the donor paid
+    EnergyDonated {
+        donor_id: u64,
+        recipient_id: u64,
+        energy: BioEnergy,
+    },
+}
+
+pub trait WorldEventListener: Send {
+    // Called with the world's tick count as of the end of the tick in which the event
+    // happened. Changes made between ticks are reported at the end of the next tick.
+    fn on_event(&mut self, tick: u64, event: &WorldEvent);
+}
+
+// So that a listener can be shared with whatever reads what it gathered, such as a
+// World::on_tick_end hook.
+impl<L: WorldEventListener> WorldEventListener for Arc<Mutex<L>> {
+    fn on_event(&mut self, tick: u64, event: &WorldEvent) {
+        self.lock().unwrap().on_event(tick, event);
+    }
+}
+
+// A world's listeners and the events waiting to be reported to them. Only keeps events
+// while there is someone to report them to.
+#[derive(Default)]
+pub struct WorldEventBus {
+    listeners: Vec<Box<dyn WorldEventListener>>,
+    pending: Vec<WorldEvent>,
+}
+
+impl WorldEventBus {
+    pub fn subscribe(&mut self, listener: Box<dyn WorldEventListener>) {
+        self.listeners.push(listener);
+    }
+
+    pub fn has_listeners(&self) -> bool {
+        !self.listeners.is_empty()
+    }
+
+    pub fn emit(&mut self, event: WorldEvent) {
+        if self.has_listeners() {
+            self.pending.push(event);
+        }
+    }
+
+    pub fn dispatch(&mut self, tick: u64) {
+        for event in &self.pending {
+            for listener in &mut self.listeners {
+                listener.on_event(tick, event);
+            }
+        }
+        self.pending.clear();
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bus_reports_pending_events_in_order_on_dispatch() {
+        let events = Arc::new(Mutex::new(EventRecorder::default()));
+        let mut bus = WorldEventBus::default();
+        bus.emit(WorldEvent::CellDied { cell_id: 1 });
+        bus.subscribe(Box::new(Arc::clone(&events)));
+
+        bus.emit(WorldEvent::CellDied { cell_id: 2 });
+        bus.emit(WorldEvent::BondBroken {
+            cell1_id: 3,
+            cell2_id: 4,
+        });
+        assert!(events.lock().unwrap().0.is_empty());
+        bus.dispatch(5);
+        bus.dispatch(6);
+
+        assert_eq!(
+            events.lock().unwrap().0,
+            vec![
+                (5, WorldEvent::CellDied { cell_id: 2 }),
+                (
+                    5,
+                    WorldEvent::BondBroken {
+                        cell1_id: 3,
+                        cell2_id: 4
+                    }
+                )
+            ]
+        );
+    }
+
+    #[derive(Default)]
+    struct EventRecorder(Vec<(u64, WorldEvent)>);
+
+    impl WorldEventListener for EventRecorder {
+        fn on_event(&mut self, tick: u64, event: &WorldEvent) {
+            self.0.push((tick, *event));
+        }
+    }
+}
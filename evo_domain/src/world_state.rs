@@ -0,0 +1,145 @@
+use crate::biology::genome::SparseNeuralNetGenome;
+use crate::physics::quantities::*;
+use crate::world::World;
+use serde::{Deserialize, Serialize};
+
+// A world's changing state saved as JSON, so that a run can be stopped and resumed.
+// Influences, layer specialties, and control wiring are code rather than data, so a
+// state is loaded into a world built by the same program (see World::load_state),
+// which must still have the saved cells, in the same order, and their bonds. That is,
+// a state saved after any births or bond formation can't be loaded yet. Not saved: the
+// random number generators of controls and of the donation tax pool, the node values
+// of neural nets, and the phylogeny, hall of fame, and energy audit.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorldState {
+    pub tick_count: u64,
+    pub next_cell_id: u64,
+    pub cells: Vec<CellState>,
+    // by cell ID
+    pub bonds: Vec<(u64, u64)>,
+    pub clouds: Vec<CloudState>,
+    // row by row from the bottom, if the world has a chemical field
+    pub chemical_concentrations: Option<Vec<Value1D>>,
+    pub donation_tax_pool: Value1D,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CellState {
+    pub cell_id: u64,
+    pub lineage_id: u64,
+    pub position: [Value1D; 2],
+    pub velocity: [Value1D; 2],
+    pub angle: Value1D,
+    pub angular_velocity: Value1D,
+    pub energy: Value1D,
+    pub peak_energy: Value1D,
+    pub energy_gathered: Value1D,
+    pub received_donated_energy: Value1D,
+    pub thrust: [Value1D; 2],
+    pub thrust_offset: Value1D,
+    pub grip: Value1D,
+    pub anchor: Option<[Value1D; 2]>,
+    pub anchor_released: bool,
+    pub open_bond_slots: u8,
+    pub ticks_lived: u64,
+    pub offspring_count: u32,
+    pub layers: Vec<LayerState>,
+    pub genome: Option<SparseNeuralNetGenome>,
+    // (mean, variance) per normalized input
+    pub input_statistics: Vec<(Value1D, Value1D)>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct LayerState {
+    pub area: Value1D,
+    pub density: Value1D,
+    pub health: Value1D,
+    pub outer_radius: Value1D,
+    pub rest_inner_radius: Option<Value1D>,
+    pub wall_stress: Value1D,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CloudState {
+    pub position: [Value1D; 2],
+    pub radius: Value1D,
+    pub concentration: Value1D,
+}
+
+impl WorldState {
+    pub fn from_world(world: &World) -> Self {
+        world.save_state()
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|err| err.to_string())
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|err| format!("Invalid world state JSON: {}", err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn world_state_json_round_trip() {
+        let state = WorldState {
+            tick_count: 12,
+            next_cell_id: 3,
+            cells: vec![CellState {
+                cell_id: 2,
+                lineage_id: 1,
+                position: [1.5, -2.0],
+                velocity: [0.1, 0.2],
+                angle: 0.25,
+                angular_velocity: -0.125,
+                energy: 7.0,
+                peak_energy: 8.0,
+                energy_gathered: 1.0,
+                received_donated_energy: 0.5,
+                thrust: [0.0, 1.0],
+                thrust_offset: 0.5,
+                grip: 2.0,
+                anchor: Some([1.5, -3.0]),
+                anchor_released: false,
+                open_bond_slots: 2,
+                ticks_lived: 11,
+                offspring_count: 1,
+                layers: vec![LayerState {
+                    area: 3.0,
+                    density: 1.0,
+                    health: 0.75,
+                    outer_radius: 1.25,
+                    rest_inner_radius: Some(0.5),
+                    wall_stress: 0.1,
+                }],
+                genome: None,
+                input_statistics: vec![(1.0, 0.5)],
+            }],
+            bonds: vec![(1, 2)],
+            clouds: vec![CloudState {
+                position: [0.0, 1.0],
+                radius: 2.0,
+                concentration: 0.5,
+            }],
+            chemical_concentrations: Some(vec![0.0, 1.5]),
+            donation_tax_pool: 0.25,
+        };
+
+        let json = state.to_json().unwrap();
+
+        assert_eq!(WorldState::from_json(&json), Ok(state));
+    }
+
+    #[test]
+    fn from_json_rejects_unknown_fields() {
+        assert!(WorldState::from_json("{\"tick\": 1}").is_err());
+    }
+}
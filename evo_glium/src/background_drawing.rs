@@ -33,19 +33,24 @@ impl BackgroundDrawing {
         frame: &mut glium::Frame,
         vertex_buffer: &glium::VertexBuffer<T>,
         screen_transform: [[f32; 4]; 4],
+        clip: glium::Rect,
     ) where
         T: Copy,
     {
         let uniforms = uniform! {
             screen_transform: screen_transform
         };
+        let params = glium::DrawParameters {
+            scissor: Some(clip),
+            ..Default::default()
+        };
         frame
             .draw(
                 vertex_buffer,
                 &self.indices,
                 &self.shader_program,
                 &uniforms,
-                &Default::default(),
+                &params,
             )
             .unwrap();
     }
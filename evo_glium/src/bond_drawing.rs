@@ -32,8 +32,9 @@ impl BondDrawing {
     pub fn draw<T>(
         &self,
         frame: &mut glium::Frame,
-        vertex_buffer: &glium::VertexBuffer<T>,
+        vertices: glium::vertex::VertexBufferSlice<T>,
         screen_transform: [[f32; 4]; 4],
+        clip: glium::Rect,
         bond_color: [f32; 4],
     ) where
         T: Copy,
@@ -44,11 +45,12 @@ impl BondDrawing {
         };
         let params = glium::DrawParameters {
             blend: glium::Blend::alpha_blending(),
+            scissor: Some(clip),
             ..Default::default()
         };
         frame
             .draw(
-                vertex_buffer,
+                vertices,
                 &self.indices,
                 &self.shader_program,
                 &uniforms,
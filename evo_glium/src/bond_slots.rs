@@ -0,0 +1,145 @@
+use crate::line_drawing::LineVertex;
+use crate::stroke_text::StrokeText;
+use crate::Point;
+use std::f32::consts::PI;
+
+// One bond slot of a selected cell, drawn as a small numbered circle on the cell's edge.
+// A slot in use sits toward the cell it bonds to and is filled in; an empty slot sits
+// at its place in an even spacing around the cell.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BondSlotMarker {
+    pub cell_center: Point,
+    pub cell_radius: f32,
+    pub index: usize,
+    pub slot_count: usize,
+    pub bonded_cell_center: Option<Point>,
+}
+
+impl BondSlotMarker {
+    const COLOR: [f32; 4] = [1.0, 1.0, 0.0, 0.9];
+    const SIZE_FRACTION_OF_CELL: f32 = 0.12;
+    const CIRCLE_SEGMENTS: usize = 12;
+    const FILL_LINES: usize = 6;
+
+    pub fn to_line_vertices(markers: &[BondSlotMarker]) -> Vec<LineVertex> {
+        let mut vertices = vec![];
+        for marker in markers {
+            marker.add_line_vertices(&mut vertices);
+        }
+        vertices
+    }
+
+    fn add_line_vertices(&self, vertices: &mut Vec<LineVertex>) {
+        let direction = self.direction();
+        let radius = self.cell_radius * Self::SIZE_FRACTION_OF_CELL;
+        let center = self.point_along(direction, self.cell_radius);
+
+        for i in 0..Self::CIRCLE_SEGMENTS {
+            let angle1 = 2.0 * PI * i as f32 / Self::CIRCLE_SEGMENTS as f32;
+            let angle2 = 2.0 * PI * (i + 1) as f32 / Self::CIRCLE_SEGMENTS as f32;
+            Self::add_line(
+                vertices,
+                Self::offset(center, radius, angle1),
+                Self::offset(center, radius, angle2),
+            );
+        }
+        if self.bonded_cell_center.is_some() {
+            for i in 0..Self::FILL_LINES {
+                let angle = PI * i as f32 / Self::FILL_LINES as f32;
+                Self::add_line(
+                    vertices,
+                    Self::offset(center, radius, angle),
+                    Self::offset(center, radius, angle + PI),
+                );
+            }
+        }
+
+        let label = self.index.to_string();
+        let text_height = 2.0 * radius;
+        let label_center = self.point_along(direction, self.cell_radius + 2.5 * radius);
+        vertices.extend(StrokeText::line_vertices(
+            &label,
+            [
+                label_center[0] - StrokeText::width(&label, text_height) / 2.0,
+                label_center[1] - text_height / 2.0,
+            ],
+            text_height,
+            Self::COLOR,
+        ));
+    }
+
+    // unit vector from the cell's center toward the marker
+    fn direction(&self) -> Point {
+        if let Some(bonded_center) = self.bonded_cell_center {
+            let dx = bonded_center[0] - self.cell_center[0];
+            let dy = bonded_center[1] - self.cell_center[1];
+            let length = (dx * dx + dy * dy).sqrt();
+            if length > 0.0 {
+                return [dx / length, dy / length];
+            }
+        }
+        let angle = 2.0 * PI * self.index as f32 / self.slot_count as f32;
+        [angle.cos(), angle.sin()]
+    }
+
+    fn point_along(&self, direction: Point, distance: f32) -> Point {
+        [
+            self.cell_center[0] + direction[0] * distance,
+            self.cell_center[1] + direction[1] * distance,
+        ]
+    }
+
+    fn offset(center: Point, radius: f32, angle: f32) -> Point {
+        [
+            center[0] + radius * angle.cos(),
+            center[1] + radius * angle.sin(),
+        ]
+    }
+
+    fn add_line(vertices: &mut Vec<LineVertex>, end1: Point, end2: Point) {
+        vertices.push(LineVertex {
+            position: end1,
+            color: Self::COLOR,
+        });
+        vertices.push(LineVertex {
+            position: end2,
+            color: Self::COLOR,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bonded_slot_points_toward_bonded_cell() {
+        let marker = marker(1, Some([0.0, -20.0]));
+        assert_eq!(marker.direction(), [0.0, -1.0]);
+    }
+
+    #[test]
+    fn empty_slots_are_spaced_evenly() {
+        let marker = marker(2, None);
+        let direction = marker.direction();
+        assert!(direction[0].abs() < 1e-6);
+        assert!((direction[1] - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn bonded_slot_is_filled_in() {
+        let empty = BondSlotMarker::to_line_vertices(&[marker(1, None)]);
+        let bonded = BondSlotMarker::to_line_vertices(&[marker(1, Some([0.0, -20.0]))]);
+        assert_eq!(bonded.len(), empty.len() + 2 * BondSlotMarker::FILL_LINES);
+    }
+
+    fn marker(index: usize, bonded_cell_center: Option<Point>) -> BondSlotMarker {
+        BondSlotMarker {
+            cell_center: [0.0, 0.0],
+            cell_radius: 10.0,
+            index,
+            slot_count: 8,
+            bonded_cell_center,
+        }
+    }
+}
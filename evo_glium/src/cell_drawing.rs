@@ -1,5 +1,7 @@
 use glium::{implement_vertex, uniform, Surface};
 
+// Each layer's tissue is an index into the palette of tissue colors and styles, so that
+// cells with different layers in different orders are all drawn right.
 #[derive(Clone, Copy)]
 pub struct CellSprite {
     pub center: [f32; 2],
@@ -8,9 +10,32 @@ pub struct CellSprite {
     pub radii_4_7: [f32; 4],
     pub health_0_3: [f32; 4],
     pub health_4_7: [f32; 4],
+    pub tissues_0_3: [u32; 4],
+    pub tissues_4_7: [u32; 4],
 }
 
-implement_vertex!(CellSprite, center, num_layers, radii_0_3, radii_4_7, health_0_3, health_4_7);
+implement_vertex!(
+    CellSprite,
+    center,
+    num_layers,
+    radii_0_3,
+    radii_4_7,
+    health_0_3,
+    health_4_7,
+    tissues_0_3,
+    tissues_4_7
+);
+
+// Patterns that tell tissues apart without relying on color alone, e.g. for color-blind
+// viewers or in crowded scenes. The values are the codes the fragment shader checks.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LayerStyle {
+    Plain = 0,
+    Stripes = 1,
+    DashedRing = 2,
+    Stipple = 3,
+    Pulse = 4,
+}
 
 pub struct CellDrawing {
     pub shader_program: glium::Program,
@@ -31,27 +56,35 @@ impl CellDrawing {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn draw<T>(
         &self,
         frame: &mut glium::Frame,
-        vertex_buffer: &glium::VertexBuffer<T>,
+        vertices: glium::vertex::VertexBufferSlice<T>,
         screen_transform: [[f32; 4]; 4],
-        layer_colors: [[f32; 4]; 8],
+        clip: glium::Rect,
+        tissue_colors: [[f32; 4]; 8],
+        tissue_styles: [u32; 8],
+        seconds: f32,
     ) where
         T: Copy,
     {
         let uniforms = uniform! {
             screen_transform: screen_transform,
-            layer_colors_0_3: [layer_colors[0], layer_colors[1], layer_colors[2], layer_colors[3]],
-            layer_colors_4_7: [layer_colors[4], layer_colors[5], layer_colors[6], layer_colors[7]],
+            tissue_colors_0_3: [tissue_colors[0], tissue_colors[1], tissue_colors[2], tissue_colors[3]],
+            tissue_colors_4_7: [tissue_colors[4], tissue_colors[5], tissue_colors[6], tissue_colors[7]],
+            tissue_styles_0_3: [tissue_styles[0], tissue_styles[1], tissue_styles[2], tissue_styles[3]],
+            tissue_styles_4_7: [tissue_styles[4], tissue_styles[5], tissue_styles[6], tissue_styles[7]],
+            seconds: seconds,
         };
         let params = glium::DrawParameters {
             blend: glium::Blend::alpha_blending(),
+            scissor: Some(clip),
             ..Default::default()
         };
         frame
             .draw(
-                vertex_buffer,
+                vertices,
                 &self.indices,
                 &self.shader_program,
                 &uniforms,
@@ -69,12 +102,15 @@ impl CellDrawing {
         in vec4 radii_4_7;
         in vec4 health_0_3;
         in vec4 health_4_7;
+        in uvec4 tissues_0_3;
+        in uvec4 tissues_4_7;
 
         out CellSprite {
             vec2 center;
             uint num_layers;
             float radii[8];
             float health[8];
+            uint tissues[8];
         } cell_out;
 
         void main() {
@@ -84,6 +120,8 @@ impl CellDrawing {
                                      radii_4_7[0], radii_4_7[1], radii_4_7[2], radii_4_7[3]);
             cell_out.health = float[](health_0_3[0], health_0_3[1], health_0_3[2], health_0_3[3],
                                       health_4_7[0], health_4_7[1], health_4_7[2], health_4_7[3]);
+            cell_out.tissues = uint[](tissues_0_3[0], tissues_0_3[1], tissues_0_3[2], tissues_0_3[3],
+                                      tissues_4_7[0], tissues_4_7[1], tissues_4_7[2], tissues_4_7[3]);
         }
     "#;
 
@@ -100,6 +138,7 @@ impl CellDrawing {
             uint num_layers;
             float radii[8];
             float health[8];
+            uint tissues[8];
         } cell_in[];
 
         out CellPoint {
@@ -107,6 +146,7 @@ impl CellDrawing {
             flat uint num_layers;
             flat float radii[8];
             flat float health[8];
+            flat uint tissues[8];
         } cell_point_out;
 
         void emit_circle_bounding_box_corner(in vec2 center, in float radius, in vec2 corner) {
@@ -115,6 +155,7 @@ impl CellDrawing {
             cell_point_out.num_layers = cell_in[0].num_layers;
             cell_point_out.radii = cell_in[0].radii;
             cell_point_out.health = cell_in[0].health;
+            cell_point_out.tissues = cell_in[0].tissues;
             gl_Position = screen_transform * vec4(center + offset, 0.0, 1.0);
             EmitVertex();
         }
@@ -137,14 +178,18 @@ impl CellDrawing {
     const FRAGMENT_SHADER_SRC: &'static str = r#"
         #version 330 core
 
-        uniform mat4 layer_colors_0_3;
-        uniform mat4 layer_colors_4_7;
+        uniform mat4 tissue_colors_0_3;
+        uniform mat4 tissue_colors_4_7;
+        uniform uvec4 tissue_styles_0_3;
+        uniform uvec4 tissue_styles_4_7;
+        uniform float seconds;
 
         in CellPoint {
             vec2 offset;
             flat uint num_layers;
             flat float radii[8];
             flat float health[8];
+            flat uint tissues[8];
         } cell_point_in;
 
         out vec4 color_out;
@@ -174,18 +219,49 @@ impl CellDrawing {
             return vec4(hsv2rgb(vec3(hsv[0], adjusted_s, adjusted_v)), color.a);
         }
 
-        void emit_color(in uint layer_index, in float health) {
-            vec4 pure_color = (layer_index < 4u)
-                ? layer_colors_0_3[layer_index]
-                : layer_colors_4_7[layer_index - 4u];
-            color_out = adjust_color_per_health(pure_color, health);
+        // The patterns are sized in pixels, so they look the same at any zoom.
+        vec4 apply_style(in vec4 color, in uint style, in float edge_distance, in float pixel) {
+            if (style == 1u) {
+                // diagonal stripes
+                if (mod(gl_FragCoord.x + gl_FragCoord.y, 8.0) < 4.0) {
+                    return vec4(0.6 * color.rgb, color.a);
+                }
+            } else if (style == 2u) {
+                // bright dashes around the layer's outer edge
+                float angle = atan(cell_point_in.offset.y, cell_point_in.offset.x);
+                if (edge_distance < 2.0 * pixel && fract(angle * 12.0 / 6.2831853) < 0.5) {
+                    return vec4(mix(color.rgb, vec3(1.0), 0.6), 1.0);
+                }
+            } else if (style == 3u) {
+                // a dark dot every few pixels
+                if (mod(gl_FragCoord.x, 4.0) < 1.5 && mod(gl_FragCoord.y, 4.0) < 1.5) {
+                    return vec4(0.4 * color.rgb, color.a);
+                }
+            } else if (style == 4u) {
+                // brightness rising and falling once a second
+                return vec4(color.rgb * (0.8 + 0.4 * sin(6.2831853 * seconds)), color.a);
+            }
+            return color;
+        }
+
+        void emit_color(in uint tissue, in float health, in float edge_distance, in float pixel) {
+            vec4 pure_color = (tissue < 4u)
+                ? tissue_colors_0_3[tissue]
+                : tissue_colors_4_7[tissue - 4u];
+            uint style = (tissue < 4u)
+                ? tissue_styles_0_3[tissue]
+                : tissue_styles_4_7[tissue - 4u];
+            color_out = apply_style(adjust_color_per_health(pure_color, health), style, edge_distance, pixel);
         }
 
         void main() {
             float radial_offset = length(cell_point_in.offset);
+            // world units per pixel, taken outside the loop, where derivatives are defined
+            float pixel = fwidth(radial_offset);
             for (uint i = 0u; i < min(8u, cell_point_in.num_layers); ++i) {
                 if (radial_offset <= cell_point_in.radii[i]) {
-                    emit_color(i, cell_point_in.health[i]);
+                    emit_color(cell_point_in.tissues[i], cell_point_in.health[i],
+                               cell_point_in.radii[i] - radial_offset, pixel);
                     return;
                 }
             }
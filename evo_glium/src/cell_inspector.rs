@@ -0,0 +1,119 @@
+use crate::line_drawing::LineVertex;
+use crate::stroke_text::StrokeText;
+use crate::Point;
+
+// A selected cell's numbers, drawn as a panel in the top left corner of its world so
+// they can be watched while the simulation runs: energy, bond count, velocity, and
+// each layer's area and health, innermost layer first.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CellInspector {
+    pub energy: f32,
+    pub bond_count: usize,
+    pub velocity: Point,
+    pub layers: Vec<LayerReadout>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LayerReadout {
+    pub area: f32,
+    pub health: f32,
+}
+
+impl CellInspector {
+    const COLOR: [f32; 4] = [1.0, 1.0, 0.0, 0.9];
+    const LINE_SPACING: f32 = 1.6;
+
+    // One panel per selected cell, stacked downward.
+    pub fn to_line_vertices(
+        inspectors: &[CellInspector],
+        min_corner: Point,
+        max_corner: Point,
+    ) -> Vec<LineVertex> {
+        let extent = (max_corner[0] - min_corner[0]).max(max_corner[1] - min_corner[1]);
+        let text_height = extent / 60.0;
+        let left = min_corner[0] + text_height;
+        let mut top = max_corner[1] - text_height;
+
+        let mut vertices = vec![];
+        for inspector in inspectors {
+            for row in inspector.rows() {
+                top -= Self::LINE_SPACING * text_height;
+                vertices.extend(StrokeText::line_vertices(
+                    &row,
+                    [left, top],
+                    text_height,
+                    Self::COLOR,
+                ));
+            }
+            // a blank row between panels
+            top -= Self::LINE_SPACING * text_height;
+        }
+        vertices
+    }
+
+    fn rows(&self) -> Vec<String> {
+        let mut rows = vec![
+            format!("E {:.2}", self.energy),
+            format!("b {}", self.bond_count),
+            format!("v {:.2} {:.2}", self.velocity[0], self.velocity[1]),
+        ];
+        for (index, layer) in self.layers.iter().enumerate() {
+            rows.push(format!(
+                "{} A {:.2} H {:.2}",
+                index, layer.area, layer.health
+            ));
+        }
+        rows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_show_cell_and_layer_numbers() {
+        let inspector = inspector();
+        assert_eq!(
+            inspector.rows(),
+            vec![
+                "E 12.50",
+                "b 2",
+                "v 1.00 -0.25",
+                "0 A 3.14 H 1.00",
+                "1 A 0.50 H 0.75",
+            ]
+        );
+    }
+
+    #[test]
+    fn panel_starts_in_top_left_corner() {
+        let vertices = CellInspector::to_line_vertices(&[inspector()], [0.0, -60.0], [60.0, 0.0]);
+        assert!(vertices
+            .iter()
+            .all(|vertex| vertex.position[0] >= 1.0 && vertex.position[1] <= -1.0));
+        let highest = vertices
+            .iter()
+            .map(|vertex| vertex.position[1])
+            .fold(f32::MIN, f32::max);
+        assert_eq!(highest, -1.0 - CellInspector::LINE_SPACING + 1.0);
+    }
+
+    fn inspector() -> CellInspector {
+        CellInspector {
+            energy: 12.5,
+            bond_count: 2,
+            velocity: [1.0, -0.25],
+            layers: vec![
+                LayerReadout {
+                    area: 3.14444,
+                    health: 1.0,
+                },
+                LayerReadout {
+                    area: 0.5,
+                    health: 0.75,
+                },
+            ],
+        }
+    }
+}
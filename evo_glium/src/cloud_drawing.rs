@@ -32,8 +32,9 @@ impl CloudDrawing {
     pub fn draw<T>(
         &self,
         frame: &mut glium::Frame,
-        vertex_buffer: &glium::VertexBuffer<T>,
+        vertices: glium::vertex::VertexBufferSlice<T>,
         screen_transform: [[f32; 4]; 4],
+        clip: glium::Rect,
         cloud_colors: [[f32; 4]; 8],
     ) where
         T: Copy,
@@ -45,11 +46,12 @@ impl CloudDrawing {
         };
         let params = glium::DrawParameters {
             blend: glium::Blend::alpha_blending(),
+            scissor: Some(clip),
             ..Default::default()
         };
         frame
             .draw(
-                vertex_buffer,
+                vertices,
                 &self.indices,
                 &self.shader_program,
                 &uniforms,
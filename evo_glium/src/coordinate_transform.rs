@@ -0,0 +1,277 @@
+use crate::Point;
+
+// Maps the world rectangle into a viewport (by default the whole window) as large as
+// it fits without stretching, centered, with bars above and below (letterbox) or to
+// the sides (pillarbox) when the viewport's shape differs from the world's. Window
+// coordinates start at the top left corner and grow rightward and downward; world y
+// grows upward. Zooming in magnifies the world about the view center, by default the
+// world's center, and the parts pushed out of the viewport are clipped.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CoordinateTransform {
+    world_min_corner: Point,
+    world_max_corner: Point,
+    window_size: [f32; 2],
+    viewport_min_corner: Point,
+    viewport_size: [f32; 2],
+    zoom: f32,
+    // the world position shown at the middle of the viewport
+    center: Point,
+}
+
+impl CoordinateTransform {
+    pub fn new(world_min_corner: Point, world_max_corner: Point, window_size: [f32; 2]) -> Self {
+        CoordinateTransform {
+            world_min_corner,
+            world_max_corner,
+            window_size,
+            viewport_min_corner: [0.0, 0.0],
+            viewport_size: window_size,
+            zoom: 1.0,
+            center: Self::midpoint(world_min_corner, world_max_corner),
+        }
+    }
+
+    // viewport_min_corner is the viewport's top left corner in window coordinates
+    pub fn with_viewport(mut self, viewport_min_corner: Point, viewport_size: [f32; 2]) -> Self {
+        self.viewport_min_corner = viewport_min_corner;
+        self.viewport_size = viewport_size;
+        self
+    }
+
+    pub fn with_zoom(mut self, zoom: f32) -> Self {
+        self.zoom = zoom;
+        self
+    }
+
+    pub fn with_center(mut self, center: Point) -> Self {
+        self.center = center;
+        self
+    }
+
+    // The whole world, as with no zoom.
+    pub fn unzoomed(self) -> Self {
+        self.with_zoom(1.0)
+            .with_center(Self::midpoint(self.world_min_corner, self.world_max_corner))
+    }
+
+    // The zoom at which the world region just fills the viewport in its tighter
+    // dimension.
+    pub fn zoom_to_show(&self, min_corner: Point, max_corner: Point) -> f32 {
+        let region_size = [
+            (max_corner[0] - min_corner[0]).max(f32::EPSILON),
+            (max_corner[1] - min_corner[1]).max(f32::EPSILON),
+        ];
+        self.fit_scale(region_size) / self.fit_scale(self.world_size())
+    }
+
+    pub fn midpoint(corner1: Point, corner2: Point) -> Point {
+        [
+            (corner1[0] + corner2[0]) / 2.0,
+            (corner1[1] + corner2[1]) / 2.0,
+        ]
+    }
+
+    pub fn viewport_contains(&self, window_pos: Point) -> bool {
+        (0..2).all(|i| {
+            window_pos[i] >= self.viewport_min_corner[i]
+                && window_pos[i] < self.viewport_min_corner[i] + self.viewport_size[i]
+        })
+    }
+
+    // window units per world unit, set by whichever dimension is the tighter fit
+    pub fn scale(&self) -> f32 {
+        self.zoom * self.fit_scale(self.world_size())
+    }
+
+    // window units per world unit that fit a world region of the size into the viewport
+    fn fit_scale(&self, size: Point) -> f32 {
+        (self.viewport_size[0] / size[0]).min(self.viewport_size[1] / size[1])
+    }
+
+    // The viewport in the frame's pixels, which start at the bottom left corner and may
+    // be smaller than window units on high-DPI screens, for clipping the drawing to it.
+    pub fn clip_rect(&self, frame_size: (u32, u32)) -> glium::Rect {
+        let pixels_per_unit = [
+            frame_size.0 as f32 / self.window_size[0],
+            frame_size.1 as f32 / self.window_size[1],
+        ];
+        let viewport_bottom = self.viewport_min_corner[1] + self.viewport_size[1];
+        glium::Rect {
+            left: (self.viewport_min_corner[0] * pixels_per_unit[0]).round() as u32,
+            bottom: ((self.window_size[1] - viewport_bottom) * pixels_per_unit[1]).round() as u32,
+            width: (self.viewport_size[0] * pixels_per_unit[0]).round() as u32,
+            height: (self.viewport_size[1] * pixels_per_unit[1]).round() as u32,
+        }
+    }
+
+    pub fn window_to_world(&self, window_pos: Point) -> Point {
+        let scale = self.scale();
+        let offset = self.world_offset_in_window();
+        [
+            self.world_min_corner[0] + (window_pos[0] - offset[0]) / scale,
+            self.world_max_corner[1] - (window_pos[1] - offset[1]) / scale,
+        ]
+    }
+
+    // The world corners, min then max, of what the viewport shows, which when zoomed in
+    // is only part of the world.
+    pub fn visible_world_corners(&self) -> (Point, Point) {
+        let top_left = self.window_to_world(self.viewport_min_corner);
+        let bottom_right = self.window_to_world([
+            self.viewport_min_corner[0] + self.viewport_size[0],
+            self.viewport_min_corner[1] + self.viewport_size[1],
+        ]);
+        (
+            [top_left[0], bottom_right[1]],
+            [bottom_right[0], top_left[1]],
+        )
+    }
+
+    // The world-to-clip-space matrix for the shaders. Clip space runs from -1 to 1
+    // across the whole window, bottom to top.
+    pub fn screen_transform(&self) -> [[f32; 4]; 4] {
+        let x_scale = 2.0 * self.scale() / self.window_size[0];
+        let y_scale = 2.0 * self.scale() / self.window_size[1];
+
+        let world_top_left = self.world_offset_in_window();
+        let x_translate = 2.0 * world_top_left[0] / self.window_size[0]
+            - 1.0
+            - x_scale * self.world_min_corner[0];
+        let y_translate = 1.0
+            - 2.0 * world_top_left[1] / self.window_size[1]
+            - y_scale * self.world_max_corner[1];
+
+        [
+            [x_scale, 0.0, 0.0, 0.0],
+            [0.0, y_scale, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [x_translate, y_translate, 0.0, 1.0],
+        ]
+    }
+
+    fn world_size(&self) -> Point {
+        [
+            self.world_max_corner[0] - self.world_min_corner[0],
+            self.world_max_corner[1] - self.world_min_corner[1],
+        ]
+    }
+
+    // the window position of the world's top left corner
+    fn world_offset_in_window(&self) -> Point {
+        let scale = self.scale();
+        [
+            self.viewport_min_corner[0] + self.viewport_size[0] / 2.0
+                - (self.center[0] - self.world_min_corner[0]) * scale,
+            self.viewport_min_corner[1] + self.viewport_size[1] / 2.0
+                - (self.world_max_corner[1] - self.center[1]) * scale,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wide_window_gets_bars_at_the_sides() {
+        let transform = CoordinateTransform::new([0.0, -100.0], [100.0, 0.0], [400.0, 200.0]);
+        assert_eq!(transform.scale(), 2.0);
+        assert_eq!(transform.window_to_world([100.0, 0.0]), [0.0, 0.0]);
+        assert_eq!(transform.window_to_world([300.0, 200.0]), [100.0, -100.0]);
+        assert_eq!(transform.window_to_world([200.0, 100.0]), [50.0, -50.0]);
+    }
+
+    #[test]
+    fn tall_window_gets_bars_above_and_below() {
+        let transform = CoordinateTransform::new([-50.0, -25.0], [50.0, 25.0], [200.0, 400.0]);
+        assert_eq!(transform.scale(), 2.0);
+        assert_eq!(transform.window_to_world([0.0, 150.0]), [-50.0, 25.0]);
+        assert_eq!(transform.window_to_world([200.0, 250.0]), [50.0, -25.0]);
+        assert_eq!(transform.window_to_world([100.0, 200.0]), [0.0, 0.0]);
+    }
+
+    #[test]
+    fn zoom_narrows_visible_world() {
+        let transform =
+            CoordinateTransform::new([0.0, -100.0], [100.0, 0.0], [200.0, 200.0]).with_zoom(4.0);
+        assert_eq!(
+            transform.visible_world_corners(),
+            ([37.5, -62.5], [62.5, -37.5])
+        );
+    }
+
+    #[test]
+    fn screen_transform_maps_world_edges_to_clip_space() {
+        let transform = CoordinateTransform::new([0.0, -100.0], [100.0, 0.0], [400.0, 200.0]);
+        let matrix = transform.screen_transform();
+        let to_clip = |pos: Point| {
+            [
+                matrix[0][0] * pos[0] + matrix[3][0],
+                matrix[1][1] * pos[1] + matrix[3][1],
+            ]
+        };
+        assert_eq!(to_clip([0.0, 0.0]), [-0.5, 1.0]);
+        assert_eq!(to_clip([100.0, -100.0]), [0.5, -1.0]);
+    }
+
+    #[test]
+    fn viewport_confines_world_to_part_of_window() {
+        let transform = CoordinateTransform::new([0.0, -100.0], [100.0, 0.0], [400.0, 200.0])
+            .with_viewport([200.0, 0.0], [200.0, 200.0]);
+        assert_eq!(transform.window_to_world([200.0, 0.0]), [0.0, 0.0]);
+        assert_eq!(transform.window_to_world([300.0, 100.0]), [50.0, -50.0]);
+        assert!(transform.viewport_contains([250.0, 50.0]));
+        assert!(!transform.viewport_contains([150.0, 50.0]));
+
+        let matrix = transform.screen_transform();
+        assert_eq!(matrix[0][0] * 100.0 + matrix[3][0], 1.0);
+        assert_eq!(matrix[0][0] * 0.0 + matrix[3][0], 0.0);
+    }
+
+    #[test]
+    fn zoom_magnifies_about_world_center() {
+        let transform =
+            CoordinateTransform::new([0.0, -100.0], [100.0, 0.0], [200.0, 200.0]).with_zoom(2.0);
+        assert_eq!(transform.scale(), 4.0);
+        assert_eq!(transform.window_to_world([100.0, 100.0]), [50.0, -50.0]);
+        assert_eq!(transform.window_to_world([0.0, 0.0]), [25.0, -25.0]);
+    }
+
+    #[test]
+    fn zoom_magnifies_about_view_center() {
+        let transform = CoordinateTransform::new([0.0, -100.0], [100.0, 0.0], [200.0, 200.0])
+            .with_zoom(4.0)
+            .with_center([20.0, -80.0]);
+        assert_eq!(transform.window_to_world([100.0, 100.0]), [20.0, -80.0]);
+        assert_eq!(
+            transform.visible_world_corners(),
+            ([7.5, -92.5], [32.5, -67.5])
+        );
+        assert_eq!(
+            transform.unzoomed().window_to_world([100.0, 100.0]),
+            [50.0, -50.0]
+        );
+    }
+
+    #[test]
+    fn zoom_to_show_fits_region_in_tighter_dimension() {
+        let transform = CoordinateTransform::new([0.0, -100.0], [100.0, 0.0], [400.0, 200.0]);
+        assert_eq!(transform.zoom_to_show([10.0, -30.0], [30.0, -20.0]), 10.0);
+        assert_eq!(transform.zoom_to_show([10.0, -60.0], [15.0, -10.0]), 2.0);
+    }
+
+    #[test]
+    fn clip_rect_is_viewport_in_frame_pixels() {
+        let transform = CoordinateTransform::new([0.0, -100.0], [100.0, 0.0], [400.0, 200.0])
+            .with_viewport([200.0, 0.0], [200.0, 200.0]);
+        assert_eq!(
+            transform.clip_rect((800, 400)),
+            glium::Rect {
+                left: 400,
+                bottom: 0,
+                width: 400,
+                height: 400
+            }
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use crate::line_drawing::LineVertex;
+use crate::Point;
+use evo_domain::physics::shapes::Circle;
+use evo_domain::world::World;
+
+// A number worked out for each cell from the world as a whole, such as how crowded the
+// cell is or how much light reaches it, drawn as a ring around each cell colored from
+// blue at the low end of the range through green to red at the high end. Add one to the
+// view with GliumView::with_derived_field; a new visualization needs no changes to the
+// world or to the rest of the drawing code.
+pub trait DerivedField: Send + Sync {
+    // one value per cell, in the world's cell order
+    fn cell_values(&self, world: &World) -> Vec<f32>;
+
+    // The values colored blue and red; those outside are clamped. Defaults to the
+    // lowest and highest values this tick.
+    fn range(&self, values: &[f32]) -> (f32, f32) {
+        values
+            .iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), &value| {
+                (min.min(value), max.max(value))
+            })
+    }
+}
+
+// How much sunlight reaches each cell's center.
+pub struct LightField;
+
+impl DerivedField for LightField {
+    fn cell_values(&self, world: &World) -> Vec<f32> {
+        world
+            .cells()
+            .iter()
+            .map(|cell| world.light_intensity_at(cell.center()) as f32)
+            .collect()
+    }
+}
+
+// How many other cells each cell touches or overlaps.
+pub struct CrowdingField;
+
+impl DerivedField for CrowdingField {
+    fn cell_values(&self, world: &World) -> Vec<f32> {
+        let cells = world.cells();
+        let mut by_min_x = (0..cells.len()).collect::<Vec<_>>();
+        by_min_x.sort_by(|&i, &j| cells[i].min_x().partial_cmp(&cells[j].min_x()).unwrap());
+        let mut counts = vec![0.0; cells.len()];
+        for (n, &i) in by_min_x.iter().enumerate() {
+            for &j in &by_min_x[(n + 1)..] {
+                if cells[j].min_x() > cells[i].max_x() {
+                    break;
+                }
+                let touching_distance = (cells[i].radius() + cells[j].radius()).value();
+                if (cells[i].center() - cells[j].center()).length().value() <= touching_distance {
+                    counts[i] += 1.0;
+                    counts[j] += 1.0;
+                }
+            }
+        }
+        counts
+    }
+
+    fn range(&self, _values: &[f32]) -> (f32, f32) {
+        (0.0, 6.0)
+    }
+}
+
+// A derived field's value for one cell, as a colored ring around it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FieldRing {
+    pub center: Point,
+    pub radius: f32,
+    pub color: [f32; 4],
+}
+
+impl FieldRing {
+    const SEGMENTS: usize = 16;
+    // just outside the cell, and a little farther out for each field after the first
+    const RADIUS_FACTOR: f32 = 1.15;
+    const RADIUS_FACTOR_STEP: f32 = 0.15;
+
+    pub fn for_field(field: &dyn DerivedField, field_index: usize, world: &World) -> Vec<Self> {
+        let values = field.cell_values(world);
+        let (min, max) = field.range(&values);
+        let radius_factor = Self::RADIUS_FACTOR + field_index as f32 * Self::RADIUS_FACTOR_STEP;
+        world
+            .cells()
+            .iter()
+            .zip(&values)
+            .map(|(cell, &value)| FieldRing {
+                center: [cell.center().x() as f32, cell.center().y() as f32],
+                radius: radius_factor * cell.radius().value() as f32,
+                color: Self::color(Self::fraction(value, min, max)),
+            })
+            .collect()
+    }
+
+    fn fraction(value: f32, min: f32, max: f32) -> f32 {
+        if max > min {
+            ((value - min) / (max - min)).clamp(0.0, 1.0)
+        } else {
+            0.5
+        }
+    }
+
+    // blue at 0, green at 0.5, red at 1
+    fn color(fraction: f32) -> [f32; 4] {
+        if fraction < 0.5 {
+            let t = 2.0 * fraction;
+            [0.0, t, 1.0 - t, 0.9]
+        } else {
+            let t = 2.0 * fraction - 1.0;
+            [t, 1.0 - t, 0.0, 0.9]
+        }
+    }
+
+    pub fn to_line_vertices(rings: &[FieldRing]) -> Vec<LineVertex> {
+        let mut vertices = Vec::with_capacity(rings.len() * Self::SEGMENTS * 2);
+        for ring in rings {
+            let point = |i: usize| {
+                let angle = i as f32 * std::f32::consts::PI * 2.0 / Self::SEGMENTS as f32;
+                LineVertex {
+                    position: [
+                        ring.center[0] + ring.radius * angle.cos(),
+                        ring.center[1] + ring.radius * angle.sin(),
+                    ],
+                    color: ring.color,
+                }
+            };
+            for i in 0..Self::SEGMENTS {
+                vertices.push(point(i));
+                vertices.push(point(i + 1));
+            }
+        }
+        vertices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use evo_domain::biology::cell::Cell;
+    use evo_domain::physics::quantities::*;
+
+    #[test]
+    fn crowding_counts_touching_cells() {
+        let world =
+            World::new(Position::new(-10.0, -10.0), Position::new(10.0, 10.0)).with_cells(vec![
+                ball(Position::new(0.0, 0.0)),
+                ball(Position::new(1.5, 0.0)),
+                ball(Position::new(0.0, 1.9)),
+                ball(Position::new(5.0, 0.0)),
+            ]);
+
+        assert_eq!(CrowdingField.cell_values(&world), vec![2.0, 1.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn rings_are_colored_by_fraction_of_range() {
+        struct Fixed;
+        impl DerivedField for Fixed {
+            fn cell_values(&self, _world: &World) -> Vec<f32> {
+                vec![0.0, 5.0, 20.0]
+            }
+
+            fn range(&self, _values: &[f32]) -> (f32, f32) {
+                (0.0, 10.0)
+            }
+        }
+        let world =
+            World::new(Position::new(-10.0, -10.0), Position::new(10.0, 10.0)).with_cells(vec![
+                ball(Position::new(-5.0, 0.0)),
+                ball(Position::new(0.0, 0.0)),
+                ball(Position::new(5.0, 0.0)),
+            ]);
+
+        let rings = FieldRing::for_field(&Fixed, 1, &world);
+
+        assert_eq!(rings[0].color, [0.0, 0.0, 1.0, 0.9]);
+        assert_eq!(rings[1].color, [0.0, 1.0, 0.0, 0.9]);
+        assert_eq!(rings[2].color, [1.0, 0.0, 0.0, 0.9]);
+        assert_eq!(rings[2].center, [5.0, 0.0]);
+        assert_eq!(rings[2].radius, 1.3);
+    }
+
+    fn ball(position: Position) -> Cell {
+        Cell::ball(Length::new(1.0), Mass::new(1.0), position, Velocity::ZERO)
+    }
+}
@@ -0,0 +1,122 @@
+use crate::line_drawing::LineVertex;
+use crate::Point;
+
+// A force drawn as an arrow from the center of the cell it acts on.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ForceArrow {
+    pub tail: Point,
+    pub force: Point,
+    pub color: [f32; 4],
+}
+
+impl ForceArrow {
+    pub const NET_FORCE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.8];
+    const COMPONENT_COLORS: [[f32; 4]; 6] = [
+        [1.0, 0.3, 0.3, 0.9],
+        [0.3, 1.0, 0.3, 0.9],
+        [0.3, 0.5, 1.0, 0.9],
+        [1.0, 1.0, 0.3, 0.9],
+        [1.0, 0.3, 1.0, 0.9],
+        [0.3, 1.0, 1.0, 0.9],
+    ];
+
+    // Same label, same color, from frame to frame.
+    pub fn component_color(label: &str) -> [f32; 4] {
+        let hash = label.bytes().fold(0_usize, |hash, byte| {
+            hash.wrapping_mul(31).wrapping_add(byte as usize)
+        });
+        Self::COMPONENT_COLORS[hash % Self::COMPONENT_COLORS.len()]
+    }
+
+    // Scales all the arrows together so that the strongest force is max_length long.
+    pub fn to_line_vertices(arrows: &[ForceArrow], max_length: f32) -> Vec<LineVertex> {
+        const HEAD_FRACTION: f32 = 0.25;
+
+        let max_force = arrows
+            .iter()
+            .map(|arrow| Self::length(arrow.force))
+            .fold(0.0, f32::max);
+        if max_force == 0.0 {
+            return vec![];
+        }
+
+        let scale = max_length / max_force;
+        let mut vertices = Vec::with_capacity(arrows.len() * 6);
+        for arrow in arrows {
+            if arrow.force == [0.0, 0.0] {
+                continue;
+            }
+            let shaft = [arrow.force[0] * scale, arrow.force[1] * scale];
+            let head = [arrow.tail[0] + shaft[0], arrow.tail[1] + shaft[1]];
+            let back = [-shaft[0] * HEAD_FRACTION, -shaft[1] * HEAD_FRACTION];
+            // the two barbs are the backward vector turned 30 degrees each way
+            let (sin, cos) = (0.5_f32, 0.75_f32.sqrt());
+            let barb1 = [back[0] * cos - back[1] * sin, back[0] * sin + back[1] * cos];
+            let barb2 = [
+                back[0] * cos + back[1] * sin,
+                -back[0] * sin + back[1] * cos,
+            ];
+            let ends = [
+                arrow.tail,
+                [head[0] + barb1[0], head[1] + barb1[1]],
+                [head[0] + barb2[0], head[1] + barb2[1]],
+            ];
+            for end in &ends {
+                vertices.push(LineVertex {
+                    position: head,
+                    color: arrow.color,
+                });
+                vertices.push(LineVertex {
+                    position: *end,
+                    color: arrow.color,
+                });
+            }
+        }
+        vertices
+    }
+
+    fn length(vector: Point) -> f32 {
+        (vector[0] * vector[0] + vector[1] * vector[1]).sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strongest_force_gets_max_length() {
+        let arrows = [
+            force_arrow([0.0, 0.0], [2.0, 0.0]),
+            force_arrow([5.0, 5.0], [0.0, -1.0]),
+        ];
+
+        let vertices = ForceArrow::to_line_vertices(&arrows, 10.0);
+
+        assert_eq!(vertices.len(), 12);
+        assert_eq!(vertices[0].position, [10.0, 0.0]);
+        assert_eq!(vertices[1].position, [0.0, 0.0]);
+        assert_eq!(vertices[6].position, [5.0, 0.0]);
+        assert_eq!(vertices[7].position, [5.0, 5.0]);
+    }
+
+    #[test]
+    fn zero_forces_are_not_drawn() {
+        let arrows = [force_arrow([0.0, 0.0], [0.0, 0.0])];
+        assert!(ForceArrow::to_line_vertices(&arrows, 10.0).is_empty());
+    }
+
+    #[test]
+    fn long_labels_get_colors() {
+        let label = "a force label long enough to overflow its hash".repeat(10);
+        assert!(ForceArrow::COMPONENT_COLORS.contains(&ForceArrow::component_color(&label)));
+    }
+
+    fn force_arrow(tail: Point, force: Point) -> ForceArrow {
+        ForceArrow {
+            tail,
+            force,
+            color: ForceArrow::NET_FORCE_COLOR,
+        }
+    }
+}
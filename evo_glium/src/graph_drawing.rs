@@ -0,0 +1,230 @@
+use crate::line_drawing::LineVertex;
+use crate::stroke_text::StrokeText;
+use crate::Point;
+use evo_domain::stats::StatsHistory;
+
+// A strip chart of a world's population and total cell energy over its most recent
+// ticks, drawn in the top right corner of the world, to show at a glance whether the
+// population is stable, booming, or dying off. Each line is scaled to its own maximum
+// over the ticks shown, and labeled with that maximum.
+pub struct PopulationGraph {
+    // (population, total energy) by tick
+    history: StatsHistory<(f32, f32)>,
+    tick_span: u64,
+}
+
+impl PopulationGraph {
+    const FRAME_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.3];
+    const POPULATION_COLOR: [f32; 4] = [0.3, 0.9, 1.0, 0.9];
+    const ENERGY_COLOR: [f32; 4] = [1.0, 0.8, 0.2, 0.9];
+    // fractions of the world's width and height
+    const WIDTH: f32 = 0.3;
+    const HEIGHT: f32 = 0.15;
+
+    pub fn new(tick_span: u64) -> Self {
+        PopulationGraph {
+            // at most one sample per tick, so the newer half, which is never thinned,
+            // holds the whole span
+            history: StatsHistory::new(2 * (tick_span as usize + 1)),
+            tick_span,
+        }
+    }
+
+    // Samples for ticks no later than the latest one, e.g. when stepping back, are
+    // skipped.
+    pub fn record(&mut self, tick: u64, population: usize, total_energy: f32) {
+        if let Some((latest_tick, _)) = self.history.samples().last() {
+            if tick <= *latest_tick {
+                return;
+            }
+        }
+        self.history.add(tick, (population as f32, total_energy));
+    }
+
+    // the samples no older than the tick span, oldest first
+    fn samples(&self) -> &[(u64, (f32, f32))] {
+        let samples = self.history.samples();
+        let latest_tick = match samples.last() {
+            Some((tick, _)) => *tick,
+            None => return samples,
+        };
+        let start = samples.partition_point(|(tick, _)| latest_tick - tick > self.tick_span);
+        &samples[start..]
+    }
+
+    pub fn line_vertices(&self, min_corner: Point, max_corner: Point) -> Vec<LineVertex> {
+        let world_size = [max_corner[0] - min_corner[0], max_corner[1] - min_corner[1]];
+        let margin = world_size[1] / 60.0;
+        let size = [Self::WIDTH * world_size[0], Self::HEIGHT * world_size[1]];
+        let max_corner = [max_corner[0] - margin, max_corner[1] - margin];
+        let min_corner = [max_corner[0] - size[0], max_corner[1] - size[1]];
+
+        let mut vertices = vec![];
+        Self::add_frame(&mut vertices, min_corner, max_corner);
+        let (latest_tick, max_population, max_energy) = match self.maximums() {
+            Some(maximums) => maximums,
+            None => return vertices,
+        };
+        let to_point = |tick: u64, value: f32, max_value: f32| {
+            let age = (latest_tick - tick) as f32 / self.tick_span as f32;
+            let height = if max_value > 0.0 {
+                value / max_value
+            } else {
+                0.0
+            };
+            [
+                max_corner[0] - age * size[0],
+                min_corner[1] + height * size[1],
+            ]
+        };
+        let samples = self.samples();
+        for ((tick1, sample1), (tick2, sample2)) in samples.iter().zip(samples.iter().skip(1)) {
+            Self::add_line(
+                &mut vertices,
+                to_point(*tick1, sample1.0, max_population),
+                to_point(*tick2, sample2.0, max_population),
+                Self::POPULATION_COLOR,
+            );
+            Self::add_line(
+                &mut vertices,
+                to_point(*tick1, sample1.1, max_energy),
+                to_point(*tick2, sample2.1, max_energy),
+                Self::ENERGY_COLOR,
+            );
+        }
+
+        let text_height = size[1] / 8.0;
+        let label_top = min_corner[1] - 1.5 * text_height;
+        vertices.extend(StrokeText::line_vertices(
+            &format!("{}", max_population),
+            [min_corner[0], label_top],
+            text_height,
+            Self::POPULATION_COLOR,
+        ));
+        let energy_label = format!("E {:.1}", max_energy);
+        vertices.extend(StrokeText::line_vertices(
+            &energy_label,
+            [
+                max_corner[0] - StrokeText::width(&energy_label, text_height),
+                label_top,
+            ],
+            text_height,
+            Self::ENERGY_COLOR,
+        ));
+        vertices
+    }
+
+    // the latest tick and the maximum population and energy, or None if there are no
+    // samples
+    fn maximums(&self) -> Option<(u64, f32, f32)> {
+        let samples = self.samples();
+        let (latest_tick, _) = samples.last()?;
+        let (max_population, max_energy) = samples.iter().fold(
+            (0.0_f32, 0.0_f32),
+            |(max_population, max_energy), (_, (population, energy))| {
+                (max_population.max(*population), max_energy.max(*energy))
+            },
+        );
+        Some((*latest_tick, max_population, max_energy))
+    }
+
+    fn add_frame(vertices: &mut Vec<LineVertex>, min_corner: Point, max_corner: Point) {
+        let corners = [
+            min_corner,
+            [max_corner[0], min_corner[1]],
+            max_corner,
+            [min_corner[0], max_corner[1]],
+        ];
+        for i in 0..corners.len() {
+            Self::add_line(
+                vertices,
+                corners[i],
+                corners[(i + 1) % corners.len()],
+                Self::FRAME_COLOR,
+            );
+        }
+    }
+
+    fn add_line(vertices: &mut Vec<LineVertex>, from: Point, to: Point, color: [f32; 4]) {
+        vertices.push(LineVertex {
+            position: from,
+            color,
+        });
+        vertices.push(LineVertex {
+            position: to,
+            color,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drops_samples_older_than_tick_span() {
+        let mut graph = PopulationGraph::new(10);
+        graph.record(0, 5, 1.0);
+        graph.record(5, 6, 2.0);
+        graph.record(15, 7, 3.0);
+
+        assert_eq!(
+            graph
+                .samples()
+                .iter()
+                .map(|sample| sample.0)
+                .collect::<Vec<_>>(),
+            vec![5, 15]
+        );
+    }
+
+    #[test]
+    fn keeps_every_sample_in_tick_span_of_long_run() {
+        let mut graph = PopulationGraph::new(10);
+        for tick in 0..1000 {
+            graph.record(tick, 1, 1.0);
+        }
+
+        assert_eq!(
+            graph
+                .samples()
+                .iter()
+                .map(|sample| sample.0)
+                .collect::<Vec<_>>(),
+            (989..1000).collect::<Vec<_>>()
+        );
+        assert!(graph.history.samples().len() <= 22);
+    }
+
+    #[test]
+    fn skips_samples_not_after_latest_tick() {
+        let mut graph = PopulationGraph::new(10);
+        graph.record(5, 6, 2.0);
+        graph.record(4, 1, 1.0);
+        graph.record(5, 1, 1.0);
+
+        assert_eq!(graph.samples().len(), 1);
+    }
+
+    #[test]
+    fn lines_span_graph_and_reach_top_at_maximum() {
+        let mut graph = PopulationGraph::new(10);
+        graph.record(0, 5, 4.0);
+        graph.record(10, 10, 2.0);
+
+        let vertices = graph.line_vertices([0.0, 0.0], [100.0, 60.0]);
+
+        // four frame sides, then the population and energy lines
+        let population_line = &vertices[8..10];
+        let energy_line = &vertices[10..12];
+        let top = 60.0 - 1.0;
+        let bottom = top - 0.15 * 60.0;
+        assert_eq!(
+            population_line[0].position,
+            [99.0 - 30.0, bottom + 0.5 * 9.0]
+        );
+        assert_eq!(population_line[1].position, [99.0, top]);
+        assert_eq!(energy_line[0].position, [99.0 - 30.0, top]);
+        assert_eq!(energy_line[1].position, [99.0, bottom + 0.5 * 9.0]);
+    }
+}
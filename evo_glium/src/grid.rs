@@ -0,0 +1,163 @@
+use crate::line_drawing::LineVertex;
+use crate::stroke_text::StrokeText;
+use crate::Point;
+
+// Grid lines with coordinate labels, plus a scale bar, over the visible part of the
+// world. The line spacing follows the visible extent, so zooming in gives finer lines.
+pub struct Grid;
+
+impl Grid {
+    const TARGET_LINES_ACROSS: f32 = 10.0;
+    const LINE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.15];
+    const LABEL_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.5];
+    const SCALE_BAR_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.9];
+
+    pub fn line_vertices(min_corner: Point, max_corner: Point) -> Vec<LineVertex> {
+        let extent = (max_corner[0] - min_corner[0]).max(max_corner[1] - min_corner[1]);
+        let spacing = Self::spacing(extent);
+        let text_height = extent / 80.0;
+        let margin = text_height / 2.0;
+
+        let mut vertices = vec![];
+        for x in Self::multiples_between(spacing, min_corner[0], max_corner[0]) {
+            Self::add_line(
+                &mut vertices,
+                [x, min_corner[1]],
+                [x, max_corner[1]],
+                Self::LINE_COLOR,
+            );
+            vertices.extend(StrokeText::line_vertices(
+                &Self::format_label(x, spacing),
+                [x + margin, min_corner[1] + margin],
+                text_height,
+                Self::LABEL_COLOR,
+            ));
+        }
+        for y in Self::multiples_between(spacing, min_corner[1], max_corner[1]) {
+            Self::add_line(
+                &mut vertices,
+                [min_corner[0], y],
+                [max_corner[0], y],
+                Self::LINE_COLOR,
+            );
+            vertices.extend(StrokeText::line_vertices(
+                &Self::format_label(y, spacing),
+                [min_corner[0] + margin, y + margin],
+                text_height,
+                Self::LABEL_COLOR,
+            ));
+        }
+        Self::add_scale_bar(
+            &mut vertices,
+            max_corner[0],
+            min_corner[1],
+            spacing,
+            text_height,
+        );
+        vertices
+    }
+
+    // A bar one grid spacing long in the bottom right corner, labeled with its length.
+    fn add_scale_bar(
+        vertices: &mut Vec<LineVertex>,
+        right: f32,
+        bottom: f32,
+        length: f32,
+        text_height: f32,
+    ) {
+        let y = bottom + 3.0 * text_height;
+        let x2 = right - 2.0 * text_height;
+        let x1 = x2 - length;
+        let tick = text_height / 2.0;
+        Self::add_line(vertices, [x1, y], [x2, y], Self::SCALE_BAR_COLOR);
+        Self::add_line(
+            vertices,
+            [x1, y - tick],
+            [x1, y + tick],
+            Self::SCALE_BAR_COLOR,
+        );
+        Self::add_line(
+            vertices,
+            [x2, y - tick],
+            [x2, y + tick],
+            Self::SCALE_BAR_COLOR,
+        );
+        let label = Self::format_label(length, length);
+        vertices.extend(StrokeText::line_vertices(
+            &label,
+            [
+                (x1 + x2 - StrokeText::width(&label, text_height)) / 2.0,
+                y + tick,
+            ],
+            text_height,
+            Self::SCALE_BAR_COLOR,
+        ));
+    }
+
+    fn add_line(vertices: &mut Vec<LineVertex>, end1: Point, end2: Point, color: [f32; 4]) {
+        vertices.push(LineVertex {
+            position: end1,
+            color,
+        });
+        vertices.push(LineVertex {
+            position: end2,
+            color,
+        });
+    }
+
+    // The 1, 2, or 5 times a power of ten that puts about TARGET_LINES_ACROSS lines
+    // across the extent.
+    fn spacing(extent: f32) -> f32 {
+        let rough_spacing = extent / Self::TARGET_LINES_ACROSS;
+        let magnitude = 10_f32.powf(rough_spacing.log10().floor());
+        let residual = rough_spacing / magnitude;
+        let nice_residual = if residual < 1.5 {
+            1.0
+        } else if residual < 3.5 {
+            2.0
+        } else if residual < 7.5 {
+            5.0
+        } else {
+            10.0
+        };
+        nice_residual * magnitude
+    }
+
+    fn multiples_between(spacing: f32, min: f32, max: f32) -> impl Iterator<Item = f32> {
+        let first = (min / spacing).ceil() as i64;
+        let last = (max / spacing).floor() as i64;
+        (first..=last).map(move |i| i as f32 * spacing)
+    }
+
+    fn format_label(value: f32, spacing: f32) -> String {
+        let decimals = (-spacing.log10().floor()).max(0.0) as usize;
+        // adding zero turns -0.0 into 0.0
+        let value = (value / spacing).round() * spacing + 0.0;
+        format!("{:.*}", decimals, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spacing_is_a_round_number() {
+        assert_eq!(Grid::spacing(1200.0), 100.0);
+        assert_eq!(Grid::spacing(300.0), 20.0);
+        assert_eq!(Grid::spacing(50.0), 5.0);
+    }
+
+    #[test]
+    fn fractional_spacing_gets_decimal_labels() {
+        assert_eq!(Grid::format_label(1.5, 0.5), "1.5");
+        assert_eq!(Grid::format_label(-100.0, 100.0), "-100");
+        assert_eq!(Grid::format_label(-0.00001, 0.5), "0.0");
+    }
+
+    #[test]
+    fn grid_lines_fall_on_multiples_of_spacing() {
+        let lines = Grid::multiples_between(100.0, -250.0, 250.0).collect::<Vec<_>>();
+        assert_eq!(lines, vec![-200.0, -100.0, 0.0, 100.0, 200.0]);
+    }
+}
@@ -0,0 +1,99 @@
+use crate::Point;
+use std::collections::HashMap;
+
+// What identifies a drawn cell across ticks, and how fast it is moving, so that frames
+// drawn between two ticks can show it partway along its path.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CellMotion {
+    pub cell_id: u64,
+    pub velocity: Point,
+}
+
+// Moves points that sit on a current cell center to where that cell is drawn in an
+// interpolated frame. Bond ends, anchors, force arrows, and trail points are all copied
+// from cell centers, so matching on the exact center moves them along with their cells.
+pub struct CellShifter {
+    offsets: HashMap<[u32; 2], Point>,
+}
+
+impl CellShifter {
+    // A fraction of 0 puts each cell where it was in the previous snapshot and 1 where
+    // it is in the current one. A cell that is new since the previous snapshot has no
+    // earlier position, so it is placed back along its velocity instead.
+    pub fn new(
+        previous_centers: &HashMap<u64, Point>,
+        current_centers: &[Point],
+        current_motions: &[CellMotion],
+        ticks_between: u64,
+        fraction: f32,
+    ) -> Self {
+        let remaining = 1.0 - fraction;
+        let offsets = current_centers
+            .iter()
+            .zip(current_motions)
+            .map(|(center, motion)| {
+                let offset = match previous_centers.get(&motion.cell_id) {
+                    Some(previous) => [
+                        (previous[0] - center[0]) * remaining,
+                        (previous[1] - center[1]) * remaining,
+                    ],
+                    None => {
+                        let time = remaining * ticks_between as f32;
+                        [-motion.velocity[0] * time, -motion.velocity[1] * time]
+                    }
+                };
+                (Self::key(*center), offset)
+            })
+            .collect();
+        CellShifter { offsets }
+    }
+
+    pub fn shift(&self, point: Point) -> Point {
+        match self.offsets.get(&Self::key(point)) {
+            Some(offset) => [point[0] + offset[0], point[1] + offset[1]],
+            None => point,
+        }
+    }
+
+    fn key(point: Point) -> [u32; 2] {
+        [point[0].to_bits(), point[1].to_bits()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cell_in_both_snapshots_moves_partway() {
+        let previous = [(7, [0.0, 0.0])].iter().cloned().collect();
+        let shifter = CellShifter::new(&previous, &[[4.0, 2.0]], &[motion(7, [0.0, 0.0])], 1, 0.25);
+
+        assert_eq!(shifter.shift([4.0, 2.0]), [1.0, 0.5]);
+    }
+
+    #[test]
+    fn new_cell_moves_back_along_velocity() {
+        let shifter = CellShifter::new(
+            &HashMap::new(),
+            &[[4.0, 2.0]],
+            &[motion(7, [1.0, -1.0])],
+            2,
+            0.5,
+        );
+
+        assert_eq!(shifter.shift([4.0, 2.0]), [3.0, 3.0]);
+    }
+
+    #[test]
+    fn points_off_cell_centers_stay_put() {
+        let previous = [(7, [0.0, 0.0])].iter().cloned().collect();
+        let shifter = CellShifter::new(&previous, &[[4.0, 2.0]], &[motion(7, [0.0, 0.0])], 1, 0.5);
+
+        assert_eq!(shifter.shift([3.0, 3.0]), [3.0, 3.0]);
+    }
+
+    fn motion(cell_id: u64, velocity: Point) -> CellMotion {
+        CellMotion { cell_id, velocity }
+    }
+}
@@ -0,0 +1,246 @@
+use evo_domain::UserAction;
+use glium::glutin::VirtualKeyCode;
+use std::collections::HashMap;
+
+// Which key triggers which user action. The defaults can be changed from a text file
+// with one "key = action" line per binding, using the names below, e.g. "Space =
+// PlayToggle". A binding replaces the default one for the same key, and "None" as the
+// action frees the key. Blank lines and lines starting with # are skipped.
+#[derive(Clone, Debug)]
+pub struct KeyBindings {
+    actions: HashMap<VirtualKeyCode, UserAction>,
+}
+
+impl KeyBindings {
+    pub fn action_for(&self, key_code: VirtualKeyCode) -> Option<UserAction> {
+        self.actions.get(&key_code).copied()
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut bindings = Self::default();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            bindings
+                .parse_binding(line)
+                .map_err(|message| format!("line {}: {}", index + 1, message))?;
+        }
+        Ok(bindings)
+    }
+
+    fn parse_binding(&mut self, line: &str) -> Result<(), String> {
+        let mut parts = line.splitn(2, '=');
+        let key_name = parts.next().unwrap().trim();
+        let action_name = parts
+            .next()
+            .ok_or_else(|| format!("expected \"key = action\", got \"{}\"", line))?
+            .trim();
+        let key_code = Self::find_by_name(BINDABLE_KEYS, key_name)
+            .ok_or_else(|| format!("unknown key \"{}\"", key_name))?;
+        if action_name.eq_ignore_ascii_case("None") {
+            self.actions.remove(&key_code);
+        } else {
+            let action = Self::find_by_name(BINDABLE_ACTIONS, action_name)
+                .ok_or_else(|| format!("unknown action \"{}\"", action_name))?;
+            self.actions.insert(key_code, action);
+        }
+        Ok(())
+    }
+
+    fn find_by_name<T: Copy + std::fmt::Debug>(candidates: &[T], name: &str) -> Option<T> {
+        candidates
+            .iter()
+            .find(|candidate| format!("{:?}", candidate).eq_ignore_ascii_case(name))
+            .copied()
+    }
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let actions = [
+            (VirtualKeyCode::D, UserAction::DebugPrint),
+            (VirtualKeyCode::E, UserAction::ExportSelected),
+            (VirtualKeyCode::J, UserAction::ExportSelectedGenome),
+            (VirtualKeyCode::L, UserAction::FollowSelectionToggle),
+            (VirtualKeyCode::N, UserAction::NetDiagramToggle),
+            (VirtualKeyCode::Escape, UserAction::Exit),
+            (VirtualKeyCode::Q, UserAction::Exit),
+            (VirtualKeyCode::X, UserAction::Exit),
+            (VirtualKeyCode::F, UserAction::FastForwardToggle),
+            (VirtualKeyCode::G, UserAction::GridToggle),
+            (VirtualKeyCode::O, UserAction::OverlayToggle),
+            (VirtualKeyCode::P, UserAction::PlayToggle),
+            (VirtualKeyCode::C, UserAction::PopulationGraphToggle),
+            (VirtualKeyCode::S, UserAction::Screenshot),
+            (VirtualKeyCode::T, UserAction::SingleTick),
+            (VirtualKeyCode::B, UserAction::StepBack),
+            (VirtualKeyCode::Comma, UserAction::SlowDown),
+            (VirtualKeyCode::Period, UserAction::SpeedUp),
+            (VirtualKeyCode::Equals, UserAction::ZoomIn),
+            (VirtualKeyCode::Add, UserAction::ZoomIn),
+            (VirtualKeyCode::Minus, UserAction::ZoomOut),
+            (VirtualKeyCode::Subtract, UserAction::ZoomOut),
+            (VirtualKeyCode::Home, UserAction::ZoomToFit),
+            (VirtualKeyCode::Key0, UserAction::ZoomToFit),
+            (VirtualKeyCode::Z, UserAction::ZoomToSelection),
+            (VirtualKeyCode::Back, UserAction::ViewBack),
+            (VirtualKeyCode::W, UserAction::WarningPanelToggle),
+        ]
+        .iter()
+        .copied()
+        .collect();
+        KeyBindings { actions }
+    }
+}
+
+// Every action except those on a cell, which need a mouse position.
+const BINDABLE_ACTIONS: &[UserAction] = &[
+    UserAction::DebugPrint,
+    UserAction::Exit,
+    UserAction::ExportSelected,
+    UserAction::ExportSelectedGenome,
+    UserAction::FastForwardToggle,
+    UserAction::FollowSelectionToggle,
+    UserAction::GridToggle,
+    UserAction::NetDiagramToggle,
+    UserAction::OverlayToggle,
+    UserAction::PlayToggle,
+    UserAction::PopulationGraphToggle,
+    UserAction::Screenshot,
+    UserAction::SingleTick,
+    UserAction::SlowDown,
+    UserAction::SpeedUp,
+    UserAction::StepBack,
+    UserAction::ViewBack,
+    UserAction::WarningPanelToggle,
+    UserAction::ZoomIn,
+    UserAction::ZoomOut,
+    UserAction::ZoomToFit,
+    UserAction::ZoomToSelection,
+];
+
+const BINDABLE_KEYS: &[VirtualKeyCode] = &[
+    VirtualKeyCode::A,
+    VirtualKeyCode::B,
+    VirtualKeyCode::C,
+    VirtualKeyCode::D,
+    VirtualKeyCode::E,
+    VirtualKeyCode::F,
+    VirtualKeyCode::G,
+    VirtualKeyCode::H,
+    VirtualKeyCode::I,
+    VirtualKeyCode::J,
+    VirtualKeyCode::K,
+    VirtualKeyCode::L,
+    VirtualKeyCode::M,
+    VirtualKeyCode::N,
+    VirtualKeyCode::O,
+    VirtualKeyCode::P,
+    VirtualKeyCode::Q,
+    VirtualKeyCode::R,
+    VirtualKeyCode::S,
+    VirtualKeyCode::T,
+    VirtualKeyCode::U,
+    VirtualKeyCode::V,
+    VirtualKeyCode::W,
+    VirtualKeyCode::X,
+    VirtualKeyCode::Y,
+    VirtualKeyCode::Z,
+    VirtualKeyCode::Key0,
+    VirtualKeyCode::Key1,
+    VirtualKeyCode::Key2,
+    VirtualKeyCode::Key3,
+    VirtualKeyCode::Key4,
+    VirtualKeyCode::Key5,
+    VirtualKeyCode::Key6,
+    VirtualKeyCode::Key7,
+    VirtualKeyCode::Key8,
+    VirtualKeyCode::Key9,
+    VirtualKeyCode::F1,
+    VirtualKeyCode::F2,
+    VirtualKeyCode::F3,
+    VirtualKeyCode::F4,
+    VirtualKeyCode::F5,
+    VirtualKeyCode::F6,
+    VirtualKeyCode::F7,
+    VirtualKeyCode::F8,
+    VirtualKeyCode::F9,
+    VirtualKeyCode::F10,
+    VirtualKeyCode::F11,
+    VirtualKeyCode::F12,
+    VirtualKeyCode::Escape,
+    VirtualKeyCode::Space,
+    VirtualKeyCode::Return,
+    VirtualKeyCode::Back,
+    VirtualKeyCode::Tab,
+    VirtualKeyCode::Left,
+    VirtualKeyCode::Right,
+    VirtualKeyCode::Up,
+    VirtualKeyCode::Down,
+    VirtualKeyCode::Home,
+    VirtualKeyCode::End,
+    VirtualKeyCode::PageUp,
+    VirtualKeyCode::PageDown,
+    VirtualKeyCode::Comma,
+    VirtualKeyCode::Period,
+    VirtualKeyCode::Slash,
+    VirtualKeyCode::Semicolon,
+    VirtualKeyCode::Apostrophe,
+    VirtualKeyCode::LBracket,
+    VirtualKeyCode::RBracket,
+    VirtualKeyCode::Backslash,
+    VirtualKeyCode::Grave,
+    VirtualKeyCode::Minus,
+    VirtualKeyCode::Equals,
+    VirtualKeyCode::Add,
+    VirtualKeyCode::Subtract,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_rebinds_and_frees_keys() {
+        let bindings = KeyBindings::parse(
+            "# play with the space bar\n\
+             Space = PlayToggle\n\
+             \n\
+             p = none\n\
+             Q = Zoomin\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            bindings.action_for(VirtualKeyCode::Space),
+            Some(UserAction::PlayToggle)
+        );
+        assert_eq!(bindings.action_for(VirtualKeyCode::P), None);
+        assert_eq!(
+            bindings.action_for(VirtualKeyCode::Q),
+            Some(UserAction::ZoomIn)
+        );
+        assert_eq!(
+            bindings.action_for(VirtualKeyCode::T),
+            Some(UserAction::SingleTick)
+        );
+    }
+
+    #[test]
+    fn config_errors_name_the_line() {
+        assert_eq!(
+            KeyBindings::parse("Space = PlayToggle\nSpace PlayToggle").unwrap_err(),
+            "line 2: expected \"key = action\", got \"Space PlayToggle\""
+        );
+        assert_eq!(
+            KeyBindings::parse("Hyper = Exit").unwrap_err(),
+            "line 1: unknown key \"Hyper\""
+        );
+        assert_eq!(
+            KeyBindings::parse("K = Explode").unwrap_err(),
+            "line 1: unknown action \"Explode\""
+        );
+    }
+}
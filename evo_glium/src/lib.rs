@@ -1,21 +1,58 @@
 use glium::{glutin, Surface};
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 
 mod background_drawing;
 mod bond_drawing;
+mod bond_slots;
 mod cell_drawing;
+mod cell_inspector;
 mod cloud_drawing;
+mod coordinate_transform;
+mod derived_fields;
+mod force_arrows;
+mod graph_drawing;
+mod grid;
+mod interpolation;
+mod key_bindings;
+mod line_drawing;
+mod net_diagram;
+mod png;
+mod stroke_text;
+mod trails;
+mod vertex_buffers;
+mod warning_panel;
 
 use background_drawing::*;
 use bond_drawing::*;
+use bond_slots::*;
 use cell_drawing::*;
+use cell_inspector::*;
 use cloud_drawing::*;
-use evo_domain::biology::cell::Cell;
-use evo_domain::biology::cloud::Cloud;
+use coordinate_transform::*;
+use derived_fields::*;
 use evo_domain::biology::layers;
-use evo_domain::physics::bond::Bond;
-use evo_domain::physics::node_graph::GraphEdge;
-use evo_domain::physics::shapes::Circle;
+use evo_domain::physics::newtonian::NewtonianBody;
+use evo_domain::physics::node_graph::{GraphEdge, GraphNode};
+use evo_domain::physics::quantities::Position;
+use evo_domain::physics::shapes::{Circle, Rectangle};
+use evo_domain::render_frame::{BondFrame, CellFrame, CloudFrame, RenderFrame};
 use evo_domain::UserAction;
+use force_arrows::*;
+use graph_drawing::*;
+use grid::*;
+use interpolation::*;
+use line_drawing::*;
+use net_diagram::*;
+use trails::*;
+use vertex_buffers::*;
+use warning_panel::*;
+
+pub use derived_fields::{CrowdingField, DerivedField, LightField};
+pub use key_bindings::KeyBindings;
+pub use trails::TrailMode;
 
 type Point = [f32; 2];
 
@@ -28,20 +65,55 @@ pub struct GliumView {
     cell_drawing: CellDrawing,
     bond_drawing: BondDrawing,
     cloud_drawing: CloudDrawing,
+    line_drawing: LineDrawing,
+    viewport_count: usize,
+    trail_mode: TrailMode,
+    trails: Vec<Trails>,
+    show_population_graph: bool,
+    population_graphs: Vec<PopulationGraph>,
+    show_net_diagram: bool,
+    show_warning_panel: bool,
+    show_force_arrows: bool,
+    derived_fields: Vec<Box<dyn DerivedField>>,
+    show_grid: bool,
+    show_tissue_styles: bool,
+    // hides the grid, trails, force arrows, and selected-cell markings all at once
+    show_overlays: bool,
+    zoom: f32,
+    // the world position shown at the middle of each viewport
+    view_center: Point,
+    // earlier zooms and centers, most recent last, for going back to them
+    view_history: Vec<(f32, Point)>,
+    // while zoomed in, keeps the selected cells in the middle of the view
+    follow_selection: bool,
+    key_bindings: KeyBindings,
+    start_time: Instant,
+    frame_dump: Option<FrameDump>,
+    last_drawn_tick: u64,
+    pick_radius: f32,
     world_vb: glium::VertexBuffer<World>,
+    viewport_buffers: Vec<ViewportBuffers>,
     mouse_position: glutin::dpi::LogicalPosition,
 }
 
 impl GliumView {
     pub fn new(world_min_corner: Point, world_max_corner: Point) -> Self {
+        Self::new_side_by_side(world_min_corner, world_max_corner, 1)
+    }
+
+    // Draws several worlds with the same corners next to each other, left to right,
+    // all at the same scale.
+    pub fn new_side_by_side(
+        world_min_corner: Point,
+        world_max_corner: Point,
+        viewport_count: usize,
+    ) -> Self {
+        assert!(viewport_count >= 1);
         let events_loop = glutin::EventsLoop::new();
         let window = glutin::WindowBuilder::new().with_dimensions(Self::calc_initial_window_size(
-            (
-                (world_max_corner[0] - world_min_corner[0]) as f64,
-                (world_max_corner[1] - world_min_corner[1]) as f64,
-            ),
+            Self::total_world_size(world_min_corner, world_max_corner, viewport_count),
             Self::get_screen_size(events_loop.get_primary_monitor()),
-            0.9,
+            Self::MAX_FRACTION_OF_SCREEN,
         ));
         let context = glutin::ContextBuilder::new().with_vsync(true);
         // .with_multisampling(4); TODO apparently does nothing
@@ -50,6 +122,7 @@ impl GliumView {
         let cell_drawing = CellDrawing::new(&display);
         let bond_drawing = BondDrawing::new(&display);
         let cloud_drawing = CloudDrawing::new(&display);
+        let line_drawing = LineDrawing::new(&display);
         let world = vec![World {
             corners: [
                 world_min_corner[0],
@@ -71,17 +144,298 @@ impl GliumView {
             cell_drawing,
             bond_drawing,
             cloud_drawing,
+            line_drawing,
+            viewport_count,
+            trail_mode: TrailMode::Off,
+            trails: vec![],
+            show_population_graph: false,
+            population_graphs: vec![],
+            show_net_diagram: false,
+            show_warning_panel: true,
+            show_force_arrows: false,
+            derived_fields: vec![],
+            show_grid: false,
+            show_tissue_styles: false,
+            show_overlays: true,
+            zoom: 1.0,
+            view_center: CoordinateTransform::midpoint(world_min_corner, world_max_corner),
+            view_history: vec![],
+            follow_selection: false,
+            key_bindings: KeyBindings::default(),
+            start_time: Instant::now(),
+            frame_dump: None,
+            last_drawn_tick: 0,
+            pick_radius: Self::DEFAULT_PICK_RADIUS,
             world_vb,
+            viewport_buffers: (0..viewport_count)
+                .map(|_| ViewportBuffers::new())
+                .collect(),
             mouse_position: glutin::dpi::LogicalPosition::new(0.0, 0.0),
         }
     }
 
+    const DEFAULT_TRAIL_LENGTH: usize = 60;
+    const POPULATION_GRAPH_TICKS: u64 = 3000;
+    const DEFAULT_PICK_RADIUS: f32 = 5.0;
+    const MAX_FRACTION_OF_SCREEN: f64 = 0.9;
+    const ZOOM_STEP: f32 = 1.25;
+    const MAX_ZOOM: f32 = 32.0;
+    const ZOOM_MARGIN: f32 = 0.25;
+    const MAX_VIEW_HISTORY: usize = 50;
+
+    // Draws each cell's positions over the last several frames.
+    pub fn with_trails(mut self, mode: TrailMode) -> Self {
+        self.trail_mode = mode;
+        self
+    }
+
+    // Draws an arrow for the net force on each cell, plus arrows for the individual
+    // forces on selected cells.
+    pub fn with_force_arrows(mut self, show_force_arrows: bool) -> Self {
+        self.show_force_arrows = show_force_arrows;
+        self
+    }
+
+    // Charts the population and total cell energy over the last few thousand ticks in
+    // the top right corner.
+    pub fn with_population_graph(mut self, show_population_graph: bool) -> Self {
+        self.show_population_graph = show_population_graph;
+        self
+    }
+
+    // Draws the neural net of the first selected cell that has one, with its node values
+    // as of the last tick, in the bottom right corner.
+    pub fn with_net_diagram(mut self, show_net_diagram: bool) -> Self {
+        self.show_net_diagram = show_net_diagram;
+        self
+    }
+
+    // Lists the world's most recent warnings, colored by severity, in the bottom left
+    // corner. On by default.
+    pub fn with_warning_panel(mut self, show_warning_panel: bool) -> Self {
+        self.show_warning_panel = show_warning_panel;
+        self
+    }
+
+    // Rings each cell in the color of its value of the field. Several fields are drawn
+    // as rings one outside the other, in the order they were added.
+    pub fn with_derived_field(mut self, field: Box<dyn DerivedField>) -> Self {
+        self.derived_fields.push(field);
+        self
+    }
+
+    pub fn with_grid(mut self, show_grid: bool) -> Self {
+        self.show_grid = show_grid;
+        self
+    }
+
+    // Marks each kind of tissue with a pattern as well as a color: stripes, stipple,
+    // a dashed outer ring, or a pulsing brightness.
+    pub fn with_tissue_styles(mut self, show_tissue_styles: bool) -> Self {
+        self.show_tissue_styles = show_tissue_styles;
+        self
+    }
+
+    pub fn with_key_bindings(mut self, key_bindings: KeyBindings) -> Self {
+        self.key_bindings = key_bindings;
+        self
+    }
+
+    // Writes every nth frame drawn to the directory as frame-000000.png, frame-000001.png,
+    // and so on, numbered in the order written, e.g. to make a video with
+    // "ffmpeg -i frame-%06d.png".
+    pub fn with_frame_dump(mut self, dir: PathBuf, every_nth_frame: u64) -> Self {
+        self.frame_dump = Some(FrameDump {
+            dir,
+            every_nth_frame: every_nth_frame.max(1),
+            frames_to_skip: 0,
+            next_file_number: 0,
+        });
+        self
+    }
+
+    // How far, in window units, a click can miss a cell and still select it. The same
+    // distance on screen covers more of the world when the world is drawn smaller.
+    pub fn with_pick_radius(mut self, pick_radius: f32) -> Self {
+        self.pick_radius = pick_radius;
+        self
+    }
+
+    // Resizes the window to draw the world at this many pixels per world unit, as long
+    // as the window still fits on the screen.
+    pub fn with_pixels_per_unit(self, pixels_per_unit: f64) -> Self {
+        let window_size = Self::calc_scaled_window_size(
+            Self::total_world_size(
+                self.world_min_corner,
+                self.world_max_corner,
+                self.viewport_count,
+            ),
+            pixels_per_unit,
+            Self::get_screen_size(self.events_loop.get_primary_monitor()),
+            Self::MAX_FRACTION_OF_SCREEN,
+        );
+        self.display
+            .gl_window()
+            .window()
+            .set_inner_size(window_size);
+        self
+    }
+
+    pub fn toggle_grid(&mut self) {
+        self.show_grid = !self.show_grid;
+    }
+
+    pub fn toggle_population_graph(&mut self) {
+        self.show_population_graph = !self.show_population_graph;
+    }
+
+    pub fn toggle_net_diagram(&mut self) {
+        self.show_net_diagram = !self.show_net_diagram;
+    }
+
+    pub fn toggle_warning_panel(&mut self) {
+        self.show_warning_panel = !self.show_warning_panel;
+    }
+
+    pub fn toggle_overlays(&mut self) {
+        self.show_overlays = !self.show_overlays;
+    }
+
+    pub fn toggle_follow_selection(&mut self) {
+        self.follow_selection = !self.follow_selection;
+    }
+
+    pub fn is_following_selection(&self) -> bool {
+        self.follow_selection
+    }
+
+    pub fn is_zoomed_in(&self) -> bool {
+        self.zoom > 1.0
+    }
+
+    // Magnifies the world about the view center, never smaller than the fit to the
+    // window.
+    pub fn zoom_in(&mut self) {
+        self.set_view(self.zoom * Self::ZOOM_STEP, self.view_center);
+    }
+
+    pub fn zoom_out(&mut self) {
+        self.set_view(self.zoom / Self::ZOOM_STEP, self.view_center);
+    }
+
+    // Shows the whole world, remembering the view before.
+    pub fn zoom_to_fit(&mut self) {
+        self.push_view_history();
+        self.set_view(1.0, self.view_center);
+    }
+
+    // Centers the world region in the view and zooms in until it, plus a margin around
+    // it, fills the view, remembering the view before.
+    pub fn zoom_to_region(&mut self, min_corner: Point, max_corner: Point) {
+        let margin = [
+            (max_corner[0] - min_corner[0]) * Self::ZOOM_MARGIN,
+            (max_corner[1] - min_corner[1]) * Self::ZOOM_MARGIN,
+        ];
+        let zoom = self.viewport_transforms()[0].zoom_to_show(
+            [min_corner[0] - margin[0], min_corner[1] - margin[1]],
+            [max_corner[0] + margin[0], max_corner[1] + margin[1]],
+        );
+        self.push_view_history();
+        self.set_view(zoom, CoordinateTransform::midpoint(min_corner, max_corner));
+    }
+
+    // Returns to the view before the last zoom to fit or to a region, if any.
+    pub fn view_back(&mut self) {
+        if let Some((zoom, center)) = self.view_history.pop() {
+            self.set_view(zoom, center);
+        }
+    }
+
+    fn push_view_history(&mut self) {
+        if self.view_history.len() == Self::MAX_VIEW_HISTORY {
+            self.view_history.remove(0);
+        }
+        self.view_history.push((self.zoom, self.view_center));
+    }
+
+    // Unzoomed, the view always shows the whole world.
+    fn set_view(&mut self, zoom: f32, center: Point) {
+        self.zoom = zoom.clamp(1.0, Self::MAX_ZOOM);
+        self.view_center = if self.zoom > 1.0 {
+            center
+        } else {
+            CoordinateTransform::midpoint(self.world_min_corner, self.world_max_corner)
+        };
+    }
+
+    // Saves the frame on screen, the last one finished, as a PNG file.
+    pub fn capture_frame_to_png(&self, path: &Path) -> io::Result<()> {
+        let image: glium::texture::RawImage2d<u8> = self.display.read_front_buffer();
+        png::write_rgba_png(path, image.width, image.height, &image.data)
+    }
+
+    // The tick of the world in the frame on screen.
+    pub fn last_drawn_tick(&self) -> u64 {
+        self.last_drawn_tick
+    }
+
+    fn dump_frame(&mut self) {
+        let dump = match &mut self.frame_dump {
+            Some(dump) => dump,
+            None => return,
+        };
+        if dump.frames_to_skip > 0 {
+            dump.frames_to_skip -= 1;
+            return;
+        }
+        dump.frames_to_skip = dump.every_nth_frame - 1;
+        let path = dump
+            .dir
+            .join(format!("frame-{:06}.png", dump.next_file_number));
+        dump.next_file_number += 1;
+        if let Err(err) = self.capture_frame_to_png(&path) {
+            eprintln!("Can't write {}: {}", path.display(), err);
+        }
+    }
+
     fn get_screen_size(monitor: glutin::MonitorId) -> glutin::dpi::LogicalSize {
         monitor
             .get_dimensions()
             .to_logical(monitor.get_hidpi_factor())
     }
 
+    fn total_world_size(
+        world_min_corner: Point,
+        world_max_corner: Point,
+        viewport_count: usize,
+    ) -> (f64, f64) {
+        (
+            (world_max_corner[0] - world_min_corner[0]) as f64 * viewport_count as f64,
+            (world_max_corner[1] - world_min_corner[1]) as f64,
+        )
+    }
+
+    fn calc_scaled_window_size(
+        world_size: (f64, f64),
+        pixels_per_unit: f64,
+        screen_size: glutin::dpi::LogicalSize,
+        max_fraction_of_screen_dimension: f64,
+    ) -> glutin::dpi::LogicalSize {
+        let width = world_size.0 * pixels_per_unit;
+        let height = world_size.1 * pixels_per_unit;
+        if width <= max_fraction_of_screen_dimension * screen_size.width
+            && height <= max_fraction_of_screen_dimension * screen_size.height
+        {
+            glutin::dpi::LogicalSize::new(width, height)
+        } else {
+            Self::calc_initial_window_size(
+                world_size,
+                screen_size,
+                max_fraction_of_screen_dimension,
+            )
+        }
+    }
+
     fn calc_initial_window_size(
         world_size: (f64, f64),
         screen_size: glutin::dpi::LogicalSize,
@@ -104,205 +458,324 @@ impl GliumView {
     }
 
     pub fn render(&mut self, world: &evo_domain::world::World) {
-        self.draw_frame(
-            &Self::world_clouds_to_cloud_sprites(world),
-            Self::get_cloud_colors(),
-            &Self::world_cells_to_cell_sprites(world),
-            Self::get_layer_colors(world),
-            &Self::world_bonds_to_bond_sprites(world),
-        );
+        self.draw(&self.snapshot(world));
     }
 
-    fn world_clouds_to_cloud_sprites(world: &evo_domain::world::World) -> Vec<CloudSprite> {
-        world
-            .clouds()
+    pub fn render_side_by_side(&mut self, worlds: &[evo_domain::world::World]) {
+        let snapshots = worlds
             .iter()
-            .map(Self::world_cloud_to_cloud_sprite)
-            .collect()
+            .map(|world| self.snapshot(world))
+            .collect::<Vec<_>>();
+        self.draw_side_by_side(&snapshots);
     }
 
-    fn world_cloud_to_cloud_sprite(cloud: &Cloud) -> CloudSprite {
-        CloudSprite {
-            center: [cloud.center().x() as f32, cloud.center().y() as f32],
-            radius: cloud.radius().value() as f32,
-            concentration: cloud.concentration().value() as f32,
-            color_index: 0,
+    // A snapshot of the world with this view's derived fields, and when zoomed in, only
+    // the cells in view. Every viewport shows the same part of its world.
+    pub fn snapshot(&self, world: &evo_domain::world::World) -> RenderSnapshot {
+        let mut snapshot = RenderSnapshot::with_derived_fields(world, &self.derived_fields);
+        if self.zoom > 1.0 {
+            let (min_corner, max_corner) = self.viewport_transforms()[0].visible_world_corners();
+            snapshot.cull_cells(
+                world,
+                Rectangle::new(
+                    Position::new(min_corner[0] as f64, min_corner[1] as f64),
+                    Position::new(max_corner[0] as f64, max_corner[1] as f64),
+                ),
+            );
         }
+        snapshot
     }
 
-    fn get_cloud_colors() -> [[f32; 4]; 8] {
-        let mut cloud_colors: [[f32; 4]; 8] = [[0.0, 0.0, 0.0, 1.0]; 8];
-        cloud_colors[0] = [1.0, 0.5, 0.5, 0.8];
-        cloud_colors
+    pub fn draw(&mut self, snapshot: &RenderSnapshot) {
+        self.draw_side_by_side(std::slice::from_ref(snapshot));
     }
 
-    fn world_cells_to_cell_sprites(world: &evo_domain::world::World) -> Vec<CellSprite> {
-        world
-            .cells()
-            .iter()
-            .map(Self::world_cell_to_cell_sprite)
-            .collect()
+    // One snapshot per viewport, left to right.
+    pub fn draw_side_by_side(&mut self, snapshots: &[RenderSnapshot]) {
+        self.draw_frame(snapshots, true);
     }
 
-    fn world_cell_to_cell_sprite(cell: &Cell) -> CellSprite {
-        let mut num_layers = cell.layers().len();
-        let mut radii: [f32; 8] = [0.0; 8];
-        let mut health: [f32; 8] = [0.0; 8];
-        assert!(num_layers <= radii.len());
-        for (i, layer) in cell.layers().iter().enumerate() {
-            radii[i] = layer.outer_radius().value() as f32;
-            health[i] = layer.health().value() as f32;
-        }
-        if cell.is_selected() {
-            num_layers += 1;
-            assert!(num_layers <= radii.len());
-            radii[num_layers - 1] = (cell.radius().value() + 1.0) as f32;
-            health[num_layers - 1] = 1.0;
-        }
-        CellSprite {
-            center: [cell.center().x() as f32, cell.center().y() as f32],
-            num_layers: num_layers as u32,
-            radii_0_3: [radii[0], radii[1], radii[2], radii[3]],
-            radii_4_7: [radii[4], radii[5], radii[6], radii[7]],
-            health_0_3: [health[0], health[1], health[2], health[3]],
-            health_4_7: [health[4], health[5], health[6], health[7]],
-        }
+    // Draws the cells the fraction of the way from the previous snapshots to the current
+    // ones, for smooth motion when ticks come slower than frames. Trails record only
+    // the snapshots themselves, so that they don't depend on the frame rate.
+    pub fn draw_interpolated_side_by_side(
+        &mut self,
+        previous: &[RenderSnapshot],
+        current: &[RenderSnapshot],
+        fraction: f32,
+    ) {
+        let snapshots = previous
+            .iter()
+            .zip(current)
+            .map(|(previous, current)| RenderSnapshot::interpolated(previous, current, fraction))
+            .collect::<Vec<_>>();
+        self.draw_frame(&snapshots, false);
     }
 
-    fn get_layer_colors(world: &evo_domain::world::World) -> [[f32; 4]; 8] {
-        const SELECTION_HALO_COLOR: [f32; 4] = [1.0, 0.0, 0.2, 1.0];
-
-        let mut layer_colors: [[f32; 4]; 8] = [[0.0, 0.0, 0.0, 1.0]; 8];
-        if !world.cells().is_empty() {
-            let sample_cell = &world.cells()[0];
-            assert!(sample_cell.layers().len() < layer_colors.len());
-            for (i, layer) in sample_cell.layers().iter().enumerate() {
-                layer_colors[i] = Self::convert_layer_tissue_to_rgb_color(layer.tissue());
+    fn draw_frame(&mut self, snapshots: &[RenderSnapshot], record_trails: bool) {
+        assert_eq!(snapshots.len(), self.viewport_count);
+        // from the snapshots drawn, so that the view moves smoothly with interpolated cells
+        if self.follow_selection {
+            if let Some(center) = RenderSnapshot::selection_center(snapshots) {
+                self.set_view(self.zoom, center);
             }
-            layer_colors[sample_cell.layers().len()] = SELECTION_HALO_COLOR;
         }
-        layer_colors
-    }
-
-    fn convert_layer_tissue_to_rgb_color(color: layers::Tissue) -> [f32; 4] {
-        match color {
-            layers::Tissue::AirBubble => [1.0, 1.0, 1.0, 0.1],
-            layers::Tissue::Bonding => [0.7, 0.7, 0.0, 0.8],
-            layers::Tissue::CellWall => [0.05, 0.05, 0.05, 0.8],
-            layers::Tissue::Photosynthetic => [0.1, 0.8, 0.1, 0.8],
+        let trail_mode = self.trail_mode;
+        self.trails.resize_with(snapshots.len(), || {
+            Trails::new(trail_mode, Self::DEFAULT_TRAIL_LENGTH)
+        });
+        let show_overlays = self.show_overlays;
+        let trails = self
+            .trails
+            .iter_mut()
+            .zip(snapshots)
+            .map(|(trails, snapshot)| {
+                if record_trails {
+                    trails.record(&snapshot.trail_points);
+                }
+                if show_overlays {
+                    trails.line_vertices(&snapshot.trail_points)
+                } else {
+                    vec![]
+                }
+            })
+            .collect::<Vec<_>>();
+        self.population_graphs.resize_with(snapshots.len(), || {
+            PopulationGraph::new(Self::POPULATION_GRAPH_TICKS)
+        });
+        // recorded even while hidden, so that toggling it on shows the recent past
+        if record_trails {
+            for (graph, snapshot) in self.population_graphs.iter_mut().zip(snapshots) {
+                graph.record(snapshot.tick, snapshot.population, snapshot.total_energy);
+            }
         }
-    }
 
-    fn world_bonds_to_bond_sprites(world: &evo_domain::world::World) -> Vec<BondSprite> {
-        world
-            .bonds()
-            .iter()
-            .map(|bond| Self::world_bond_to_bond_sprite(bond, world))
-            .collect()
-    }
-
-    fn world_bond_to_bond_sprite(
-        bond: &Bond<Cell>,
-        world: &evo_domain::world::World,
-    ) -> BondSprite {
-        let cell1 = world.cell(bond.node1_handle());
-        let cell2 = world.cell(bond.node2_handle());
-        BondSprite {
-            end1: [cell1.center().x() as f32, cell1.center().y() as f32],
-            end2: [cell2.center().x() as f32, cell2.center().y() as f32],
-            radius1: cell1.radius().value() as f32,
-            radius2: cell2.radius().value() as f32,
+        for (((buffers, snapshot), trails), graph) in self
+            .viewport_buffers
+            .iter_mut()
+            .zip(snapshots)
+            .zip(&trails)
+            .zip(&self.population_graphs)
+        {
+            let graph = if self.show_population_graph {
+                graph.line_vertices(self.world_min_corner, self.world_max_corner)
+            } else {
+                vec![]
+            };
+            buffers.graph.write(&self.display, &graph);
+            let net_diagram = match &snapshot.net_diagram {
+                Some(diagram) if self.show_net_diagram && show_overlays => {
+                    diagram.line_vertices(self.world_min_corner, self.world_max_corner)
+                }
+                _ => vec![],
+            };
+            buffers.net_diagram.write(&self.display, &net_diagram);
+            let warning_panel = if self.show_warning_panel {
+                snapshot
+                    .warning_panel
+                    .line_vertices(self.world_min_corner, self.world_max_corner)
+            } else {
+                vec![]
+            };
+            buffers.warning_panel.write(&self.display, &warning_panel);
+            Self::write_viewport_buffers(
+                buffers,
+                &self.display,
+                snapshot,
+                trails,
+                self.show_force_arrows && show_overlays,
+                show_overlays,
+                self.show_grid && show_overlays,
+                show_overlays,
+                self.world_min_corner,
+                self.world_max_corner,
+            );
         }
-    }
 
-    fn draw_frame(
-        &mut self,
-        clouds: &[CloudSprite],
-        cloud_colors: [[f32; 4]; 8],
-        cells: &[CellSprite],
-        layer_colors: [[f32; 4]; 8],
-        bonds: &[BondSprite],
-    ) {
-        let clouds_vb = glium::VertexBuffer::new(&self.display, clouds).unwrap();
-        let cells_vb = glium::VertexBuffer::new(&self.display, cells).unwrap();
-        let bonds_vb = glium::VertexBuffer::new(&self.display, bonds).unwrap();
-        let screen_transform = self.current_screen_transform();
+        let transforms = self.viewport_transforms();
         let mut frame = self.display.draw();
         frame.clear_color(0.0, 0.0, 0.0, 1.0);
-        self.background_drawing
-            .draw(&mut frame, &self.world_vb, screen_transform);
-        self.cell_drawing
-            .draw(&mut frame, &cells_vb, screen_transform, layer_colors);
-        self.bond_drawing.draw(
-            &mut frame,
-            &bonds_vb,
-            screen_transform,
-            [1.0, 1.0, 0.0, 1.0],
-        );
-        self.cloud_drawing
-            .draw(&mut frame, &clouds_vb, screen_transform, cloud_colors);
+        for ((snapshot, buffers), transform) in snapshots
+            .iter()
+            .zip(&self.viewport_buffers)
+            .zip(&transforms)
+        {
+            self.draw_viewport(&mut frame, snapshot, buffers, transform);
+        }
         frame.finish().unwrap();
+        self.last_drawn_tick = snapshots[0].tick;
+        self.dump_frame();
     }
 
-    fn current_screen_transform(&mut self) -> [[f32; 4]; 4] {
-        // TODO more efficient to do this only on glutin::WindowEvent::Resized
-        let window_size = self.window_size();
-        let window_dim = [window_size.width as f32, window_size.height as f32];
-        Self::calc_screen_transform(self.world_min_corner, self.world_max_corner, window_dim)
-    }
-
-    fn window_size(&self) -> glutin::dpi::LogicalSize {
-        self.display.gl_window().window().get_inner_size().unwrap()
-    }
-
-    fn calc_screen_transform(
+    #[allow(clippy::too_many_arguments)]
+    fn write_viewport_buffers(
+        buffers: &mut ViewportBuffers,
+        display: &glium::Display,
+        snapshot: &RenderSnapshot,
+        trails: &[LineVertex],
+        show_force_arrows: bool,
+        show_field_rings: bool,
+        show_grid: bool,
+        show_selection_markers: bool,
         world_min_corner: Point,
         world_max_corner: Point,
-        window_dim: [f32; 2],
-    ) -> [[f32; 4]; 4] {
-        let world_dim = [
-            world_max_corner[0] - world_min_corner[0],
-            world_max_corner[1] - world_min_corner[1],
-        ];
-
-        let (x_scale, y_scale) = if world_dim[0] / world_dim[1] > window_dim[0] / window_dim[1] {
-            (
-                2.0 / world_dim[0],
-                2.0 / world_dim[0] * (window_dim[0] / window_dim[1]),
-            )
+    ) {
+        let force_arrows = if show_force_arrows {
+            ForceArrow::to_line_vertices(&snapshot.force_arrows, snapshot.force_arrow_max_length)
         } else {
+            vec![]
+        };
+        let field_rings = if show_field_rings {
+            FieldRing::to_line_vertices(&snapshot.field_rings)
+        } else {
+            vec![]
+        };
+        let grid = if show_grid {
+            Grid::line_vertices(world_min_corner, world_max_corner)
+        } else {
+            vec![]
+        };
+        let (bond_slots, inspectors) = if show_selection_markers {
             (
-                2.0 / world_dim[1] * (window_dim[1] / window_dim[0]),
-                2.0 / world_dim[1],
+                BondSlotMarker::to_line_vertices(&snapshot.bond_slot_markers),
+                CellInspector::to_line_vertices(
+                    &snapshot.cell_inspectors,
+                    world_min_corner,
+                    world_max_corner,
+                ),
             )
+        } else {
+            (vec![], vec![])
         };
 
-        let x_midpoint = world_min_corner[0] + world_dim[0] / 2.0;
-        let y_midpoint = world_min_corner[1] + world_dim[1] / 2.0;
-        let x_translate = -x_scale * x_midpoint;
-        let y_translate = -y_scale * y_midpoint;
+        buffers.grid.write(display, &grid);
+        buffers.trails.write(display, trails);
+        buffers.clouds.write(display, &snapshot.clouds);
+        buffers.cells.write(display, &snapshot.cells);
+        buffers.bonds.write(display, &snapshot.bonds);
+        buffers.anchors.write(display, &snapshot.anchors);
+        buffers.force_arrows.write(display, &force_arrows);
+        buffers.field_rings.write(display, &field_rings);
+        buffers.bond_slots.write(display, &bond_slots);
+        buffers.inspectors.write(display, &inspectors);
+    }
 
-        [
-            [x_scale, 0.0, 0.0, 0.0],
-            [0.0, y_scale, 0.0, 0.0],
-            [0.0, 0.0, 1.0, 0.0],
-            [x_translate, y_translate, 0.0, 1.0],
-        ]
+    fn draw_viewport(
+        &self,
+        frame: &mut glium::Frame,
+        snapshot: &RenderSnapshot,
+        buffers: &ViewportBuffers,
+        transform: &CoordinateTransform,
+    ) {
+        let screen_transform = transform.screen_transform();
+        let clip = transform.clip_rect(frame.get_dimensions());
+        self.background_drawing
+            .draw(frame, &self.world_vb, screen_transform, clip);
+        let line_buffers = [&buffers.grid, &buffers.trails];
+        for vertices in line_buffers.iter().filter_map(|buffer| buffer.vertices()) {
+            self.line_drawing
+                .draw(frame, vertices, screen_transform, clip);
+        }
+        if let Some(vertices) = buffers.cells.vertices() {
+            let tissue_styles = if self.show_tissue_styles {
+                RenderSnapshot::tissue_styles()
+            } else {
+                [LayerStyle::Plain as u32; 8]
+            };
+            self.cell_drawing.draw(
+                frame,
+                vertices,
+                screen_transform,
+                clip,
+                RenderSnapshot::tissue_colors(),
+                tissue_styles,
+                self.start_time.elapsed().as_secs_f32(),
+            );
+        }
+        if let Some(vertices) = buffers.bonds.vertices() {
+            self.bond_drawing.draw(
+                frame,
+                vertices,
+                screen_transform,
+                clip,
+                [1.0, 1.0, 0.0, 1.0],
+            );
+        }
+        if let Some(vertices) = buffers.anchors.vertices() {
+            self.bond_drawing.draw(
+                frame,
+                vertices,
+                screen_transform,
+                clip,
+                [0.6, 0.4, 0.2, 1.0],
+            );
+        }
+        if let Some(vertices) = buffers.clouds.vertices() {
+            self.cloud_drawing.draw(
+                frame,
+                vertices,
+                screen_transform,
+                clip,
+                snapshot.cloud_colors,
+            );
+        }
+        let line_buffers = [
+            &buffers.field_rings,
+            &buffers.bond_slots,
+            &buffers.force_arrows,
+            &buffers.inspectors,
+        ];
+        for vertices in line_buffers.iter().filter_map(|buffer| buffer.vertices()) {
+            self.line_drawing
+                .draw(frame, vertices, screen_transform, clip);
+        }
+        // unzoomed, so that they stay in their corners of the window
+        let corner_buffers = [&buffers.graph, &buffers.net_diagram, &buffers.warning_panel];
+        for vertices in corner_buffers.iter().filter_map(|buffer| buffer.vertices()) {
+            self.line_drawing.draw(
+                frame,
+                vertices,
+                transform.unzoomed().screen_transform(),
+                clip,
+            );
+        }
+    }
+
+    // TODO more efficient to do this only on glutin::WindowEvent::Resized
+    fn viewport_transforms(&self) -> Vec<CoordinateTransform> {
+        let window_size = self.window_size();
+        let window_size = [window_size.width as f32, window_size.height as f32];
+        let viewport_size = [window_size[0] / self.viewport_count as f32, window_size[1]];
+        (0..self.viewport_count)
+            .map(|i| {
+                CoordinateTransform::new(self.world_min_corner, self.world_max_corner, window_size)
+                    .with_viewport([i as f32 * viewport_size[0], 0.0], viewport_size)
+                    .with_zoom(self.zoom)
+                    .with_center(self.view_center)
+            })
+            .collect()
+    }
+
+    fn window_size(&self) -> glutin::dpi::LogicalSize {
+        self.display.gl_window().window().get_inner_size().unwrap()
     }
 
     pub fn check_for_user_action(&mut self) -> Option<UserAction> {
         let mut result = None;
-        let logical_position_to_world_position = LogicalPositionToWorldPosition::new(
-            self.window_size(),
-            self.world_min_corner,
-            self.world_max_corner,
-        );
+        let transforms = self.viewport_transforms();
+        let pick_tolerance = self.pick_radius / transforms[0].scale();
         let mouse_position = &mut self.mouse_position;
+        let key_bindings = &self.key_bindings;
         self.events_loop.poll_events(|event| {
             // drain the event queue, capturing the first user action
             if result == None {
-                result =
-                    Self::handle_event(&event, &logical_position_to_world_position, mouse_position);
+                result = Self::handle_event(
+                    &event,
+                    &transforms,
+                    pick_tolerance,
+                    mouse_position,
+                    key_bindings,
+                );
             }
         });
         result
@@ -310,17 +783,19 @@ impl GliumView {
 
     pub fn wait_for_user_action(&mut self) -> UserAction {
         let mut result = UserAction::Exit; // bogus initial value
-        let logical_position_to_world_position = LogicalPositionToWorldPosition::new(
-            self.window_size(),
-            self.world_min_corner,
-            self.world_max_corner,
-        );
+        let transforms = self.viewport_transforms();
+        let pick_tolerance = self.pick_radius / transforms[0].scale();
         let mouse_position = &mut self.mouse_position;
+        let key_bindings = &self.key_bindings;
         self.events_loop
             .run_forever(|event| -> glutin::ControlFlow {
-                if let Some(user_action) =
-                    Self::handle_event(&event, &logical_position_to_world_position, mouse_position)
-                {
+                if let Some(user_action) = Self::handle_event(
+                    &event,
+                    &transforms,
+                    pick_tolerance,
+                    mouse_position,
+                    key_bindings,
+                ) {
                     result = user_action;
                     glutin::ControlFlow::Break
                 } else {
@@ -332,8 +807,10 @@ impl GliumView {
 
     fn handle_event(
         event: &glutin::Event,
-        logical_position_to_world_position: &LogicalPositionToWorldPosition,
+        viewport_transforms: &[CoordinateTransform],
+        pick_tolerance: f32,
         mouse_position: &mut glutin::dpi::LogicalPosition,
+        key_bindings: &KeyBindings,
     ) -> Option<UserAction> {
         match event {
             glutin::Event::WindowEvent { event, .. } => match event {
@@ -352,19 +829,49 @@ impl GliumView {
                             ..
                         },
                     ..
-                } => Self::interpret_key_as_user_action(*key_code),
+                } => key_bindings.action_for(*key_code),
+
+                glutin::WindowEvent::MouseWheel { delta, .. } => {
+                    let scroll = match delta {
+                        glutin::MouseScrollDelta::LineDelta(_, y) => *y as f64,
+                        glutin::MouseScrollDelta::PixelDelta(position) => position.y,
+                    };
+                    if scroll > 0.0 {
+                        Some(UserAction::ZoomIn)
+                    } else if scroll < 0.0 {
+                        Some(UserAction::ZoomOut)
+                    } else {
+                        None
+                    }
+                }
 
                 glutin::WindowEvent::MouseInput {
-                    button: glutin::MouseButton::Left,
+                    button,
                     state: glutin::ElementState::Pressed,
+                    modifiers,
                     ..
                 } => {
-                    let world_position =
-                        logical_position_to_world_position.convert(*mouse_position);
-                    Some(UserAction::SelectCellToggle {
-                        x: world_position.0,
-                        y: world_position.1,
-                    })
+                    // with several viewports, the click means the same spot in every world
+                    let window_position = [mouse_position.x as f32, mouse_position.y as f32];
+                    viewport_transforms
+                        .iter()
+                        .find(|transform| transform.viewport_contains(window_position))
+                        .and_then(|transform| {
+                            let world_position = transform.window_to_world(window_position);
+                            let x = world_position[0] as f64;
+                            let y = world_position[1] as f64;
+                            let tolerance = pick_tolerance as f64;
+                            match button {
+                                glutin::MouseButton::Left if modifiers.shift => {
+                                    Some(UserAction::RemoveCellAt { x, y, tolerance })
+                                }
+                                glutin::MouseButton::Left => {
+                                    Some(UserAction::SelectCellToggle { x, y, tolerance })
+                                }
+                                glutin::MouseButton::Right => Some(UserAction::AddCellAt { x, y }),
+                                _ => None,
+                            }
+                        })
                 }
 
                 _ => None,
@@ -373,60 +880,550 @@ impl GliumView {
             _ => None,
         }
     }
+}
 
-    fn interpret_key_as_user_action(key_code: glutin::VirtualKeyCode) -> Option<UserAction> {
-        match key_code {
-            glutin::VirtualKeyCode::D => Some(UserAction::DebugPrint),
-            glutin::VirtualKeyCode::Escape
-            | glutin::VirtualKeyCode::Q
-            | glutin::VirtualKeyCode::X => Some(UserAction::Exit),
-            glutin::VirtualKeyCode::F => Some(UserAction::FastForwardToggle),
-            glutin::VirtualKeyCode::P => Some(UserAction::PlayToggle),
-            glutin::VirtualKeyCode::T => Some(UserAction::SingleTick),
-            _ => None,
-        }
-    }
+struct FrameDump {
+    dir: PathBuf,
+    every_nth_frame: u64,
+    frames_to_skip: u64,
+    next_file_number: u64,
 }
 
-struct LogicalPositionToWorldPosition {
-    window_size: glutin::dpi::LogicalSize,
-    world_min_corner: Point,
-    world_max_corner: Point,
+// Everything needed to draw one frame, copied out of the world so that the world
+// can go on to its next tick while the frame is being drawn.
+#[derive(Clone)]
+pub struct RenderSnapshot {
+    tick: u64,
+    population: usize,
+    total_energy: f32,
+    clouds: Vec<CloudSprite>,
+    cloud_colors: [[f32; 4]; 8],
+    cells: Vec<CellSprite>,
+    // in the same order as the cells
+    cell_motions: Vec<CellMotion>,
+    bonds: Vec<BondSprite>,
+    anchors: Vec<BondSprite>,
+    trail_points: Vec<TrailPoint>,
+    force_arrows: Vec<ForceArrow>,
+    force_arrow_max_length: f32,
+    bond_slot_markers: Vec<BondSlotMarker>,
+    cell_inspectors: Vec<CellInspector>,
+    net_diagram: Option<NetDiagram>,
+    warning_panel: WarningPanel,
+    field_rings: Vec<FieldRing>,
 }
 
-impl LogicalPositionToWorldPosition {
-    fn new(
-        window_size: glutin::dpi::LogicalSize,
-        world_min_corner: Point,
-        world_max_corner: Point,
+impl RenderSnapshot {
+    pub fn new(world: &evo_domain::world::World) -> Self {
+        Self::with_derived_fields(world, &[])
+    }
+
+    pub fn with_derived_fields(
+        world: &evo_domain::world::World,
+        derived_fields: &[Box<dyn DerivedField>],
     ) -> Self {
-        LogicalPositionToWorldPosition {
-            window_size,
-            world_min_corner,
-            world_max_corner,
+        // the cells, bonds, and clouds, which any front end would draw, come from the
+        // shared RenderFrame; the rest are this view's own readouts
+        let frame = RenderFrame::new(world);
+        RenderSnapshot {
+            tick: frame.tick,
+            population: frame.cells.len(),
+            total_energy: frame.total_cell_energy().value() as f32,
+            clouds: Self::cloud_frames_to_cloud_sprites(&frame.clouds),
+            cloud_colors: Self::get_cloud_colors(),
+            cells: Self::cell_frames_to_cell_sprites(&frame.cells),
+            cell_motions: Self::cell_frames_to_cell_motions(&frame.cells),
+            bonds: Self::bond_frames_to_bond_sprites(&frame.bonds),
+            anchors: Self::bond_frames_to_bond_sprites(&frame.anchors),
+            trail_points: Self::cell_frames_to_trail_points(&frame.cells),
+            force_arrows: Self::world_cells_to_force_arrows(world),
+            force_arrow_max_length: Self::force_arrow_max_length(world),
+            bond_slot_markers: Self::selected_cells_to_bond_slot_markers(world),
+            cell_inspectors: Self::selected_cells_to_cell_inspectors(world),
+            net_diagram: Self::selected_cell_to_net_diagram(world),
+            warning_panel: WarningPanel::new(world.warnings()),
+            field_rings: derived_fields
+                .iter()
+                .enumerate()
+                .flat_map(|(index, field)| FieldRing::for_field(field.as_ref(), index, world))
+                .collect(),
         }
     }
 
-    fn convert(&self, logical_pos: glutin::dpi::LogicalPosition) -> (f64, f64) {
-        let (world_width, world_height) = self.world_size();
-        (
-            self.world_min_corner[0] as f64 + logical_pos.x * world_width / self.window_size.width,
-            self.world_max_corner[1] as f64
-                - logical_pos.y * world_height / self.window_size.height,
-        )
+    // The current snapshot with its cells, and everything drawn at their centers, moved
+    // back toward the previous snapshot. Clouds and inspector readouts are not moved.
+    pub fn interpolated(
+        previous: &RenderSnapshot,
+        current: &RenderSnapshot,
+        fraction: f32,
+    ) -> Self {
+        let previous_centers = previous
+            .cell_motions
+            .iter()
+            .zip(&previous.cells)
+            .map(|(motion, cell)| (motion.cell_id, cell.center))
+            .collect::<HashMap<_, _>>();
+        let current_centers = current
+            .cells
+            .iter()
+            .map(|cell| cell.center)
+            .collect::<Vec<_>>();
+        let shifter = CellShifter::new(
+            &previous_centers,
+            &current_centers,
+            &current.cell_motions,
+            current.tick.saturating_sub(previous.tick),
+            fraction,
+        );
+
+        let mut snapshot = current.clone();
+        for cell in &mut snapshot.cells {
+            cell.center = shifter.shift(cell.center);
+        }
+        for bond in snapshot.bonds.iter_mut().chain(&mut snapshot.anchors) {
+            bond.end1 = shifter.shift(bond.end1);
+            bond.end2 = shifter.shift(bond.end2);
+        }
+        for point in &mut snapshot.trail_points {
+            point.center = shifter.shift(point.center);
+        }
+        for arrow in &mut snapshot.force_arrows {
+            arrow.tail = shifter.shift(arrow.tail);
+        }
+        for ring in &mut snapshot.field_rings {
+            ring.center = shifter.shift(ring.center);
+        }
+        for marker in &mut snapshot.bond_slot_markers {
+            marker.cell_center = shifter.shift(marker.cell_center);
+            marker.bonded_cell_center = marker
+                .bonded_cell_center
+                .map(|center| shifter.shift(center));
+        }
+        snapshot
     }
 
-    fn world_size(&self) -> (f64, f64) {
-        (
-            (self.world_max_corner[0] - self.world_min_corner[0]) as f64,
-            (self.world_max_corner[1] - self.world_min_corner[1]) as f64,
-        )
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    // The mean center of the selected cells in all the snapshots, if any are selected.
+    fn selection_center(snapshots: &[RenderSnapshot]) -> Option<Point> {
+        let (sum, count) = snapshots
+            .iter()
+            .flat_map(|snapshot| &snapshot.trail_points)
+            .filter(|point| point.selected)
+            .fold(([0.0, 0.0], 0), |(sum, count), point| {
+                (
+                    [sum[0] + point.center[0], sum[1] + point.center[1]],
+                    count + 1,
+                )
+            });
+        if count == 0 {
+            None
+        } else {
+            Some([sum[0] / count as f32, sum[1] / count as f32])
+        }
+    }
+
+    // Drops the cells whose bounding boxes are outside the region, leaving the rest.
+    fn cull_cells(&mut self, world: &evo_domain::world::World, region: Rectangle) {
+        let visible_ids = world
+            .cells_overlapping(region)
+            .iter()
+            .map(|cell| cell.cell_id())
+            .collect::<HashSet<_>>();
+        let (cells, cell_motions) = self
+            .cells
+            .drain(..)
+            .zip(self.cell_motions.drain(..))
+            .filter(|(_, motion)| visible_ids.contains(&motion.cell_id))
+            .unzip();
+        self.cells = cells;
+        self.cell_motions = cell_motions;
+    }
+
+    fn cloud_frames_to_cloud_sprites(clouds: &[CloudFrame]) -> Vec<CloudSprite> {
+        clouds
+            .iter()
+            .map(|cloud| CloudSprite {
+                center: Self::to_point(cloud.center),
+                radius: cloud.radius.value() as f32,
+                concentration: cloud.concentration.value() as f32,
+                color_index: 0,
+            })
+            .collect()
+    }
+
+    fn to_point(position: Position) -> Point {
+        [position.x() as f32, position.y() as f32]
+    }
+
+    fn get_cloud_colors() -> [[f32; 4]; 8] {
+        let mut cloud_colors: [[f32; 4]; 8] = [[0.0, 0.0, 0.0, 1.0]; 8];
+        cloud_colors[0] = [1.0, 0.5, 0.5, 0.8];
+        cloud_colors
+    }
+
+    fn cell_frames_to_cell_sprites(cells: &[CellFrame]) -> Vec<CellSprite> {
+        cells.iter().map(Self::cell_frame_to_cell_sprite).collect()
+    }
+
+    fn cell_frame_to_cell_sprite(cell: &CellFrame) -> CellSprite {
+        let mut num_layers = cell.layers.len();
+        let mut radii: [f32; 8] = [0.0; 8];
+        let mut health: [f32; 8] = [0.0; 8];
+        let mut tissues: [u32; 8] = [0; 8];
+        assert!(num_layers <= radii.len());
+        for (i, layer) in cell.layers.iter().enumerate() {
+            radii[i] = layer.outer_radius.value() as f32;
+            health[i] = layer.health.value() as f32;
+            tissues[i] = Self::tissue_index(layer.tissue);
+        }
+        if cell.selected {
+            num_layers += 1;
+            assert!(num_layers <= radii.len());
+            radii[num_layers - 1] = (cell.radius.value() + 1.0) as f32;
+            health[num_layers - 1] = 1.0;
+            tissues[num_layers - 1] = Self::SELECTION_HALO_INDEX;
+        }
+        CellSprite {
+            center: Self::to_point(cell.center),
+            num_layers: num_layers as u32,
+            radii_0_3: [radii[0], radii[1], radii[2], radii[3]],
+            radii_4_7: [radii[4], radii[5], radii[6], radii[7]],
+            health_0_3: [health[0], health[1], health[2], health[3]],
+            health_4_7: [health[4], health[5], health[6], health[7]],
+            tissues_0_3: [tissues[0], tissues[1], tissues[2], tissues[3]],
+            tissues_4_7: [tissues[4], tissues[5], tissues[6], tissues[7]],
+        }
+    }
+
+    fn cell_frames_to_cell_motions(cells: &[CellFrame]) -> Vec<CellMotion> {
+        cells
+            .iter()
+            .map(|cell| CellMotion {
+                cell_id: cell.cell_id,
+                velocity: [cell.velocity.x() as f32, cell.velocity.y() as f32],
+            })
+            .collect()
+    }
+
+    fn cell_frames_to_trail_points(cells: &[CellFrame]) -> Vec<TrailPoint> {
+        cells
+            .iter()
+            .map(|cell| TrailPoint {
+                center: Self::to_point(cell.center),
+                selected: cell.selected,
+            })
+            .collect()
+    }
+
+    fn world_cells_to_force_arrows(world: &evo_domain::world::World) -> Vec<ForceArrow> {
+        let mut arrows = vec![];
+        for cell in world.cells() {
+            let tail = [cell.center().x() as f32, cell.center().y() as f32];
+            let net_force = cell.last_net_force();
+            arrows.push(ForceArrow {
+                tail,
+                force: [
+                    net_force.net_force().x() as f32,
+                    net_force.net_force().y() as f32,
+                ],
+                color: ForceArrow::NET_FORCE_COLOR,
+            });
+            if cell.is_selected() {
+                let mut components = vec![
+                    (
+                        [net_force.dominant_x_force() as f32, 0.0],
+                        net_force.dominant_x_force_label(),
+                    ),
+                    (
+                        [0.0, net_force.dominant_y_force() as f32],
+                        net_force.dominant_y_force_label(),
+                    ),
+                ];
+                if let Some(additions) = net_force.non_dominant_force_additions() {
+                    components.extend(additions.iter().map(|addition| {
+                        (
+                            [addition.force.x() as f32, addition.force.y() as f32],
+                            addition.label,
+                        )
+                    }));
+                }
+                arrows.extend(components.into_iter().map(|(force, label)| ForceArrow {
+                    tail,
+                    force,
+                    color: ForceArrow::component_color(label),
+                }));
+            }
+        }
+        arrows
+    }
+
+    fn selected_cells_to_bond_slot_markers(
+        world: &evo_domain::world::World,
+    ) -> Vec<BondSlotMarker> {
+        let mut markers = vec![];
+        for cell in world.cells().iter().filter(|cell| cell.is_selected()) {
+            let slot_count = cell.edge_handles().len();
+            for index in 0..slot_count {
+                let bonded_cell_center = if cell.has_edge(index) {
+                    let bond = world.bond(cell.edge_handle(index));
+                    let bonded_cell = world.cell(bond.other_node_handle(cell.node_handle()));
+                    Some([
+                        bonded_cell.center().x() as f32,
+                        bonded_cell.center().y() as f32,
+                    ])
+                } else {
+                    None
+                };
+                markers.push(BondSlotMarker {
+                    cell_center: [cell.center().x() as f32, cell.center().y() as f32],
+                    cell_radius: cell.radius().value() as f32,
+                    index,
+                    slot_count,
+                    bonded_cell_center,
+                });
+            }
+        }
+        markers
+    }
+
+    fn selected_cells_to_cell_inspectors(world: &evo_domain::world::World) -> Vec<CellInspector> {
+        world
+            .cells()
+            .iter()
+            .filter(|cell| cell.is_selected())
+            .map(|cell| CellInspector {
+                energy: cell.energy().value() as f32,
+                bond_count: cell
+                    .edge_handles()
+                    .iter()
+                    .filter(|handle| handle.is_some())
+                    .count(),
+                velocity: [cell.velocity().x() as f32, cell.velocity().y() as f32],
+                layers: cell
+                    .layers()
+                    .iter()
+                    .map(|layer| LayerReadout {
+                        area: layer.area().value() as f32,
+                        health: layer.health().value() as f32,
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    fn selected_cell_to_net_diagram(world: &evo_domain::world::World) -> Option<NetDiagram> {
+        world
+            .cells()
+            .iter()
+            .filter(|cell| cell.is_selected())
+            .find_map(|cell| cell.neural_net_snapshot())
+            .map(|snapshot| NetDiagram::new(&snapshot))
+    }
+
+    fn force_arrow_max_length(world: &evo_domain::world::World) -> f32 {
+        world
+            .cells()
+            .iter()
+            .map(|cell| cell.radius().value() as f32)
+            .fold(0.0, f32::max)
+            * 3.0
+    }
+
+    // The palette of colors that cell sprites' tissue indexes look up, with the
+    // selection halo after the tissues.
+    fn tissue_colors() -> [[f32; 4]; 8] {
+        const SELECTION_HALO_COLOR: [f32; 4] = [1.0, 0.0, 0.2, 1.0];
+
+        let mut tissue_colors: [[f32; 4]; 8] = [[0.0, 0.0, 0.0, 1.0]; 8];
+        for (i, tissue) in layers::Tissue::ALL.iter().enumerate() {
+            tissue_colors[i] = Self::convert_layer_tissue_to_rgb_color(*tissue);
+        }
+        tissue_colors[Self::SELECTION_HALO_INDEX as usize] = SELECTION_HALO_COLOR;
+        tissue_colors
+    }
+
+    const SELECTION_HALO_INDEX: u32 = layers::Tissue::ALL.len() as u32;
+
+    fn tissue_index(tissue: layers::Tissue) -> u32 {
+        layers::Tissue::ALL
+            .iter()
+            .position(|each| *each == tissue)
+            .unwrap() as u32
+    }
+
+    fn convert_layer_tissue_to_rgb_color(color: layers::Tissue) -> [f32; 4] {
+        match color {
+            layers::Tissue::AirBubble => [1.0, 1.0, 1.0, 0.1],
+            layers::Tissue::Bonding => [0.7, 0.7, 0.0, 0.8],
+            layers::Tissue::CellWall => [0.05, 0.05, 0.05, 0.8],
+            layers::Tissue::Photosynthetic => [0.1, 0.8, 0.1, 0.8],
+            layers::Tissue::Chemosynthetic => [0.8, 0.4, 0.1, 0.8],
+            layers::Tissue::Predatory => [0.8, 0.1, 0.1, 0.8],
+        }
+    }
+
+    // Plain for the selection halo.
+    fn tissue_styles() -> [u32; 8] {
+        let mut tissue_styles = [LayerStyle::Plain as u32; 8];
+        for (i, tissue) in layers::Tissue::ALL.iter().enumerate() {
+            tissue_styles[i] = Self::convert_layer_tissue_to_style(*tissue) as u32;
+        }
+        tissue_styles
+    }
+
+    fn convert_layer_tissue_to_style(tissue: layers::Tissue) -> LayerStyle {
+        match tissue {
+            layers::Tissue::AirBubble => LayerStyle::Plain,
+            layers::Tissue::Bonding => LayerStyle::DashedRing,
+            layers::Tissue::CellWall => LayerStyle::Plain,
+            layers::Tissue::Photosynthetic => LayerStyle::Stipple,
+            layers::Tissue::Chemosynthetic => LayerStyle::Stripes,
+            layers::Tissue::Predatory => LayerStyle::Pulse,
+        }
+    }
+
+    // Anchors are drawn the same way, as bonds from the cells to their anchor points.
+    fn bond_frames_to_bond_sprites(bonds: &[BondFrame]) -> Vec<BondSprite> {
+        bonds
+            .iter()
+            .map(|bond| BondSprite {
+                end1: Self::to_point(bond.end1),
+                end2: Self::to_point(bond.end2),
+                radius1: bond.radius1.value() as f32,
+                radius2: bond.radius2.value() as f32,
+            })
+            .collect()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use evo_domain::biology::cell::Cell;
+
+    #[test]
+    fn cell_sprites_index_each_layer_tissue() {
+        let layer = |tissue| {
+            layers::CellLayer::new(
+                evo_domain::physics::quantities::Area::new(1.0),
+                evo_domain::physics::quantities::Density::new(1.0),
+                tissue,
+                Box::new(layers::NullCellLayerSpecialty::new()),
+            )
+        };
+        let cell = Cell::new(
+            evo_domain::physics::quantities::Position::ORIGIN,
+            evo_domain::physics::quantities::Velocity::ZERO,
+            vec![
+                layer(layers::Tissue::Predatory),
+                layer(layers::Tissue::CellWall),
+            ],
+        );
+
+        let sprite = RenderSnapshot::cell_frame_to_cell_sprite(&CellFrame::new(&cell));
+
+        let colors = RenderSnapshot::tissue_colors();
+        assert_eq!(
+            colors[sprite.tissues_0_3[0] as usize],
+            RenderSnapshot::convert_layer_tissue_to_rgb_color(layers::Tissue::Predatory)
+        );
+        assert_eq!(
+            colors[sprite.tissues_0_3[1] as usize],
+            RenderSnapshot::convert_layer_tissue_to_rgb_color(layers::Tissue::CellWall)
+        );
+    }
+
+    #[test]
+    fn culling_keeps_cells_in_region_with_their_motions() {
+        use evo_domain::physics::quantities::*;
+        let ball = |x| {
+            Cell::ball(
+                Length::new(1.0),
+                Mass::new(1.0),
+                Position::new(x, 0.0),
+                Velocity::new(x, 0.0),
+            )
+        };
+        let world =
+            evo_domain::world::World::new(Position::new(-10.0, -10.0), Position::new(10.0, 10.0))
+                .with_cells(vec![ball(-5.0), ball(0.0), ball(5.0)]);
+        let mut snapshot = RenderSnapshot::new(&world);
+
+        snapshot.cull_cells(
+            &world,
+            Rectangle::new(Position::new(-2.0, -2.0), Position::new(10.0, 2.0)),
+        );
+
+        assert_eq!(
+            snapshot
+                .cells
+                .iter()
+                .map(|cell| cell.center[0])
+                .collect::<Vec<_>>(),
+            vec![0.0, 5.0]
+        );
+        assert_eq!(
+            snapshot
+                .cell_motions
+                .iter()
+                .map(|motion| motion.velocity[0])
+                .collect::<Vec<_>>(),
+            vec![0.0, 5.0]
+        );
+    }
+
+    #[test]
+    fn selection_center_is_mean_of_selected_cells() {
+        use evo_domain::physics::quantities::*;
+        let ball = |x, y, selected| {
+            let mut cell = Cell::ball(
+                Length::new(1.0),
+                Mass::new(1.0),
+                Position::new(x, y),
+                Velocity::ZERO,
+            );
+            cell.set_selected(selected);
+            cell
+        };
+        let world =
+            evo_domain::world::World::new(Position::new(-10.0, -10.0), Position::new(10.0, 10.0))
+                .with_cells(vec![
+                    ball(-4.0, 0.0, true),
+                    ball(0.0, 0.0, false),
+                    ball(2.0, 6.0, true),
+                ]);
+        let snapshots = [RenderSnapshot::new(&world)];
+
+        assert_eq!(
+            RenderSnapshot::selection_center(&snapshots),
+            Some([-1.0, 3.0])
+        );
+        assert_eq!(
+            RenderSnapshot::selection_center(&[RenderSnapshot::new(
+                &evo_domain::world::World::new(Position::ORIGIN, Position::new(1.0, 1.0))
+            )]),
+            None
+        );
+    }
+
+    #[test]
+    fn energy_tissues_have_distinct_styles() {
+        let styles = [
+            layers::Tissue::Bonding,
+            layers::Tissue::Photosynthetic,
+            layers::Tissue::Chemosynthetic,
+            layers::Tissue::Predatory,
+        ]
+        .iter()
+        .map(|&tissue| RenderSnapshot::convert_layer_tissue_to_style(tissue) as u32)
+        .collect::<std::collections::HashSet<_>>();
+
+        assert_eq!(styles.len(), 4);
+        assert!(!styles.contains(&(LayerStyle::Plain as u32)));
+    }
 
     #[test]
     fn initial_window_size_for_world_wider_than_screen() {
@@ -447,4 +1444,26 @@ mod tests {
         );
         assert_eq!(initial_size, glutin::dpi::LogicalSize::new(250.0, 500.0));
     }
+
+    #[test]
+    fn scaled_window_size_follows_pixels_per_unit() {
+        let size = GliumView::calc_scaled_window_size(
+            (200.0, 100.0),
+            2.0,
+            glutin::dpi::LogicalSize::new(1000.0, 1000.0),
+            0.5,
+        );
+        assert_eq!(size, glutin::dpi::LogicalSize::new(400.0, 200.0));
+    }
+
+    #[test]
+    fn scaled_window_size_is_limited_by_screen() {
+        let size = GliumView::calc_scaled_window_size(
+            (200.0, 100.0),
+            10.0,
+            glutin::dpi::LogicalSize::new(1000.0, 1000.0),
+            0.5,
+        );
+        assert_eq!(size, glutin::dpi::LogicalSize::new(500.0, 250.0));
+    }
 }
@@ -0,0 +1,84 @@
+use glium::{implement_vertex, uniform, Surface};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LineVertex {
+    pub position: [f32; 2],
+    pub color: [f32; 4],
+}
+
+implement_vertex!(LineVertex, position, color);
+
+// Draws independent line segments, two vertices per segment.
+pub struct LineDrawing {
+    pub shader_program: glium::Program,
+    pub indices: glium::index::NoIndices,
+}
+
+impl LineDrawing {
+    pub fn new(display: &glium::Display) -> Self {
+        LineDrawing {
+            shader_program: glium::Program::from_source(
+                display,
+                Self::VERTEX_SHADER_SRC,
+                Self::FRAGMENT_SHADER_SRC,
+                None,
+            )
+            .unwrap(),
+            indices: glium::index::NoIndices(glium::index::PrimitiveType::LinesList),
+        }
+    }
+
+    pub fn draw(
+        &self,
+        frame: &mut glium::Frame,
+        vertices: glium::vertex::VertexBufferSlice<LineVertex>,
+        screen_transform: [[f32; 4]; 4],
+        clip: glium::Rect,
+    ) {
+        let uniforms = uniform! {
+            screen_transform: screen_transform,
+        };
+        let params = glium::DrawParameters {
+            blend: glium::Blend::alpha_blending(),
+            scissor: Some(clip),
+            ..Default::default()
+        };
+        frame
+            .draw(
+                vertices,
+                &self.indices,
+                &self.shader_program,
+                &uniforms,
+                &params,
+            )
+            .unwrap();
+    }
+
+    const VERTEX_SHADER_SRC: &'static str = r#"
+        #version 330 core
+
+        uniform mat4 screen_transform;
+
+        in vec2 position;
+        in vec4 color;
+
+        out vec4 line_color;
+
+        void main() {
+            line_color = color;
+            gl_Position = screen_transform * vec4(position, 0.0, 1.0);
+        }
+    "#;
+
+    const FRAGMENT_SHADER_SRC: &'static str = r#"
+        #version 330 core
+
+        in vec4 line_color;
+
+        out vec4 color_out;
+
+        void main() {
+            color_out = line_color;
+        }
+    "#;
+}
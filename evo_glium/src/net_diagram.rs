@@ -0,0 +1,233 @@
+use crate::line_drawing::LineVertex;
+use crate::stroke_text::StrokeText;
+use crate::Point;
+use evo_domain::biology::control::{NeuralNetNodeKind, NeuralNetSnapshot};
+use std::f32::consts::PI;
+
+// A selected cell's neural net as a node-link diagram in the bottom right corner of its
+// world: inputs in the left column, hidden nodes in the middle, and outputs on the right,
+// each numbered by node index as in the D printout. A node is green when its value is
+// positive and red when negative, brighter the farther from zero, and a link is blue for
+// a positive weight and orange for a negative one, more opaque the heavier it is.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NetDiagram {
+    // (column, value) by node index
+    nodes: Vec<(usize, f32)>,
+    // (from node, to node, weight)
+    links: Vec<(usize, usize, f32)>,
+}
+
+impl NetDiagram {
+    const FRAME_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.3];
+    const LABEL_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.7];
+    const POSITIVE_LINK_COLOR: [f32; 3] = [0.3, 0.6, 1.0];
+    const NEGATIVE_LINK_COLOR: [f32; 3] = [1.0, 0.6, 0.2];
+    // fractions of the world's width and height
+    const WIDTH: f32 = 0.3;
+    const HEIGHT: f32 = 0.3;
+    const NODE_SIDES: usize = 8;
+
+    pub fn new(snapshot: &NeuralNetSnapshot) -> Self {
+        NetDiagram {
+            nodes: snapshot
+                .nodes
+                .iter()
+                .map(|node| {
+                    let column = match node.kind {
+                        NeuralNetNodeKind::Input => 0,
+                        NeuralNetNodeKind::Hidden => 1,
+                        NeuralNetNodeKind::Output => 2,
+                    };
+                    (column, node.value)
+                })
+                .collect(),
+            links: snapshot
+                .connections
+                .iter()
+                .map(|(from, to, weight)| (*from as usize, *to as usize, *weight))
+                .collect(),
+        }
+    }
+
+    pub fn line_vertices(&self, min_corner: Point, max_corner: Point) -> Vec<LineVertex> {
+        let world_size = [max_corner[0] - min_corner[0], max_corner[1] - min_corner[1]];
+        let margin = world_size[1] / 60.0;
+        let size = [Self::WIDTH * world_size[0], Self::HEIGHT * world_size[1]];
+        let max_x = max_corner[0] - margin;
+        let min_y = min_corner[1] + margin;
+        let panel_min = [max_x - size[0], min_y];
+        let panel_max = [max_x, min_y + size[1]];
+
+        let mut vertices = vec![];
+        Self::add_frame(&mut vertices, panel_min, panel_max);
+        let (centers, radius) = self.layout(panel_min, size);
+
+        let max_weight = self
+            .links
+            .iter()
+            .map(|(_, _, weight)| weight.abs())
+            .fold(0.0, f32::max);
+        for (from, to, weight) in &self.links {
+            if from == to || *from >= centers.len() || *to >= centers.len() {
+                continue;
+            }
+            let (from, to) = (centers[*from], centers[*to]);
+            let length = ((to[0] - from[0]).powi(2) + (to[1] - from[1]).powi(2)).sqrt();
+            if length <= 2.0 * radius {
+                continue;
+            }
+            // from edge to edge, so the links don't hide the nodes
+            let step = [
+                (to[0] - from[0]) * radius / length,
+                (to[1] - from[1]) * radius / length,
+            ];
+            let rgb = if *weight >= 0.0 {
+                Self::POSITIVE_LINK_COLOR
+            } else {
+                Self::NEGATIVE_LINK_COLOR
+            };
+            let alpha = 0.15 + 0.85 * weight.abs() / max_weight;
+            Self::add_line(
+                &mut vertices,
+                [from[0] + step[0], from[1] + step[1]],
+                [to[0] - step[0], to[1] - step[1]],
+                [rgb[0], rgb[1], rgb[2], alpha],
+            );
+        }
+
+        for (index, ((_, value), center)) in self.nodes.iter().zip(&centers).enumerate() {
+            Self::add_node(&mut vertices, *center, radius, Self::node_color(*value));
+            vertices.extend(StrokeText::line_vertices(
+                &index.to_string(),
+                [center[0] - radius, center[1] + 1.2 * radius],
+                radius,
+                Self::LABEL_COLOR,
+            ));
+        }
+        vertices
+    }
+
+    // The center of each node, by index, and the radius of every node. Each column's
+    // nodes are spread evenly from top to bottom.
+    fn layout(&self, panel_min: Point, size: Point) -> (Vec<Point>, f32) {
+        let mut column_counts = [0_usize; 3];
+        for (column, _) in &self.nodes {
+            column_counts[*column] += 1;
+        }
+        let max_count = *column_counts.iter().max().unwrap_or(&0);
+        let radius = (size[1] / (3.0 * max_count.max(1) as f32)).min(size[0] / 12.0);
+
+        let mut column_positions = [0_usize; 3];
+        let centers = self
+            .nodes
+            .iter()
+            .map(|(column, _)| {
+                let position = column_positions[*column];
+                column_positions[*column] += 1;
+                let spacing = size[1] / column_counts[*column] as f32;
+                [
+                    panel_min[0] + (0.15 + 0.35 * *column as f32) * size[0],
+                    panel_min[1] + size[1] - (position as f32 + 0.5) * spacing,
+                ]
+            })
+            .collect();
+        (centers, radius)
+    }
+
+    fn node_color(value: f32) -> [f32; 4] {
+        let brightness = 0.3 + 0.7 * value.tanh().abs();
+        if value >= 0.0 {
+            [0.2, brightness, 0.2, 1.0]
+        } else {
+            [brightness, 0.2, 0.2, 1.0]
+        }
+    }
+
+    fn add_node(vertices: &mut Vec<LineVertex>, center: Point, radius: f32, color: [f32; 4]) {
+        let corner = |i: usize| {
+            let angle = 2.0 * PI * i as f32 / Self::NODE_SIDES as f32;
+            [
+                center[0] + radius * angle.cos(),
+                center[1] + radius * angle.sin(),
+            ]
+        };
+        for i in 0..Self::NODE_SIDES {
+            Self::add_line(vertices, corner(i), corner(i + 1), color);
+        }
+    }
+
+    fn add_frame(vertices: &mut Vec<LineVertex>, min_corner: Point, max_corner: Point) {
+        let corners = [
+            min_corner,
+            [max_corner[0], min_corner[1]],
+            max_corner,
+            [min_corner[0], max_corner[1]],
+        ];
+        for i in 0..corners.len() {
+            Self::add_line(
+                vertices,
+                corners[i],
+                corners[(i + 1) % corners.len()],
+                Self::FRAME_COLOR,
+            );
+        }
+    }
+
+    fn add_line(vertices: &mut Vec<LineVertex>, from: Point, to: Point, color: [f32; 4]) {
+        vertices.push(LineVertex {
+            position: from,
+            color,
+        });
+        vertices.push(LineVertex {
+            position: to,
+            color,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use evo_domain::biology::control::NeuralNetNode;
+
+    #[test]
+    fn columns_hold_inputs_hidden_nodes_and_outputs() {
+        let diagram = diagram();
+
+        let (centers, radius) = diagram.layout([0.0, 0.0], [30.0, 30.0]);
+
+        assert_eq!(centers[0], [4.5, 22.5]);
+        assert_eq!(centers[1], [4.5, 7.5]);
+        assert_eq!(centers[2], [15.0, 15.0]);
+        assert_eq!(centers[3], [25.5, 15.0]);
+        assert_eq!(radius, 2.5);
+    }
+
+    #[test]
+    fn link_color_shows_weight() {
+        let vertices = diagram().line_vertices([0.0, -100.0], [100.0, 0.0]);
+
+        // four frame sides, then the links in connection order
+        let links = &vertices[8..14];
+        assert_eq!(links[0].color, [0.3, 0.6, 1.0, 0.15 + 0.85 * 0.5]);
+        assert_eq!(links[2].color, [1.0, 0.6, 0.2, 0.15 + 0.85 * 1.0]);
+        assert_eq!(links[4].color, [0.3, 0.6, 1.0, 0.15 + 0.85 * 0.25]);
+    }
+
+    fn diagram() -> NetDiagram {
+        let node = |kind, value| NeuralNetNode {
+            label: "",
+            kind,
+            value,
+        };
+        NetDiagram::new(&NeuralNetSnapshot {
+            nodes: vec![
+                node(NeuralNetNodeKind::Input, 1.0),
+                node(NeuralNetNodeKind::Input, -1.0),
+                node(NeuralNetNodeKind::Hidden, 0.5),
+                node(NeuralNetNodeKind::Output, 0.0),
+            ],
+            connections: vec![(0, 2, 1.0), (1, 2, -2.0), (2, 3, 0.5)],
+        })
+    }
+}
@@ -0,0 +1,95 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// Writes 8-bit RGBA pixels, given bottom row first as OpenGL reads them back, as a PNG
+// file.
+pub fn write_rgba_png(path: &Path, width: u32, height: u32, pixels: &[u8]) -> io::Result<()> {
+    fs::write(path, encode_rgba_png(width, height, pixels))
+}
+
+pub fn encode_rgba_png(width: u32, height: u32, pixels: &[u8]) -> Vec<u8> {
+    const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+    const BIT_DEPTH: u8 = 8;
+    const RGBA_COLOR_TYPE: u8 = 6;
+    const NO_FILTER: u8 = 0;
+
+    let row_len = width as usize * 4;
+    assert_eq!(pixels.len(), row_len * height as usize);
+    // each row, top row first, starts with its filter type
+    let mut scanlines = Vec::with_capacity((row_len + 1) * height as usize);
+    for row in pixels.chunks(row_len.max(1)).rev() {
+        scanlines.push(NO_FILTER);
+        scanlines.extend_from_slice(row);
+    }
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    header.extend_from_slice(&[BIT_DEPTH, RGBA_COLOR_TYPE, 0, 0, 0]);
+
+    let mut png = SIGNATURE.to_vec();
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(
+        &mut png,
+        b"IDAT",
+        &miniz_oxide::deflate::compress_to_vec_zlib(&scanlines, 6),
+    );
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn write_chunk(png: &mut Vec<u8>, chunk_type: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(chunk_type);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+// the CRC-32 that PNG chunks end with, from the PNG specification's sample code
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffff_u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                0xedb8_8320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crc_matches_known_value() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+    }
+
+    #[test]
+    fn png_has_header_and_flipped_rows() {
+        // bottom row red, top row blue
+        let pixels = [255, 0, 0, 255, 0, 0, 255, 255];
+
+        let png = encode_rgba_png(1, 2, &pixels);
+
+        assert_eq!(&png[1..4], b"PNG");
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[16..24], &[0, 0, 0, 1, 0, 0, 0, 2]);
+        assert_eq!(
+            &png[png.len() - 12..],
+            &[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]
+        );
+        let idat_len = u32::from_be_bytes([png[33], png[34], png[35], png[36]]) as usize;
+        let scanlines =
+            miniz_oxide::inflate::decompress_to_vec_zlib(&png[41..41 + idat_len]).unwrap();
+        assert_eq!(scanlines, vec![0, 0, 0, 255, 255, 0, 255, 0, 0, 255]);
+    }
+}
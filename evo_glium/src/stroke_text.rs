@@ -0,0 +1,249 @@
+use crate::line_drawing::LineVertex;
+use crate::Point;
+
+// Draws numbers with line segments, like a seven-segment display, so overlays can label
+// things without a font. Supports digits, '-', '.', ':', and the letters a seven-segment
+// display can show, in whichever case looks clearer: A, E, H, b, v (drawn as a u), and
+// so on, but not k, m, w, or x. Other characters become spaces.
+pub struct StrokeText;
+
+impl StrokeText {
+    // segments of a character cell one unit high and half a unit wide
+    const TOP: [Point; 2] = [[0.0, 1.0], [0.5, 1.0]];
+    const UPPER_RIGHT: [Point; 2] = [[0.5, 1.0], [0.5, 0.5]];
+    const LOWER_RIGHT: [Point; 2] = [[0.5, 0.5], [0.5, 0.0]];
+    const BOTTOM: [Point; 2] = [[0.0, 0.0], [0.5, 0.0]];
+    const LOWER_LEFT: [Point; 2] = [[0.0, 0.0], [0.0, 0.5]];
+    const UPPER_LEFT: [Point; 2] = [[0.0, 0.5], [0.0, 1.0]];
+    const MIDDLE: [Point; 2] = [[0.0, 0.5], [0.5, 0.5]];
+    const DOT: [Point; 2] = [[0.2, 0.0], [0.3, 0.0]];
+    const LOWER_COLON_DOT: [Point; 2] = [[0.2, 0.25], [0.3, 0.25]];
+    const UPPER_COLON_DOT: [Point; 2] = [[0.2, 0.75], [0.3, 0.75]];
+
+    const ADVANCE: f32 = 0.8;
+
+    pub fn width(text: &str, height: f32) -> f32 {
+        text.chars().count() as f32 * Self::ADVANCE * height
+    }
+
+    // origin is the bottom left corner of the text
+    pub fn line_vertices(
+        text: &str,
+        origin: Point,
+        height: f32,
+        color: [f32; 4],
+    ) -> Vec<LineVertex> {
+        let mut vertices = vec![];
+        for (i, ch) in text.chars().enumerate() {
+            let char_origin = [origin[0] + i as f32 * Self::ADVANCE * height, origin[1]];
+            for segment in Self::segments(ch) {
+                for end in segment {
+                    vertices.push(LineVertex {
+                        position: [
+                            char_origin[0] + end[0] * height,
+                            char_origin[1] + end[1] * height,
+                        ],
+                        color,
+                    });
+                }
+            }
+        }
+        vertices
+    }
+
+    fn segments(ch: char) -> &'static [[Point; 2]] {
+        match ch {
+            '0' => &[
+                Self::TOP,
+                Self::UPPER_RIGHT,
+                Self::LOWER_RIGHT,
+                Self::BOTTOM,
+                Self::LOWER_LEFT,
+                Self::UPPER_LEFT,
+            ],
+            '1' => &[Self::UPPER_RIGHT, Self::LOWER_RIGHT],
+            '2' | 'z' => &[
+                Self::TOP,
+                Self::UPPER_RIGHT,
+                Self::MIDDLE,
+                Self::LOWER_LEFT,
+                Self::BOTTOM,
+            ],
+            '3' => &[
+                Self::TOP,
+                Self::UPPER_RIGHT,
+                Self::MIDDLE,
+                Self::LOWER_RIGHT,
+                Self::BOTTOM,
+            ],
+            '4' => &[
+                Self::UPPER_LEFT,
+                Self::MIDDLE,
+                Self::UPPER_RIGHT,
+                Self::LOWER_RIGHT,
+            ],
+            '5' | 's' => &[
+                Self::TOP,
+                Self::UPPER_LEFT,
+                Self::MIDDLE,
+                Self::LOWER_RIGHT,
+                Self::BOTTOM,
+            ],
+            '6' => &[
+                Self::TOP,
+                Self::UPPER_LEFT,
+                Self::MIDDLE,
+                Self::LOWER_LEFT,
+                Self::LOWER_RIGHT,
+                Self::BOTTOM,
+            ],
+            '7' => &[Self::TOP, Self::UPPER_RIGHT, Self::LOWER_RIGHT],
+            '8' => &[
+                Self::TOP,
+                Self::UPPER_RIGHT,
+                Self::LOWER_RIGHT,
+                Self::BOTTOM,
+                Self::LOWER_LEFT,
+                Self::UPPER_LEFT,
+                Self::MIDDLE,
+            ],
+            '9' | 'g' => &[
+                Self::TOP,
+                Self::UPPER_RIGHT,
+                Self::LOWER_RIGHT,
+                Self::BOTTOM,
+                Self::UPPER_LEFT,
+                Self::MIDDLE,
+            ],
+            'A' | 'a' => &[
+                Self::TOP,
+                Self::UPPER_RIGHT,
+                Self::LOWER_RIGHT,
+                Self::LOWER_LEFT,
+                Self::UPPER_LEFT,
+                Self::MIDDLE,
+            ],
+            'E' | 'e' => &[
+                Self::TOP,
+                Self::UPPER_LEFT,
+                Self::MIDDLE,
+                Self::LOWER_LEFT,
+                Self::BOTTOM,
+            ],
+            'H' => &[
+                Self::UPPER_LEFT,
+                Self::LOWER_LEFT,
+                Self::MIDDLE,
+                Self::UPPER_RIGHT,
+                Self::LOWER_RIGHT,
+            ],
+            'b' => &[
+                Self::UPPER_LEFT,
+                Self::LOWER_LEFT,
+                Self::BOTTOM,
+                Self::LOWER_RIGHT,
+                Self::MIDDLE,
+            ],
+            'u' | 'v' => &[Self::LOWER_LEFT, Self::BOTTOM, Self::LOWER_RIGHT],
+            'c' => &[Self::MIDDLE, Self::LOWER_LEFT, Self::BOTTOM],
+            'd' => &[
+                Self::UPPER_RIGHT,
+                Self::LOWER_RIGHT,
+                Self::BOTTOM,
+                Self::LOWER_LEFT,
+                Self::MIDDLE,
+            ],
+            'f' => &[Self::TOP, Self::UPPER_LEFT, Self::MIDDLE, Self::LOWER_LEFT],
+            'h' => &[
+                Self::UPPER_LEFT,
+                Self::LOWER_LEFT,
+                Self::MIDDLE,
+                Self::LOWER_RIGHT,
+            ],
+            'i' => &[Self::LOWER_LEFT],
+            'j' => &[Self::UPPER_RIGHT, Self::LOWER_RIGHT, Self::BOTTOM],
+            'l' => &[Self::UPPER_LEFT, Self::LOWER_LEFT],
+            'n' => &[Self::MIDDLE, Self::LOWER_LEFT, Self::LOWER_RIGHT],
+            'o' => &[
+                Self::MIDDLE,
+                Self::LOWER_LEFT,
+                Self::LOWER_RIGHT,
+                Self::BOTTOM,
+            ],
+            'p' => &[
+                Self::TOP,
+                Self::UPPER_LEFT,
+                Self::UPPER_RIGHT,
+                Self::MIDDLE,
+                Self::LOWER_LEFT,
+            ],
+            'q' => &[
+                Self::TOP,
+                Self::UPPER_LEFT,
+                Self::UPPER_RIGHT,
+                Self::MIDDLE,
+                Self::LOWER_RIGHT,
+            ],
+            'r' => &[Self::MIDDLE, Self::LOWER_LEFT],
+            't' => &[
+                Self::UPPER_LEFT,
+                Self::LOWER_LEFT,
+                Self::MIDDLE,
+                Self::BOTTOM,
+            ],
+            'y' => &[
+                Self::UPPER_LEFT,
+                Self::MIDDLE,
+                Self::UPPER_RIGHT,
+                Self::LOWER_RIGHT,
+                Self::BOTTOM,
+            ],
+            '-' => &[Self::MIDDLE],
+            '.' => &[Self::DOT],
+            ':' => &[Self::LOWER_COLON_DOT, Self::UPPER_COLON_DOT],
+            // letters with only one form
+            _ if ch.is_ascii_uppercase() => Self::segments(ch.to_ascii_lowercase()),
+            _ => &[],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digits_are_drawn_with_their_segments() {
+        let vertices = StrokeText::line_vertices("18", [0.0, 0.0], 1.0, [1.0; 4]);
+        assert_eq!(vertices.len(), 2 * (2 + 7));
+    }
+
+    #[test]
+    fn characters_advance_left_to_right() {
+        let vertices = StrokeText::line_vertices("-1", [10.0, 0.0], 5.0, [1.0; 4]);
+        assert_eq!(vertices[0].position, [10.0, 2.5]);
+        assert_eq!(vertices[2].position, [16.5, 5.0]);
+    }
+
+    #[test]
+    fn inspector_letters_are_drawn() {
+        let vertices = StrokeText::line_vertices("AEHbv", [0.0, 0.0], 1.0, [1.0; 4]);
+        assert_eq!(vertices.len(), 2 * (6 + 5 + 5 + 5 + 3));
+    }
+
+    #[test]
+    fn letters_are_drawn_in_either_case() {
+        let vertices = StrokeText::line_vertices("Cell", [0.0, 0.0], 1.0, [1.0; 4]);
+        assert_eq!(vertices.len(), 2 * (3 + 5 + 2 + 2));
+        assert_eq!(
+            StrokeText::line_vertices("Hh", [0.0, 0.0], 1.0, [1.0; 4]).len(),
+            2 * (5 + 4)
+        );
+    }
+
+    #[test]
+    fn unsupported_characters_are_blank() {
+        assert!(StrokeText::line_vertices("x", [0.0, 0.0], 1.0, [1.0; 4]).is_empty());
+        assert_eq!(StrokeText::width("x1", 1.0), 1.6);
+    }
+}
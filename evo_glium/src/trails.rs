@@ -0,0 +1,133 @@
+use crate::line_drawing::LineVertex;
+use crate::Point;
+use std::collections::VecDeque;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TrailMode {
+    Off,
+    Selected,
+    All,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TrailPoint {
+    pub center: Point,
+    pub selected: bool,
+}
+
+// Recent positions of each cell, oldest first, one ring buffer per cell.
+pub struct Trails {
+    mode: TrailMode,
+    length: usize,
+    trails: Vec<VecDeque<Point>>,
+}
+
+impl Trails {
+    const SELECTED_TRAIL_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.9];
+    const UNSELECTED_TRAIL_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.25];
+
+    pub fn new(mode: TrailMode, length: usize) -> Self {
+        assert!(length >= 2);
+        Trails {
+            mode,
+            length,
+            trails: vec![],
+        }
+    }
+
+    // Cells have no identity that survives the removal of another cell (the world moves
+    // its last cell into the gap), so all trails restart whenever the cell count drops.
+    pub fn record(&mut self, cells: &[TrailPoint]) {
+        if self.mode == TrailMode::Off {
+            return;
+        }
+
+        if cells.len() < self.trails.len() {
+            self.trails.clear();
+        }
+        let length = self.length;
+        self.trails
+            .resize_with(cells.len(), || VecDeque::with_capacity(length));
+        for (trail, cell) in self.trails.iter_mut().zip(cells) {
+            if trail.len() == length {
+                trail.pop_front();
+            }
+            trail.push_back(cell.center);
+        }
+    }
+
+    // Line segments that fade out toward the oldest end of each trail.
+    pub fn line_vertices(&self, cells: &[TrailPoint]) -> Vec<LineVertex> {
+        let mut vertices = vec![];
+        for (trail, cell) in self.trails.iter().zip(cells) {
+            let color = match self.mode {
+                TrailMode::Off => continue,
+                TrailMode::Selected if !cell.selected => continue,
+                _ if cell.selected => Self::SELECTED_TRAIL_COLOR,
+                _ => Self::UNSELECTED_TRAIL_COLOR,
+            };
+            for i in 1..trail.len() {
+                vertices.push(Self::faded_vertex(trail, i - 1, color));
+                vertices.push(Self::faded_vertex(trail, i, color));
+            }
+        }
+        vertices
+    }
+
+    fn faded_vertex(trail: &VecDeque<Point>, index: usize, color: [f32; 4]) -> LineVertex {
+        let fade = (index + 1) as f32 / trail.len() as f32;
+        LineVertex {
+            position: trail[index],
+            color: [color[0], color[1], color[2], color[3] * fade],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trail_keeps_only_most_recent_positions() {
+        let mut trails = Trails::new(TrailMode::All, 2);
+        for x in 0..3 {
+            trails.record(&[trail_point(x as f32, false)]);
+        }
+
+        let vertices = trails.line_vertices(&[trail_point(2.0, false)]);
+
+        assert_eq!(vertices.len(), 2);
+        assert_eq!(vertices[0].position, [1.0, 0.0]);
+        assert_eq!(vertices[1].position, [2.0, 0.0]);
+        assert!(vertices[0].color[3] < vertices[1].color[3]);
+    }
+
+    #[test]
+    fn selected_mode_draws_only_selected_cells() {
+        let mut trails = Trails::new(TrailMode::Selected, 10);
+        let cells = [trail_point(0.0, false), trail_point(5.0, true)];
+        trails.record(&cells);
+        trails.record(&cells);
+
+        let vertices = trails.line_vertices(&cells);
+
+        assert_eq!(vertices.len(), 2);
+        assert_eq!(vertices[0].position, [5.0, 0.0]);
+    }
+
+    #[test]
+    fn trails_restart_when_a_cell_is_removed() {
+        let mut trails = Trails::new(TrailMode::All, 10);
+        trails.record(&[trail_point(0.0, false), trail_point(5.0, false)]);
+        trails.record(&[trail_point(5.0, false)]);
+
+        assert!(trails.line_vertices(&[trail_point(5.0, false)]).is_empty());
+    }
+
+    fn trail_point(x: f32, selected: bool) -> TrailPoint {
+        TrailPoint {
+            center: [x, 0.0],
+            selected,
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use crate::bond_drawing::BondSprite;
+use crate::cell_drawing::CellSprite;
+use crate::cloud_drawing::CloudSprite;
+use crate::line_drawing::LineVertex;
+
+// A vertex buffer that is kept from frame to frame and rewritten in place, so that
+// drawing a large world doesn't allocate GPU memory every frame. It is reallocated,
+// with room to spare, only when there are more vertices than fit.
+pub struct GrowableVertexBuffer<T: Copy> {
+    buffer: Option<glium::VertexBuffer<T>>,
+    len: usize,
+}
+
+impl<T> GrowableVertexBuffer<T>
+where
+    T: glium::Vertex + Send + 'static,
+{
+    pub fn new() -> Self {
+        GrowableVertexBuffer {
+            buffer: None,
+            len: 0,
+        }
+    }
+
+    pub fn write(&mut self, display: &glium::Display, vertices: &[T]) {
+        self.len = vertices.len();
+        if vertices.is_empty() {
+            return;
+        }
+        let capacity = self.buffer.as_ref().map_or(0, |buffer| buffer.len());
+        if vertices.len() > capacity {
+            self.buffer = Some(
+                glium::VertexBuffer::empty_dynamic(display, Self::grown_capacity(vertices.len()))
+                    .unwrap(),
+            );
+        }
+        let buffer = self.buffer.as_ref().unwrap();
+        buffer.slice(0..vertices.len()).unwrap().write(vertices);
+    }
+
+    // the vertices written last, or None if there were none
+    pub fn vertices(&self) -> Option<glium::vertex::VertexBufferSlice<'_, T>> {
+        if self.len == 0 {
+            return None;
+        }
+        self.buffer
+            .as_ref()
+            .and_then(|buffer| buffer.slice(0..self.len))
+    }
+
+    // Doubles from a small minimum, so that a growing population reallocates only a
+    // few times.
+    fn grown_capacity(len: usize) -> usize {
+        const MIN_CAPACITY: usize = 64;
+        len.next_power_of_two().max(MIN_CAPACITY)
+    }
+}
+
+// The vertex buffers for one viewport.
+pub struct ViewportBuffers {
+    pub grid: GrowableVertexBuffer<LineVertex>,
+    pub trails: GrowableVertexBuffer<LineVertex>,
+    pub clouds: GrowableVertexBuffer<CloudSprite>,
+    pub cells: GrowableVertexBuffer<CellSprite>,
+    pub bonds: GrowableVertexBuffer<BondSprite>,
+    pub anchors: GrowableVertexBuffer<BondSprite>,
+    pub force_arrows: GrowableVertexBuffer<LineVertex>,
+    pub field_rings: GrowableVertexBuffer<LineVertex>,
+    pub bond_slots: GrowableVertexBuffer<LineVertex>,
+    pub inspectors: GrowableVertexBuffer<LineVertex>,
+    pub graph: GrowableVertexBuffer<LineVertex>,
+    pub net_diagram: GrowableVertexBuffer<LineVertex>,
+    pub warning_panel: GrowableVertexBuffer<LineVertex>,
+}
+
+impl ViewportBuffers {
+    pub fn new() -> Self {
+        ViewportBuffers {
+            grid: GrowableVertexBuffer::new(),
+            trails: GrowableVertexBuffer::new(),
+            clouds: GrowableVertexBuffer::new(),
+            cells: GrowableVertexBuffer::new(),
+            bonds: GrowableVertexBuffer::new(),
+            anchors: GrowableVertexBuffer::new(),
+            force_arrows: GrowableVertexBuffer::new(),
+            field_rings: GrowableVertexBuffer::new(),
+            bond_slots: GrowableVertexBuffer::new(),
+            inspectors: GrowableVertexBuffer::new(),
+            graph: GrowableVertexBuffer::new(),
+            net_diagram: GrowableVertexBuffer::new(),
+            warning_panel: GrowableVertexBuffer::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capacity_grows_to_power_of_two_above_minimum() {
+        assert_eq!(GrowableVertexBuffer::<LineVertex>::grown_capacity(1), 64);
+        assert_eq!(GrowableVertexBuffer::<LineVertex>::grown_capacity(64), 64);
+        assert_eq!(GrowableVertexBuffer::<LineVertex>::grown_capacity(65), 128);
+        assert_eq!(
+            GrowableVertexBuffer::<LineVertex>::grown_capacity(10_000),
+            16_384
+        );
+    }
+}
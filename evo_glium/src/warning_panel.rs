@@ -0,0 +1,90 @@
+use crate::line_drawing::LineVertex;
+use crate::stroke_text::StrokeText;
+use crate::Point;
+use evo_domain::warnings::{Severity, WarningLog};
+
+// A world's most recent warnings, one per row, drawn in the bottom left corner of the
+// world with the newest at the bottom, so that new ones scroll the older ones up and
+// out. Each row is the tick or ticks of the warning and its message, colored by
+// severity.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WarningPanel {
+    // oldest first
+    rows: Vec<(Severity, String)>,
+}
+
+impl WarningPanel {
+    const MAX_ROWS: usize = 6;
+    const LINE_SPACING: f32 = 1.6;
+    const INFO_COLOR: [f32; 4] = [0.6, 0.8, 1.0, 0.8];
+    const WARNING_COLOR: [f32; 4] = [1.0, 0.7, 0.2, 0.9];
+    const ERROR_COLOR: [f32; 4] = [1.0, 0.25, 0.25, 1.0];
+
+    pub fn new(log: &WarningLog) -> Self {
+        let warnings = log.warnings();
+        WarningPanel {
+            rows: warnings
+                .iter()
+                .skip(warnings.len().saturating_sub(Self::MAX_ROWS))
+                .map(|warning| (warning.severity, warning.to_string()))
+                .collect(),
+        }
+    }
+
+    pub fn line_vertices(&self, min_corner: Point, max_corner: Point) -> Vec<LineVertex> {
+        let extent = (max_corner[0] - min_corner[0]).max(max_corner[1] - min_corner[1]);
+        let text_height = extent / 60.0;
+        let left = min_corner[0] + text_height;
+        let mut bottom = min_corner[1] + text_height;
+
+        let mut vertices = vec![];
+        for (severity, row) in self.rows.iter().rev() {
+            vertices.extend(StrokeText::line_vertices(
+                row,
+                [left, bottom],
+                text_height,
+                Self::color(*severity),
+            ));
+            bottom += Self::LINE_SPACING * text_height;
+        }
+        vertices
+    }
+
+    fn color(severity: Severity) -> [f32; 4] {
+        match severity {
+            Severity::Info => Self::INFO_COLOR,
+            Severity::Warning => Self::WARNING_COLOR,
+            Severity::Error => Self::ERROR_COLOR,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn panel_shows_newest_rows_at_the_bottom() {
+        let mut log = WarningLog::new(20);
+        let sources = ["a", "b", "c", "d", "e", "f", "g"];
+        for (tick, source) in sources.iter().enumerate() {
+            log.add(tick as u64, Severity::Warning, source, source.to_string());
+        }
+        log.add(7, Severity::Error, "h", "flung apart".to_string());
+
+        let panel = WarningPanel::new(&log);
+        assert_eq!(panel.rows.len(), WarningPanel::MAX_ROWS);
+        assert_eq!(panel.rows[0], (Severity::Warning, "2 c".to_string()));
+
+        let vertices = panel.line_vertices([0.0, -60.0], [60.0, 0.0]);
+        let bottom_row_colors = vertices
+            .iter()
+            .filter(|vertex| vertex.position[1] < -58.0)
+            .map(|vertex| vertex.color)
+            .collect::<Vec<_>>();
+        assert!(!bottom_row_colors.is_empty());
+        assert!(bottom_row_colors
+            .iter()
+            .all(|color| *color == WarningPanel::ERROR_COLOR));
+    }
+}
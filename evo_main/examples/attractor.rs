@@ -0,0 +1,60 @@
+use evo_domain::biology::cell::Cell;
+use evo_domain::environment::force_registry::ForceRegistry;
+use evo_domain::environment::influences::*;
+use evo_domain::physics::newtonian::NewtonianBody;
+use evo_domain::physics::quantities::*;
+use evo_domain::physics::shapes::Circle;
+use evo_domain::world::World;
+use evo_main::main_support::*;
+
+// Balls with no forces of their own, for trying out forces added with --force, including
+// one this program adds to the standard ones, e.g.
+//   cargo run --example attractor -- --force attractor:x=200,y=-200,strength=0.0005
+fn main() {
+    let forces = ForceRegistry::with_standard_forces().with_force("attractor", |args| {
+        Ok(Box::new(Attractor {
+            center: Position::new(args.require("x")?, args.require("y")?),
+            strength: args.get("strength", 0.001),
+        }))
+    });
+    init_and_run_side_by_side_with_forces(|_seed, _overrides| vec![create_world()], forces);
+}
+
+// Pulls each cell toward the center like a spring, harder the farther away it is.
+struct Attractor {
+    center: Position,
+    strength: Value1D,
+}
+
+impl SimpleInfluenceForce for Attractor {
+    fn calc_force(&self, cell: &Cell) -> Force {
+        let offset = self.center - cell.center();
+        Force::new(
+            self.strength * offset.x() * cell.mass().value(),
+            self.strength * offset.y() * cell.mass().value(),
+        )
+    }
+
+    fn label(&self) -> &'static str {
+        "attractor"
+    }
+}
+
+fn create_world() -> World {
+    World::new(Position::new(0.0, -400.0), Position::new(400.0, 0.0))
+        .with_standard_influences()
+        .with_cells(vec![
+            Cell::ball(
+                Length::new(20.0),
+                Mass::new(1.0),
+                Position::new(50.0, -50.0),
+                Velocity::new(1.0, 0.0),
+            ),
+            Cell::ball(
+                Length::new(10.0),
+                Mass::new(1.0),
+                Position::new(300.0, -300.0),
+                Velocity::new(0.0, 1.0),
+            ),
+        ])
+}
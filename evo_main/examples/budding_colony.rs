@@ -0,0 +1,8 @@
+use evo_domain::test_support::budding_colony;
+use evo_main::main_support::*;
+
+// The colony that the budding colony tests grow: one founder that buds into a line of
+// bonded cells.
+fn main() {
+    init_and_run(|_seed| budding_colony());
+}
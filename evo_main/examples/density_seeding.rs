@@ -0,0 +1,32 @@
+use evo_domain::biology::cell::Cell;
+use evo_domain::physics::quantities::*;
+use evo_domain::seeding::DensityMap;
+use evo_domain::world::World;
+use evo_main::main_support::*;
+use std::env;
+use std::path::Path;
+
+// Places balls according to a grayscale PGM image named by the EVO_SEED_MAP
+// environment variable, or according to Perlin noise if it is not set.
+fn main() {
+    init_and_run(create_world);
+}
+
+fn create_world(seed: u64) -> World {
+    let min_corner = Position::new(-400.0, -300.0);
+    let max_corner = Position::new(400.0, 300.0);
+    let density_map = match env::var("EVO_SEED_MAP") {
+        Ok(path) => DensityMap::from_pgm_file(Path::new(&path)).unwrap(),
+        Err(_) => DensityMap::perlin_noise(80, 60, 20.0, seed),
+    };
+    let radii = vec![Length::new(5.0); 300];
+    let cells = density_map
+        .sample_non_overlapping_positions(min_corner, max_corner, &radii, Length::new(1.0), seed)
+        .into_iter()
+        .zip(radii)
+        .map(|(position, radius)| Cell::ball(radius, Mass::new(1.0), position, Velocity::ZERO))
+        .collect();
+    World::new(min_corner, max_corner)
+        .with_standard_influences()
+        .with_cells(cells)
+}
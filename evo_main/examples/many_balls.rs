@@ -1,13 +1,16 @@
 use evo_domain::biology::cell::Cell;
 use evo_domain::physics::quantities::*;
+use evo_domain::seeding::PlacementJitter;
 use evo_domain::world::World;
 use evo_main::main_support::*;
 
 fn main() {
-    init_and_run(|_seed| create_world());
+    init_and_run(create_world);
 }
 
-fn create_world() -> World {
+fn create_world(seed: u64) -> World {
+    // small enough that neighbors never touch
+    let jitter = PlacementJitter::new(Length::new(4.0), seed);
     let mut world = World::new(Position::new(-750.0, -350.0), Position::new(750.0, 350.0))
         .with_standard_influences();
     for i in 0..48 {
@@ -15,7 +18,10 @@ fn create_world() -> World {
             world = world.with_cell(Cell::ball(
                 Length::new(10.0),
                 Mass::new(1.0),
-                Position::new(-700.0 + (i * 30) as f64, -300.0 + (j * 30) as f64),
+                jitter.apply(
+                    i * 21 + j,
+                    Position::new(-700.0 + (i * 30) as f64, -300.0 + (j * 30) as f64),
+                ),
                 Velocity::new(2.0, 2.0),
             ));
         }
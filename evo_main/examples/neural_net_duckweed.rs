@@ -34,6 +34,7 @@ fn create_world(seed: u64) -> World {
             resize_factor: Positive::new(1.01),
             minimum_concentration: Fraction::new(0.1),
         },
+        ..Parameters::DEFAULT
     };
     World::new(Position::new(0.0, -400.0), Position::new(400.0, 0.0))
         .with_parameters(parameters)
@@ -145,14 +146,22 @@ fn create_bonding_layer() -> CellLayer {
 fn create_cell_wall() -> CellLayer {
     const LAYER_PARAMS: LayerParameters = LayerParameters {
         healing_energy_delta: BioEnergyDelta::new(-1.0),
+        healing_cost_temperature_factor: 0.0,
         entropic_damage_health_delta: HealthDelta::new(-0.01),
+        entropic_damage_temperature_factor: 0.0,
+        aging_damage_health_delta: HealthDelta::ZERO,
         overlap_damage_health_delta: HealthDelta::new(OVERLAP_DAMAGE_HEALTH_DELTA),
+        impact_damage_health_delta: HealthDelta::ZERO,
         growth_energy_delta: BioEnergyDelta::new(-0.1),
         max_growth_rate: Positive::unchecked(10.0),
         shrinkage_energy_delta: BioEnergyDelta::new(-0.01),
         max_shrinkage_rate: 0.5,
+        mass_per_energy: 0.0,
         decay_rate: Fraction::unchecked(0.005),
         minimum_intact_thickness: Fraction::unchecked(0.01),
+        max_wall_stress: Positive::MAX,
+        wall_relaxation_rate: Fraction::ONE,
+        pressure_damage_health_delta: HealthDelta::ZERO,
     };
 
     CellLayer::new(
@@ -0,0 +1,32 @@
+use evo_domain::biology::cell::Cell;
+use evo_domain::environment::influences::*;
+use evo_domain::physics::quantities::*;
+use evo_domain::world::World;
+use evo_main::main_support::*;
+
+// The same balls with light drag on the left and heavy drag on the right.
+fn main() {
+    init_and_run_side_by_side(|_seed| vec![create_world(0.0005), create_world(0.002)]);
+}
+
+fn create_world(drag: f64) -> World {
+    World::new(Position::new(0.0, -400.0), Position::new(400.0, 0.0))
+        .with_perimeter_walls()
+        .with_per_cell_influence(Box::new(SimpleForceInfluence::new(Box::new(
+            DragForce::new(drag),
+        ))))
+        .with_cells(vec![
+            Cell::ball(
+                Length::new(20.0),
+                Mass::new(1.0),
+                Position::new(50.0, -200.0),
+                Velocity::new(10.0, 1.0),
+            ),
+            Cell::ball(
+                Length::new(10.0),
+                Mass::new(0.5),
+                Position::new(200.0, -100.0),
+                Velocity::new(-3.0, -8.0),
+            ),
+        ])
+}
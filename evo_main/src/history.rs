@@ -0,0 +1,115 @@
+use std::collections::VecDeque;
+
+// The most recent states before the live one, so that a paused run can be stepped back
+// through to catch the moment something happened, and then forward again to the live
+// state. The oldest states are dropped once there are as many as the capacity.
+pub struct History<T> {
+    states: VecDeque<T>,
+    capacity: usize,
+    // how many states back from the live one is being shown; 0 for the live one
+    steps_back: usize,
+}
+
+impl<T> History<T> {
+    pub fn new(capacity: usize) -> Self {
+        History {
+            states: VecDeque::with_capacity(capacity),
+            capacity,
+            steps_back: 0,
+        }
+    }
+
+    // Adds the latest state before the live one and returns to the live one.
+    pub fn record(&mut self, state: T) {
+        if self.states.len() == self.capacity {
+            self.states.pop_front();
+        }
+        self.states.push_back(state);
+        self.steps_back = 0;
+    }
+
+    pub fn steps_back(&self) -> usize {
+        self.steps_back
+    }
+
+    // The state being shown, or None for the live one.
+    pub fn current(&self) -> Option<&T> {
+        if self.steps_back == 0 {
+            None
+        } else {
+            self.states.get(self.states.len() - self.steps_back)
+        }
+    }
+
+    // Whether there was an earlier state to step back to.
+    pub fn step_back(&mut self) -> bool {
+        if self.steps_back < self.states.len() {
+            self.steps_back += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    // Whether there was a later state, maybe the live one, to step forward to.
+    pub fn step_forward(&mut self) -> bool {
+        if self.steps_back > 0 {
+            self.steps_back -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn return_to_live(&mut self) {
+        self.steps_back = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_back_through_recorded_states_and_forward_to_live() {
+        let mut history = History::new(3);
+        history.record(1);
+        history.record(2);
+
+        assert_eq!(history.current(), None);
+        assert!(history.step_back());
+        assert_eq!(history.current(), Some(&2));
+        assert!(history.step_back());
+        assert_eq!(history.current(), Some(&1));
+        assert!(!history.step_back());
+        assert!(history.step_forward());
+        assert!(history.step_forward());
+        assert_eq!(history.current(), None);
+        assert!(!history.step_forward());
+    }
+
+    #[test]
+    fn drops_oldest_state_beyond_capacity() {
+        let mut history = History::new(2);
+        history.record(1);
+        history.record(2);
+        history.record(3);
+
+        assert!(history.step_back());
+        assert!(history.step_back());
+        assert_eq!(history.current(), Some(&2));
+        assert!(!history.step_back());
+    }
+
+    #[test]
+    fn recording_returns_to_live() {
+        let mut history = History::new(2);
+        history.record(1);
+        history.step_back();
+
+        history.record(2);
+
+        assert_eq!(history.steps_back(), 0);
+        assert_eq!(history.current(), None);
+    }
+}
@@ -1,2 +1,3 @@
+pub mod history;
 pub mod main_support;
 pub mod view;
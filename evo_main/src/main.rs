@@ -1,174 +1,34 @@
-use evo_domain::biology::cell::Cell;
-use evo_domain::biology::cloud::CloudParameters;
 use evo_domain::biology::control::*;
 use evo_domain::biology::genome::*;
 use evo_domain::biology::layers::*;
-use evo_domain::environment::influences::*;
 use evo_domain::physics::quantities::*;
-use evo_domain::world::World;
-use evo_domain::Parameters;
+use evo_domain::scenario::ControlRegistry;
 use evo_main::main_support::*;
-use std::f64::consts::PI;
 
+// The world, apart from its cells' control, is described in scenarios/default.toml.
 fn main() {
-    init_and_run(create_world);
+    init_and_run_scenario(
+        include_str!("../scenarios/default.toml"),
+        ControlRegistry::new().with_control("neural_net", |seed| {
+            Box::new(create_control(SeededMutationRandomness::new(
+                seed,
+                &SOME_MUTATION,
+            )))
+        }),
+    );
 }
 
-const FLUID_DENSITY: f64 = 0.001;
-const FLOAT_LAYER_DENSITY: f64 = 0.0001;
-const PHOTO_LAYER_DENSITY: f64 = 0.002;
-const BONDING_LAYER_DENSITY: f64 = 0.002;
-const CELL_WALL_DENSITY: f64 = 0.002;
-const GRAVITY: f64 = -0.05;
-const OVERLAP_DAMAGE_HEALTH_DELTA: f64 = -0.1;
+const SOME_MUTATION: MutationParameters = MutationParameters {
+    weight_mutation_probability: 0.5,
+    weight_mutation_stdev: 1.0,
+    ..MutationParameters::NO_MUTATION
+};
 
 const FLOAT_LAYER_INDEX: usize = 0;
 const PHOTO_LAYER_INDEX: usize = 1;
 const BONDING_LAYER_INDEX: usize = 2;
 const CELL_WALL_INDEX: usize = 3;
 
-fn create_world(seed: u64) -> World {
-    let parameters = Parameters {
-        cloud_params: CloudParameters {
-            resize_factor: Positive::new(1.01),
-            minimum_concentration: Fraction::new(0.1),
-        },
-        ..Parameters::DEFAULT
-    };
-    World::new(Position::new(0.0, -400.0), Position::new(1200.0, 0.0))
-        .with_parameters(parameters)
-        .with_standard_influences()
-        .with_sunlight(0.0, 1.0)
-        .with_per_cell_influences(vec![
-            Box::new(SimpleForceInfluence::new(Box::new(WeightForce::new(
-                GRAVITY,
-            )))),
-            Box::new(SimpleForceInfluence::new(Box::new(BuoyancyForce::new(
-                GRAVITY,
-                FLUID_DENSITY,
-            )))),
-            Box::new(SimpleForceInfluence::new(Box::new(DragForce::new(0.005)))),
-        ])
-        .with_cell(
-            create_cell(seed)
-                .with_initial_energy(BioEnergy::new(50.0))
-                .with_initial_position(Position::new(200.0, -50.0)),
-        )
-}
-
-fn create_cell(seed: u64) -> Cell {
-    const SOME_MUTATION: MutationParameters = MutationParameters {
-        weight_mutation_probability: 0.5,
-        weight_mutation_stdev: 1.0,
-        ..MutationParameters::NO_MUTATION
-    };
-
-    Cell::new(
-        Position::ORIGIN,
-        Velocity::ZERO,
-        vec![
-            create_float_layer(),
-            create_photo_layer(),
-            create_bonding_layer(),
-            create_cell_wall(),
-        ],
-    )
-    .with_control(Box::new(create_control(SeededMutationRandomness::new(
-        seed,
-        &SOME_MUTATION,
-    ))))
-}
-
-fn create_float_layer() -> CellLayer {
-    const LAYER_PARAMS: LayerParameters = LayerParameters {
-        healing_energy_delta: BioEnergyDelta::new(-1.0),
-        entropic_damage_health_delta: HealthDelta::new(-0.01),
-        overlap_damage_health_delta: HealthDelta::new(OVERLAP_DAMAGE_HEALTH_DELTA),
-        growth_energy_delta: BioEnergyDelta::new(-0.1),
-        max_growth_rate: Positive::unchecked(10.0),
-        shrinkage_energy_delta: BioEnergyDelta::new(-0.01),
-        max_shrinkage_rate: 0.5,
-        decay_rate: Fraction::unchecked(0.05),
-        ..LayerParameters::DEFAULT
-    };
-
-    CellLayer::new(
-        Area::new(5.0 * PI),
-        Density::new(FLOAT_LAYER_DENSITY),
-        Tissue::AirBubble,
-        Box::new(NullCellLayerSpecialty::new()),
-    )
-    .with_parameters(&LAYER_PARAMS)
-}
-
-fn create_photo_layer() -> CellLayer {
-    const LAYER_PARAMS: LayerParameters = LayerParameters {
-        healing_energy_delta: BioEnergyDelta::new(-1.0),
-        entropic_damage_health_delta: HealthDelta::new(-0.01),
-        overlap_damage_health_delta: HealthDelta::new(OVERLAP_DAMAGE_HEALTH_DELTA),
-        growth_energy_delta: BioEnergyDelta::new(-1.0),
-        max_growth_rate: Positive::unchecked(10.0),
-        shrinkage_energy_delta: BioEnergyDelta::new(0.0),
-        max_shrinkage_rate: 0.1,
-        ..LayerParameters::DEFAULT
-    };
-
-    CellLayer::new(
-        Area::new(5.0 * PI),
-        Density::new(PHOTO_LAYER_DENSITY),
-        Tissue::Photosynthetic,
-        Box::new(PhotoCellLayerSpecialty::new(Fraction::new(0.1))), // 0.02
-    )
-    .with_parameters(&LAYER_PARAMS)
-}
-
-fn create_bonding_layer() -> CellLayer {
-    const LAYER_PARAMS: LayerParameters = LayerParameters {
-        healing_energy_delta: BioEnergyDelta::new(-1.0),
-        entropic_damage_health_delta: HealthDelta::new(-0.01),
-        overlap_damage_health_delta: HealthDelta::new(OVERLAP_DAMAGE_HEALTH_DELTA),
-        growth_energy_delta: BioEnergyDelta::new(-1.0),
-        max_growth_rate: Positive::unchecked(10.0),
-        shrinkage_energy_delta: BioEnergyDelta::new(0.0),
-        max_shrinkage_rate: 0.1,
-        ..LayerParameters::DEFAULT
-    };
-    const BONDING_PARAMS: BondingLayerParameters = BondingLayerParameters {
-        max_donation_energy_per_unit_area: BioEnergy::unchecked(0.5),
-        donation_energy_tax_rate: Fraction::unchecked(0.1),
-    };
-
-    CellLayer::new(
-        Area::new(5.0 * PI),
-        Density::new(BONDING_LAYER_DENSITY),
-        Tissue::Bonding,
-        Box::new(BondingCellLayerSpecialty::new().with_parameters(&BONDING_PARAMS)),
-    )
-    .with_parameters(&LAYER_PARAMS)
-}
-
-fn create_cell_wall() -> CellLayer {
-    const LAYER_PARAMS: LayerParameters = LayerParameters {
-        healing_energy_delta: BioEnergyDelta::new(-1.0),
-        entropic_damage_health_delta: HealthDelta::new(-0.01),
-        overlap_damage_health_delta: HealthDelta::new(OVERLAP_DAMAGE_HEALTH_DELTA),
-        growth_energy_delta: BioEnergyDelta::new(-0.1),
-        max_growth_rate: Positive::unchecked(10.0),
-        shrinkage_energy_delta: BioEnergyDelta::new(-0.01),
-        max_shrinkage_rate: 0.5,
-        decay_rate: Fraction::unchecked(0.005),
-        minimum_intact_thickness: Fraction::unchecked(0.01),
-    };
-
-    CellLayer::new(
-        Area::new(2.0 * PI),
-        Density::new(CELL_WALL_DENSITY),
-        Tissue::CellWall,
-        Box::new(NullCellLayerSpecialty::new()),
-    )
-    .with_parameters(&LAYER_PARAMS)
-}
-
 fn create_control(randomness: SeededMutationRandomness) -> NeuralNetControl {
     let mut builder = NeuralNetControlBuilder::new(TransferFn::IDENTITY);
 
@@ -184,6 +44,9 @@ fn create_control(randomness: SeededMutationRandomness) -> NeuralNetControl {
     let _float_layer_area_input_index = builder.add_input_node("<float area", |cell_state| {
         cell_state.layers[FLOAT_LAYER_INDEX].area.value()
     });
+    let _float_layer_density_input_index = builder.add_input_node("<float density", |cell_state| {
+        cell_state.layers[FLOAT_LAYER_INDEX].density.value()
+    });
     let photo_layer_health_input_index = builder.add_input_node("<photo health", |cell_state| {
         cell_state.layers[PHOTO_LAYER_INDEX].health.value()
     });
@@ -224,6 +87,15 @@ fn create_control(randomness: SeededMutationRandomness) -> NeuralNetControl {
         0.0,
         |value| CellLayer::resize_request(FLOAT_LAYER_INDEX, AreaDelta::new(value)),
     );
+    builder.add_output_node(
+        ">float pump",
+        &[
+            (desired_y_velocity_index, -0.0005),
+            (y_velocity_input_index, 0.0001),
+        ],
+        0.0,
+        |value| FloatCellLayerSpecialty::density_request(FLOAT_LAYER_INDEX, value),
+    );
     builder.add_output_node(
         ">photo healing",
         &[(photo_layer_health_input_index, -1.0)],
@@ -1,24 +1,327 @@
 use crate::view::*;
-use clap::{clap_app, ArgMatches};
-use evo_domain::physics::quantities::Position;
+use clap::{clap_app, Arg, ArgMatches, SubCommand};
+use evo_domain::benchmark::dense_world;
+use evo_domain::biology::genome::SparseNeuralNetGenome;
+use evo_domain::biology::template::CellTemplate;
+use evo_domain::energy_audit::OnImbalance;
+use evo_domain::environment::force_registry::ForceRegistry;
+use evo_domain::evaluation::{evaluate_founders, FitnessReport};
+use evo_domain::experiment::ExperimentMetadata;
+use evo_domain::explosion_watchdog::ExplosionWatchdogParameters;
+use evo_domain::hall_of_fame::HallOfFame;
+use evo_domain::physics::node_graph::GraphNode;
+use evo_domain::physics::quantities::{Fraction, Length, Position};
+use evo_domain::scenario::{ControlRegistry, ScenarioFile, ScenarioFormat};
+use evo_domain::soak::{run_soak, SoakParameters};
+use evo_domain::speciation::SpeciesTracker;
+use evo_domain::stats::{GenomeDistanceStats, ParetoFront, StatsCollector};
 use evo_domain::world::World;
-use evo_domain::UserAction;
+use evo_domain::{Parameters, UserAction};
+use evo_glium::{CrowdingField, DerivedField, KeyBindings, LightField, TrailMode};
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use std::process::{self, Command};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
 
 pub fn init_and_run<F>(create_world: F)
 where
     F: Fn(u64) -> World,
+{
+    init_and_run_with_overrides(|seed, _overrides| create_world(seed));
+}
+
+// Like init_and_run, for a create_world that reads values such as gravity through
+// overrides.get, so that they can be changed with --set.
+pub fn init_and_run_with_overrides<F>(create_world: F)
+where
+    F: Fn(u64, &ParameterOverrides) -> World,
+{
+    init_and_run_side_by_side_with_overrides(|seed, overrides| vec![create_world(seed, overrides)]);
+}
+
+// Runs several worlds in step, drawn side by side at the same scale, e.g. to compare
+// the same seed under different parameters. The worlds must have the same corners.
+// A click selects the cell at that spot in every world.
+pub fn init_and_run_side_by_side<F>(create_worlds: F)
+where
+    F: Fn(u64) -> Vec<World>,
+{
+    init_and_run_side_by_side_with_overrides(|seed, _overrides| create_worlds(seed));
+}
+
+pub fn init_and_run_side_by_side_with_overrides<F>(create_worlds: F)
+where
+    F: Fn(u64, &ParameterOverrides) -> Vec<World>,
+{
+    init_and_run_side_by_side_with_forces(create_worlds, ForceRegistry::with_standard_forces());
+}
+
+// Like init_and_run_side_by_side_with_overrides, with the forces that --force can add
+// to every world, e.g. the standard ones plus a program's own.
+pub fn init_and_run_side_by_side_with_forces<F>(create_worlds: F, forces: ForceRegistry)
+where
+    F: Fn(u64, &ParameterOverrides) -> Vec<World>,
+{
+    run_with_registries(create_worlds, &forces, &ControlRegistry::new());
+}
+
+// Runs the world described by a scenario file (see ScenarioSpec), the one given with
+// --scenario or else default_scenario, which is TOML. The scenario's cells can use the
+// controls registered by name, and its variables can be changed with --set.
+pub fn init_and_run_scenario(default_scenario: &str, controls: ControlRegistry) {
+    let forces = ForceRegistry::with_standard_forces();
+    let default_scenario = ScenarioFile::parse(default_scenario, ScenarioFormat::Toml)
+        .unwrap_or_else(|message| {
+            eprintln!("error: default scenario: {}", message);
+            process::exit(2);
+        });
+    run_with_registries(
+        |seed, overrides| {
+            vec![scenario_world(
+                &default_scenario,
+                seed,
+                overrides,
+                &forces,
+                &controls,
+            )]
+        },
+        &forces,
+        &controls,
+    );
+}
+
+fn run_with_registries<F>(create_worlds: F, forces: &ForceRegistry, controls: &ControlRegistry)
+where
+    F: Fn(u64, &ParameterOverrides) -> Vec<World>,
 {
     let args = parse_command_line();
-    let world = create_world(args.seed);
-    let view = View::new(world.min_corner(), world.max_corner());
-    run(world, view, args.start_paused);
+    if let Some(bench_args) = &args.bench_world {
+        run_bench_world(bench_args, args.seed);
+        return;
+    }
+    if let Some(soak_args) = &args.soak {
+        run_soak_test(soak_args, args.seed);
+        return;
+    }
+    if !args.evaluate.is_empty() {
+        evaluate_genomes(&args, &create_worlds, forces, controls);
+        return;
+    }
+    let mut worlds = create_configured_worlds(&args, &create_worlds, forces, controls);
+    let (min_corner, max_corner) = (worlds[0].min_corner(), worlds[0].max_corner());
+    if let Some(path) = &args.founder {
+        apply_cell_template(&mut worlds, path);
+    }
+    if let Some(path) = &args.genome {
+        apply_genome(&mut worlds, path);
+    }
+    let metadata = experiment_metadata(&args, &worlds);
+    print!("{}", metadata);
+    if !args.scheduled_overrides.is_empty() {
+        log_parameter_changes(&mut worlds, &metadata);
+    }
+    if let Some(interval) = args.stats_interval {
+        log_genome_stats(&mut worlds, interval, &metadata);
+    }
+    if args.tick_stats {
+        log_population_stats(&mut worlds, &metadata);
+    }
+    if args.phylogeny {
+        for world in &mut worlds {
+            world.enable_phylogeny();
+        }
+    }
+    if let Some(interval) = args.hall_of_fame_interval {
+        for world in &mut worlds {
+            world.enable_hall_of_fame(interval, HallOfFame::DEFAULT_CAPACITY);
+        }
+    }
+    if args.audit_energy {
+        for world in &mut worlds {
+            world.enable_energy_audit(OnImbalance::Log);
+        }
+    }
+    if args.watchdog {
+        for world in &mut worlds {
+            world.enable_explosion_watchdog(ExplosionWatchdogParameters {
+                pause: args.pause_on_explosion,
+                log: true,
+                ..ExplosionWatchdogParameters::DEFAULT
+            });
+        }
+    }
+    let pareto_fronts = if args.pareto {
+        track_pareto_fronts(&mut worlds)
+    } else {
+        vec![]
+    };
+    let species_trackers = match args.species_threshold {
+        Some(threshold) => track_species(
+            &mut worlds,
+            threshold,
+            args.stats_interval.unwrap_or(100),
+            &metadata,
+        ),
+        None => vec![],
+    };
+    install_shutdown_handler();
+    let start = Instant::now();
+    if args.headless {
+        run_headless(&mut worlds, args.max_ticks, args.run_for);
+    } else {
+        let view = View::new_side_by_side(min_corner, max_corner, worlds.len())
+            .with_pipelining(args.pipelined)
+            .with_interpolation(args.interpolated)
+            .with_frame_timing(args.report_timing)
+            .with_trails(args.trails)
+            .with_force_arrows(args.force_arrows)
+            .with_derived_fields(derived_fields(&args.derived_fields))
+            .with_grid(args.grid)
+            .with_frame_dump(args.dump_frames.as_deref(), args.dump_every)
+            .with_population_graph(args.population_graph)
+            .with_net_diagram(args.net_diagram)
+            .with_warning_panel(!args.hide_warnings)
+            .with_tissue_styles(args.tissue_styles)
+            .with_key_bindings(load_key_bindings(args.keys.as_deref()))
+            .with_pick_radius(args.pick_radius)
+            .with_pixels_per_unit(worlds[0].parameters().units.pixels_per_unit);
+        run(&mut worlds, view, args.start_paused, &metadata);
+    }
+    print_run_summary(&worlds, start.elapsed());
+    if args.pareto {
+        print_pareto_fronts(&worlds, &pareto_fronts);
+    }
+    print_species_summaries(&species_trackers);
+    if args.phylogeny {
+        write_phylogenies(&worlds, &metadata);
+    }
+    if args.hall_of_fame_interval.is_some() {
+        write_halls_of_fame(&worlds, &metadata);
+    }
+    if args.audit_energy {
+        print_energy_audit_summaries(&worlds);
+    }
 }
 
 pub struct CommandLineArgs {
+    pub name: Option<String>,
+    pub description: String,
     pub seed: u64,
     pub start_paused: bool,
+    pub pipelined: bool,
+    pub interpolated: bool,
+    pub report_timing: bool,
+    pub trails: TrailMode,
+    pub force_arrows: bool,
+    pub derived_fields: Vec<String>,
+    pub grid: bool,
+    pub dump_frames: Option<String>,
+    pub dump_every: u64,
+    pub population_graph: bool,
+    pub net_diagram: bool,
+    pub hide_warnings: bool,
+    pub tissue_styles: bool,
+    pub pick_radius: f32,
+    pub stats_interval: Option<u64>,
+    pub tick_stats: bool,
+    pub phylogeny: bool,
+    pub hall_of_fame_interval: Option<u64>,
+    pub audit_energy: bool,
+    pub watchdog: bool,
+    pub pause_on_explosion: bool,
+    pub pareto: bool,
+    pub species_threshold: Option<f32>,
+    pub control_budget: Option<Duration>,
+    pub slow_control_penalty: Fraction,
+    pub headless: bool,
+    pub max_ticks: Option<u64>,
+    pub run_for: Option<Duration>,
+    pub overrides: Vec<(String, f64)>,
+    pub forces: Vec<String>,
+    pub scenario: Option<String>,
+    pub scheduled_overrides: Vec<(u64, String, f64)>,
+    pub founder: Option<String>,
+    pub genome: Option<String>,
+    pub evaluate: Vec<String>,
+    pub keys: Option<String>,
+    pub bench_world: Option<BenchWorldArgs>,
+    pub soak: Option<SoakArgs>,
+}
+
+pub struct BenchWorldArgs {
+    pub cells: usize,
+    pub ticks: u64,
+}
+
+pub struct SoakArgs {
+    pub cells: usize,
+    pub ticks: u64,
+    pub sample_every: u64,
+}
+
+// Values set with --set name=value. A name of a Parameters field (see Parameters::NAMES)
+// is set in every world. Any other name, e.g. "gravity", must be read by create_world
+// with get, or the run stops with an error rather than silently ignore a typo.
+pub struct ParameterOverrides {
+    values: Vec<(String, f64)>,
+    names_read: RefCell<HashSet<String>>,
+}
+
+impl ParameterOverrides {
+    pub fn new(values: Vec<(String, f64)>) -> Self {
+        ParameterOverrides {
+            values,
+            names_read: RefCell::new(HashSet::new()),
+        }
+    }
+
+    // The last value set for the name, or else the default.
+    pub fn get(&self, name: &str, default_value: f64) -> f64 {
+        self.names_read.borrow_mut().insert(name.to_string());
+        self.values
+            .iter()
+            .rev()
+            .find(|(value_name, _)| value_name == name)
+            .map_or(default_value, |(_, value)| *value)
+    }
+
+    fn apply(&self, worlds: Vec<World>) -> Result<Vec<World>, String> {
+        let names_read = self.names_read.borrow();
+        worlds
+            .into_iter()
+            .map(|world| {
+                let mut parameters = *world.parameters();
+                for (name, value) in &self.values {
+                    if Parameters::NAMES.contains(&name.as_str()) {
+                        parameters.set(name, *value)?;
+                    } else if !names_read.contains(name) {
+                        return Err(self.unknown_name_message(name));
+                    }
+                }
+                Ok(world.with_parameters(parameters))
+            })
+            .collect()
+    }
+
+    fn unknown_name_message(&self, name: &str) -> String {
+        let mut known_names = Parameters::NAMES
+            .iter()
+            .map(|name| name.to_string())
+            .chain(self.names_read.borrow().iter().cloned())
+            .collect::<Vec<_>>();
+        known_names.sort();
+        format!(
+            "Unknown parameter: {} (known: {})",
+            name,
+            known_names.join(", ")
+        )
+    }
 }
 
 pub fn parse_command_line() -> CommandLineArgs {
@@ -27,13 +330,647 @@ pub fn parse_command_line() -> CommandLineArgs {
         (author: "Franz Amador <franzamador@gmail.com>")
         (about: "Evolution of simple digital organisms")
         (@arg seed: -s --seed +takes_value {is_u64_arg} "Random seed, defaults to 0")
+        (@arg name: --name +takes_value "Experiment name for outputs, defaults to the program name")
+        (@arg description: --description +takes_value "Experiment description for outputs")
         (@arg paused: -p --paused "Start with the simulation paused. Press P to resume, T to run single tick.")
+        (@arg pipelined: --pipelined "Compute each tick while drawing the previous one")
+        (@arg interpolate: --interpolate "Compute each tick while drawing frames that move the cells smoothly from the tick before, for large worlds that tick slower than the screen refreshes")
+        (@arg timing: --timing "Periodically print the average time per frame")
+        (@arg grid: --grid "Draw a coordinate grid and scale bar. Press G to toggle.")
+        (@arg graph: --graph "Chart the population and total cell energy over the last 3000 ticks. Press C to toggle.")
+        (@arg net_diagram: --("net-diagram") "Draw the neural net of the selected cell, with its node values as of the last tick. Press N to toggle.")
+        (@arg hide_warnings: --("hide-warnings") "Start with the panel of recent warnings, such as capped control requests and physics explosions, hidden. Press W to toggle.")
+        (@arg tissue_styles: --("tissue-styles") "Mark each kind of tissue with a pattern as well as a color, e.g. for color-blind viewers")
+        (@arg forces: --forces "Draw arrows for the forces on each cell")
+        (@arg fields: --field +takes_value +multiple number_of_values(1) possible_value[crowding light] "Ring each cell in a color from blue to red for how many cells it touches (crowding) or how much light reaches it (light). May be repeated.")
+        (@arg pick_radius: --("pick-radius") +takes_value {is_u64_arg} "How many pixels a click can miss a cell by and still select it, defaults to 5")
+        (@arg stats_every: --("stats-every") +takes_value {is_u64_arg} "Every this many ticks, append genome distance stats to <name>-s<seed>-<commit>-stats.csv")
+        (@arg tick_stats: --("tick-stats") "Every tick, append population, energy, age, bond, and layer area stats to <name>-s<seed>-<commit>-population.csv")
+        (@arg phylogeny: --phylogeny "At exit, write who budded from whom to <name>-s<seed>-<commit>-phylogeny.nwk (Newick) and .dot (GraphViz)")
+        (@arg hall_of_fame: --("hall-of-fame") +takes_value {is_u64_arg} "Every this many ticks, keep the genomes of the longest-lived cell and of the best cell of the richest lineage, and at exit write the most recent ones as <name>-s<seed>-<commit>-hof<n>-...-genome.json files that --genome can use")
+        (@arg audit_energy: --("audit-energy") "Check every tick that the cells' energy changes add up, print the books of each tick that doesn't balance, and at exit print how many didn't")
+        (@arg watchdog: --watchdog "Watch for physics explosions, a cell faster than 100 units per tick or the cells' kinetic energy jumping tenfold in a tick, and print the fastest cells and the forces on them")
+        (@arg pause_on_explosion: --("pause-on-explosion") requires[watchdog] "Pause when the --watchdog finds an explosion")
+        (@arg pareto: --pareto "At exit, print the cells with the best trade-offs of lifespan, offspring, and peak energy")
+        (@arg species: --species +takes_value {is_positive_number_arg} "Every --stats-every ticks, group cells into species of genomes within this distance, and record species originations and extinctions in <name>-s<seed>-<commit>-species.csv")
+        (@arg control_budget: --("control-budget") +takes_value {is_u64_arg} "Count each cell control that runs longer than this many microseconds in a tick")
+        (@arg slow_control_penalty: --("slow-control-penalty") +takes_value {is_fraction_arg} requires[control_budget] "The fraction of its energy a cell loses each time its control runs over --control-budget, defaults to 0")
+        (@arg headless: --headless "Run without a window, e.g. on a server, until Ctrl-C or a limit set by --ticks or --run-for, then print a summary")
+        (@arg ticks: --ticks +takes_value {is_u64_arg} "Run without a window for this many ticks, then print a summary and exit")
+        (@arg run_for: --("run-for") +takes_value {is_duration_arg} "Run without a window for this long, e.g. 90s, 30m, or 2h, then print a summary and exit")
+        (@arg set: --set +takes_value +multiple number_of_values(1) {is_override_arg} "Override a world parameter without recompiling, e.g. --set gravity=-0.1. May be repeated.")
+        (@arg set_at: --("set-at") +takes_value +multiple number_of_values(1) {is_scheduled_override_arg} "Change a world parameter once this many ticks have run, e.g. --set-at 5000:cloud_resize_factor=1.05. May be repeated.")
+        (@arg force: --force +takes_value +multiple number_of_values(1) "Add a force to every world, by name with any values it takes, e.g. --force weight:gravity=-0.05 or --force constant:x=0.01,y=0. Standard forces: buoyancy, constant, drag, weight. May be repeated.")
+        (@arg scenario: --scenario +takes_value "Run the world described by this TOML or RON scenario file instead of the program's own. Its [variables] can be changed with --set.")
+        (@arg founder: --founder +takes_value "Start the initial cells from a cell saved by pressing E in an earlier run")
+        (@arg genome: --genome +takes_value "Give the initial cells a genome saved by pressing J in an earlier run")
+        (@arg evaluate: --evaluate +takes_value +multiple "Run each of these cells saved by pressing E as the founder of a fresh world for --ticks ticks, defaulting to 10000, and print how long the founders lived, the energy they gathered, and their offspring")
+        (@arg dump_frames: --("dump-frames") +takes_value "Write frames to this directory as numbered PNG files, e.g. to make a video")
+        (@arg dump_every: --("dump-every") +takes_value {is_u64_arg} requires[dump_frames] "With --dump-frames, write only every this many frames, defaults to 1")
+        (@arg keys: --keys +takes_value "Change which keys do what, from a file of \"key = action\" lines such as \"Space = PlayToggle\"")
+        (@arg trails: --trails +takes_value possible_value[selected all] "Draw recent positions of selected cells or of all cells")
+    )
+    .subcommand(
+        SubCommand::with_name("bench-world")
+            .about("Time the ticks of a standard dense world, whatever the program's own world, and print ticks per second and the time taken by each phase of a tick, to compare performance across changes and machines")
+            .arg(
+                Arg::with_name("cells")
+                    .long("cells")
+                    .takes_value(true)
+                    .validator(is_u64_arg)
+                    .help("How many cells, defaults to 1000"),
+            )
+            .arg(
+                Arg::with_name("ticks")
+                    .long("ticks")
+                    .takes_value(true)
+                    .validator(is_u64_arg)
+                    .help("How many ticks to time, defaults to 300"),
+            ),
+    )
+    .subcommand(
+        SubCommand::with_name("soak")
+            .about("Run the standard dense world headless for a long time, sampling resident memory and how many items each part of the world holds, and exit with an error if any of them keeps growing")
+            .arg(
+                Arg::with_name("cells")
+                    .long("cells")
+                    .takes_value(true)
+                    .validator(is_u64_arg)
+                    .help("How many cells, defaults to 1000"),
+            )
+            .arg(
+                Arg::with_name("ticks")
+                    .long("ticks")
+                    .takes_value(true)
+                    .validator(is_u64_arg)
+                    .help("How many ticks to run, defaults to 1000000"),
+            )
+            .arg(
+                Arg::with_name("sample_every")
+                    .long("sample-every")
+                    .takes_value(true)
+                    .validator(is_u64_arg)
+                    .help("How many ticks between samples, defaults to 10000"),
+            ),
     )
     .get_matches();
 
     CommandLineArgs {
+        name: matches.value_of("name").map(|name| name.to_string()),
+        description: matches.value_of("description").unwrap_or("").to_string(),
         seed: get_u64_arg(&matches, "seed", 0),
         start_paused: matches.is_present("paused"),
+        pipelined: matches.is_present("pipelined"),
+        interpolated: matches.is_present("interpolate"),
+        report_timing: matches.is_present("timing"),
+        trails: match matches.value_of("trails") {
+            Some("selected") => TrailMode::Selected,
+            Some("all") => TrailMode::All,
+            _ => TrailMode::Off,
+        },
+        force_arrows: matches.is_present("forces"),
+        derived_fields: matches
+            .values_of("fields")
+            .map(|names| names.map(|name| name.to_string()).collect())
+            .unwrap_or_default(),
+        grid: matches.is_present("grid"),
+        dump_frames: matches.value_of("dump_frames").map(|dir| dir.to_string()),
+        dump_every: get_u64_arg(&matches, "dump_every", 1),
+        population_graph: matches.is_present("graph"),
+        net_diagram: matches.is_present("net_diagram"),
+        hide_warnings: matches.is_present("hide_warnings"),
+        tissue_styles: matches.is_present("tissue_styles"),
+        pick_radius: get_u64_arg(&matches, "pick_radius", 5) as f32,
+        stats_interval: matches
+            .value_of("stats_every")
+            .map(|arg| arg.parse::<u64>().unwrap().max(1)),
+        tick_stats: matches.is_present("tick_stats"),
+        phylogeny: matches.is_present("phylogeny"),
+        hall_of_fame_interval: matches
+            .value_of("hall_of_fame")
+            .map(|arg| arg.parse::<u64>().unwrap().max(1)),
+        audit_energy: matches.is_present("audit_energy"),
+        watchdog: matches.is_present("watchdog"),
+        pause_on_explosion: matches.is_present("pause_on_explosion"),
+        pareto: matches.is_present("pareto"),
+        species_threshold: matches
+            .value_of("species")
+            .map(|arg| arg.parse::<f32>().unwrap()),
+        control_budget: matches
+            .value_of("control_budget")
+            .map(|arg| Duration::from_micros(arg.parse::<u64>().unwrap())),
+        slow_control_penalty: matches
+            .value_of("slow_control_penalty")
+            .map_or(Fraction::ZERO, |arg| {
+                Fraction::new(arg.parse::<f64>().unwrap())
+            }),
+        headless: matches.is_present("headless")
+            || matches.is_present("ticks")
+            || matches.is_present("run_for"),
+        max_ticks: matches
+            .value_of("ticks")
+            .map(|arg| arg.parse::<u64>().unwrap()),
+        run_for: matches
+            .value_of("run_for")
+            .map(|arg| parse_duration(arg).unwrap()),
+        overrides: matches.values_of("set").map_or(vec![], |args| {
+            args.map(|arg| parse_override(arg).unwrap()).collect()
+        }),
+        forces: matches
+            .values_of("force")
+            .map_or(vec![], |specs| specs.map(|spec| spec.to_string()).collect()),
+        scheduled_overrides: matches.values_of("set_at").map_or(vec![], |args| {
+            args.map(|arg| parse_scheduled_override(arg).unwrap())
+                .collect()
+        }),
+        scenario: matches.value_of("scenario").map(|path| path.to_string()),
+        founder: matches.value_of("founder").map(|path| path.to_string()),
+        genome: matches.value_of("genome").map(|path| path.to_string()),
+        keys: matches.value_of("keys").map(|path| path.to_string()),
+        bench_world: matches
+            .subcommand_matches("bench-world")
+            .map(|bench_matches| BenchWorldArgs {
+                cells: get_u64_arg(bench_matches, "cells", 1000) as usize,
+                ticks: get_u64_arg(bench_matches, "ticks", 300),
+            }),
+        soak: matches
+            .subcommand_matches("soak")
+            .map(|soak_matches| SoakArgs {
+                cells: get_u64_arg(soak_matches, "cells", 1000) as usize,
+                ticks: get_u64_arg(soak_matches, "ticks", SoakParameters::DEFAULT.ticks),
+                sample_every: get_u64_arg(
+                    soak_matches,
+                    "sample_every",
+                    SoakParameters::DEFAULT.sample_interval,
+                )
+                .max(1),
+            }),
+        evaluate: matches
+            .values_of("evaluate")
+            .map_or(vec![], |paths| paths.map(|path| path.to_string()).collect()),
+    }
+}
+
+fn experiment_metadata(args: &CommandLineArgs, worlds: &[World]) -> ExperimentMetadata {
+    let name = match &args.name {
+        Some(name) => name.clone(),
+        None => program_name(),
+    };
+    ExperimentMetadata::new(&name, args.seed)
+        .with_description(&args.description)
+        .with_git_commit(current_git_commit())
+        .with_config(&(
+            worlds
+                .iter()
+                .map(|world| (world.min_corner(), world.max_corner(), world.parameters()))
+                .collect::<Vec<_>>(),
+            &args.overrides,
+            &args.forces,
+            args.scenario
+                .as_ref()
+                .map(|path| fs::read_to_string(path).unwrap_or_default()),
+            &args.scheduled_overrides,
+        ))
+        .with_overrides(&args.overrides)
+}
+
+// The worlds as create_worlds builds them, or as --scenario describes them, with the
+// command line's parameter overrides, added forces, control time budget, and scheduled
+// parameter changes.
+fn create_configured_worlds<F>(
+    args: &CommandLineArgs,
+    create_worlds: &F,
+    forces: &ForceRegistry,
+    controls: &ControlRegistry,
+) -> Vec<World>
+where
+    F: Fn(u64, &ParameterOverrides) -> Vec<World>,
+{
+    let overrides = ParameterOverrides::new(args.overrides.clone());
+    let worlds = match &args.scenario {
+        Some(path) => {
+            let scenario = ScenarioFile::load(Path::new(path)).unwrap_or_else(|message| {
+                eprintln!("error: {}", message);
+                process::exit(2);
+            });
+            vec![scenario_world(
+                &scenario, args.seed, &overrides, forces, controls,
+            )]
+        }
+        None => create_worlds(args.seed, &overrides),
+    };
+    let mut worlds = overrides.apply(worlds).unwrap_or_else(|message| {
+        eprintln!("error: {}", message);
+        process::exit(2);
+    });
+    worlds = worlds
+        .into_iter()
+        .map(|world| add_forces(world, &args.forces, forces))
+        .collect();
+    if let Some(budget) = args.control_budget {
+        worlds = worlds
+            .into_iter()
+            .map(|world| world.with_control_time_budget(budget, args.slow_control_penalty))
+            .collect();
+    }
+    assert!(!worlds.is_empty());
+    let (min_corner, max_corner) = (worlds[0].min_corner(), worlds[0].max_corner());
+    assert!(worlds
+        .iter()
+        .all(|world| world.min_corner() == min_corner && world.max_corner() == max_corner));
+    for world in &mut worlds {
+        schedule_parameter_changes(world, &args.scheduled_overrides);
+    }
+    worlds
+}
+
+fn scenario_world(
+    scenario: &ScenarioFile,
+    seed: u64,
+    overrides: &ParameterOverrides,
+    forces: &ForceRegistry,
+    controls: &ControlRegistry,
+) -> World {
+    scenario
+        .spec(|name, value| overrides.get(name, value))
+        .and_then(|spec| spec.build_world(seed, forces, controls))
+        .unwrap_or_else(|message| {
+            eprintln!("error: scenario: {}", message);
+            process::exit(2);
+        })
+}
+
+fn add_forces(mut world: World, specs: &[String], forces: &ForceRegistry) -> World {
+    for spec in specs {
+        let influence = forces.create_influence(spec).unwrap_or_else(|message| {
+            eprintln!("error: {}", message);
+            process::exit(2);
+        });
+        world = world.with_per_cell_influence(influence);
+    }
+    world
+}
+
+fn apply_cell_template(worlds: &mut [World], path: &str) {
+    let template = fs::read_to_string(path)
+        .map_err(|err| format!("Can't read {}: {}", path, err))
+        .and_then(|text| CellTemplate::parse(&text))
+        .unwrap_or_else(|message| {
+            eprintln!("error: {}", message);
+            process::exit(2);
+        });
+    for world in worlds {
+        if let Err(message) = world.apply_cell_template(&template) {
+            eprintln!("error: {}: {}", path, message);
+            process::exit(2);
+        }
+    }
+}
+
+fn apply_genome(worlds: &mut [World], path: &str) {
+    let genome = fs::read_to_string(path)
+        .map_err(|err| format!("Can't read {}: {}", path, err))
+        .and_then(|json| SparseNeuralNetGenome::from_json(&json))
+        .unwrap_or_else(|message| {
+            eprintln!("error: {}", message);
+            process::exit(2);
+        });
+    for world in worlds {
+        if let Err(message) = world.apply_genome(&genome) {
+            eprintln!("error: {}: {}", path, message);
+            process::exit(2);
+        }
+    }
+}
+
+fn load_key_bindings(path: Option<&str>) -> KeyBindings {
+    match path {
+        Some(path) => fs::read_to_string(path)
+            .map_err(|err| format!("Can't read {}: {}", path, err))
+            .and_then(|text| {
+                KeyBindings::parse(&text).map_err(|message| format!("{}: {}", path, message))
+            })
+            .unwrap_or_else(|message| {
+                eprintln!("error: {}", message);
+                process::exit(2);
+            }),
+        None => KeyBindings::default(),
+    }
+}
+
+// The --field names were checked by clap.
+fn derived_fields(names: &[String]) -> Vec<Box<dyn DerivedField>> {
+    names
+        .iter()
+        .map(|name| -> Box<dyn DerivedField> {
+            match name.as_str() {
+                "crowding" => Box::new(CrowdingField),
+                "light" => Box::new(LightField),
+                _ => unreachable!(),
+            }
+        })
+        .collect()
+}
+
+// Runs each cell given with --evaluate as the founder of the same fresh worlds, and
+// prints a CSV row per cell and world of how the founders fared, so that saved genomes
+// can be compared without the noise of the run that evolved them.
+fn evaluate_genomes<F>(
+    args: &CommandLineArgs,
+    create_worlds: &F,
+    forces: &ForceRegistry,
+    controls: &ControlRegistry,
+) where
+    F: Fn(u64, &ParameterOverrides) -> Vec<World>,
+{
+    const DEFAULT_EVALUATION_TICKS: u64 = 10000;
+    let ticks = args.max_ticks.unwrap_or(DEFAULT_EVALUATION_TICKS);
+    println!("genome,world,{}", FitnessReport::CSV_HEADER);
+    for path in &args.evaluate {
+        let mut worlds = create_configured_worlds(args, create_worlds, forces, controls);
+        apply_cell_template(&mut worlds, path);
+        for (index, world) in worlds.iter_mut().enumerate() {
+            let report = evaluate_founders(world, ticks);
+            println!("{},{},{}", path, index + 1, report.to_csv_row());
+        }
+    }
+}
+
+fn schedule_parameter_changes(world: &mut World, scheduled_overrides: &[(u64, String, f64)]) {
+    for (tick, name, value) in scheduled_overrides {
+        if let Err(message) = world.schedule_parameter_change(*tick, name, *value) {
+            eprintln!("error: {}", message);
+            process::exit(2);
+        }
+    }
+}
+
+// Records each mid-run parameter change in a CSV file named for the experiment, and on
+// stdout, so that later shifts in behavior can be lined up with the change that caused
+// them, e.g. against the stats file by tick.
+fn log_parameter_changes(worlds: &mut [World], metadata: &ExperimentMetadata) {
+    let path = format!("{}-events.csv", metadata.file_stem());
+    let mut file = File::create(&path).unwrap();
+    for line in metadata.header_lines() {
+        writeln!(file, "{}", line).unwrap();
+    }
+    writeln!(file, "tick,world,parameter,value").unwrap();
+    println!("# events file: {}", path);
+
+    let file = Arc::new(Mutex::new(file));
+    for (index, world) in worlds.iter_mut().enumerate() {
+        let file = Arc::clone(&file);
+        world.on_parameter_change(move |change| {
+            println!(
+                "# tick {}: world {} set {}={}",
+                change.tick,
+                index + 1,
+                change.name,
+                change.value
+            );
+            writeln!(
+                file.lock().unwrap(),
+                "{},{},{},{}",
+                change.tick,
+                index + 1,
+                change.name,
+                change.value
+            )
+            .unwrap();
+        });
+    }
+}
+
+// Every interval ticks, appends each world's genome distance stats to a CSV file named
+// for the experiment, flushing as it goes so the file can be watched during the run.
+// The state hash column lets the files of two runs that should be identical be diffed
+// to find where they first diverge.
+fn log_genome_stats(worlds: &mut [World], interval: u64, metadata: &ExperimentMetadata) {
+    let path = format!("{}-stats.csv", metadata.file_stem());
+    let mut file = File::create(&path).unwrap();
+    for line in metadata.header_lines() {
+        writeln!(file, "{}", line).unwrap();
+    }
+    writeln!(
+        file,
+        "tick,world,{},slow_controls,state_hash",
+        GenomeDistanceStats::CSV_HEADER
+    )
+    .unwrap();
+    println!("# stats file: {}", path);
+
+    let file = Arc::new(Mutex::new(file));
+    for (index, world) in worlds.iter_mut().enumerate() {
+        let founders = GenomeDistanceStats::founder_genomes(world);
+        let file = Arc::clone(&file);
+        world.on_tick_end(move |world| {
+            let tick = world.tick_count();
+            if tick % interval == 0 {
+                let stats = GenomeDistanceStats::measure(&founders, world);
+                writeln!(
+                    file.lock().unwrap(),
+                    "{},{},{},{},{:016x}",
+                    tick,
+                    index + 1,
+                    stats.to_csv_row(),
+                    world.slow_control_count(),
+                    world.state_hash()
+                )
+                .unwrap();
+            }
+        });
+    }
+}
+
+// Every tick, appends each world's population stats, and the births, deaths, bond
+// changes, and donations of the tick, to a CSV file named for the experiment, one file
+// per world.
+fn log_population_stats(worlds: &mut [World], metadata: &ExperimentMetadata) {
+    let world_count = worlds.len();
+    for (index, world) in worlds.iter_mut().enumerate() {
+        let world_suffix = if world_count > 1 {
+            format!("-w{}", index + 1)
+        } else {
+            String::new()
+        };
+        let path = format!("{}{}-population.csv", metadata.file_stem(), world_suffix);
+        let mut file = File::create(&path).unwrap();
+        for line in metadata.header_lines() {
+            writeln!(file, "{}", line).unwrap();
+        }
+        println!("# population stats file: {}", path);
+
+        let collector = Arc::new(Mutex::new(
+            StatsCollector::new().with_csv(Box::new(file)).unwrap(),
+        ));
+        world.subscribe(Box::new(Arc::clone(&collector)));
+        world.on_tick_end(move |world| collector.lock().unwrap().collect(world).unwrap());
+    }
+}
+
+fn write_phylogenies(worlds: &[World], metadata: &ExperimentMetadata) {
+    for (index, world) in worlds.iter().enumerate() {
+        let phylogeny = world.phylogeny().unwrap();
+        let world_suffix = if worlds.len() > 1 {
+            format!("-w{}", index + 1)
+        } else {
+            String::new()
+        };
+        let stem = format!("{}{}-phylogeny", metadata.file_stem(), world_suffix);
+        fs::write(format!("{}.nwk", stem), phylogeny.to_newick() + "\n").unwrap();
+        fs::write(format!("{}.dot", stem), phylogeny.to_graphviz()).unwrap();
+        println!(
+            "# phylogeny of {} cells: {}.nwk, {}.dot",
+            phylogeny.len(),
+            stem,
+            stem
+        );
+    }
+}
+
+// Writes each hall of fame entry's genome to its own file, oldest first, named so that
+// the files sort in the order they were taken.
+fn write_halls_of_fame(worlds: &[World], metadata: &ExperimentMetadata) {
+    for (index, world) in worlds.iter().enumerate() {
+        let hall_of_fame = world.hall_of_fame().unwrap();
+        let world_suffix = if worlds.len() > 1 {
+            format!("-w{}", index + 1)
+        } else {
+            String::new()
+        };
+        for (number, entry) in hall_of_fame.entries().iter().enumerate() {
+            let path = format!(
+                "{}{}-hof{:03}-{}-t{}-cell{}-genome.json",
+                metadata.file_stem(),
+                world_suffix,
+                number + 1,
+                entry.distinction.name(),
+                entry.first_tick,
+                entry.cell_id
+            );
+            match entry.genome.to_json() {
+                Ok(json) => {
+                    fs::write(&path, json + "\n").unwrap();
+                    println!("# hall of fame {}: {}", entry, path);
+                }
+                Err(message) => println!("# can't write hall of fame {}: {}", entry, message),
+            }
+        }
+    }
+}
+
+fn print_energy_audit_summaries(worlds: &[World]) {
+    for (index, world) in worlds.iter().enumerate() {
+        let audit = world.energy_audit().unwrap();
+        println!(
+            "# energy audit{}: {} of {} ticks imbalanced",
+            if worlds.len() > 1 {
+                format!(" of world {}", index + 1)
+            } else {
+                String::new()
+            },
+            audit.imbalanced_tick_count(),
+            world.tick_count()
+        );
+    }
+}
+
+// One front per world, fed each cell as it dies.
+fn track_pareto_fronts(worlds: &mut [World]) -> Vec<Arc<Mutex<ParetoFront>>> {
+    worlds
+        .iter_mut()
+        .map(|world| {
+            let front = Arc::new(Mutex::new(ParetoFront::new()));
+            let hook_front = Arc::clone(&front);
+            world.on_death(move |cell| hook_front.lock().unwrap().add_cell(cell));
+            front
+        })
+        .collect()
+}
+
+// Every interval ticks, sorts each world's cells into species and appends any species
+// originations and extinctions to a CSV file named for the experiment.
+fn track_species(
+    worlds: &mut [World],
+    threshold: f32,
+    interval: u64,
+    metadata: &ExperimentMetadata,
+) -> Vec<Arc<Mutex<SpeciesTracker>>> {
+    let path = format!("{}-species.csv", metadata.file_stem());
+    let mut file = File::create(&path).unwrap();
+    for line in metadata.header_lines() {
+        writeln!(file, "{}", line).unwrap();
+    }
+    writeln!(file, "# species distance threshold: {}", threshold).unwrap();
+    writeln!(file, "tick,world,{}", SpeciesTracker::CSV_HEADER).unwrap();
+    println!("# species file: {}", path);
+
+    let file = Arc::new(Mutex::new(file));
+    worlds
+        .iter_mut()
+        .enumerate()
+        .map(|(index, world)| {
+            let tracker = Arc::new(Mutex::new(SpeciesTracker::new(threshold)));
+            let hook_tracker = Arc::clone(&tracker);
+            let file = Arc::clone(&file);
+            world.on_tick_end(move |world| {
+                if world.tick_count() % interval != 0 {
+                    return;
+                }
+                let events = hook_tracker.lock().unwrap().update_from_world(world);
+                let mut file = file.lock().unwrap();
+                for event in events {
+                    writeln!(file, "{},{},{}", event.tick, index + 1, event.to_csv_row()).unwrap();
+                }
+            });
+            tracker
+        })
+        .collect()
+}
+
+fn print_species_summaries(trackers: &[Arc<Mutex<SpeciesTracker>>]) {
+    for (index, tracker) in trackers.iter().enumerate() {
+        if trackers.len() > 1 {
+            println!("World {}:", index + 1);
+        }
+        print!("{}", tracker.lock().unwrap());
+    }
+}
+
+fn print_pareto_fronts(worlds: &[World], fronts: &[Arc<Mutex<ParetoFront>>]) {
+    for (index, (world, front)) in worlds.iter().zip(fronts).enumerate() {
+        let mut front = front.lock().unwrap();
+        for cell in world.cells() {
+            front.add_cell(cell);
+        }
+        if worlds.len() > 1 {
+            println!("World {}:", index + 1);
+        }
+        print!("{}", front);
+    }
+}
+
+fn program_name() -> String {
+    std::env::args()
+        .next()
+        .and_then(|path| {
+            Path::new(&path)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| "evo".to_string())
+}
+
+// None when not run from inside the repository, or when git is not installed.
+fn current_git_commit() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()?;
+    if output.status.success() {
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        None
     }
 }
 
@@ -45,6 +982,71 @@ fn is_u64_arg(v: String) -> Result<(), String> {
     }
 }
 
+fn is_positive_number_arg(v: String) -> Result<(), String> {
+    match v.parse::<f64>() {
+        Ok(number) if number > 0.0 && number.is_finite() => Ok(()),
+        _ => Err("Not a positive number".to_string()),
+    }
+}
+
+fn is_fraction_arg(v: String) -> Result<(), String> {
+    match v.parse::<f64>() {
+        Ok(number) if (0.0..=1.0).contains(&number) => Ok(()),
+        _ => Err("Not a number from 0 to 1".to_string()),
+    }
+}
+
+fn is_duration_arg(v: String) -> Result<(), String> {
+    parse_duration(&v).map(|_| ())
+}
+
+// A number with an optional unit: s (the default), m, h, or d.
+fn parse_duration(arg: &str) -> Result<Duration, String> {
+    let (number, unit_secs) = match arg.chars().last() {
+        Some('s') => (&arg[..arg.len() - 1], 1.0),
+        Some('m') => (&arg[..arg.len() - 1], 60.0),
+        Some('h') => (&arg[..arg.len() - 1], 60.0 * 60.0),
+        Some('d') => (&arg[..arg.len() - 1], 24.0 * 60.0 * 60.0),
+        _ => (arg, 1.0),
+    };
+    // too big for a Duration is no duration either
+    number
+        .parse::<f64>()
+        .ok()
+        .and_then(|number| Duration::try_from_secs_f64(number * unit_secs).ok())
+        .ok_or_else(|| "Not a duration such as 90s, 30m, or 2h".to_string())
+}
+
+fn is_override_arg(v: String) -> Result<(), String> {
+    parse_override(&v).map(|_| ())
+}
+
+fn parse_override(arg: &str) -> Result<(String, f64), String> {
+    let mut parts = arg.splitn(2, '=');
+    match (parts.next(), parts.next().map(|value| value.parse::<f64>())) {
+        (Some(name), Some(Ok(value))) if !name.is_empty() && value.is_finite() => {
+            Ok((name.to_string(), value))
+        }
+        _ => Err("Not a name=value pair such as gravity=-0.1".to_string()),
+    }
+}
+
+fn is_scheduled_override_arg(v: String) -> Result<(), String> {
+    parse_scheduled_override(&v).map(|_| ())
+}
+
+// e.g. "5000:cloud_resize_factor=1.05"
+fn parse_scheduled_override(arg: &str) -> Result<(u64, String, f64), String> {
+    let mut parts = arg.splitn(2, ':');
+    match (parts.next().map(|tick| tick.parse::<u64>()), parts.next()) {
+        (Some(Ok(tick)), Some(name_value)) => {
+            let (name, value) = parse_override(name_value)?;
+            Ok((tick, name, value))
+        }
+        _ => Err("Not a tick:name=value such as 5000:cloud_resize_factor=1.05".to_string()),
+    }
+}
+
 fn get_u64_arg(matches: &ArgMatches, name: &str, default_value: u64) -> u64 {
     if let Some(arg) = matches.value_of(name) {
         arg.parse::<u64>().unwrap()
@@ -53,8 +1055,8 @@ fn get_u64_arg(matches: &ArgMatches, name: &str, default_value: u64) -> u64 {
     }
 }
 
-fn run(mut world: World, mut view: View, start_paused: bool) {
-    view.render(&world);
+fn run(worlds: &mut [World], mut view: View, start_paused: bool, metadata: &ExperimentMetadata) {
+    view.render(worlds);
 
     let mut next_action = if start_paused {
         Some(view.wait_for_user_action())
@@ -64,30 +1066,313 @@ fn run(mut world: World, mut view: View, start_paused: bool) {
 
     while let Some(this_action) = next_action {
         next_action = match this_action {
-            UserAction::DebugPrint => Some(debug_print_action(&mut world, &mut view)),
+            UserAction::AddCellAt { x, y } => {
+                Some(add_cell_action(worlds, &mut view, Position::new(x, y)))
+            }
 
-            UserAction::Exit => None,
+            UserAction::DebugPrint => Some(debug_print_action(worlds, &mut view)),
 
-            UserAction::FastForwardToggle => Some(fast_forward_action(&mut world, &mut view)),
+            UserAction::Exit => None,
 
-            UserAction::PlayToggle => Some(play_action(&mut world, &mut view)),
+            UserAction::ExportSelected => Some(export_selected_action(worlds, &mut view, metadata)),
 
-            UserAction::SelectCellToggle { x, y } => {
-                Some(toggle_select_cell_action(&mut world, &mut view, x, y))
+            UserAction::ExportSelectedGenome => {
+                Some(export_selected_genome_action(worlds, &mut view, metadata))
             }
 
-            UserAction::SingleTick => Some(single_tick_action(&mut world, &mut view)),
+            UserAction::FastForwardToggle => Some(fast_forward_action(worlds, &mut view)),
+
+            UserAction::FollowSelectionToggle
+            | UserAction::GridToggle
+            | UserAction::NetDiagramToggle
+            | UserAction::OverlayToggle
+            | UserAction::PopulationGraphToggle
+            | UserAction::Screenshot
+            | UserAction::ViewBack
+            | UserAction::WarningPanelToggle
+            | UserAction::ZoomIn
+            | UserAction::ZoomOut
+            | UserAction::ZoomToFit
+            | UserAction::ZoomToSelection => Some(display_action(worlds, &mut view, this_action)),
+
+            UserAction::PlayToggle => Some(play_action(worlds, &mut view)),
+
+            UserAction::RemoveCellAt { x, y, tolerance } => Some(remove_cell_action(
+                worlds,
+                &mut view,
+                Position::new(x, y),
+                Length::new(tolerance),
+            )),
+
+            UserAction::SelectCellToggle { x, y, tolerance } => Some(toggle_select_cell_action(
+                worlds,
+                &mut view,
+                Position::new(x, y),
+                Length::new(tolerance),
+            )),
+
+            UserAction::SingleTick => Some(single_tick_action(worlds, &mut view)),
+
+            UserAction::StepBack => Some(step_back_action(&mut view)),
+
+            UserAction::SlowDown | UserAction::SpeedUp => {
+                change_speed(&mut view, this_action);
+                Some(view.wait_for_user_action())
+            }
         };
     }
 }
 
-fn debug_print_action(world: &mut World, view: &mut View) -> UserAction {
-    world.debug_print_cells();
+static SHUTDOWN_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+// The first Ctrl-C or SIGTERM asks the run to stop once the current tick is done, so
+// that output files end on a whole tick; a second one exits at once, e.g. when the run
+// is paused waiting for a key. Stats rows are written as they are made, so there is
+// nothing left to flush, and the worlds can't yet be saved, so there is no checkpoint
+// to write.
+fn install_shutdown_handler() {
+    ctrlc::set_handler(|| {
+        if SHUTDOWN_REQUESTED.swap(true, Ordering::SeqCst) {
+            process::exit(130);
+        }
+        eprintln!("Stopping after this tick. Press Ctrl-C again to stop at once.");
+    })
+    .unwrap();
+}
+
+fn shutdown_requested() -> bool {
+    SHUTDOWN_REQUESTED.load(Ordering::SeqCst)
+}
+
+// Ticks without a window until a tick limit or wall-clock budget is reached, or until
+// Ctrl-C, as batch jobs and CI runs want.
+fn run_headless(worlds: &mut [World], max_ticks: Option<u64>, budget: Option<Duration>) {
+    let start = Instant::now();
+    while !shutdown_requested()
+        && max_ticks.is_none_or(|max_ticks| worlds[0].tick_count() < max_ticks)
+        && budget.is_none_or(|budget| start.elapsed() < budget)
+    {
+        tick_all(worlds);
+    }
+}
+
+// untimed, so that caches and the thread pool are warm
+const BENCH_WARMUP_TICKS: u64 = 10;
+
+// Times ticks of the standard dense world (see benchmark::dense_world) and prints ticks
+// per second and where the time went.
+fn run_bench_world(args: &BenchWorldArgs, seed: u64) {
+    let mut world = dense_world(args.cells, seed);
+    for _ in 0..BENCH_WARMUP_TICKS {
+        world.tick();
+    }
+    world.enable_phase_timing();
+    let start = Instant::now();
+    for _ in 0..args.ticks {
+        world.tick();
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "bench-world: {} cells, seed {}, commit {}",
+        args.cells,
+        seed,
+        current_git_commit().unwrap_or_else(|| "unknown".to_string())
+    );
+    println!(
+        "Ran {} ticks in {:.2}s ({:.1} ticks/s)",
+        args.ticks,
+        elapsed.as_secs_f64(),
+        args.ticks as f64 / elapsed.as_secs_f64()
+    );
+    let phase_times = world.phase_times().unwrap();
+    let total = phase_times.total().as_secs_f64();
+    for (phase, duration) in phase_times.phases() {
+        println!(
+            "  {:<28}{:>9.3}s{:>7.1}%{:>10.3}ms/tick",
+            phase,
+            duration.as_secs_f64(),
+            100.0 * duration.as_secs_f64() / total,
+            1000.0 * duration.as_secs_f64() / phase_times.ticks() as f64
+        );
+    }
+    println!(
+        "At end: {} cells, {} bonds",
+        world.cells().len(),
+        world.bonds().len()
+    );
+}
+
+// Soaks the standard dense world (see soak::run_soak), printing each sample as it is
+// taken, and exits with status 1 if anything kept growing.
+fn run_soak_test(args: &SoakArgs, seed: u64) {
+    let mut world = dense_world(args.cells, seed);
+    let parameters = SoakParameters {
+        ticks: args.ticks,
+        sample_interval: args.sample_every,
+        ..SoakParameters::DEFAULT
+    };
+    println!(
+        "soak: {} cells, {} ticks, seed {}, commit {}",
+        args.cells,
+        args.ticks,
+        seed,
+        current_git_commit().unwrap_or_else(|| "unknown".to_string())
+    );
+    let report = run_soak(&mut world, &parameters, |sample| {
+        let rss = sample.rss_bytes.map_or("?".to_string(), |bytes| {
+            format!("{:.1}MB", bytes as f64 / (1 << 20) as f64)
+        });
+        let footprint = sample
+            .footprint
+            .iter()
+            .filter(|(_, count)| *count > 0)
+            .map(|(part, count)| format!("{} {}", part, count))
+            .collect::<Vec<_>>()
+            .join(", ");
+        println!("Tick {}: RSS {}; {}", sample.tick, rss, footprint);
+    });
+    if report.passed() {
+        println!("Passed: nothing kept growing");
+    } else {
+        for failure in &report.failures {
+            eprintln!("Failed: {}", failure);
+        }
+        std::process::exit(1);
+    }
+}
+
+fn print_run_summary(worlds: &[World], elapsed: Duration) {
+    let ticks = worlds[0].tick_count();
+    println!(
+        "Ran {} ticks in {:.1}s ({:.1} ticks/s)",
+        ticks,
+        elapsed.as_secs_f64(),
+        ticks as f64 / elapsed.as_secs_f64()
+    );
+    for (index, world) in worlds.iter().enumerate() {
+        let total_energy: f64 = world.cells().iter().map(|cell| cell.energy().value()).sum();
+        println!(
+            "World {}: {} cells, {} bonds, {} clouds, {:.4} total cell energy",
+            index + 1,
+            world.cells().len(),
+            world.bonds().len(),
+            world.clouds().len(),
+            total_energy
+        );
+        if let Some(watchdog) = world.explosion_watchdog() {
+            println!(
+                "World {}: {} ticks with physics explosions",
+                index + 1,
+                watchdog.explosion_count()
+            );
+        }
+        if world.slow_control_count() > 0 {
+            println!(
+                "World {}: {} cell control runs over the time budget",
+                index + 1,
+                world.slow_control_count()
+            );
+        }
+    }
+}
+
+fn debug_print_action(worlds: &[World], view: &mut View) -> UserAction {
+    for (index, world) in worlds.iter().enumerate() {
+        if worlds.len() > 1 {
+            println!("World {}:", index + 1);
+        }
+        world.debug_print_cells();
+        println!(
+            "Tick {} state hash: {:016x}",
+            world.tick_count(),
+            world.state_hash()
+        );
+    }
     view.wait_for_user_action()
 }
 
-fn fast_forward_action(world: &mut World, view: &mut View) -> UserAction {
-    let action = fast_forward(world, view);
+// Saves each selected cell as a template that --founder can start a later run from.
+fn export_selected_action(
+    worlds: &[World],
+    view: &mut View,
+    metadata: &ExperimentMetadata,
+) -> UserAction {
+    for (index, world) in worlds.iter().enumerate() {
+        for cell in world.cells().iter().filter(|cell| cell.is_selected()) {
+            let world_label = if worlds.len() > 1 {
+                format!("-w{}", index + 1)
+            } else {
+                String::new()
+            };
+            let path = format!(
+                "{}{}-t{}-cell{}.txt",
+                metadata.file_stem(),
+                world_label,
+                world.tick_count(),
+                cell.node_handle()
+            );
+            let mut file = File::create(&path).unwrap();
+            for line in metadata.header_lines() {
+                writeln!(file, "{}", line).unwrap();
+            }
+            writeln!(file, "# tick: {}", world.tick_count()).unwrap();
+            writeln!(file, "# world parameters: {:?}", world.parameters()).unwrap();
+            write!(file, "{}", CellTemplate::from_cell(cell)).unwrap();
+            println!("Exported cell {} to {}", cell.node_handle(), path);
+        }
+    }
+    view.wait_for_user_action()
+}
+
+// Saves the genome of each selected cell as JSON that --genome can give the cells of a
+// later run.
+fn export_selected_genome_action(
+    worlds: &[World],
+    view: &mut View,
+    metadata: &ExperimentMetadata,
+) -> UserAction {
+    for (index, world) in worlds.iter().enumerate() {
+        for cell in world.cells().iter().filter(|cell| cell.is_selected()) {
+            let genome = match cell.genome() {
+                Some(genome) => genome,
+                None => {
+                    println!("Cell {} has no genome", cell.node_handle());
+                    continue;
+                }
+            };
+            let world_label = if worlds.len() > 1 {
+                format!("-w{}", index + 1)
+            } else {
+                String::new()
+            };
+            let path = format!(
+                "{}{}-t{}-cell{}-genome.json",
+                metadata.file_stem(),
+                world_label,
+                world.tick_count(),
+                cell.node_handle()
+            );
+            match genome.to_json() {
+                Ok(json) => {
+                    fs::write(&path, json + "\n").unwrap();
+                    println!("Exported genome of cell {} to {}", cell.node_handle(), path);
+                }
+                Err(message) => {
+                    println!(
+                        "Can't export genome of cell {}: {}",
+                        cell.node_handle(),
+                        message
+                    )
+                }
+            }
+        }
+    }
+    view.wait_for_user_action()
+}
+
+fn fast_forward_action(worlds: &mut [World], view: &mut View) -> UserAction {
+    let action = fast_forward(worlds, view);
     if action == UserAction::FastForwardToggle {
         view.wait_for_user_action()
     } else {
@@ -95,26 +1380,50 @@ fn fast_forward_action(world: &mut World, view: &mut View) -> UserAction {
     }
 }
 
-fn fast_forward(world: &mut World, view: &mut View) -> UserAction {
+fn fast_forward(worlds: &mut [World], view: &mut View) -> UserAction {
     loop {
+        if shutdown_requested() {
+            return UserAction::Exit;
+        }
         if let Some(user_action) = view.check_for_user_action() {
-            return user_action;
+            if !change_display(worlds, view, user_action) {
+                return user_action;
+            }
         }
 
-        tick_for(world, Duration::from_millis(16));
-        view.render(world);
+        view.tick_and_render(worlds, |worlds| tick_for(worlds, Duration::from_millis(16)));
+        if explosion_pause_requested(worlds) {
+            return UserAction::FastForwardToggle;
+        }
     }
 }
 
-fn tick_for(world: &mut World, duration: Duration) {
+// Ticks every world the same number of times, so side-by-side worlds stay in step.
+fn tick_for(worlds: &mut [World], duration: Duration) {
     let end_time = Instant::now() + duration;
-    while Instant::now() < end_time {
+    while Instant::now() < end_time && !explosion_pause_requested(worlds) {
+        tick_all(worlds);
+    }
+}
+
+// Whether a world's explosion watchdog found an explosion in the last tick and wants the
+// run paused, e.g. to look at the cells involved.
+fn explosion_pause_requested(worlds: &[World]) -> bool {
+    worlds.iter().any(|world| {
+        world
+            .explosion_watchdog()
+            .is_some_and(|watchdog| watchdog.parameters().pause && watchdog.exploded_last_tick())
+    })
+}
+
+fn tick_all(worlds: &mut [World]) {
+    for world in worlds {
         world.tick();
     }
 }
 
-fn play_action(world: &mut World, view: &mut View) -> UserAction {
-    let action = play(world, view);
+fn play_action(worlds: &mut [World], view: &mut View) -> UserAction {
+    let action = play(worlds, view);
     if action == UserAction::PlayToggle {
         view.wait_for_user_action()
     } else {
@@ -122,20 +1431,60 @@ fn play_action(world: &mut World, view: &mut View) -> UserAction {
     }
 }
 
-fn play(world: &mut World, view: &mut View) -> UserAction {
+fn play(worlds: &mut [World], view: &mut View) -> UserAction {
     let mut next_tick = Instant::now();
     loop {
-        if let Some(user_action) = view.check_for_user_action() {
-            return user_action;
+        if shutdown_requested() {
+            return UserAction::Exit;
+        }
+        match view.check_for_user_action() {
+            Some(action) if change_display(worlds, view, action) => {}
+            Some(action @ UserAction::SlowDown) | Some(action @ UserAction::SpeedUp) => {
+                change_speed(view, action)
+            }
+            Some(user_action) => return user_action,
+            None => {}
         }
 
-        single_tick(world, view);
-
-        next_tick += Duration::from_millis(16);
+        // in slow motion, each tick plays out over several frames, and sped up, each
+        // frame shows the last of several ticks
+        let frame_period = Duration::from_millis(16);
+        if view.speed() < 1.0 {
+            let tick_period = frame_period.div_f64(view.speed());
+            view.tick_and_render_slowly(worlds, tick_all, tick_period);
+            next_tick += tick_period;
+        } else {
+            let ticks_per_frame = view.speed() as u64;
+            view.tick_and_render(worlds, |worlds| {
+                for _ in 0..ticks_per_frame {
+                    tick_all(worlds);
+                    if explosion_pause_requested(worlds) {
+                        break;
+                    }
+                }
+            });
+            next_tick += frame_period;
+        }
+        if explosion_pause_requested(worlds) {
+            return UserAction::PlayToggle;
+        }
         await_next_tick(next_tick);
     }
 }
 
+fn change_speed(view: &mut View, action: UserAction) {
+    if action == UserAction::SlowDown {
+        view.slow_down();
+    } else {
+        view.speed_up();
+    }
+    if view.speed() < 1.0 {
+        println!("Speed: 1/{}", (1.0 / view.speed()).round());
+    } else {
+        println!("Speed: {} ticks per frame", view.speed());
+    }
+}
+
 fn await_next_tick(next_tick: Instant) {
     let now = Instant::now();
     if now < next_tick {
@@ -143,18 +1492,177 @@ fn await_next_tick(next_tick: Instant) {
     }
 }
 
-fn toggle_select_cell_action(world: &mut World, view: &mut View, x: f64, y: f64) -> UserAction {
-    world.toggle_select_cell_at(Position::new(x, y));
-    view.render(world);
+fn display_action(worlds: &[World], view: &mut View, action: UserAction) -> UserAction {
+    change_display(worlds, view, action);
+    view.render(worlds);
     view.wait_for_user_action()
 }
 
-fn single_tick_action(world: &mut World, view: &mut View) -> UserAction {
-    single_tick(world, view);
+// Whether the action only changes how the worlds are drawn, and so was done here
+// without pausing or resuming them.
+fn change_display(worlds: &[World], view: &mut View, action: UserAction) -> bool {
+    match action {
+        UserAction::GridToggle => view.toggle_grid(),
+        UserAction::NetDiagramToggle => view.toggle_net_diagram(),
+        UserAction::OverlayToggle => view.toggle_overlays(),
+        UserAction::PopulationGraphToggle => view.toggle_population_graph(),
+        UserAction::WarningPanelToggle => view.toggle_warning_panel(),
+        UserAction::Screenshot => view.save_screenshot(),
+        UserAction::ZoomIn => view.zoom_in(),
+        UserAction::ZoomOut => view.zoom_out(),
+        UserAction::ZoomToFit => view.zoom_to_fit(),
+        UserAction::ZoomToSelection => {
+            if !view.zoom_to_selection(worlds) {
+                println!("Select a cell to zoom to");
+            }
+        }
+        UserAction::ViewBack => view.view_back(),
+        UserAction::FollowSelectionToggle => {
+            if !view.toggle_follow_selection(worlds) {
+                println!("Select a cell to follow");
+            }
+        }
+        _ => return false,
+    }
+    true
+}
+
+// Adds a copy of the selected cell, or of the world's placeable cell if none is selected.
+fn add_cell_action(worlds: &mut [World], view: &mut View, pos: Position) -> UserAction {
+    for world in worlds.iter_mut() {
+        if world.add_cell_at(pos).is_none() {
+            println!("Select a cell to copy");
+        }
+    }
+    view.render(worlds);
+    view.wait_for_user_action()
+}
+
+fn remove_cell_action(
+    worlds: &mut [World],
+    view: &mut View,
+    pos: Position,
+    tolerance: Length,
+) -> UserAction {
+    for world in worlds.iter_mut() {
+        world.remove_cell_at(pos, tolerance);
+    }
+    view.render(worlds);
+    view.wait_for_user_action()
+}
+
+fn toggle_select_cell_action(
+    worlds: &mut [World],
+    view: &mut View,
+    pos: Position,
+    tolerance: Length,
+) -> UserAction {
+    for world in worlds.iter_mut() {
+        world.toggle_select_cell_at(pos, tolerance);
+    }
+    view.render(worlds);
+    view.wait_for_user_action()
+}
+
+// After stepping back, steps forward through the kept ticks before ticking again.
+fn single_tick_action(worlds: &mut [World], view: &mut View) -> UserAction {
+    if !view.step_forward(worlds) {
+        single_tick(worlds, view);
+    }
+    view.wait_for_user_action()
+}
+
+fn step_back_action(view: &mut View) -> UserAction {
+    if !view.step_back() {
+        println!("No earlier ticks kept");
+    }
     view.wait_for_user_action()
 }
 
-fn single_tick(world: &mut World, view: &mut View) {
-    world.tick();
-    view.render(world);
+fn single_tick(worlds: &mut [World], view: &mut View) {
+    view.tick_and_render(worlds, tick_all);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use evo_domain::biology::control::NullControl;
+
+    #[test]
+    fn shipped_scenarios_build() {
+        let controls =
+            ControlRegistry::new().with_control("neural_net", |_seed| Box::new(NullControl::new()));
+        for name in &["default.toml", "floating_balls.ron"] {
+            let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("scenarios")
+                .join(name);
+            let world = ScenarioFile::load(&path)
+                .and_then(|scenario| scenario.spec(|_name, value| value))
+                .and_then(|spec| {
+                    spec.build_world(0, &ForceRegistry::with_standard_forces(), &controls)
+                })
+                .unwrap();
+            assert!(!world.cells().is_empty(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn parses_durations_with_units() {
+        assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("90s"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("1.5m"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("2h"), Ok(Duration::from_secs(2 * 60 * 60)));
+        assert_eq!(parse_duration("1d"), Ok(Duration::from_secs(24 * 60 * 60)));
+    }
+
+    #[test]
+    fn parses_overrides() {
+        assert_eq!(
+            parse_override("gravity=-0.1"),
+            Ok(("gravity".to_string(), -0.1))
+        );
+        assert!(parse_override("gravity").is_err());
+        assert!(parse_override("=1").is_err());
+        assert!(parse_override("gravity=heavy").is_err());
+    }
+
+    #[test]
+    fn parses_scheduled_overrides() {
+        assert_eq!(
+            parse_scheduled_override("5000:cloud_resize_factor=1.05"),
+            Ok((5000, "cloud_resize_factor".to_string(), 1.05))
+        );
+        assert!(parse_scheduled_override("cloud_resize_factor=1.05").is_err());
+        assert!(parse_scheduled_override("-1:cloud_resize_factor=1.05").is_err());
+        assert!(parse_scheduled_override("5000:cloud_resize_factor").is_err());
+    }
+
+    #[test]
+    fn overrides_set_parameters_and_reject_unread_names() {
+        let overrides = ParameterOverrides::new(vec![
+            ("cloud_resize_factor".to_string(), 1.5),
+            ("gravity".to_string(), -0.1),
+        ]);
+        let new_world = || World::new(Position::ORIGIN, Position::new(1.0, 1.0));
+        assert!(overrides.apply(vec![new_world()]).is_err());
+
+        assert_eq!(overrides.get("gravity", -0.05), -0.1);
+        assert_eq!(overrides.get("drag", 0.005), 0.005);
+        let worlds = overrides.apply(vec![new_world()]).unwrap();
+        assert_eq!(
+            worlds[0].parameters().cloud_params.resize_factor.value(),
+            1.5
+        );
+    }
+
+    #[test]
+    fn rejects_bad_durations() {
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("h").is_err());
+        assert!(parse_duration("-1m").is_err());
+        assert!(parse_duration("2w").is_err());
+        assert!(parse_duration("nan").is_err());
+        assert!(parse_duration("1e300").is_err());
+        assert!(parse_duration("1e20d").is_err());
+    }
 }
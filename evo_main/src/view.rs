@@ -1,22 +1,246 @@
+use crate::history::History;
+use evo_domain::physics::node_graph::GraphNode;
 use evo_domain::physics::quantities::*;
 use evo_domain::world::World;
-use evo_domain::UserAction;
-use evo_glium::GliumView;
+use evo_domain::{ElapsedTimeProbe, UserAction};
+use evo_glium::{DerivedField, GliumView, KeyBindings, RenderSnapshot, TrailMode};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
 
 pub struct View {
     view: GliumView,
+    pipelined: bool,
+    interpolated: bool,
+    previous_snapshots: Option<Vec<RenderSnapshot>>,
+    last_tick_time: Duration,
+    speed: f64,
+    // what was drawn before each recent tick, to step back through while paused
+    history: History<Vec<RenderSnapshot>>,
+    frame_probe: Option<ElapsedTimeProbe>,
 }
 
 impl View {
     pub fn new(world_min_corner: Position, world_max_corner: Position) -> Self {
+        Self::new_side_by_side(world_min_corner, world_max_corner, 1)
+    }
+
+    // Shows world_count worlds with the same corners next to each other.
+    pub fn new_side_by_side(
+        world_min_corner: Position,
+        world_max_corner: Position,
+        world_count: usize,
+    ) -> Self {
         View {
-            view: GliumView::new(
+            view: GliumView::new_side_by_side(
                 [world_min_corner.x() as f32, world_min_corner.y() as f32],
                 [world_max_corner.x() as f32, world_max_corner.y() as f32],
+                world_count,
             ),
+            pipelined: false,
+            interpolated: false,
+            previous_snapshots: None,
+            last_tick_time: Duration::from_millis(16),
+            speed: 1.0,
+            history: History::new(Self::HISTORY_LENGTH),
+            frame_probe: None,
+        }
+    }
+
+    const HISTORY_LENGTH: usize = 300;
+
+    // Draws each frame while the world is computing its next tick on another thread.
+    // The frame shown is one tick behind the world.
+    pub fn with_pipelining(mut self, pipelined: bool) -> Self {
+        self.pipelined = pipelined;
+        self
+    }
+
+    // While the world computes its next tick on another thread, keeps drawing frames
+    // that move the cells from where they were one tick earlier to where they are now,
+    // paced by how long the last tick took. The frames shown are one tick behind.
+    pub fn with_interpolation(mut self, interpolated: bool) -> Self {
+        self.interpolated = interpolated;
+        self
+    }
+
+    pub fn with_trails(mut self, mode: TrailMode) -> Self {
+        self.view = self.view.with_trails(mode);
+        self
+    }
+
+    pub fn with_force_arrows(mut self, show_force_arrows: bool) -> Self {
+        self.view = self.view.with_force_arrows(show_force_arrows);
+        self
+    }
+
+    pub fn with_frame_dump(mut self, dir: Option<&str>, every_nth_frame: u64) -> Self {
+        if let Some(dir) = dir {
+            if let Err(err) = fs::create_dir_all(dir) {
+                eprintln!("Can't create {}: {}", dir, err);
+            }
+            self.view = self
+                .view
+                .with_frame_dump(PathBuf::from(dir), every_nth_frame);
+        }
+        self
+    }
+
+    pub fn with_derived_fields(mut self, fields: Vec<Box<dyn DerivedField>>) -> Self {
+        for field in fields {
+            self.view = self.view.with_derived_field(field);
+        }
+        self
+    }
+
+    pub fn with_grid(mut self, show_grid: bool) -> Self {
+        self.view = self.view.with_grid(show_grid);
+        self
+    }
+
+    pub fn with_net_diagram(mut self, show_net_diagram: bool) -> Self {
+        self.view = self.view.with_net_diagram(show_net_diagram);
+        self
+    }
+
+    pub fn with_warning_panel(mut self, show_warning_panel: bool) -> Self {
+        self.view = self.view.with_warning_panel(show_warning_panel);
+        self
+    }
+
+    pub fn with_population_graph(mut self, show_population_graph: bool) -> Self {
+        self.view = self.view.with_population_graph(show_population_graph);
+        self
+    }
+
+    pub fn with_tissue_styles(mut self, show_tissue_styles: bool) -> Self {
+        self.view = self.view.with_tissue_styles(show_tissue_styles);
+        self
+    }
+
+    pub fn with_key_bindings(mut self, key_bindings: KeyBindings) -> Self {
+        self.view = self.view.with_key_bindings(key_bindings);
+        self
+    }
+
+    pub fn with_pick_radius(mut self, pick_radius: f32) -> Self {
+        self.view = self.view.with_pick_radius(pick_radius);
+        self
+    }
+
+    pub fn with_pixels_per_unit(mut self, pixels_per_unit: Option<f64>) -> Self {
+        if let Some(pixels_per_unit) = pixels_per_unit {
+            self.view = self.view.with_pixels_per_unit(pixels_per_unit);
+        }
+        self
+    }
+
+    // Saves what is on screen as screenshot-<tick>.png in the current directory.
+    pub fn save_screenshot(&self) {
+        let file_name = format!("screenshot-{:06}.png", self.view.last_drawn_tick());
+        match self.view.capture_frame_to_png(Path::new(&file_name)) {
+            Ok(()) => println!("Saved {}", file_name),
+            Err(err) => eprintln!("Can't write {}: {}", file_name, err),
+        }
+    }
+
+    pub fn toggle_grid(&mut self) {
+        self.view.toggle_grid();
+    }
+
+    pub fn toggle_net_diagram(&mut self) {
+        self.view.toggle_net_diagram();
+    }
+
+    pub fn toggle_population_graph(&mut self) {
+        self.view.toggle_population_graph();
+    }
+
+    pub fn toggle_warning_panel(&mut self) {
+        self.view.toggle_warning_panel();
+    }
+
+    pub fn toggle_overlays(&mut self) {
+        self.view.toggle_overlays();
+    }
+
+    pub fn zoom_in(&mut self) {
+        self.view.zoom_in();
+    }
+
+    pub fn zoom_out(&mut self) {
+        self.view.zoom_out();
+    }
+
+    pub fn zoom_to_fit(&mut self) {
+        self.view.zoom_to_fit();
+    }
+
+    // Zooms to the colonies of the selected cells in all the worlds, or returns false if
+    // no cell is selected.
+    pub fn zoom_to_selection(&mut self, worlds: &[World]) -> bool {
+        let bounds = worlds
+            .iter()
+            .flat_map(|world| {
+                world
+                    .cells()
+                    .iter()
+                    .filter(|cell| cell.is_selected())
+                    .map(move |cell| world.colony_of(cell.node_handle()).bounds())
+            })
+            .reduce(|bounds1, bounds2| bounds1.union(bounds2));
+        match bounds {
+            Some(bounds) => {
+                let (min_corner, max_corner) = (bounds.min_corner(), bounds.max_corner());
+                self.view.zoom_to_region(
+                    [min_corner.x() as f32, min_corner.y() as f32],
+                    [max_corner.x() as f32, max_corner.y() as f32],
+                );
+                true
+            }
+            None => false,
         }
     }
 
+    pub fn view_back(&mut self) {
+        self.view.view_back();
+    }
+
+    // Starts or stops keeping the selected cells in the middle of the view, zooming to
+    // them first if the view shows the whole world. Returns false, without starting, if
+    // no cell is selected.
+    pub fn toggle_follow_selection(&mut self, worlds: &[World]) -> bool {
+        if !self.view.is_following_selection() {
+            if !worlds
+                .iter()
+                .any(|world| world.cells().iter().any(|cell| cell.is_selected()))
+            {
+                return false;
+            }
+            if !self.view.is_zoomed_in() {
+                self.zoom_to_selection(worlds);
+            }
+        }
+        self.view.toggle_follow_selection();
+        true
+    }
+
+    // Periodically prints the average time to tick and draw a frame.
+    pub fn with_frame_timing(mut self, report_timing: bool) -> Self {
+        self.frame_probe = if report_timing {
+            let name = if self.pipelined {
+                "pipelined frame"
+            } else {
+                "frame"
+            };
+            Some(ElapsedTimeProbe::new(name, Duration::from_secs(5)))
+        } else {
+            None
+        };
+        self
+    }
+
     pub fn check_for_user_action(&mut self) -> Option<UserAction> {
         self.view.check_for_user_action()
     }
@@ -25,7 +249,146 @@ impl View {
         self.view.wait_for_user_action()
     }
 
-    pub fn render(&mut self, world: &World) {
-        self.view.render(world);
+    // Slow-motion speeds are 1/2, 1/4, and so on down to 1/64 of normal speed, one tick
+    // per frame. Fast speeds are 2, 4, and so on up to 64 ticks per frame.
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    pub fn slow_down(&mut self) {
+        self.speed = (self.speed / 2.0).max(1.0 / Self::MAX_SPEED);
+    }
+
+    pub fn speed_up(&mut self) {
+        self.speed = (self.speed * 2.0).min(Self::MAX_SPEED);
+    }
+
+    const MAX_SPEED: f64 = 64.0;
+
+    // Draws the live worlds, even after stepping back.
+    pub fn render(&mut self, worlds: &[World]) {
+        self.history.return_to_live();
+        self.view.render_side_by_side(worlds);
+    }
+
+    // Shows the worlds as they were before the tick before the one shown, if that is
+    // still kept. In fast forward, only the ticks that were drawn are kept.
+    pub fn step_back(&mut self) -> bool {
+        if !self.history.step_back() {
+            return false;
+        }
+        self.draw_history();
+        true
+    }
+
+    // Undoes a step back, or does nothing and returns false if the live worlds are shown.
+    pub fn step_forward(&mut self, worlds: &[World]) -> bool {
+        if !self.history.step_forward() {
+            return false;
+        }
+        match self.history.current() {
+            Some(_) => self.draw_history(),
+            None => self.view.render_side_by_side(worlds),
+        }
+        true
+    }
+
+    fn draw_history(&mut self) {
+        let snapshots = self.history.current().unwrap();
+        println!(
+            "Tick {} ({} back)",
+            snapshots[0].tick(),
+            self.history.steps_back()
+        );
+        self.view.draw_side_by_side(snapshots);
+    }
+
+    pub fn tick_and_render<F>(&mut self, worlds: &mut [World], tick: F)
+    where
+        F: FnOnce(&mut [World]) + Send,
+    {
+        if let Some(probe) = &mut self.frame_probe {
+            probe.begin();
+        }
+
+        if self.interpolated {
+            self.tick_and_render_interpolated(worlds, tick, Duration::ZERO);
+        } else if self.pipelined {
+            self.previous_snapshots = None;
+            let snapshots = worlds
+                .iter()
+                .map(|world| self.view.snapshot(world))
+                .collect::<Vec<_>>();
+            let view = &mut self.view;
+            thread::scope(|scope| {
+                scope.spawn(|| tick(worlds));
+                view.draw_side_by_side(&snapshots);
+            });
+            self.history.record(snapshots);
+        } else {
+            self.previous_snapshots = None;
+            let snapshots = worlds
+                .iter()
+                .map(|world| self.view.snapshot(world))
+                .collect::<Vec<_>>();
+            tick(worlds);
+            self.view.render_side_by_side(worlds);
+            self.history.record(snapshots);
+        }
+
+        if let Some(probe) = &mut self.frame_probe {
+            probe.end();
+        }
+    }
+
+    // For slow motion: ticks the worlds once, taking at least the tick period, and draws
+    // interpolated frames all the while, so each tick plays out smoothly over the period.
+    pub fn tick_and_render_slowly<F>(&mut self, worlds: &mut [World], tick: F, period: Duration)
+    where
+        F: FnOnce(&mut [World]) + Send,
+    {
+        self.tick_and_render_interpolated(worlds, tick, period);
+    }
+
+    // The frames pace themselves by how long the last tick took to compute, or by the
+    // minimum time, if that is longer.
+    fn tick_and_render_interpolated<F>(&mut self, worlds: &mut [World], tick: F, min_time: Duration)
+    where
+        F: FnOnce(&mut [World]) + Send,
+    {
+        let snapshots = worlds
+            .iter()
+            .map(|world| self.view.snapshot(world))
+            .collect::<Vec<_>>();
+        let previous_snapshots = self.previous_snapshots.take();
+        let frames_secs = self.last_tick_time.max(min_time).as_secs_f32();
+        let view = &mut self.view;
+        let start = Instant::now();
+        self.last_tick_time = thread::scope(|scope| {
+            let ticker = scope.spawn(|| {
+                tick(worlds);
+                start.elapsed()
+            });
+            match &previous_snapshots {
+                Some(previous_snapshots) => {
+                    view.draw_side_by_side(previous_snapshots);
+                    while !ticker.is_finished() || start.elapsed() < min_time {
+                        let fraction = (start.elapsed().as_secs_f32() / frames_secs).min(1.0);
+                        view.draw_interpolated_side_by_side(
+                            previous_snapshots,
+                            &snapshots,
+                            fraction,
+                        );
+                    }
+                }
+                None => {
+                    view.draw_side_by_side(&snapshots);
+                    thread::sleep(min_time.saturating_sub(start.elapsed()));
+                }
+            }
+            ticker.join().unwrap()
+        });
+        self.history.record(snapshots.clone());
+        self.previous_snapshots = Some(snapshots);
     }
 }